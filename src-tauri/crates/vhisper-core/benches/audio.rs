@@ -0,0 +1,87 @@
+//! 音频热路径基准：重采样、PCM/WAV/Opus 编码、流式分块
+//!
+//! 运行：cargo bench --features bench --bench audio
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use vhisper_core::audio::{encode_to_ogg_opus, encode_to_pcm, encode_to_wav, Resampler};
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// 生成指定时长的测试音频（440Hz 正弦波）
+fn sine(sample_rate: u32, channels: usize, secs: usize) -> Vec<f32> {
+    let frames = sample_rate as usize * secs;
+    (0..frames)
+        .flat_map(|i| {
+            let v = (i as f32 * 440.0 * std::f32::consts::TAU / sample_rate as f32).sin() * 0.5;
+            std::iter::repeat_n(v, channels)
+        })
+        .collect()
+}
+
+fn resample(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resample");
+    // 常见的设备格式：48kHz 立体声、44.1kHz 单声道
+    for (source_rate, channels) in [(48000u32, 2usize), (44100, 1)] {
+        let input = sine(source_rate, channels, 10);
+        group.throughput(Throughput::Elements((input.len() / channels) as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}Hz_{}ch", source_rate, channels)),
+            &input,
+            |b, input| {
+                b.iter(|| {
+                    let mut resampler = Resampler::new(source_rate, SAMPLE_RATE, channels);
+                    let mut out = Vec::with_capacity(SAMPLE_RATE as usize * 10);
+                    // 按设备回调的典型块大小（10ms）处理
+                    for block in input.chunks(source_rate as usize / 100 * channels) {
+                        resampler.process(block, &mut out);
+                    }
+                    black_box(out)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    for secs in [10usize, 60] {
+        let samples = sine(SAMPLE_RATE, 1, secs);
+        group.throughput(Throughput::Elements(samples.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("pcm", secs), &samples, |b, samples| {
+            b.iter(|| black_box(encode_to_pcm(samples)))
+        });
+        group.bench_with_input(BenchmarkId::new("wav", secs), &samples, |b, samples| {
+            b.iter(|| black_box(encode_to_wav(samples, SAMPLE_RATE, 1).unwrap()))
+        });
+        // 未启用 opus feature 时编码直接返回错误，结果没有参考意义
+        if cfg!(feature = "opus") {
+            group.bench_with_input(BenchmarkId::new("opus", secs), &samples, |b, samples| {
+                b.iter(|| black_box(encode_to_ogg_opus(samples, SAMPLE_RATE).unwrap()))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn streaming_chunks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("streaming_chunks");
+    let samples = sine(SAMPLE_RATE, 1, 10);
+    group.throughput(Throughput::Elements(samples.len() as u64));
+    // 流式上传的分块间隔：正常 50ms，低带宽 200ms
+    for chunk_ms in [50usize, 200] {
+        let chunk_len = SAMPLE_RATE as usize * chunk_ms / 1000;
+        group.bench_with_input(BenchmarkId::from_parameter(chunk_ms), &samples, |b, samples| {
+            b.iter(|| {
+                for chunk in samples.chunks(chunk_len) {
+                    black_box(encode_to_pcm(chunk));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, resample, encode, streaming_chunks);
+criterion_main!(benches);
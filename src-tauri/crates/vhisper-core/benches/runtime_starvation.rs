@@ -0,0 +1,85 @@
+//! 长录音编码对异步运行时的影响
+//!
+//! 在单工作线程的运行时上运行一个 5ms 周期的计时任务，同时编码 10 分钟的录音，
+//! 对比直接在异步任务中编码和使用 encode_blocking 时计时任务的最大延迟。
+//!
+//! 运行：cargo bench --bench runtime_starvation
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use vhisper_core::audio::{encode, encode_blocking, EncodeFormat};
+
+const SAMPLE_RATE: u32 = 16000;
+const RECORDING_SECS: usize = 600;
+const TICK: Duration = Duration::from_millis(5);
+
+fn recording() -> Arc<Vec<f32>> {
+    let len = SAMPLE_RATE as usize * RECORDING_SECS;
+    Arc::new(
+        (0..len)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / SAMPLE_RATE as f32).sin() * 0.5)
+            .collect(),
+    )
+}
+
+/// 运行编码任务，返回计时任务观测到的最大延迟
+async fn max_tick_lateness<F>(encode_task: F) -> Duration
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    let done = Arc::new(AtomicBool::new(false));
+
+    let ticker = {
+        let done = done.clone();
+        tokio::spawn(async move {
+            let mut worst = Duration::ZERO;
+            while !done.load(Ordering::SeqCst) {
+                let start = Instant::now();
+                tokio::time::sleep(TICK).await;
+                worst = worst.max(start.elapsed().saturating_sub(TICK));
+            }
+            worst
+        })
+    };
+
+    // 让计时任务先跑起来
+    tokio::time::sleep(TICK * 4).await;
+    tokio::spawn(encode_task).await.unwrap();
+    done.store(true, Ordering::SeqCst);
+
+    ticker.await.unwrap()
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .unwrap();
+    let samples = recording();
+
+    for (name, format) in [
+        ("pcm", EncodeFormat::Pcm),
+        ("wav", EncodeFormat::Wav { channels: 1 }),
+    ] {
+        let inline = {
+            let samples = samples.clone();
+            runtime.block_on(max_tick_lateness(async move {
+                let _ = encode(&samples, SAMPLE_RATE, format);
+            }))
+        };
+        let blocking = {
+            let samples = samples.clone();
+            runtime.block_on(max_tick_lateness(async move {
+                let _ = encode_blocking(samples, SAMPLE_RATE, format).await;
+            }))
+        };
+
+        println!(
+            "{:>4} {}s: max tick lateness inline={:?} spawn_blocking={:?}",
+            name, RECORDING_SECS, inline, blocking
+        );
+    }
+}
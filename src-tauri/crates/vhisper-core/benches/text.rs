@@ -0,0 +1,47 @@
+//! 文本热路径基准：替换规则、规范化、标点
+//!
+//! 运行：cargo bench --features bench --bench text
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use vhisper_core::config::settings::TextReplacement;
+use vhisper_core::text::stages::{apply_replacements, normalize, punctuate};
+
+/// 一段中英混合的口述文本
+const SENTENCE: &str = "我们明天下午三点在会议室讨论 vhisper 的 release plan，记得带上 Q3 的 roadmap 和 KPI 数据 ";
+
+fn rules(count: usize) -> Vec<TextReplacement> {
+    (0..count)
+        .map(|i| TextReplacement {
+            from: format!("term{}", i),
+            to: format!("Term{}", i),
+            case_sensitive: i % 2 == 0,
+        })
+        .chain(std::iter::once(TextReplacement {
+            from: "vhisper".to_string(),
+            to: "Vhisper".to_string(),
+            case_sensitive: false,
+        }))
+        .collect()
+}
+
+fn replacements(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_replacements");
+    let text = SENTENCE.repeat(20);
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    for count in [10usize, 100] {
+        let rules = rules(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &rules, |b, rules| {
+            b.iter(|| black_box(apply_replacements(&text, rules)))
+        });
+    }
+    group.finish();
+}
+
+fn stages(c: &mut Criterion) {
+    let text = SENTENCE.repeat(20);
+    c.bench_function("normalize", |b| b.iter(|| black_box(normalize(&text))));
+    c.bench_function("punctuate", |b| b.iter(|| black_box(punctuate(&text))));
+}
+
+criterion_group!(benches, replacements, stages);
+criterion_main!(benches);
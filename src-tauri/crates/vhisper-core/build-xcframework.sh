@@ -22,16 +22,16 @@ rustup target add aarch64-apple-darwin x86_64-apple-darwin 2>/dev/null || true
 # 2. 编译双架构
 echo "[2/4] 编译 aarch64-apple-darwin..."
 if [ "$MODE" = "release" ]; then
-    cargo build --release --target aarch64-apple-darwin
+    cargo build --release --features ffi --target aarch64-apple-darwin
 else
-    cargo build --target aarch64-apple-darwin
+    cargo build --features ffi --target aarch64-apple-darwin
 fi
 
 echo "[2/4] 编译 x86_64-apple-darwin..."
 if [ "$MODE" = "release" ]; then
-    cargo build --release --target x86_64-apple-darwin
+    cargo build --release --features ffi --target x86_64-apple-darwin
 else
-    cargo build --target x86_64-apple-darwin
+    cargo build --features ffi --target x86_64-apple-darwin
 fi
 
 # 3. 合并为 universal 静态库
@@ -44,6 +44,11 @@ typedef void (*VhisperStreamingCallback)(void *context,
                                           const char *stash,
                                           const char *error);
 
+/// 结构化事件回调函数
+/// @param context 用户传入的上下文指针
+/// @param event_json 事件 JSON（UTF-8），包含 version、session_id、type、data 字段
+typedef void (*VhisperEventCallback)(void *context, const char *event_json);
+
 // ============================================================================
 // 生命周期
 // ============================================================================
@@ -120,6 +125,32 @@ int32_t vhisper_cancel_streaming(VhisperHandle *handle);
 /// @return 1=流式模式, 0=非流式模式, -1=handle无效
 int32_t vhisper_is_streaming(VhisperHandle *handle);
 
+// ============================================================================
+// 文本处理
+// ============================================================================
+
+/// 对任意文本执行后处理流程（异步，不涉及录音）
+/// @param handle Vhisper 实例
+/// @param text 待处理的文本（UTF-8）
+/// @param profile 应用标识（如 bundle id），可以为 NULL
+/// @param callback 结果回调函数
+/// @param context 传递给回调的用户上下文
+/// @return 0=任务已提交, -1=handle或text无效, -2=字符串不是有效的UTF-8
+int32_t vhisper_process_text(VhisperHandle *handle,
+                              const char *text,
+                              const char *profile,
+                              VhisperResultCallback callback,
+                              void *context);
+
+// ============================================================================
+// 事件
+// ============================================================================
+
+/// 设置结构化事件回调（全局，后设置的覆盖先前的）
+/// @param callback 事件回调函数，NULL 表示移除
+/// @param context 传递给回调的用户上下文
+void vhisper_set_event_callback(VhisperEventCallback callback, void *context);
+
 // ============================================================================
 // 配置
 // ============================================================================
@@ -138,6 +169,21 @@ int32_t vhisper_update_config(VhisperHandle *handle, const char *config_json);
 /// @param s 由本库返回的字符串指针
 void vhisper_string_free(char *s);
 
+/// 获取听写统计历史
+/// @return JSON 数组（每个会话的 session_id、finished_at、words、duration_ms、wpm），
+///         需要用 vhisper_string_free 释放；读取失败返回 NULL
+char *vhisper_get_history(void);
+
+/// 空闲超过节能配置的时长时释放本地模型和保活连接，宿主定时调用（如每 30 秒）
+/// @param handle Vhisper 实例
+/// @return 1 已释放，0 未释放（未到空闲时长、正在使用或已经释放过），-1 handle 无效
+int32_t vhisper_release_if_idle(VhisperHandle *handle);
+
+/// 设置内部 tokio runtime 的工作线程数，必须在首次调用其他函数之前调用
+/// @param worker_threads 工作线程数，0 表示使用默认值（CPU 核数）
+/// @return 0 成功，-1 runtime 已创建
+int32_t vhisper_set_runtime_threads(uint32_t worker_threads);
+
 /// 获取版本号
 /// @return 版本字符串（静态，无需释放）
 const char *vhisper_version(void);
@@ -0,0 +1,410 @@
+//! AssemblyAI ASR 服务
+//!
+//! - 批量识别：上传音频 → 创建转写任务 → 轮询结果
+//! - 流式识别：WebSocket 实时接口 (Universal Streaming v3)
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::streaming_session::StreamingSession;
+use super::traits::{
+    AsrError, AsrResult, AsrSegment, AsrService, StreamingAsrEvent, StreamingAsrService,
+    StreamingControl,
+};
+use crate::audio::record_upload;
+use crate::debug::{capture_audio, capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::net::{connect_async, proxied_client};
+
+const API_BASE: &str = "https://api.assemblyai.com/v2";
+const STREAMING_HOST: &str = "streaming.assemblyai.com";
+/// 轮询转写结果的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+/// 等待转写完成的最长时间
+const POLL_TIMEOUT: Duration = Duration::from_secs(60);
+/// WebSocket 连接超时时间
+const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// AssemblyAI ASR 服务
+pub struct AssemblyAiAsr {
+    api_key: String,
+    language: String,
+    /// 是否开启说话人分离（仅批量接口）
+    diarization: bool,
+    /// 热词（仅批量接口）
+    vocabulary: Vec<String>,
+    client: Client,
+}
+
+impl AssemblyAiAsr {
+    pub fn new(api_key: String, language: String) -> Self {
+        Self {
+            api_key,
+            language,
+            diarization: false,
+            vocabulary: Vec::new(),
+            client: proxied_client(),
+        }
+    }
+
+    /// 使用指定的 HTTP 客户端（如设置了连接超时的客户端）
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 设置热词
+    pub fn with_vocabulary(mut self, vocabulary: Vec<String>) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    /// 设置是否开启说话人分离
+    pub fn with_diarization(mut self, diarization: bool) -> Self {
+        self.diarization = diarization;
+        self
+    }
+
+    /// 上传音频，返回服务端的音频地址
+    async fn upload(&self, audio_data: &[u8]) -> Result<String, AsrError> {
+        let upload_start = Instant::now();
+        let response = self
+            .client
+            .post(format!("{}/upload", API_BASE))
+            .header("Authorization", &self.api_key)
+            .header("Content-Type", "application/octet-stream")
+            .body(audio_data.to_vec())
+            .send()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+        record_upload(audio_data.len(), upload_start.elapsed());
+
+        let upload: UploadResponse = parse_response(response).await?;
+        Ok(upload.upload_url)
+    }
+
+    /// 创建转写任务，返回任务 ID
+    async fn create_transcript(&self, audio_url: String) -> Result<String, AsrError> {
+        let request = TranscriptRequest {
+            audio_url,
+            language_code: self.language.clone(),
+            punctuate: true,
+            format_text: true,
+            speaker_labels: self.diarization,
+            word_boost: self.vocabulary.clone(),
+        };
+        let body = serde_json::to_string(&request).map_err(|e| AsrError::Encoding(e.to_string()))?;
+        capture_text("AssemblyAI", CaptureDirection::Send, &body);
+
+        let response = self
+            .client
+            .post(format!("{}/transcript", API_BASE))
+            .header("Authorization", &self.api_key)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let transcript: TranscriptResponse = parse_response(response).await?;
+        Ok(transcript.id)
+    }
+
+    /// 轮询转写任务直到完成
+    async fn poll_transcript(&self, id: &str) -> Result<TranscriptResponse, AsrError> {
+        let poll = async {
+            loop {
+                let response = self
+                    .client
+                    .get(format!("{}/transcript/{}", API_BASE, id))
+                    .header("Authorization", &self.api_key)
+                    .send()
+                    .await
+                    .map_err(|e| AsrError::Network(e.to_string()))?;
+
+                let transcript: TranscriptResponse = parse_response(response).await?;
+                match transcript.status.as_str() {
+                    "completed" => return Ok(transcript),
+                    "error" => {
+                        return Err(AsrError::Api(
+                            transcript.error.unwrap_or_else(|| "转写失败".to_string()),
+                        ))
+                    }
+                    _ => tokio::time::sleep(POLL_INTERVAL).await,
+                }
+            }
+        };
+
+        timeout(POLL_TIMEOUT, poll)
+            .await
+            .map_err(|_| AsrError::Network("等待转写结果超时".to_string()))?
+    }
+}
+
+// ============================================================================
+// 批量接口
+// ============================================================================
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    upload_url: String,
+}
+
+#[derive(Serialize)]
+struct TranscriptRequest {
+    audio_url: String,
+    language_code: String,
+    punctuate: bool,
+    format_text: bool,
+    speaker_labels: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    word_boost: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptResponse {
+    id: String,
+    status: String,
+    text: Option<String>,
+    error: Option<String>,
+    #[serde(default)]
+    words: Vec<Word>,
+}
+
+#[derive(Deserialize)]
+struct Word {
+    text: String,
+    start: u64,
+    end: u64,
+    /// 开启 speaker_labels 时的说话人（"A"、"B"...）
+    #[serde(default)]
+    speaker: Option<String>,
+}
+
+/// 说话人字母转换为从 1 开始的编号
+fn speaker_number(label: &str) -> Option<u32> {
+    match label.as_bytes() {
+        [c @ b'A'..=b'Z'] => Some(u32::from(c - b'A') + 1),
+        _ => label.parse().ok(),
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    error: String,
+}
+
+/// 解析响应，非 2xx 时提取错误信息
+async fn parse_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, AsrError> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))?;
+    capture_text("AssemblyAI", CaptureDirection::Recv, &body);
+
+    if !status.is_success() {
+        if let Ok(error) = serde_json::from_str::<ApiError>(&body) {
+            return Err(AsrError::Api(error.error));
+        }
+        return Err(AsrError::Api(format!("HTTP {}: {}", status, body)));
+    }
+
+    serde_json::from_str(&body).map_err(|e| AsrError::Api(e.to_string()))
+}
+
+#[async_trait]
+impl AsrService for AssemblyAiAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        capture_audio("AssemblyAI", CaptureDirection::Send, audio_data, sample_rate);
+
+        let audio_url = self.upload(audio_data).await?;
+        let id = self.create_transcript(audio_url).await?;
+        let transcript = self.poll_transcript(&id).await?;
+
+        let segments = transcript
+            .words
+            .into_iter()
+            .map(|w| AsrSegment {
+                text: w.text,
+                start_ms: w.start,
+                end_ms: w.end,
+                speaker: w.speaker.as_deref().and_then(speaker_number),
+            })
+            .collect();
+
+        Ok(AsrResult {
+            text: transcript.text.unwrap_or_default(),
+            is_final: true,
+            segments,
+            ..Default::default()
+        })
+    }
+}
+
+// ============================================================================
+// 实时接口
+// ============================================================================
+
+#[derive(Deserialize)]
+struct StreamingMessage {
+    #[serde(rename = "type")]
+    message_type: String,
+    #[serde(default)]
+    transcript: String,
+    #[serde(default)]
+    end_of_turn: bool,
+    #[serde(default)]
+    turn_is_formatted: bool,
+    error: Option<String>,
+}
+
+#[async_trait]
+impl StreamingAsrService for AssemblyAiAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<StreamingSession, AsrError> {
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        // 非英文时使用多语言模型
+        let url = format!(
+            "wss://{}/v3/ws?sample_rate={}&encoding=pcm_s16le&format_turns=true{}",
+            STREAMING_HOST,
+            sample_rate,
+            if self.language == "en" {
+                ""
+            } else {
+                "&speech_model=universal-streaming-multilingual"
+            }
+        );
+
+        let request = http::Request::builder()
+            .uri(&url)
+            .header("Authorization", &self.api_key)
+            .header(
+                "Sec-WebSocket-Key",
+                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+            )
+            .header("Sec-WebSocket-Version", "13")
+            .header("Host", STREAMING_HOST)
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .body(())
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let (ws_stream, _) = timeout(WS_CONNECT_TIMEOUT, connect_async(request))
+            .await
+            .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))?
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        trace_ws_marker("AssemblyAI", "ws.connected");
+
+        let (mut write, mut read) = ws_stream.split();
+
+        tokio::spawn(async move {
+            // 当前轮次的文本（未格式化），连接关闭时作为兜底结果
+            let mut turn_text = String::new();
+
+            loop {
+                tokio::select! {
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                capture_audio("AssemblyAI", CaptureDirection::Send, &data, sample_rate);
+                                if write.send(Message::Binary(data.into())).await.is_err() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(
+                                        "发送音频失败".to_string()
+                                    )).await;
+                                    break;
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                // 强制结束当前轮次，服务端随后返回格式化的最终结果
+                                let json = r#"{"type":"ForceEndpoint"}"#;
+                                trace_ws_message("AssemblyAI", CaptureDirection::Send, json);
+                                let _ = write.send(Message::Text(json.into())).await;
+                            }
+                            StreamingControl::Cancel => {
+                                let json = r#"{"type":"Terminate"}"#;
+                                trace_ws_message("AssemblyAI", CaptureDirection::Send, json);
+                                let _ = write.send(Message::Text(json.into())).await;
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(Message::Text(text)) => {
+                                capture_text("AssemblyAI", CaptureDirection::Recv, &text);
+                                trace_ws_message("AssemblyAI", CaptureDirection::Recv, &text);
+                                let Ok(message) = serde_json::from_str::<StreamingMessage>(&text) else {
+                                    continue;
+                                };
+
+                                if let Some(error) = message.error {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(error)).await;
+                                    break;
+                                }
+
+                                match message.message_type.as_str() {
+                                    "Turn" => {
+                                        // 一轮结束且已格式化：输出最终结果并关闭连接
+                                        if message.end_of_turn && message.turn_is_formatted {
+                                            let _ = event_tx.send(StreamingAsrEvent::Final {
+                                                text: message.transcript,
+                                            }).await;
+                                            let json = r#"{"type":"Terminate"}"#;
+                                            trace_ws_message("AssemblyAI", CaptureDirection::Send, json);
+                                            let _ = write.send(Message::Text(json.into())).await;
+                                            break;
+                                        }
+
+                                        turn_text = message.transcript.clone();
+                                        let _ = event_tx.send(StreamingAsrEvent::Partial {
+                                            text: String::new(),
+                                            stash: message.transcript,
+                                        }).await;
+                                    }
+                                    "Termination" => {
+                                        let _ = event_tx.send(StreamingAsrEvent::Final {
+                                            text: std::mem::take(&mut turn_text),
+                                        }).await;
+                                        break;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            Ok(Message::Close(_)) => {
+                                trace_ws_marker("AssemblyAI", "ws.closed");
+                                let _ = event_tx.send(StreamingAsrEvent::Final {
+                                    text: std::mem::take(&mut turn_text),
+                                }).await;
+                                break;
+                            }
+                            Err(e) => {
+                                trace_ws_marker("AssemblyAI", "ws.error");
+                                let _ = event_tx.send(StreamingAsrEvent::Error(
+                                    e.to_string()
+                                )).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    else => break,
+                }
+            }
+        });
+
+        Ok(StreamingSession::new(control_tx, event_rx))
+    }
+}
@@ -1,23 +1,202 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
 use async_trait::async_trait;
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use uuid::Uuid;
 
-use super::traits::{AsrError, AsrResult, AsrService};
+use super::streaming_session::StreamingSession;
+use super::traits::{
+    AsrError, AsrResult, AsrService, StreamingAsrEvent, StreamingAsrService, StreamingControl,
+};
+use crate::debug::{capture_audio, capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::net::{connect_async, proxied_client};
+use crate::audio::is_ogg;
+
+const WS_URL: &str = "wss://dashscope.aliyuncs.com/api-ws/v1/inference";
+/// 定制热词接口
+const CUSTOMIZATION_URL: &str =
+    "https://dashscope.aliyuncs.com/api/v1/services/audio/asr/customization";
+/// 本应用创建的热词表前缀，同步时先清理旧表，避免占满账号的热词表配额
+const VOCABULARY_PREFIX: &str = "vhisper";
+/// 热词权重（1-5）
+const VOCABULARY_WEIGHT: u8 = 4;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 /// DashScope ASR 服务 (WebSocket 实时语音识别)
 pub struct DashScopeAsr {
     api_key: String,
     model: String,
+    language_hints: Vec<String>,
+    /// 热词
+    vocabulary: Vec<String>,
+    /// 自动添加标点
+    punctuation: bool,
+    /// 逆文本标准化（中文数字转阿拉伯数字等）
+    inverse_text_normalization: bool,
 }
 
 impl DashScopeAsr {
-    pub fn new(api_key: String, model: String) -> Self {
-        Self { api_key, model }
+    pub fn new(api_key: String, model: String, language_hints: Vec<String>) -> Self {
+        Self {
+            api_key,
+            model,
+            language_hints,
+            vocabulary: Vec::new(),
+            punctuation: true,
+            inverse_text_normalization: true,
+        }
+    }
+
+    /// 设置热词
+    pub fn with_vocabulary(mut self, vocabulary: Vec<String>) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    /// 设置是否自动添加标点
+    pub fn with_punctuation(mut self, punctuation: bool) -> Self {
+        self.punctuation = punctuation;
+        self
+    }
+
+    /// 设置是否开启逆文本标准化
+    pub fn with_inverse_text_normalization(mut self, enabled: bool) -> Self {
+        self.inverse_text_normalization = enabled;
+        self
+    }
+
+    /// 建立 WebSocket 连接，发送 run-task 并等待 task-started
+    async fn start_task(
+        &self,
+        task_id: &str,
+        format: &str,
+        sample_rate: u32,
+    ) -> Result<(SplitSink<WsStream, Message>, SplitStream<WsStream>), AsrError> {
+        // 热词表同步失败不影响识别
+        let vocabulary_id = if self.vocabulary.is_empty() {
+            None
+        } else {
+            match vocabulary_id(&self.api_key, &self.model, &self.vocabulary).await {
+                Ok(id) => Some(id),
+                Err(e) => {
+                    tracing::warn!("Failed to sync DashScope vocabulary: {}", e);
+                    None
+                }
+            }
+        };
+
+        // 创建带认证头的请求
+        let request = http::Request::builder()
+            .uri(WS_URL)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
+            .header("Sec-WebSocket-Version", "13")
+            .header("Host", "dashscope.aliyuncs.com")
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .body(())
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        // 连接 WebSocket
+        let (ws_stream, _) = connect_async(request)
+            .await
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        trace_ws_marker("DashScope", "ws.connected");
+
+        let (mut write, mut read) = ws_stream.split();
+
+        // 发送 run-task 指令
+        let run_task = WsRequest {
+            header: WsHeader {
+                action: "run-task".to_string(),
+                task_id: task_id.to_string(),
+                streaming: "duplex".to_string(),
+            },
+            payload: WsPayload {
+                task_group: Some("audio".to_string()),
+                task: Some("asr".to_string()),
+                function: Some("recognition".to_string()),
+                model: Some(self.model.clone()),
+                parameters: Some(WsParameters {
+                    format: format.to_string(),
+                    sample_rate,
+                    language_hints: Some(self.language_hints.clone()),
+                    vocabulary_id,
+                    punctuation_prediction_enabled: self.punctuation,
+                    inverse_text_normalization_enabled: self.inverse_text_normalization,
+                }),
+                input: serde_json::json!({}),
+            },
+        };
+
+        let run_task_json = serde_json::to_string(&run_task)
+            .map_err(|e| AsrError::Encoding(e.to_string()))?;
+
+        capture_text("DashScope", CaptureDirection::Send, &run_task_json);
+        trace_ws_message("DashScope", CaptureDirection::Send, &run_task_json);
+        write
+            .send(Message::Text(run_task_json.into()))
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        // 等待 task-started 事件
+        while let Some(msg) = read.next().await {
+            match msg {
+                Ok(Message::Text(text)) => {
+                    capture_text("DashScope", CaptureDirection::Recv, &text);
+                    trace_ws_message("DashScope", CaptureDirection::Recv, &text);
+                    let response: WsResponse = serde_json::from_str(&text)
+                        .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+
+                    if let Some(message) = response.error() {
+                        return Err(AsrError::Api(message));
+                    }
+
+                    if response.header.event == "task-started" {
+                        return Ok((write, read));
+                    }
+                }
+                Ok(Message::Close(_)) => {
+                    return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
+                }
+                Err(e) => {
+                    return Err(AsrError::Network(e.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        Err(AsrError::Api("未收到 task-started 事件".to_string()))
     }
 }
 
+/// finish-task 指令：服务端识别完剩余音频后返回 task-finished
+fn finish_task_json(task_id: &str) -> String {
+    let finish_task = WsRequest {
+        header: WsHeader {
+            action: "finish-task".to_string(),
+            task_id: task_id.to_string(),
+            streaming: "duplex".to_string(),
+        },
+        payload: WsPayload {
+            task_group: None,
+            task: None,
+            function: None,
+            model: None,
+            parameters: None,
+            input: serde_json::json!({}),
+        },
+    };
+    serde_json::to_string(&finish_task).unwrap_or_default()
+}
+
 // WebSocket 请求结构
 #[derive(Serialize)]
 struct WsRequest {
@@ -53,6 +232,10 @@ struct WsParameters {
     sample_rate: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     language_hints: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vocabulary_id: Option<String>,
+    punctuation_prediction_enabled: bool,
+    inverse_text_normalization_enabled: bool,
 }
 
 // WebSocket 响应结构
@@ -73,6 +256,24 @@ struct WsResponseHeader {
     error_message: Option<String>,
 }
 
+impl WsResponse {
+    /// 服务端返回的错误（task-failed）
+    fn error(&self) -> Option<String> {
+        self.header.error_code.as_ref().map(|code| {
+            format!(
+                "{}: {}",
+                code,
+                self.header.error_message.as_deref().unwrap_or_default()
+            )
+        })
+    }
+
+    /// result-generated 中的句子
+    fn sentence(self) -> Option<WsSentence> {
+        self.payload?.output?.sentence
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct WsResponsePayload {
     output: Option<WsOutput>,
@@ -94,96 +295,13 @@ struct WsSentence {
 impl AsrService for DashScopeAsr {
     async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
         let task_id = Uuid::new_v4().to_string().replace("-", "");
-
-        // 构建 WebSocket URL 和请求
-        let url = "wss://dashscope.aliyuncs.com/api-ws/v1/inference";
-
-        // 创建带认证头的请求
-        let request = http::Request::builder()
-            .uri(url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
-            .header("Sec-WebSocket-Version", "13")
-            .header("Host", "dashscope.aliyuncs.com")
-            .header("Connection", "Upgrade")
-            .header("Upgrade", "websocket")
-            .body(())
-            .map_err(|e| AsrError::Network(e.to_string()))?;
-
-        // 连接 WebSocket
-        let (ws_stream, _) = connect_async(request)
-            .await
-            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
-
-        let (mut write, mut read) = ws_stream.split();
-
-        // 发送 run-task 指令
-        let run_task = WsRequest {
-            header: WsHeader {
-                action: "run-task".to_string(),
-                task_id: task_id.clone(),
-                streaming: "duplex".to_string(),
-            },
-            payload: WsPayload {
-                task_group: Some("audio".to_string()),
-                task: Some("asr".to_string()),
-                function: Some("recognition".to_string()),
-                model: Some(self.model.clone()),
-                parameters: Some(WsParameters {
-                    format: "pcm".to_string(),
-                    sample_rate,
-                    language_hints: Some(vec!["zh".to_string(), "en".to_string()]),
-                }),
-                input: serde_json::json!({}),
-            },
-        };
-
-        let run_task_json = serde_json::to_string(&run_task)
-            .map_err(|e| AsrError::Encoding(e.to_string()))?;
-
-        write
-            .send(Message::Text(run_task_json.into()))
-            .await
-            .map_err(|e| AsrError::Network(e.to_string()))?;
-
-        // 等待 task-started 事件
-        let mut task_started = false;
-        while let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    let response: WsResponse = serde_json::from_str(&text)
-                        .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
-
-                    if let Some(error_code) = &response.header.error_code {
-                        return Err(AsrError::Api(format!(
-                            "{}: {}",
-                            error_code,
-                            response.header.error_message.unwrap_or_default()
-                        )));
-                    }
-
-                    if response.header.event == "task-started" {
-                        task_started = true;
-                        break;
-                    }
-                }
-                Ok(Message::Close(_)) => {
-                    return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
-                }
-                Err(e) => {
-                    return Err(AsrError::Network(e.to_string()));
-                }
-                _ => {}
-            }
-        }
-
-        if !task_started {
-            return Err(AsrError::Api("未收到 task-started 事件".to_string()));
-        }
+        let format = if is_ogg(audio_data) { "opus" } else { "pcm" };
+        let (mut write, mut read) = self.start_task(&task_id, format, sample_rate).await?;
 
         // 分块发送音频数据（每块约 3200 字节，对应 100ms @ 16kHz 16bit）
         let chunk_size = (sample_rate as usize) * 2 / 10; // 100ms 的数据量
         for chunk in audio_data.chunks(chunk_size) {
+            capture_audio("DashScope", CaptureDirection::Send, chunk, sample_rate);
             write
                 .send(Message::Binary(chunk.to_vec().into()))
                 .await
@@ -191,25 +309,8 @@ impl AsrService for DashScopeAsr {
         }
 
         // 发送 finish-task 指令
-        let finish_task = WsRequest {
-            header: WsHeader {
-                action: "finish-task".to_string(),
-                task_id: task_id.clone(),
-                streaming: "duplex".to_string(),
-            },
-            payload: WsPayload {
-                task_group: None,
-                task: None,
-                function: None,
-                model: None,
-                parameters: None,
-                input: serde_json::json!({}),
-            },
-        };
-
-        let finish_task_json = serde_json::to_string(&finish_task)
-            .map_err(|e| AsrError::Encoding(e.to_string()))?;
-
+        let finish_task_json = finish_task_json(&task_id);
+        capture_text("DashScope", CaptureDirection::Send, &finish_task_json);
         write
             .send(Message::Text(finish_task_json.into()))
             .await
@@ -221,15 +322,12 @@ impl AsrService for DashScopeAsr {
         while let Some(msg) = read.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
+                    capture_text("DashScope", CaptureDirection::Recv, &text);
                     let response: WsResponse = serde_json::from_str(&text)
                         .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
 
-                    if let Some(error_code) = &response.header.error_code {
-                        return Err(AsrError::Api(format!(
-                            "{}: {}",
-                            error_code,
-                            response.header.error_message.unwrap_or_default()
-                        )));
+                    if let Some(message) = response.error() {
+                        return Err(AsrError::Api(message));
                     }
 
                     match response.header.event.as_str() {
@@ -270,28 +368,218 @@ impl AsrService for DashScopeAsr {
         Ok(AsrResult {
             text: final_text,
             is_final: true,
+            ..Default::default()
         })
     }
 }
 
-/// 测试 DashScope API 连接
-pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
-    use reqwest::Client;
+/// paraformer-realtime 流式识别：sentence_end 之前的结果作为暂定文本，句子结束后确认
+#[async_trait]
+impl StreamingAsrService for DashScopeAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<StreamingSession, AsrError> {
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        let task_id = Uuid::new_v4().simple().to_string();
+        let (mut write, mut read) = self.start_task(&task_id, "pcm", sample_rate).await?;
+
+        tokio::spawn(async move {
+            // 已结束的句子
+            let mut committed = String::new();
+            // 当前句的中间结果
+            let mut pending = String::new();
+
+            loop {
+                tokio::select! {
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                capture_audio("DashScope", CaptureDirection::Send, &data, sample_rate);
+                                if write.send(Message::Binary(data.into())).await.is_err() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(
+                                        "发送音频失败".to_string()
+                                    )).await;
+                                    break;
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                let finish_task_json = finish_task_json(&task_id);
+                                trace_ws_message("DashScope", CaptureDirection::Send, &finish_task_json);
+                                let _ = write.send(Message::Text(finish_task_json.into())).await;
+                            }
+                            StreamingControl::Cancel => {
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(Message::Text(text)) => {
+                                capture_text("DashScope", CaptureDirection::Recv, &text);
+                                trace_ws_message("DashScope", CaptureDirection::Recv, &text);
+                                let Ok(response) = serde_json::from_str::<WsResponse>(&text) else {
+                                    continue;
+                                };
+
+                                if let Some(message) = response.error() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(message)).await;
+                                    break;
+                                }
 
-    let client = Client::new();
+                                match response.header.event.as_str() {
+                                    "result-generated" => {
+                                        let Some(sentence) = response.sentence() else {
+                                            continue;
+                                        };
+                                        let text = sentence.text.unwrap_or_default();
+                                        if sentence.sentence_end {
+                                            committed.push_str(&text);
+                                            pending.clear();
+                                        } else {
+                                            pending = text;
+                                        }
+                                        let _ = event_tx.send(StreamingAsrEvent::Partial {
+                                            text: committed.clone(),
+                                            stash: pending.clone(),
+                                        }).await;
+                                    }
+                                    "task-finished" => {
+                                        committed.push_str(&pending);
+                                        let _ = event_tx.send(StreamingAsrEvent::Final { text: committed }).await;
+                                        let _ = write.close().await;
+                                        break;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            Ok(Message::Close(_)) => {
+                                trace_ws_marker("DashScope", "ws.closed");
+                                committed.push_str(&pending);
+                                let _ = event_tx.send(StreamingAsrEvent::Final { text: committed }).await;
+                                break;
+                            }
+                            Err(e) => {
+                                trace_ws_marker("DashScope", "ws.error");
+                                let _ = event_tx.send(StreamingAsrEvent::Error(e.to_string())).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    else => break,
+                }
+            }
+        });
+
+        Ok(StreamingSession::new(control_tx, event_rx))
+    }
+}
+
+// ============================================================================
+// 热词表
+// ============================================================================
+
+/// 已同步的热词表 ID，按 (API Key, 模型, 热词) 缓存，配置变化时重新同步
+fn vocabulary_cache() -> &'static Mutex<HashMap<String, String>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 获取热词对应的热词表 ID，首次使用时在服务端创建
+async fn vocabulary_id(api_key: &str, model: &str, vocabulary: &[String]) -> Result<String, AsrError> {
+    let key = format!("{}\n{}\n{}", api_key, model, vocabulary.join("\n"));
+    if let Some(id) = vocabulary_cache().lock().ok().and_then(|c| c.get(&key).cloned()) {
+        return Ok(id);
+    }
+
+    let client = proxied_client();
+    // 清理之前创建的热词表（热词或模型已变化）
+    let existing = customization(
+        &client,
+        api_key,
+        serde_json::json!({ "action": "list_vocabulary", "prefix": VOCABULARY_PREFIX }),
+    )
+    .await?;
+    let ids = existing["vocabulary_list"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v["vocabulary_id"].as_str());
+    for id in ids {
+        customization(
+            &client,
+            api_key,
+            serde_json::json!({ "action": "delete_vocabulary", "vocabulary_id": id }),
+        )
+        .await?;
+    }
+
+    let words: Vec<serde_json::Value> = vocabulary
+        .iter()
+        .map(|text| {
+            let lang = if text.is_ascii() { "en" } else { "zh" };
+            serde_json::json!({ "text": text, "weight": VOCABULARY_WEIGHT, "lang": lang })
+        })
+        .collect();
+    let created = customization(
+        &client,
+        api_key,
+        serde_json::json!({
+            "action": "create_vocabulary",
+            "target_model": model,
+            "prefix": VOCABULARY_PREFIX,
+            "vocabulary": words,
+        }),
+    )
+    .await?;
+    let id = created["vocabulary_id"]
+        .as_str()
+        .ok_or_else(|| AsrError::Api("创建热词表未返回 vocabulary_id".to_string()))?
+        .to_string();
+    tracing::info!("DashScope vocabulary created: {}", id);
+
+    if let Ok(mut cache) = vocabulary_cache().lock() {
+        // 服务端只保留最新的热词表
+        cache.clear();
+        cache.insert(key, id.clone());
+    }
+    Ok(id)
+}
+
+/// 调用定制热词接口，返回 output
+async fn customization(
+    client: &reqwest::Client,
+    api_key: &str,
+    input: serde_json::Value,
+) -> Result<serde_json::Value, AsrError> {
+    let body = serde_json::json!({ "model": "speech-biasing", "input": input }).to_string();
+    capture_text("DashScope", CaptureDirection::Send, &body);
     let response = client
-        .get("https://dashscope.aliyuncs.com/api/v1/models")
+        .post(CUSTOMIZATION_URL)
         .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .body(body)
         .send()
         .await
         .map_err(|e| AsrError::Network(e.to_string()))?;
 
-    if response.status().is_success() {
-        Ok("API Key 验证成功".to_string())
-    } else {
-        Err(AsrError::Api(format!(
-            "API Key 无效: HTTP {}",
-            response.status()
-        )))
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))?;
+    capture_text("DashScope", CaptureDirection::Recv, &body);
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(&body).map_err(|_| AsrError::Api(format!("HTTP {}: {}", status, body)))?;
+    if !status.is_success() {
+        return Err(AsrError::Api(
+            value["message"].as_str().unwrap_or(&body).to_string(),
+        ));
     }
+    Ok(value["output"].take())
 }
@@ -0,0 +1,443 @@
+//! Deepgram ASR 服务
+//!
+//! - 批量识别：预录音频接口 (POST /v1/listen)
+//! - 流式识别：WebSocket 实时接口 (wss /v1/listen)，边说边识别
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::streaming_session::StreamingSession;
+use super::traits::{
+    AsrError, AsrResult, AsrSegment, AsrService, StreamingAsrEvent, StreamingAsrService,
+    StreamingControl,
+};
+use crate::audio::is_ogg;
+use crate::config::settings::AUTO_LANGUAGE;
+use crate::debug::{capture_audio, capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::net::{connect_async, proxied_client};
+
+const API_HOST: &str = "api.deepgram.com";
+/// WebSocket 连接超时时间
+const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Deepgram ASR 服务
+pub struct DeepgramAsr {
+    api_key: String,
+    model: String,
+    /// 识别语言，"auto" 表示自动检测
+    language: String,
+    /// 是否开启说话人分离（仅预录音频接口）
+    diarization: bool,
+    /// 热词
+    vocabulary: Vec<String>,
+    /// 自动添加标点
+    punctuation: bool,
+    /// 智能格式化（数字、日期、货币等转为书面形式）
+    smart_format: bool,
+    client: Client,
+}
+
+impl DeepgramAsr {
+    pub fn new(api_key: String, model: String, language: String) -> Self {
+        Self {
+            api_key,
+            model,
+            language,
+            diarization: false,
+            vocabulary: Vec::new(),
+            punctuation: true,
+            smart_format: true,
+            client: proxied_client(),
+        }
+    }
+
+    /// 使用指定的 HTTP 客户端（如设置了连接超时的客户端）
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 设置热词
+    pub fn with_vocabulary(mut self, vocabulary: Vec<String>) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    /// 设置是否开启说话人分离
+    pub fn with_diarization(mut self, diarization: bool) -> Self {
+        self.diarization = diarization;
+        self
+    }
+
+    /// 设置是否自动添加标点
+    pub fn with_punctuation(mut self, punctuation: bool) -> Self {
+        self.punctuation = punctuation;
+        self
+    }
+
+    /// 设置是否开启逆文本标准化（Deepgram 的 smart_format）
+    pub fn with_inverse_text_normalization(mut self, enabled: bool) -> Self {
+        self.smart_format = enabled;
+        self
+    }
+
+    /// 两种接口共用的查询参数（不含语言）
+    fn base_query(&self) -> String {
+        let mut query = format!(
+            "model={}&punctuate={}&smart_format={}",
+            self.model, self.punctuation, self.smart_format
+        );
+        // Nova-3 使用 keyterm，更早的模型使用带权重的 keywords
+        for term in &self.vocabulary {
+            if self.model.starts_with("nova-3") {
+                query.push_str(&format!("&keyterm={}", encode_component(term)));
+            } else {
+                query.push_str(&format!("&keywords={}:2", encode_component(term)));
+            }
+        }
+        query
+    }
+
+    /// 预录音频接口的语言参数，自动检测时返回检测结果
+    fn prerecorded_language_query(&self) -> String {
+        if self.language == AUTO_LANGUAGE {
+            "detect_language=true".to_string()
+        } else {
+            format!("language={}", self.language)
+        }
+    }
+
+    /// 实时接口的语言参数，实时接口不支持 detect_language，自动检测时使用多语言模式
+    fn live_language_query(&self) -> String {
+        if self.language == AUTO_LANGUAGE {
+            "language=multi".to_string()
+        } else {
+            format!("language={}", self.language)
+        }
+    }
+}
+
+// ============================================================================
+// 预录音频接口
+// ============================================================================
+
+#[derive(Deserialize)]
+struct PrerecordedResponse {
+    results: PrerecordedResults,
+}
+
+#[derive(Deserialize)]
+struct PrerecordedResults {
+    channels: Vec<Channel>,
+    #[serde(default)]
+    utterances: Vec<Utterance>,
+}
+
+#[derive(Deserialize)]
+struct Channel {
+    alternatives: Vec<Alternative>,
+    /// 开启 detect_language 时检测到的语言
+    #[serde(default)]
+    detected_language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Alternative {
+    transcript: String,
+}
+
+#[derive(Deserialize)]
+struct Utterance {
+    transcript: String,
+    start: f64,
+    end: f64,
+    /// 开启 diarize 时的说话人编号（从 0 开始）
+    #[serde(default)]
+    speaker: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct DeepgramError {
+    #[serde(alias = "err_msg")]
+    message: Option<String>,
+}
+
+/// 查询参数值的百分号编码
+fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn first_transcript(channels: &[Channel]) -> String {
+    channels
+        .first()
+        .and_then(|c| c.alternatives.first())
+        .map(|a| a.transcript.clone())
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl AsrService for DeepgramAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        // Ogg/Opus 带容器，Deepgram 自动识别；PCM 需要声明编码和采样率
+        let mut query = format!("{}&{}", self.base_query(), self.prerecorded_language_query());
+        if self.diarization {
+            query.push_str("&diarize=true");
+        }
+        let (url, content_type) = if is_ogg(audio_data) {
+            (
+                format!("https://{}/v1/listen?{}&utterances=true", API_HOST, query),
+                "audio/ogg",
+            )
+        } else {
+            (
+                format!(
+                    "https://{}/v1/listen?{}&utterances=true&encoding=linear16&sample_rate={}&channels=1",
+                    API_HOST,
+                    query,
+                    sample_rate
+                ),
+                "application/octet-stream",
+            )
+        };
+
+        capture_audio("Deepgram", CaptureDirection::Send, audio_data, sample_rate);
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Token {}", self.api_key))
+            .header("Content-Type", content_type)
+            .body(audio_data.to_vec())
+            .send()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+        capture_text("Deepgram", CaptureDirection::Recv, &body);
+
+        if !status.is_success() {
+            if let Some(message) = serde_json::from_str::<DeepgramError>(&body)
+                .ok()
+                .and_then(|e| e.message)
+            {
+                return Err(AsrError::Api(message));
+            }
+            return Err(AsrError::Api(format!("HTTP {}: {}", status, body)));
+        }
+
+        let response: PrerecordedResponse =
+            serde_json::from_str(&body).map_err(|e| AsrError::Api(e.to_string()))?;
+
+        let segments = response
+            .results
+            .utterances
+            .iter()
+            .map(|u| AsrSegment {
+                text: u.transcript.clone(),
+                start_ms: (u.start * 1000.0) as u64,
+                end_ms: (u.end * 1000.0) as u64,
+                speaker: u.speaker.map(|s| s + 1),
+            })
+            .collect();
+
+        let language = response
+            .results
+            .channels
+            .first()
+            .and_then(|c| c.detected_language.clone());
+
+        Ok(AsrResult {
+            text: first_transcript(&response.results.channels),
+            is_final: true,
+            segments,
+            language,
+        })
+    }
+}
+
+// ============================================================================
+// 实时接口
+// ============================================================================
+
+#[derive(Deserialize)]
+struct LiveMessage {
+    #[serde(rename = "type")]
+    message_type: String,
+    channel: Option<Channel>,
+    #[serde(default)]
+    is_final: bool,
+    #[serde(default)]
+    speech_final: bool,
+    #[serde(default)]
+    from_finalize: bool,
+    description: Option<String>,
+}
+
+#[async_trait]
+impl StreamingAsrService for DeepgramAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<StreamingSession, AsrError> {
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        let url = format!(
+            "wss://{}/v1/listen?{}&{}&encoding=linear16&sample_rate={}&channels=1&interim_results=true&endpointing=500",
+            API_HOST,
+            self.base_query(),
+            self.live_language_query(),
+            sample_rate
+        );
+
+        let request = http::Request::builder()
+            .uri(&url)
+            .header("Authorization", format!("Token {}", self.api_key))
+            .header(
+                "Sec-WebSocket-Key",
+                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+            )
+            .header("Sec-WebSocket-Version", "13")
+            .header("Host", API_HOST)
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .body(())
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let (ws_stream, _) = timeout(WS_CONNECT_TIMEOUT, connect_async(request))
+            .await
+            .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))?
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        trace_ws_marker("Deepgram", "ws.connected");
+
+        let (mut write, mut read) = ws_stream.split();
+
+        tokio::spawn(async move {
+            // 本轮已确认的文本（is_final 结果拼接）
+            let mut confirmed = String::new();
+
+            loop {
+                tokio::select! {
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                capture_audio("Deepgram", CaptureDirection::Send, &data, sample_rate);
+                                if write.send(Message::Binary(data.into())).await.is_err() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(
+                                        "发送音频失败".to_string()
+                                    )).await;
+                                    break;
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                // 要求服务端立即输出缓冲区中的结果
+                                let json = r#"{"type":"Finalize"}"#;
+                                trace_ws_message("Deepgram", CaptureDirection::Send, json);
+                                let _ = write.send(Message::Text(json.into())).await;
+                            }
+                            StreamingControl::Cancel => {
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(Message::Text(text)) => {
+                                capture_text("Deepgram", CaptureDirection::Recv, &text);
+                                trace_ws_message("Deepgram", CaptureDirection::Recv, &text);
+                                let Ok(message) = serde_json::from_str::<LiveMessage>(&text) else {
+                                    continue;
+                                };
+
+                                match message.message_type.as_str() {
+                                    "Results" => {
+                                        let transcript = message
+                                            .channel
+                                            .map(|c| first_transcript(std::slice::from_ref(&c)))
+                                            .unwrap_or_default();
+
+                                        if !message.is_final {
+                                            let _ = event_tx.send(StreamingAsrEvent::Partial {
+                                                text: confirmed.clone(),
+                                                stash: transcript,
+                                            }).await;
+                                            continue;
+                                        }
+
+                                        if !transcript.is_empty() {
+                                            if !confirmed.is_empty() {
+                                                confirmed.push(' ');
+                                            }
+                                            confirmed.push_str(&transcript);
+                                        }
+
+                                        // 一句话结束（端点检测或主动 Finalize）：输出最终结果并关闭连接，
+                                        // 按住快捷键时 pipeline 会为下一句重新连接
+                                        if message.speech_final || message.from_finalize {
+                                            let _ = event_tx.send(StreamingAsrEvent::Final {
+                                                text: std::mem::take(&mut confirmed),
+                                            }).await;
+                                            let json = r#"{"type":"CloseStream"}"#;
+                                            trace_ws_message("Deepgram", CaptureDirection::Send, json);
+                                            let _ = write.send(Message::Text(json.into())).await;
+                                            break;
+                                        }
+
+                                        let _ = event_tx.send(StreamingAsrEvent::Partial {
+                                            text: confirmed.clone(),
+                                            stash: String::new(),
+                                        }).await;
+                                    }
+                                    "Error" => {
+                                        let _ = event_tx.send(StreamingAsrEvent::Error(
+                                            message.description.unwrap_or_else(|| "Deepgram 错误".to_string())
+                                        )).await;
+                                        break;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            Ok(Message::Close(_)) => {
+                                trace_ws_marker("Deepgram", "ws.closed");
+                                // 关闭前未输出的已确认文本作为最终结果
+                                let _ = event_tx.send(StreamingAsrEvent::Final {
+                                    text: std::mem::take(&mut confirmed),
+                                }).await;
+                                break;
+                            }
+                            Err(e) => {
+                                trace_ws_marker("Deepgram", "ws.error");
+                                let _ = event_tx.send(StreamingAsrEvent::Error(
+                                    e.to_string()
+                                )).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    else => break,
+                }
+            }
+        });
+
+        Ok(StreamingSession::new(control_tx, event_rx))
+    }
+}
@@ -0,0 +1,61 @@
+//! ASR 备用服务商链
+//!
+//! 按顺序尝试主服务商和备用服务商，网络或 API 错误时换下一个，
+//! 单个服务商故障不会导致听写失败
+
+use async_trait::async_trait;
+
+use super::requires_wav;
+use super::traits::{AsrError, AsrResult, AsrService};
+use crate::audio::wav_payload;
+
+/// 按顺序尝试的 ASR 服务
+pub(crate) struct FallbackAsr {
+    services: Vec<(String, Box<dyn AsrService>)>,
+}
+
+impl FallbackAsr {
+    pub(crate) fn new(services: Vec<(String, Box<dyn AsrService>)>) -> Self {
+        Self { services }
+    }
+}
+
+/// 换个服务商可能成功的错误
+fn should_fall_back(error: &AsrError) -> bool {
+    matches!(error, AsrError::Network(_) | AsrError::Api(_))
+}
+
+/// 按服务商调整音频格式：音频按链中要求最严格的服务商编码，
+/// 不接受 WAV 的服务商去掉文件头只发送 PCM
+fn audio_for<'a>(provider: &str, audio_data: &'a [u8]) -> &'a [u8] {
+    if requires_wav(provider) {
+        return audio_data;
+    }
+    wav_payload(audio_data).unwrap_or(audio_data)
+}
+
+#[async_trait]
+impl AsrService for FallbackAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        let mut last_error = AsrError::Config("没有可用的 ASR 服务商".to_string());
+        for (index, (provider, service)) in self.services.iter().enumerate() {
+            match service
+                .recognize(audio_for(provider, audio_data), sample_rate)
+                .await
+            {
+                Ok(result) => {
+                    if index > 0 {
+                        tracing::info!("ASR fell back to {}", provider);
+                    }
+                    return Ok(result);
+                }
+                Err(e) if should_fall_back(&e) => {
+                    tracing::warn!("ASR provider {} failed: {}", provider, e);
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+}
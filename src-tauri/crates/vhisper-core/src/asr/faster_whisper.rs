@@ -0,0 +1,168 @@
+//! faster-whisper-server ASR 服务
+//!
+//! 兼容 OpenAI 的转写接口 (POST /v1/audio/transcriptions)，
+//! 额外支持 faster-whisper 的 vad_filter、beam_size 等参数，适合自建服务调优识别效果。
+
+use async_trait::async_trait;
+use reqwest::{multipart, Client};
+use serde::Deserialize;
+
+use super::traits::{AsrError, AsrResult, AsrSegment, AsrService};
+use crate::audio::is_ogg;
+use crate::config::settings::FasterWhisperConfig;
+use crate::debug::{capture_audio, capture_text, CaptureDirection};
+use crate::net::proxied_client;
+
+/// faster-whisper-server ASR 服务
+pub struct FasterWhisperAsr {
+    config: FasterWhisperConfig,
+    language: Option<String>,
+    /// 识别提示（最近的识别结果），接在配置的 initial_prompt 之后
+    context: Option<String>,
+    client: Client,
+}
+
+impl FasterWhisperAsr {
+    pub fn new(config: FasterWhisperConfig, language: Option<String>) -> Self {
+        Self {
+            config,
+            language,
+            context: None,
+            client: proxied_client(),
+        }
+    }
+
+    /// 使用指定的 HTTP 客户端（如设置了连接超时的客户端）
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 设置识别提示
+    pub fn with_context(mut self, context: Option<String>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// 配置的提示词和最近的识别结果合并为 initial_prompt
+    fn prompt(&self) -> Option<String> {
+        let parts: Vec<&str> = [
+            self.config.initial_prompt.as_deref(),
+            self.context.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+#[derive(Deserialize)]
+struct VerboseResponse {
+    text: String,
+    #[serde(default)]
+    segments: Vec<Segment>,
+    /// 识别语言（未指定语言时为检测结果）
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Segment {
+    text: String,
+    start: f64,
+    end: f64,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    detail: serde_json::Value,
+}
+
+#[async_trait]
+impl AsrService for FasterWhisperAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        let (file_name, mime) = if is_ogg(audio_data) {
+            ("audio.ogg", "audio/ogg")
+        } else {
+            ("audio.wav", "audio/wav")
+        };
+        let file_part = multipart::Part::bytes(audio_data.to_vec())
+            .file_name(file_name)
+            .mime_str(mime)
+            .map_err(|e| AsrError::Encoding(e.to_string()))?;
+
+        let mut form = multipart::Form::new()
+            .part("file", file_part)
+            .text("model", self.config.model.clone())
+            .text("response_format", "verbose_json")
+            .text("vad_filter", self.config.vad_filter.to_string());
+        if let Some(language) = &self.language {
+            form = form.text("language", language.clone());
+        }
+        if let Some(beam_size) = self.config.beam_size {
+            form = form.text("beam_size", beam_size.to_string());
+        }
+        if let Some(prompt) = self.prompt() {
+            form = form.text("prompt", prompt);
+        }
+
+        capture_audio(
+            "FasterWhisper",
+            CaptureDirection::Send,
+            audio_data,
+            sample_rate,
+        );
+        let mut request = self
+            .client
+            .post(format!(
+                "{}/v1/audio/transcriptions",
+                self.config.endpoint.trim_end_matches('/')
+            ))
+            .multipart(form);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+        capture_text("FasterWhisper", CaptureDirection::Recv, &body);
+
+        if !status.is_success() {
+            if let Ok(error) = serde_json::from_str::<ErrorResponse>(&body) {
+                return Err(AsrError::Api(error.detail.to_string()));
+            }
+            return Err(AsrError::Api(format!("HTTP {}: {}", status, body)));
+        }
+
+        let result: VerboseResponse =
+            serde_json::from_str(&body).map_err(|e| AsrError::Api(e.to_string()))?;
+
+        let segments = result
+            .segments
+            .into_iter()
+            .map(|s| AsrSegment {
+                text: s.text.trim().to_string(),
+                start_ms: (s.start * 1000.0) as u64,
+                end_ms: (s.end * 1000.0) as u64,
+                speaker: None,
+            })
+            .collect();
+
+        Ok(AsrResult {
+            text: result.text.trim().to_string(),
+            is_final: true,
+            segments,
+            language: result.language,
+        })
+    }
+}
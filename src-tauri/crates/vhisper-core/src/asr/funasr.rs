@@ -1,41 +1,63 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
 use tokio_tungstenite::tungstenite::Message;
 
-use super::traits::{AsrError, AsrResult, AsrService};
+use super::streaming_session::StreamingSession;
+use super::traits::{
+    AsrError, AsrResult, AsrSegment, AsrService, StreamingAsrEvent, StreamingAsrService,
+    StreamingControl,
+};
+use crate::debug::{capture_audio, capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::config::settings::TlsOptions;
+use crate::net::connect_websocket;
+use crate::text::stages::normalize;
+
+/// 提交后等待最终结果的时间，服务端没有剩余音频时可能不再返回结果
+const FINAL_TIMEOUT: Duration = Duration::from_secs(3);
 
 /// FunASR 本地服务 (WebSocket 实时语音识别)
 pub struct FunAsr {
     endpoint: String,
+    tls: TlsOptions,
+    /// 逆文本标准化（中文数字转阿拉伯数字等）
+    itn: bool,
 }
 
 impl FunAsr {
-    pub fn new(endpoint: String) -> Self {
-        // 将 HTTP 端点转换为 WebSocket Secure 端点 (FunASR 默认启用 SSL)
-        let ws_endpoint = endpoint
-            .replace("http://", "wss://")
-            .replace("https://", "wss://")
-            .replace("ws://", "wss://");
+    pub fn new(endpoint: String, tls: TlsOptions) -> Self {
         Self {
-            endpoint: ws_endpoint,
+            endpoint: to_ws_endpoint(&endpoint),
+            tls,
+            itn: true,
         }
     }
+
+    /// 设置是否开启逆文本标准化
+    pub fn with_inverse_text_normalization(mut self, enabled: bool) -> Self {
+        self.itn = enabled;
+        self
+    }
 }
 
-/// 创建接受自签名证书的 TLS 连接器
-fn create_tls_connector() -> Result<tokio_tungstenite::Connector, AsrError> {
-    let tls_connector = native_tls::TlsConnector::builder()
-        .danger_accept_invalid_certs(true)
-        .danger_accept_invalid_hostnames(true)
-        .build()
-        .map_err(|e| AsrError::Network(format!("TLS 配置失败: {}", e)))?;
-    Ok(tokio_tungstenite::Connector::NativeTls(tls_connector))
+/// 将 HTTP 端点转换为 WebSocket Secure 端点 (FunASR 默认启用 SSL)
+fn to_ws_endpoint(endpoint: &str) -> String {
+    endpoint
+        .replace("http://", "wss://")
+        .replace("https://", "wss://")
+        .replace("ws://", "wss://")
 }
 
 // FunASR WebSocket 请求结构
 #[derive(Serialize)]
 struct FunAsrStartMessage {
+    /// offline / online / 2pass，不填时使用服务端默认模式
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<&'static str>,
     chunk_size: Vec<i32>,
     chunk_interval: i32,
     wav_name: String,
@@ -56,41 +78,85 @@ struct FunAsrResponse {
     text: Option<String>,
     #[serde(default)]
     is_final: bool,
+    /// offline / online / 2pass-online / 2pass-offline
     mode: Option<String>,
+    /// 逐字时间戳，JSON 字符串形式的 [[start, end], ...]（毫秒）
+    timestamp: Option<String>,
+    /// 带标点和时间戳的分句
+    stamp_sents: Option<Vec<StampSentence>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct StampSentence {
+    #[serde(default)]
+    text_seg: String,
+    #[serde(default)]
+    punc: String,
+    start: i64,
+    end: i64,
+}
+
+impl FunAsrResponse {
+    /// 解析分句时间戳
+    ///
+    /// 优先使用 stamp_sents；没有时退化为覆盖整段文本的一个片段
+    fn segments(&self) -> Vec<AsrSegment> {
+        if let Some(sentences) = &self.stamp_sents {
+            return sentences
+                .iter()
+                .filter(|s| !s.text_seg.trim().is_empty() || !s.punc.is_empty())
+                .map(|s| AsrSegment {
+                    // text_seg 按字/词以空格分隔
+                    text: format!("{}{}", normalize(&s.text_seg), s.punc),
+                    start_ms: s.start.max(0) as u64,
+                    end_ms: s.end.max(0) as u64,
+                    speaker: None,
+                })
+                .collect();
+        }
+
+        let text = self.text.as_deref().unwrap_or_default();
+        let stamps: Vec<[i64; 2]> = self
+            .timestamp
+            .as_deref()
+            .and_then(|t| serde_json::from_str(t).ok())
+            .unwrap_or_default();
+        match (stamps.first(), stamps.last()) {
+            (Some(first), Some(last)) if !text.is_empty() => vec![AsrSegment {
+                text: text.to_string(),
+                start_ms: first[0].max(0) as u64,
+                end_ms: last[1].max(0) as u64,
+                speaker: None,
+            }],
+            _ => Vec::new(),
+        }
+    }
 }
 
 #[async_trait]
 impl AsrService for FunAsr {
     async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
-        // 创建 TLS 连接器（接受自签名证书）
-        let connector = create_tls_connector()?;
-
         // 连接 WebSocket (使用 wss://)
-        let (ws_stream, _) = tokio_tungstenite::connect_async_tls_with_config(
-            &self.endpoint,
-            None,
-            false,
-            Some(connector),
-        )
-        .await
-        .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        let ws_stream = connect_websocket(&self.endpoint, &self.tls).await?;
 
         let (mut write, mut read) = ws_stream.split();
 
         // 发送开始消息
         let start_msg = FunAsrStartMessage {
+            mode: None,
             chunk_size: vec![5, 10, 5],
             chunk_interval: 10,
             wav_name: "audio".to_string(),
             wav_format: "pcm".to_string(),
             audio_fs: sample_rate,
-            itn: true,
+            itn: self.itn,
             is_speaking: true,
         };
 
         let start_json = serde_json::to_string(&start_msg)
             .map_err(|e| AsrError::Encoding(e.to_string()))?;
 
+        capture_text("FunAsr", CaptureDirection::Send, &start_json);
         write
             .send(Message::Text(start_json.into()))
             .await
@@ -99,6 +165,7 @@ impl AsrService for FunAsr {
         // 分块发送音频数据（每块约 6400 字节，对应 200ms @ 16kHz 16bit）
         let chunk_size = (sample_rate as usize) * 2 / 5; // 200ms 的数据量
         for chunk in audio_data.chunks(chunk_size) {
+            capture_audio("FunAsr", CaptureDirection::Send, chunk, sample_rate);
             write
                 .send(Message::Binary(chunk.to_vec().into()))
                 .await
@@ -110,24 +177,42 @@ impl AsrService for FunAsr {
         let end_json = serde_json::to_string(&end_msg)
             .map_err(|e| AsrError::Encoding(e.to_string()))?;
 
+        capture_text("FunAsr", CaptureDirection::Send, &end_json);
         write
             .send(Message::Text(end_json.into()))
             .await
             .map_err(|e| AsrError::Network(e.to_string()))?;
 
         // 收集识别结果
-        let mut final_text = String::new();
+        // - 2pass-offline: 一句话的最终结果（带标点、ITN），逐句追加
+        // - online / 2pass-online: 增量的临时结果，会被后续 2pass-offline 替换
+        // - offline: 整段的完整结果
+        let mut committed_text = String::new();
+        let mut pending_text = String::new();
+        let mut segments = Vec::new();
 
         while let Some(msg) = read.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
+                    capture_text("FunAsr", CaptureDirection::Recv, &text);
                     if let Ok(response) = serde_json::from_str::<FunAsrResponse>(&text) {
-                        if let Some(result_text) = response.text {
-                            // FunASR 返回的是累积结果，取最后一个
-                            final_text = result_text;
+                        let mode = response.mode.as_deref().unwrap_or("offline");
+                        let result_text = response.text.clone().unwrap_or_default();
+                        match mode {
+                            "online" | "2pass-online" => pending_text.push_str(&result_text),
+                            "2pass-offline" => {
+                                committed_text.push_str(&result_text);
+                                pending_text.clear();
+                                segments.extend(response.segments());
+                            }
+                            _ => {
+                                committed_text = result_text;
+                                pending_text.clear();
+                                segments = response.segments();
+                            }
                         }
                         // 如果是最终结果或者模式是 offline，则结束
-                        if response.is_final || response.mode.as_deref() == Some("offline") {
+                        if response.is_final || mode == "offline" {
                             break;
                         }
                     }
@@ -137,7 +222,7 @@ impl AsrService for FunAsr {
                 }
                 Err(e) => {
                     // 如果已经有结果，忽略关闭错误
-                    if final_text.is_empty() {
+                    if committed_text.is_empty() && pending_text.is_empty() {
                         return Err(AsrError::Network(e.to_string()));
                     }
                     break;
@@ -146,39 +231,139 @@ impl AsrService for FunAsr {
             }
         }
 
+        // 未收到最终结果的部分使用临时结果兜底
+        committed_text.push_str(&pending_text);
+
         Ok(AsrResult {
-            text: final_text,
+            text: committed_text,
             is_final: true,
+            segments,
+            ..Default::default()
         })
     }
 }
 
-/// 测试 FunASR 服务连接
-pub async fn test_api(endpoint: &str) -> Result<String, AsrError> {
-    // 将 HTTP 端点转换为 WebSocket Secure 端点
-    let ws_endpoint = endpoint
-        .replace("http://", "wss://")
-        .replace("https://", "wss://")
-        .replace("ws://", "wss://");
-
-    // 创建 TLS 连接器（接受自签名证书）
-    let connector = create_tls_connector()?;
-
-    // 尝试建立 WebSocket 连接
-    let result = tokio::time::timeout(
-        std::time::Duration::from_secs(5),
-        tokio_tungstenite::connect_async_tls_with_config(
-            &ws_endpoint,
-            None,
-            false,
-            Some(connector),
-        ),
-    )
-    .await;
-
-    match result {
-        Ok(Ok(_)) => Ok("FunASR 服务连接成功".to_string()),
-        Ok(Err(e)) => Err(AsrError::Network(format!("WebSocket 连接失败: {}", e))),
-        Err(_) => Err(AsrError::Network("连接超时".to_string())),
+/// 2pass 实时识别：online 结果作为暂定文本，每句结束后由 2pass-offline 的带标点结果替换
+#[async_trait]
+impl StreamingAsrService for FunAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<StreamingSession, AsrError> {
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        let ws_stream = connect_websocket(&self.endpoint, &self.tls).await?;
+        trace_ws_marker("FunAsr", "ws.connected");
+        let (mut write, mut read) = ws_stream.split();
+
+        let start_msg = FunAsrStartMessage {
+            mode: Some("2pass"),
+            chunk_size: vec![5, 10, 5],
+            chunk_interval: 10,
+            wav_name: "stream".to_string(),
+            wav_format: "pcm".to_string(),
+            audio_fs: sample_rate,
+            itn: self.itn,
+            is_speaking: true,
+        };
+        let start_json = serde_json::to_string(&start_msg)
+            .map_err(|e| AsrError::Encoding(e.to_string()))?;
+        trace_ws_message("FunAsr", CaptureDirection::Send, &start_json);
+        write
+            .send(Message::Text(start_json.into()))
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        tokio::spawn(async move {
+            // 已完成二遍识别的句子
+            let mut committed = String::new();
+            // 当前句的实时结果
+            let mut pending = String::new();
+            // 提交后等待最终结果的截止时间
+            let mut deadline: Option<Instant> = None;
+
+            loop {
+                tokio::select! {
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                capture_audio("FunAsr", CaptureDirection::Send, &data, sample_rate);
+                                if write.send(Message::Binary(data.into())).await.is_err() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(
+                                        "发送音频失败".to_string()
+                                    )).await;
+                                    break;
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                let end_json = serde_json::to_string(&FunAsrEndMessage { is_speaking: false })
+                                    .unwrap_or_default();
+                                trace_ws_message("FunAsr", CaptureDirection::Send, &end_json);
+                                let _ = write.send(Message::Text(end_json.into())).await;
+                                deadline = Some(Instant::now() + FINAL_TIMEOUT);
+                            }
+                            StreamingControl::Cancel => {
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(Message::Text(text)) => {
+                                capture_text("FunAsr", CaptureDirection::Recv, &text);
+                                trace_ws_message("FunAsr", CaptureDirection::Recv, &text);
+                                let Ok(response) = serde_json::from_str::<FunAsrResponse>(&text) else {
+                                    continue;
+                                };
+                                let result_text = response.text.unwrap_or_default();
+                                match response.mode.as_deref() {
+                                    Some("online" | "2pass-online") => pending.push_str(&result_text),
+                                    _ => {
+                                        committed.push_str(&result_text);
+                                        pending.clear();
+                                    }
+                                }
+
+                                if response.is_final {
+                                    committed.push_str(&pending);
+                                    let _ = event_tx.send(StreamingAsrEvent::Final { text: committed }).await;
+                                    let _ = write.close().await;
+                                    break;
+                                }
+                                let _ = event_tx.send(StreamingAsrEvent::Partial {
+                                    text: committed.clone(),
+                                    stash: pending.clone(),
+                                }).await;
+                            }
+                            Ok(Message::Close(_)) => {
+                                trace_ws_marker("FunAsr", "ws.closed");
+                                committed.push_str(&pending);
+                                let _ = event_tx.send(StreamingAsrEvent::Final { text: committed }).await;
+                                break;
+                            }
+                            Err(e) => {
+                                trace_ws_marker("FunAsr", "ws.error");
+                                let _ = event_tx.send(StreamingAsrEvent::Error(e.to_string())).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                        // 提交后没有剩余结果，使用已有文本结束会话
+                        trace_ws_marker("FunAsr", "final.timeout");
+                        committed.push_str(&pending);
+                        let _ = event_tx.send(StreamingAsrEvent::Final { text: committed }).await;
+                        let _ = write.close().await;
+                        break;
+                    }
+                    else => break,
+                }
+            }
+        });
+
+        Ok(StreamingSession::new(control_tx, event_rx))
     }
 }
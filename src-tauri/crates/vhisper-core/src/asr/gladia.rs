@@ -0,0 +1,467 @@
+//! Gladia ASR 服务
+//!
+//! - 批量识别：上传音频 → 创建转写任务 → 轮询结果
+//! - 流式识别：先创建实时会话获取 WebSocket 地址，再推送音频
+//!
+//! 实时会话可指定服务区域（如 eu-west），音频只在该区域处理。
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::streaming_session::StreamingSession;
+use super::traits::{
+    AsrError, AsrResult, AsrSegment, AsrService, StreamingAsrEvent, StreamingAsrService,
+    StreamingControl,
+};
+use crate::audio::record_upload;
+use crate::debug::{capture_audio, capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::net::{connect_async, proxied_client};
+
+const API_BASE: &str = "https://api.gladia.io/v2";
+/// 轮询转写结果的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+/// 等待转写完成的最长时间
+const POLL_TIMEOUT: Duration = Duration::from_secs(60);
+/// WebSocket 连接超时时间
+const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 停止录音后等待最终结果的时间，超时后以已有文本结束会话
+const FINAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Gladia ASR 服务
+pub struct GladiaAsr {
+    api_key: String,
+    /// 识别语言，None 表示自动检测
+    language: Option<String>,
+    /// 多语言提示，非空时优先于 language，多于一种语言时开启语码转换
+    language_hints: Vec<String>,
+    /// 实时接口的服务区域，None 使用默认区域
+    region: Option<String>,
+    /// 是否开启说话人分离（仅批量接口）
+    diarization: bool,
+    client: Client,
+}
+
+impl GladiaAsr {
+    pub fn new(api_key: String, language: Option<String>, region: Option<String>) -> Self {
+        Self {
+            api_key,
+            language,
+            language_hints: Vec::new(),
+            region,
+            diarization: false,
+            client: proxied_client(),
+        }
+    }
+
+    /// 使用指定的 HTTP 客户端（如设置了连接超时的客户端）
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 设置是否开启说话人分离
+    pub fn with_diarization(mut self, diarization: bool) -> Self {
+        self.diarization = diarization;
+        self
+    }
+
+    /// 设置多语言提示（如中英混合）
+    pub fn with_language_hints(mut self, hints: Vec<String>) -> Self {
+        self.language_hints = hints;
+        self
+    }
+
+    /// 两种接口共用的语言参数，语言列表为空时自动检测
+    fn language_config(&self) -> serde_json::Value {
+        if self.language_hints.is_empty() {
+            return serde_json::json!({ "languages": self.language.iter().collect::<Vec<_>>() });
+        }
+        serde_json::json!({
+            "languages": self.language_hints,
+            "code_switching": self.language_hints.len() > 1,
+        })
+    }
+
+    /// 上传音频，返回服务端的音频地址
+    async fn upload(&self, audio_data: &[u8]) -> Result<String, AsrError> {
+        let part = reqwest::multipart::Part::bytes(audio_data.to_vec())
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| AsrError::Encoding(e.to_string()))?;
+        let form = reqwest::multipart::Form::new().part("audio", part);
+
+        let upload_start = Instant::now();
+        let response = self
+            .client
+            .post(format!("{}/upload", API_BASE))
+            .header("x-gladia-key", &self.api_key)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+        record_upload(audio_data.len(), upload_start.elapsed());
+
+        let upload: UploadResponse = parse_response(response).await?;
+        Ok(upload.audio_url)
+    }
+
+    /// 创建转写任务，返回任务 ID
+    async fn create_transcription(&self, audio_url: String) -> Result<String, AsrError> {
+        let body = serde_json::json!({
+            "audio_url": audio_url,
+            "language_config": self.language_config(),
+            "diarization": self.diarization,
+        })
+        .to_string();
+        capture_text("Gladia", CaptureDirection::Send, &body);
+
+        let response = self
+            .client
+            .post(format!("{}/pre-recorded", API_BASE))
+            .header("x-gladia-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let job: JobResponse = parse_response(response).await?;
+        Ok(job.id)
+    }
+
+    /// 轮询转写任务直到完成
+    async fn poll_transcription(&self, id: &str) -> Result<Transcription, AsrError> {
+        let poll = async {
+            loop {
+                let response = self
+                    .client
+                    .get(format!("{}/pre-recorded/{}", API_BASE, id))
+                    .header("x-gladia-key", &self.api_key)
+                    .send()
+                    .await
+                    .map_err(|e| AsrError::Network(e.to_string()))?;
+
+                let job: JobStatus = parse_response(response).await?;
+                match job.status.as_str() {
+                    "done" => {
+                        return job
+                            .result
+                            .map(|r| r.transcription)
+                            .ok_or_else(|| AsrError::Api("转写结果为空".to_string()))
+                    }
+                    "error" => {
+                        return Err(AsrError::Api(format!(
+                            "转写失败: {}",
+                            job.error_code.map(|c| c.to_string()).unwrap_or_default()
+                        )))
+                    }
+                    _ => tokio::time::sleep(POLL_INTERVAL).await,
+                }
+            }
+        };
+
+        timeout(POLL_TIMEOUT, poll)
+            .await
+            .map_err(|_| AsrError::Network("等待转写结果超时".to_string()))?
+    }
+}
+
+// ============================================================================
+// 批量接口
+// ============================================================================
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    audio_url: String,
+}
+
+#[derive(Deserialize)]
+struct JobResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct JobStatus {
+    status: String,
+    error_code: Option<u32>,
+    result: Option<JobResult>,
+}
+
+#[derive(Deserialize)]
+struct JobResult {
+    transcription: Transcription,
+}
+
+#[derive(Deserialize)]
+struct Transcription {
+    #[serde(default)]
+    full_transcript: String,
+    #[serde(default)]
+    utterances: Vec<Utterance>,
+}
+
+#[derive(Deserialize)]
+struct Utterance {
+    text: String,
+    #[serde(default)]
+    start: f64,
+    #[serde(default)]
+    end: f64,
+    /// 开启 diarization 时的说话人编号（从 0 开始）
+    #[serde(default)]
+    speaker: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+/// 解析响应，非 2xx 时提取错误信息
+async fn parse_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, AsrError> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))?;
+    capture_text("Gladia", CaptureDirection::Recv, &body);
+
+    if !status.is_success() {
+        if let Ok(error) = serde_json::from_str::<ApiError>(&body) {
+            return Err(AsrError::Api(error.message));
+        }
+        return Err(AsrError::Api(format!("HTTP {}: {}", status, body)));
+    }
+
+    serde_json::from_str(&body).map_err(|e| AsrError::Api(e.to_string()))
+}
+
+#[async_trait]
+impl AsrService for GladiaAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        capture_audio("Gladia", CaptureDirection::Send, audio_data, sample_rate);
+
+        let audio_url = self.upload(audio_data).await?;
+        let id = self.create_transcription(audio_url).await?;
+        let transcription = self.poll_transcription(&id).await?;
+
+        let segments = transcription
+            .utterances
+            .iter()
+            .map(|u| AsrSegment {
+                text: u.text.clone(),
+                start_ms: (u.start * 1000.0) as u64,
+                end_ms: (u.end * 1000.0) as u64,
+                speaker: u.speaker.map(|s| s + 1),
+            })
+            .collect();
+
+        Ok(AsrResult {
+            text: transcription.full_transcript,
+            is_final: true,
+            segments,
+            ..Default::default()
+        })
+    }
+}
+
+// ============================================================================
+// 实时接口
+// ============================================================================
+
+#[derive(Deserialize)]
+struct LiveSession {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct LiveMessage {
+    #[serde(rename = "type")]
+    message_type: String,
+    data: Option<LiveTranscript>,
+    error: Option<LiveError>,
+}
+
+#[derive(Deserialize)]
+struct LiveTranscript {
+    #[serde(default)]
+    is_final: bool,
+    utterance: LiveUtterance,
+}
+
+#[derive(Deserialize)]
+struct LiveUtterance {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct LiveError {
+    message: String,
+}
+
+/// 追加一段识别结果，句间补空格
+fn append_sentence(confirmed: &mut String, sentence: &str) {
+    let sentence = sentence.trim();
+    if sentence.is_empty() {
+        return;
+    }
+    if !confirmed.is_empty() {
+        confirmed.push(' ');
+    }
+    confirmed.push_str(sentence);
+}
+
+#[async_trait]
+impl StreamingAsrService for GladiaAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<StreamingSession, AsrError> {
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        // 创建实时会话，返回带临时令牌的 WebSocket 地址
+        let body = serde_json::json!({
+            "encoding": "wav/pcm",
+            "bit_depth": 16,
+            "sample_rate": sample_rate,
+            "channels": 1,
+            "language_config": self.language_config(),
+            "messages_config": {
+                "receive_partial_transcripts": true,
+                "receive_final_transcripts": true,
+            },
+        })
+        .to_string();
+        capture_text("Gladia", CaptureDirection::Send, &body);
+
+        let mut request = self
+            .client
+            .post(format!("{}/live", API_BASE))
+            .header("x-gladia-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .body(body);
+        if let Some(region) = &self.region {
+            request = request.query(&[("region", region)]);
+        }
+        let response = timeout(WS_CONNECT_TIMEOUT, request.send())
+            .await
+            .map_err(|_| AsrError::Network("创建实时会话超时".to_string()))?
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+        let session: LiveSession = parse_response(response).await?;
+
+        let (ws_stream, _) = timeout(WS_CONNECT_TIMEOUT, connect_async(session.url.as_str()))
+            .await
+            .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))?
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        trace_ws_marker("Gladia", "ws.connected");
+
+        let (mut write, mut read) = ws_stream.split();
+
+        tokio::spawn(async move {
+            // 已确认的文本（is_final 结果拼接）
+            let mut confirmed = String::new();
+            // 当前句子的暂定文本
+            let mut pending = String::new();
+            // 停止录音后等待最终结果的截止时间
+            let mut deadline: Option<tokio::time::Instant> = None;
+
+            loop {
+                tokio::select! {
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                capture_audio("Gladia", CaptureDirection::Send, &data, sample_rate);
+                                if write.send(Message::Binary(data.into())).await.is_err() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(
+                                        "发送音频失败".to_string()
+                                    )).await;
+                                    break;
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                // 停止录音：服务端输出剩余的最终结果后关闭连接
+                                let json = r#"{"type":"stop_recording"}"#;
+                                trace_ws_message("Gladia", CaptureDirection::Send, json);
+                                let _ = write.send(Message::Text(json.into())).await;
+                                deadline = Some(tokio::time::Instant::now() + FINAL_TIMEOUT);
+                            }
+                            StreamingControl::Cancel => {
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(Message::Text(text)) => {
+                                capture_text("Gladia", CaptureDirection::Recv, &text);
+                                trace_ws_message("Gladia", CaptureDirection::Recv, &text);
+                                let Ok(message) = serde_json::from_str::<LiveMessage>(&text) else {
+                                    continue;
+                                };
+
+                                if let Some(error) = message.error {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(error.message)).await;
+                                    break;
+                                }
+
+                                if message.message_type != "transcript" {
+                                    continue;
+                                }
+                                let Some(transcript) = message.data else {
+                                    continue;
+                                };
+
+                                if transcript.is_final {
+                                    append_sentence(&mut confirmed, &transcript.utterance.text);
+                                    pending.clear();
+                                } else {
+                                    pending = transcript.utterance.text;
+                                }
+                                let _ = event_tx.send(StreamingAsrEvent::Partial {
+                                    text: confirmed.clone(),
+                                    stash: pending.clone(),
+                                }).await;
+                            }
+                            Ok(Message::Close(_)) => {
+                                trace_ws_marker("Gladia", "ws.closed");
+                                let _ = event_tx.send(StreamingAsrEvent::Final {
+                                    text: std::mem::take(&mut confirmed),
+                                }).await;
+                                break;
+                            }
+                            Err(e) => {
+                                trace_ws_marker("Gladia", "ws.error");
+                                let _ = event_tx.send(StreamingAsrEvent::Error(
+                                    e.to_string()
+                                )).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)), if deadline.is_some() => {
+                        // 服务端迟迟不返回最终结果，使用已有文本结束会话
+                        trace_ws_marker("Gladia", "final.timeout");
+                        append_sentence(&mut confirmed, &pending);
+                        let _ = event_tx.send(StreamingAsrEvent::Final { text: confirmed }).await;
+                        let _ = write.close().await;
+                        break;
+                    }
+                    else => break,
+                }
+            }
+        });
+
+        Ok(StreamingSession::new(control_tx, event_rx))
+    }
+}
@@ -0,0 +1,185 @@
+//! ASR 服务商健康统计
+//!
+//! 记录每个服务商最近的成功率和延迟，供 "Auto" 服务商选择当前最健康的后端
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use super::traits::{AsrError, AsrResult, AsrService};
+
+/// 每个服务商保留的最近请求数
+const WINDOW_SIZE: usize = 20;
+/// 没有任何记录的服务商的评分（略差于健康的服务商，但优于明显故障的）
+const UNKNOWN_SCORE: f32 = 0.2;
+/// 切换服务商所需的最小评分差，避免来回切换
+const SWITCH_MARGIN: f32 = 0.15;
+/// 当前服务商至少有这么多记录后才允许切走
+const MIN_SAMPLES: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    success: bool,
+    latency: Duration,
+}
+
+#[derive(Default)]
+struct HealthRegistry {
+    samples: HashMap<String, VecDeque<Sample>>,
+    /// Auto 模式当前选中的服务商
+    selected: Option<String>,
+}
+
+static REGISTRY: OnceLock<Mutex<HealthRegistry>> = OnceLock::new();
+
+fn registry() -> &'static Mutex<HealthRegistry> {
+    REGISTRY.get_or_init(|| Mutex::new(HealthRegistry::default()))
+}
+
+/// 服务商统计信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderStats {
+    pub provider: String,
+    pub successes: usize,
+    pub errors: usize,
+    /// 最近窗口内的错误率 (0.0 - 1.0)
+    pub error_rate: f32,
+    /// 成功请求的平均延迟
+    pub avg_latency_ms: u64,
+}
+
+impl ProviderStats {
+    fn from_samples(provider: &str, samples: &VecDeque<Sample>) -> Self {
+        let successes = samples.iter().filter(|s| s.success).count();
+        let errors = samples.len() - successes;
+        let total_latency: Duration = samples
+            .iter()
+            .filter(|s| s.success)
+            .map(|s| s.latency)
+            .sum();
+
+        Self {
+            provider: provider.to_string(),
+            successes,
+            errors,
+            error_rate: if samples.is_empty() {
+                0.0
+            } else {
+                errors as f32 / samples.len() as f32
+            },
+            avg_latency_ms: if successes == 0 {
+                0
+            } else {
+                (total_latency / successes as u32).as_millis() as u64
+            },
+        }
+    }
+
+    /// 评分，越低越健康（10 秒平均延迟相当于 100% 错误率）
+    fn score(&self) -> f32 {
+        self.error_rate + self.avg_latency_ms as f32 / 10_000.0
+    }
+}
+
+/// 记录一次识别结果
+pub fn record_result(provider: &str, success: bool, latency: Duration) {
+    let mut registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+    let samples = registry.samples.entry(provider.to_string()).or_default();
+    if samples.len() == WINDOW_SIZE {
+        samples.pop_front();
+    }
+    samples.push_back(Sample { success, latency });
+    drop(registry);
+
+    crate::telemetry::record_asr(provider, success, latency);
+}
+
+/// 获取所有服务商的统计信息
+pub fn provider_stats() -> Vec<ProviderStats> {
+    let registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+    let mut stats: Vec<ProviderStats> = registry
+        .samples
+        .iter()
+        .map(|(provider, samples)| ProviderStats::from_samples(provider, samples))
+        .collect();
+    stats.sort_by(|a, b| a.provider.cmp(&b.provider));
+    stats
+}
+
+/// 从候选服务商中选择最健康的一个
+///
+/// 当前选中的服务商只有在记录足够且明显差于最佳候选时才会被替换
+pub(crate) fn select_provider(candidates: &[&str]) -> Option<String> {
+    let mut registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+
+    let score_of = |registry: &HealthRegistry, provider: &str| -> (f32, usize) {
+        match registry.samples.get(provider) {
+            Some(samples) if !samples.is_empty() => (
+                ProviderStats::from_samples(provider, samples).score(),
+                samples.len(),
+            ),
+            _ => (UNKNOWN_SCORE, 0),
+        }
+    };
+
+    // 候选按配置顺序排列，评分相同时优先靠前的
+    let (best, best_score) = candidates
+        .iter()
+        .map(|&p| (p, score_of(&registry, p).0))
+        .fold(None, |acc: Option<(&str, f32)>, (p, score)| match acc {
+            Some((_, best_score)) if best_score <= score => acc,
+            _ => Some((p, score)),
+        })?;
+
+    let selected = match registry.selected.as_deref() {
+        Some(current) if candidates.contains(&current) => {
+            let (current_score, samples) = score_of(&registry, current);
+            if samples >= MIN_SAMPLES && current_score - best_score > SWITCH_MARGIN {
+                tracing::info!(
+                    "ASR auto failover: {} (score {:.2}) -> {} (score {:.2})",
+                    current,
+                    current_score,
+                    best,
+                    best_score
+                );
+                best.to_string()
+            } else {
+                current.to_string()
+            }
+        }
+        _ => best.to_string(),
+    };
+
+    registry.selected = Some(selected.clone());
+    Some(selected)
+}
+
+/// 记录健康统计的 ASR 服务包装
+pub(crate) struct MonitoredAsr {
+    provider: String,
+    inner: Box<dyn AsrService>,
+}
+
+impl MonitoredAsr {
+    pub(crate) fn new(provider: String, inner: Box<dyn AsrService>) -> Self {
+        Self { provider, inner }
+    }
+}
+
+#[async_trait]
+impl AsrService for MonitoredAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        let start = std::time::Instant::now();
+        let result = self.inner.recognize(audio_data, sample_rate).await;
+
+        // 取消不代表服务商故障，不计入统计
+        if !matches!(result, Err(AsrError::Cancelled)) {
+            record_result(&self.provider, result.is_ok(), start.elapsed());
+        }
+
+        result
+    }
+}
@@ -1,102 +1,936 @@
+#[cfg(feature = "assemblyai")]
+mod assemblyai;
+#[cfg(feature = "dashscope")]
 mod dashscope;
+#[cfg(feature = "deepgram")]
+mod deepgram;
+mod fallback;
+#[cfg(feature = "local-whisper")]
+mod faster_whisper;
+#[cfg(feature = "funasr")]
 mod funasr;
+#[cfg(feature = "gladia")]
+mod gladia;
+mod health;
+#[cfg(feature = "openai")]
 mod openai_whisper;
+#[cfg(feature = "qwen")]
 mod qwen;
+#[cfg(feature = "qwen")]
 mod qwen_realtime;
+mod race;
+mod result_cache;
+mod retry;
+#[cfg(feature = "qwen")]
+mod session_cache;
+#[cfg(feature = "sherpa-onnx")]
+mod sherpa_onnx;
+mod streaming_session;
 mod traits;
+#[cfg(feature = "volcengine")]
+mod volcengine;
+#[cfg(feature = "vosk")]
+mod vosk;
 
+#[cfg(feature = "assemblyai")]
+pub use assemblyai::AssemblyAiAsr;
+#[cfg(feature = "dashscope")]
 pub use dashscope::DashScopeAsr;
+#[cfg(feature = "deepgram")]
+pub use deepgram::DeepgramAsr;
+#[cfg(feature = "local-whisper")]
+pub use faster_whisper::FasterWhisperAsr;
+#[cfg(feature = "funasr")]
 pub use funasr::FunAsr;
+#[cfg(feature = "gladia")]
+pub use gladia::GladiaAsr;
+pub use health::{provider_stats, record_result, ProviderStats};
+#[cfg(feature = "openai")]
 pub use openai_whisper::OpenAiWhisper;
+#[cfg(feature = "qwen")]
 pub use qwen::QwenAsr;
+#[cfg(feature = "qwen")]
 pub use qwen_realtime::QwenRealtimeAsr;
-pub use traits::{AsrError, AsrResult, AsrService, StreamingAsrEvent, StreamingAsrService, StreamingControl};
+#[cfg(feature = "sherpa-onnx")]
+pub use sherpa_onnx::SherpaOnnxAsr;
+#[cfg(feature = "volcengine")]
+pub use volcengine::VolcengineAsr;
+#[cfg(feature = "vosk")]
+pub use vosk::VoskAsr;
+pub use streaming_session::{StreamingSession, StreamingSessionState};
+pub use traits::{AsrError, AsrResult, AsrSegment, AsrService, StreamingAsrEvent, StreamingAsrService, StreamingControl};
 
-use crate::config::settings::AsrConfig;
+use std::time::Duration;
+
+#[cfg(feature = "assemblyai")]
+use crate::config::settings::AssemblyAiAsrConfig;
+#[cfg(feature = "dashscope")]
+use crate::config::settings::DashScopeAsrConfig;
+#[cfg(feature = "deepgram")]
+use crate::config::settings::DeepgramAsrConfig;
+#[cfg(feature = "local-whisper")]
+use crate::config::settings::FasterWhisperConfig;
+#[cfg(feature = "funasr")]
+use crate::config::settings::FunAsrConfig;
+#[cfg(feature = "gladia")]
+use crate::config::settings::GladiaAsrConfig;
+#[cfg(feature = "sherpa-onnx")]
+use crate::config::settings::SherpaOnnxConfig;
+#[cfg(feature = "volcengine")]
+use crate::config::settings::VolcengineAsrConfig;
+#[cfg(any(
+    feature = "qwen",
+    feature = "dashscope",
+    feature = "assemblyai",
+    feature = "local-whisper",
+    feature = "gladia"
+))]
+use crate::config::settings::AUTO_LANGUAGE;
+use crate::config::settings::{AsrConfig, VoskConfig};
+use crate::net::{connect_address, is_local, openai_compatible_models};
+
+/// 连通性检查的超时时间
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(3);
+/// DashScope 的 OpenAI 兼容接口（通义千问和 Paraformer 共用 API Key）
+#[cfg(any(feature = "qwen", feature = "dashscope"))]
+const DASHSCOPE_COMPATIBLE_URL: &str = "https://dashscope.aliyuncs.com/compatible-mode/v1";
+#[cfg(feature = "openai")]
+const OPENAI_API_URL: &str = "https://api.openai.com/v1";
+
+/// 配置的固定识别语言，自动检测视为未配置（用于不支持检测的服务商）
+#[cfg(any(
+    feature = "qwen",
+    feature = "dashscope",
+    feature = "assemblyai",
+    feature = "local-whisper",
+    feature = "gladia"
+))]
+fn fixed_language(config: &AsrConfig) -> Option<String> {
+    config
+        .language
+        .clone()
+        .filter(|language| language != AUTO_LANGUAGE)
+}
+
+/// 识别语言，未配置时默认中文
+#[cfg(feature = "qwen")]
+fn asr_language(config: &AsrConfig) -> String {
+    fixed_language(config).unwrap_or_else(|| "zh".to_string())
+}
+
+/// AssemblyAI 识别语言，未配置时默认英文
+#[cfg(feature = "assemblyai")]
+fn english_default_language(config: &AsrConfig) -> String {
+    fixed_language(config).unwrap_or_else(|| "en".to_string())
+}
+
+/// Deepgram 识别语言，未配置时默认英文，"auto" 交给服务端检测
+#[cfg(feature = "deepgram")]
+fn deepgram_language(config: &AsrConfig) -> String {
+    config.language.clone().unwrap_or_else(|| "en".to_string())
+}
+
+/// 多语言提示（去除空白和重复，统一小写）
+#[cfg(any(feature = "dashscope", feature = "gladia"))]
+fn language_hints(config: &AsrConfig) -> Vec<String> {
+    let mut hints: Vec<String> = Vec::new();
+    for hint in config.language_hints.iter().map(|h| h.trim().to_ascii_lowercase()) {
+        if !hint.is_empty() && hint != AUTO_LANGUAGE && !hints.contains(&hint) {
+            hints.push(hint);
+        }
+    }
+    hints
+}
+
+/// DashScope 语言提示：优先使用多语言提示，都未配置时按中英混合处理
+#[cfg(feature = "dashscope")]
+fn dashscope_language_hints(config: &AsrConfig) -> Vec<String> {
+    let hints = language_hints(config);
+    if !hints.is_empty() {
+        return hints;
+    }
+    match fixed_language(config) {
+        Some(language) => vec![language],
+        None => vec!["zh".to_string(), "en".to_string()],
+    }
+}
+
+/// 热词（去除空白和重复）
+fn vocabulary(config: &AsrConfig) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in config.vocabulary.iter().map(|w| w.trim()) {
+        if !word.is_empty() && !words.iter().any(|w| w == word) {
+            words.push(word.to_string());
+        }
+    }
+    words
+}
+
+/// 基于提示词的服务商使用的识别提示：热词在前，最近的识别结果在后
+fn prompt_with_vocabulary(config: &AsrConfig, context: Option<String>) -> Option<String> {
+    let words = vocabulary(config);
+    if words.is_empty() {
+        return context;
+    }
+    let words = words.join(", ");
+    Some(match context {
+        Some(context) => format!("{}\n{}", words, context),
+        None => words,
+    })
+}
+
+/// "Auto" 服务商：按健康状况在已配置的服务商之间自动切换
+pub const AUTO_PROVIDER: &str = "Auto";
+
+/// 已配置的服务商（按优先级排列）
+fn configured_providers(config: &AsrConfig) -> Vec<&'static str> {
+    let mut providers = Vec::new();
+    if config.qwen.is_some() {
+        providers.push("Qwen");
+    }
+    if config.dashscope.is_some() {
+        providers.push("DashScope");
+    }
+    if config.openai.is_some() {
+        providers.push("OpenAIWhisper");
+    }
+    if config.funasr.is_some() {
+        providers.push("FunAsr");
+    }
+    if config.faster_whisper.is_some() {
+        providers.push("FasterWhisper");
+    }
+    if config.deepgram.is_some() {
+        providers.push("Deepgram");
+    }
+    if config.assemblyai.is_some() {
+        providers.push("AssemblyAI");
+    }
+    if config.volcengine.is_some() {
+        providers.push("Volcengine");
+    }
+    if config.gladia.is_some() {
+        providers.push("Gladia");
+    }
+    #[cfg(feature = "sherpa-onnx")]
+    if config.sherpa_onnx.is_some() {
+        providers.push("SherpaOnnx");
+    }
+    #[cfg(feature = "vosk")]
+    if config.vosk.is_some() {
+        providers.push("Vosk");
+    }
+    if config.local_only {
+        providers.retain(|provider| is_local_provider(config, provider));
+    }
+    providers
+}
+
+/// 服务商是否在本机处理音频：本地模型，或地址为本机的自部署服务
+pub fn is_local_provider(config: &AsrConfig, provider: &str) -> bool {
+    match provider {
+        "SherpaOnnx" | "Vosk" => true,
+        "FunAsr" | "FasterWhisper" => {
+            provider_address(config, provider).is_some_and(|(host, _)| is_local(&host))
+        }
+        _ => false,
+    }
+}
+
+/// 开启仅本机识别时，拒绝会把音频发送到本机以外的服务商
+fn ensure_local_provider(config: &AsrConfig, provider: &str) -> Result<(), AsrError> {
+    if config.local_only && !is_local_provider(config, provider) {
+        return Err(AsrError::Config(format!(
+            "已开启仅本机识别，不能使用 {}（音频会发送到本机以外）",
+            provider
+        )));
+    }
+    Ok(())
+}
+
+/// "Auto" 时没有可用的已配置服务商
+fn no_configured_provider(config: &AsrConfig) -> AsrError {
+    if config.local_only {
+        AsrError::Config(
+            "已开启仅本机识别，但没有配置本机服务商（sherpa-onnx、Vosk 或本机部署的 FunASR/faster-whisper）"
+                .to_string(),
+        )
+    } else {
+        AsrError::Config("没有已配置的 ASR 服务商".to_string())
+    }
+}
+
+/// 解析实际使用的服务商
+///
+/// 非 "Auto" 时原样返回；"Auto" 时选择当前最健康的已配置服务商
+pub fn resolve_provider(config: &AsrConfig) -> String {
+    if config.provider != AUTO_PROVIDER {
+        return config.provider.clone();
+    }
+
+    health::select_provider(&configured_providers(config))
+        .unwrap_or_else(|| config.provider.clone())
+}
+
+/// 非流式识别依次尝试的服务商：主服务商（"Auto" 时为当前选中的）在前，备用服务商按配置顺序在后
+pub fn provider_chain(config: &AsrConfig) -> Vec<String> {
+    let mut chain = vec![resolve_provider(config)];
+    for provider in &config.fallback_providers {
+        if provider != AUTO_PROVIDER && !chain.contains(provider) {
+            chain.push(provider.clone());
+        }
+    }
+    chain
+}
+
+/// 竞速服务商（与主服务商相同或为 Auto 时不生效）
+pub fn race_provider(config: &AsrConfig) -> Option<String> {
+    let provider = config.race_provider.as_deref()?.trim();
+    (!provider.is_empty() && provider != AUTO_PROVIDER && provider != config.provider)
+        .then(|| provider.to_string())
+}
+
+/// 服务商是否接受 Ogg/Opus 音频
+pub fn accepts_opus(provider: &str) -> bool {
+    matches!(
+        provider,
+        "DashScope" | "OpenAIWhisper" | "Deepgram" | "AssemblyAI" | "FasterWhisper"
+    )
+}
+
+/// 服务商是否要求带容器的音频（WAV），不接受裸 PCM
+pub fn requires_wav(provider: &str) -> bool {
+    matches!(provider, "OpenAIWhisper" | "AssemblyAI" | "Gladia" | "FasterWhisper")
+}
 
 /// 根据配置创建 ASR 服务
 pub fn create_asr_service(config: &AsrConfig) -> Result<Box<dyn AsrService>, AsrError> {
-    match config.provider.as_str() {
+    create_asr_service_with_context(config, None)
+}
+
+/// 根据配置创建 ASR 服务，并以最近的识别结果作为识别提示
+///
+/// 配置了备用服务商时返回按顺序尝试的组合服务；配置了竞速服务商时，
+/// 主服务商（含备用链）与竞速服务商同时请求。配置不完整的备用或竞速服务商会被跳过。
+/// 开启结果缓存时，同一段音频再次识别直接返回缓存的结果
+pub fn create_asr_service_with_context(
+    config: &AsrConfig,
+    context: Option<String>,
+) -> Result<Box<dyn AsrService>, AsrError> {
+    let service = create_uncached_service(config, context)?;
+    if !config.result_cache {
+        return Ok(service);
+    }
+    Ok(Box::new(result_cache::CachedAsr::new(
+        result_cache::config_key(config),
+        service,
+    )))
+}
+
+fn create_uncached_service(
+    config: &AsrConfig,
+    context: Option<String>,
+) -> Result<Box<dyn AsrService>, AsrError> {
+    let mut chain = provider_chain(config).into_iter();
+    let provider = chain.next().unwrap_or_else(|| config.provider.clone());
+    let racer = race_provider(config).filter(|racer| *racer != provider);
+    let primary = create_monitored_service(config, &provider, context.clone())?;
+
+    let mut services = vec![(provider, primary)];
+    for provider in chain {
+        if racer.as_ref() == Some(&provider) {
+            continue;
+        }
+        match create_monitored_service(config, &provider, context.clone()) {
+            Ok(service) => services.push((provider, service)),
+            Err(e) => tracing::warn!("Skipping fallback ASR provider {}: {}", provider, e),
+        }
+    }
+
+    let racer = racer.and_then(|racer| {
+        match create_monitored_service(config, &racer, context) {
+            Ok(service) => Some((racer, service)),
+            Err(e) => {
+                tracing::warn!("Skipping race ASR provider {}: {}", racer, e);
+                None
+            }
+        }
+    });
+
+    match racer {
+        // 两路都经过 FallbackAsr，按各自服务商调整音频格式
+        Some(racer) => Ok(Box::new(race::RacingAsr::new(vec![
+            Box::new(fallback::FallbackAsr::new(services)),
+            Box::new(fallback::FallbackAsr::new(vec![racer])),
+        ]))),
+        None if services.len() == 1 => Ok(services.remove(0).1),
+        None => Ok(Box::new(fallback::FallbackAsr::new(services))),
+    }
+}
+
+/// 创建服务商，加上超时重试并记录健康统计
+fn create_monitored_service(
+    config: &AsrConfig,
+    provider: &str,
+    context: Option<String>,
+) -> Result<Box<dyn AsrService>, AsrError> {
+    let service = create_provider_service(config, provider, context)?;
+    let service = retry::RetryingAsr::new(
+        provider.to_string(),
+        config.request_policy_for(provider).clone(),
+        service,
+    );
+    Ok(Box::new(health::MonitoredAsr::new(provider.to_string(), Box::new(service))))
+}
+
+fn create_provider_service(
+    config: &AsrConfig,
+    provider: &str,
+    context: Option<String>,
+) -> Result<Box<dyn AsrService>, AsrError> {
+    if provider == AUTO_PROVIDER {
+        return Err(no_configured_provider(config));
+    }
+    ensure_local_provider(config, provider)?;
+    // 识别提示和 HTTP 客户端只有部分服务商使用
+    #[cfg_attr(
+        not(any(feature = "qwen", feature = "openai", feature = "local-whisper")),
+        allow(unused_variables)
+    )]
+    let context = prompt_with_vocabulary(config, context);
+    #[cfg_attr(
+        not(any(
+            feature = "openai",
+            feature = "local-whisper",
+            feature = "deepgram",
+            feature = "assemblyai",
+            feature = "gladia"
+        )),
+        allow(unused_variables)
+    )]
+    let client = || retry::http_client(config.request_policy_for(provider));
+    match provider {
+        #[cfg(feature = "qwen")]
         "Qwen" => {
             let qwen_config = config
                 .qwen
                 .as_ref()
                 .ok_or_else(|| AsrError::Config("通义千问 ASR 配置缺失".to_string()))?;
-            Ok(Box::new(QwenAsr::new(
-                qwen_config.api_key.clone(),
-                qwen_config.model.clone(),
-            )))
+            Ok(Box::new(
+                QwenAsr::new(
+                    qwen_config.api_key.clone(),
+                    qwen_config.model.clone(),
+                    asr_language(config),
+                )
+                .with_context(context),
+            ))
         }
+        #[cfg(feature = "dashscope")]
         "DashScope" => {
-            let dashscope_config = config
-                .dashscope
-                .as_ref()
-                .ok_or_else(|| AsrError::Config("DashScope 配置缺失".to_string()))?;
+            let dashscope_config = dashscope_config(config)?;
             Ok(Box::new(DashScopeAsr::new(
                 dashscope_config.api_key.clone(),
                 dashscope_config.model.clone(),
-            )))
+                dashscope_language_hints(config),
+            )
+            .with_vocabulary(vocabulary(config))
+            .with_punctuation(config.punctuation)
+            .with_inverse_text_normalization(config.inverse_text_normalization)))
         }
+        #[cfg(feature = "openai")]
         "OpenAIWhisper" => {
             let openai_config = config
                 .openai
                 .as_ref()
                 .ok_or_else(|| AsrError::Config("OpenAI 配置缺失".to_string()))?;
-            Ok(Box::new(OpenAiWhisper::new(
-                openai_config.api_key.clone(),
-                openai_config.model.clone(),
-                openai_config.language.clone(),
-            )))
+            Ok(Box::new(
+                OpenAiWhisper::new(
+                    openai_config.api_key.clone(),
+                    openai_config.model.clone(),
+                    openai_config.language.clone(),
+                )
+                .with_prompt(context)
+                .with_client(client()),
+            ))
         }
+        #[cfg(feature = "funasr")]
         "FunAsr" => {
-            let funasr_config = config
-                .funasr
-                .as_ref()
-                .ok_or_else(|| AsrError::Config("FunASR 配置缺失".to_string()))?;
-            Ok(Box::new(FunAsr::new(funasr_config.endpoint.clone())))
+            let funasr_config = funasr_config(config)?;
+            Ok(Box::new(
+                FunAsr::new(funasr_config.endpoint.clone(), funasr_config.tls.clone())
+                    .with_inverse_text_normalization(config.inverse_text_normalization),
+            ))
         }
-        _ => Err(AsrError::Config(format!(
-            "未知的 ASR 服务商: {}",
-            config.provider
+        #[cfg(feature = "local-whisper")]
+        "FasterWhisper" => Ok(Box::new(
+            FasterWhisperAsr::new(faster_whisper_config(config)?.clone(), fixed_language(config))
+                .with_context(context)
+                .with_client(client()),
+        )),
+        #[cfg(feature = "deepgram")]
+        "Deepgram" => {
+            let deepgram_config = deepgram_config(config)?;
+            Ok(Box::new(DeepgramAsr::new(
+                deepgram_config.api_key.clone(),
+                deepgram_config.model.clone(),
+                deepgram_language(config),
+            )
+            .with_diarization(config.diarization)
+            .with_vocabulary(vocabulary(config))
+            .with_punctuation(config.punctuation)
+            .with_inverse_text_normalization(config.inverse_text_normalization)
+            .with_client(client())))
+        }
+        #[cfg(feature = "assemblyai")]
+        "AssemblyAI" => {
+            let assemblyai_config = assemblyai_config(config)?;
+            Ok(Box::new(AssemblyAiAsr::new(
+                assemblyai_config.api_key.clone(),
+                english_default_language(config),
+            )
+            .with_diarization(config.diarization)
+            .with_vocabulary(vocabulary(config))
+            .with_client(client())))
+        }
+        #[cfg(feature = "volcengine")]
+        "Volcengine" => {
+            let volcengine_config = volcengine_config(config)?;
+            Ok(Box::new(VolcengineAsr::new(
+                volcengine_config.app_id.clone(),
+                volcengine_config.access_token.clone(),
+                volcengine_config.resource_id.clone(),
+            )))
+        }
+        #[cfg(feature = "gladia")]
+        "Gladia" => {
+            let gladia_config = gladia_config(config)?;
+            Ok(Box::new(GladiaAsr::new(
+                gladia_config.api_key.clone(),
+                fixed_language(config),
+                gladia_config.region.clone(),
+            )
+            .with_language_hints(language_hints(config))
+            .with_diarization(config.diarization)
+            .with_client(client())))
+        }
+        #[cfg(feature = "sherpa-onnx")]
+        "SherpaOnnx" => Ok(Box::new(SherpaOnnxAsr::new(
+            sherpa_onnx_config(config)?.clone(),
         ))),
+        #[cfg(feature = "vosk")]
+        "Vosk" => Ok(Box::new(VoskAsr::new(vosk_config(config)?.clone()))),
+        _ => Err(unavailable_provider(provider)),
+    }
+}
+
+/// 支持流式识别的服务商
+const STREAMING_PROVIDERS: &[&str] = &[
+    "Qwen",
+    "DashScope",
+    "Deepgram",
+    "AssemblyAI",
+    "Volcengine",
+    "Gladia",
+    "FunAsr",
+    "SherpaOnnx",
+];
+
+/// 服务商对应的 cargo feature
+fn provider_feature(provider: &str) -> Option<&'static str> {
+    match provider {
+        "Qwen" => Some("qwen"),
+        "DashScope" => Some("dashscope"),
+        "OpenAIWhisper" => Some("openai"),
+        "FunAsr" => Some("funasr"),
+        "FasterWhisper" => Some("local-whisper"),
+        "Deepgram" => Some("deepgram"),
+        "AssemblyAI" => Some("assemblyai"),
+        "Volcengine" => Some("volcengine"),
+        "Gladia" => Some("gladia"),
+        "SherpaOnnx" => Some("sherpa-onnx"),
+        "Vosk" => Some("vosk"),
+        _ => None,
+    }
+}
+
+/// 没有匹配到服务商：已知服务商说明未编译进当前版本，否则为未知服务商
+fn unavailable_provider(provider: &str) -> AsrError {
+    match provider_feature(provider) {
+        Some(feature) => AsrError::Config(format!(
+            "当前版本未包含 ASR 服务商 {}（需启用 {} feature）",
+            provider, feature
+        )),
+        None => AsrError::Config(format!("未知的 ASR 服务商: {}", provider)),
+    }
+}
+
+#[cfg(feature = "dashscope")]
+fn dashscope_config(config: &AsrConfig) -> Result<&DashScopeAsrConfig, AsrError> {
+    config
+        .dashscope
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("DashScope 配置缺失".to_string()))
+}
+
+#[cfg(feature = "funasr")]
+fn funasr_config(config: &AsrConfig) -> Result<&FunAsrConfig, AsrError> {
+    config
+        .funasr
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("FunASR 配置缺失".to_string()))
+}
+
+#[cfg(feature = "local-whisper")]
+fn faster_whisper_config(config: &AsrConfig) -> Result<&FasterWhisperConfig, AsrError> {
+    config
+        .faster_whisper
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("faster-whisper-server 配置缺失".to_string()))
+}
+
+#[cfg(feature = "deepgram")]
+fn deepgram_config(config: &AsrConfig) -> Result<&DeepgramAsrConfig, AsrError> {
+    config
+        .deepgram
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("Deepgram 配置缺失".to_string()))
+}
+
+#[cfg(feature = "assemblyai")]
+fn assemblyai_config(config: &AsrConfig) -> Result<&AssemblyAiAsrConfig, AsrError> {
+    config
+        .assemblyai
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("AssemblyAI 配置缺失".to_string()))
+}
+
+#[cfg(feature = "gladia")]
+fn gladia_config(config: &AsrConfig) -> Result<&GladiaAsrConfig, AsrError> {
+    config
+        .gladia
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("Gladia 配置缺失".to_string()))
+}
+
+#[cfg(feature = "volcengine")]
+fn volcengine_config(config: &AsrConfig) -> Result<&VolcengineAsrConfig, AsrError> {
+    config
+        .volcengine
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("火山引擎 ASR 配置缺失".to_string()))
+}
+
+#[cfg(feature = "sherpa-onnx")]
+fn sherpa_onnx_config(config: &AsrConfig) -> Result<&SherpaOnnxConfig, AsrError> {
+    config
+        .sherpa_onnx
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("sherpa-onnx 配置缺失".to_string()))
+}
+
+fn vosk_config(config: &AsrConfig) -> Result<&VoskConfig, AsrError> {
+    config
+        .vosk
+        .as_ref()
+        .ok_or_else(|| AsrError::Config("Vosk 配置缺失".to_string()))
+}
+
+/// 服务商的主机地址 (host, port)
+fn provider_address(config: &AsrConfig, provider: &str) -> Option<(String, u16)> {
+    match provider {
+        "Qwen" | "DashScope" => Some(("dashscope.aliyuncs.com".to_string(), 443)),
+        "OpenAIWhisper" => Some(("api.openai.com".to_string(), 443)),
+        "Deepgram" => Some(("api.deepgram.com".to_string(), 443)),
+        "AssemblyAI" => Some(("api.assemblyai.com".to_string(), 443)),
+        "Volcengine" => Some(("openspeech.bytedance.com".to_string(), 443)),
+        "Gladia" => Some(("api.gladia.io".to_string(), 443)),
+        "FunAsr" => {
+            let endpoint = &config.funasr.as_ref()?.endpoint;
+            let url = reqwest::Url::parse(endpoint).ok()?;
+            Some((url.host_str()?.to_string(), url.port_or_known_default()?))
+        }
+        "FasterWhisper" => {
+            let endpoint = &config.faster_whisper.as_ref()?.endpoint;
+            let url = reqwest::Url::parse(endpoint).ok()?;
+            Some((url.host_str()?.to_string(), url.port_or_known_default()?))
+        }
+        _ => None,
+    }
+}
+
+/// 检查当前服务商是否可达，返回实际检查的服务商
+///
+/// 只建立 TCP 连接，不发送识别请求，不消耗额度
+pub async fn check_provider_reachable(config: &AsrConfig) -> Result<String, AsrError> {
+    let provider = resolve_provider(config);
+
+    // 本地识别没有远端服务，检查模型是否已下载
+    match provider.as_str() {
+        #[cfg(feature = "sherpa-onnx")]
+        "SherpaOnnx" => {
+            sherpa_onnx::check_model(sherpa_onnx_config(config)?)?;
+            return Ok(provider);
+        }
+        "Vosk" => {
+            let model_dir = &vosk_config(config)?.model_dir;
+            if !std::path::Path::new(model_dir).is_dir() {
+                return Err(AsrError::Config(format!("Vosk 模型目录不存在: {}", model_dir)));
+            }
+            return Ok(provider);
+        }
+        _ => {}
+    }
+
+    let (host, port) = provider_address(config, &provider)
+        .ok_or_else(|| AsrError::Config(format!("ASR 服务商 {} 配置不完整", provider)))?;
+
+    // 设置了代理时检查代理是否可达
+    let (host, port) = connect_address(&host, port);
+    tokio::time::timeout(
+        REACHABILITY_TIMEOUT,
+        tokio::net::TcpStream::connect((host.as_str(), port)),
+    )
+    .await
+    .map_err(|_| AsrError::Network(format!("连接 {}:{} 超时", host, port)))?
+    .map_err(|e| AsrError::Network(format!("无法连接 {}:{}: {}", host, port, e)))?;
+
+    Ok(provider)
+}
+
+/// 测试音频的时长（毫秒）
+const TEST_AUDIO_MS: usize = 1000;
+/// 测试音频的采样率，与录音一致
+const TEST_SAMPLE_RATE: u32 = 16000;
+
+/// 服务商配置的识别模型，没有模型选项的服务商返回 None
+fn provider_model(config: &AsrConfig, provider: &str) -> Option<String> {
+    match provider {
+        "Qwen" => config.qwen.as_ref().map(|c| c.model.clone()),
+        "DashScope" => config.dashscope.as_ref().map(|c| c.model.clone()),
+        "OpenAIWhisper" => config.openai.as_ref().map(|c| c.model.clone()),
+        "FasterWhisper" => config.faster_whisper.as_ref().map(|c| c.model.clone()),
+        "Deepgram" => config.deepgram.as_ref().map(|c| c.model.clone()),
+        "SherpaOnnx" => config.sherpa_onnx.as_ref().map(|c| c.model.clone()),
+        _ => None,
     }
 }
 
-/// 测试通义千问 ASR API
-pub async fn test_qwen_api(api_key: &str) -> Result<String, AsrError> {
-    qwen::test_api(api_key).await
+/// 测试当前选中的服务商
+///
+/// 按实际识别的方式创建服务（配置的模型、地址、证书等），识别一段静音。
+/// 不经过结果缓存、重试和备用服务商，识别结果为空也视为成功
+pub async fn test_asr_api(config: &AsrConfig) -> Result<String, AsrError> {
+    let provider = resolve_provider(config);
+    let service = create_provider_service(config, &provider, None)?;
+
+    let samples = vec![0.0; TEST_SAMPLE_RATE as usize * TEST_AUDIO_MS / 1000];
+    let wav = crate::audio::encode_to_wav(&samples, TEST_SAMPLE_RATE, 1)
+        .map_err(|e| AsrError::Encoding(e.to_string()))?;
+    let audio = if requires_wav(&provider) {
+        wav.as_slice()
+    } else {
+        crate::audio::wav_payload(&wav).unwrap_or(&wav)
+    };
+
+    let timeout = Duration::from_secs(config.request_policy_for(&provider).request_timeout_secs);
+    tokio::time::timeout(timeout, service.recognize(audio, TEST_SAMPLE_RATE))
+        .await
+        .map_err(|_| AsrError::Network("请求超时".to_string()))??;
+
+    Ok(match provider_model(config, &provider) {
+        Some(model) => format!("{} 验证成功（模型 {}）", provider, model),
+        None => format!("{} 验证成功", provider),
+    })
 }
 
-/// 测试 DashScope API
-pub async fn test_dashscope_api(api_key: &str) -> Result<String, AsrError> {
-    dashscope::test_api(api_key).await
+/// 查询服务商可用的识别模型，供设置界面选择
+///
+/// 支持通义千问、DashScope 和 OpenAI，其他服务商返回配置错误（界面保留手动输入）
+pub async fn list_asr_models(provider: &str, api_key: &str) -> Result<Vec<String>, AsrError> {
+    let (base_url, is_asr_model): (&str, fn(&str) -> bool) = match provider {
+        #[cfg(feature = "qwen")]
+        "Qwen" => (DASHSCOPE_COMPATIBLE_URL, |id| id.contains("asr")),
+        #[cfg(feature = "dashscope")]
+        "DashScope" => (DASHSCOPE_COMPATIBLE_URL, |id| id.starts_with("paraformer")),
+        #[cfg(feature = "openai")]
+        "OpenAIWhisper" => (OPENAI_API_URL, |id| {
+            id.contains("whisper") || id.contains("transcribe")
+        }),
+        _ => {
+            return Err(AsrError::Config(format!(
+                "ASR 服务商 {} 不支持查询模型列表",
+                provider
+            )))
+        }
+    };
+
+    let models = openai_compatible_models(base_url, api_key).await?;
+    Ok(models.into_iter().filter(|id| is_asr_model(id)).collect())
 }
 
-/// 测试 OpenAI API
-pub async fn test_openai_api(api_key: &str) -> Result<String, AsrError> {
-    openai_whisper::test_api(api_key).await
+#[cfg(feature = "sherpa-onnx")]
+/// 下载 sherpa-onnx 模型到默认模型目录，返回模型目录
+pub async fn download_sherpa_onnx_model(model: &str) -> Result<String, AsrError> {
+    let config = SherpaOnnxConfig {
+        model: model.to_string(),
+        ..Default::default()
+    };
+    let dir = sherpa_onnx::download_model(&config).await?;
+    Ok(dir.display().to_string())
 }
 
-/// 测试 FunASR API
-pub async fn test_funasr_api(endpoint: &str) -> Result<String, AsrError> {
-    funasr::test_api(endpoint).await
+#[cfg(feature = "sherpa-onnx")]
+/// 停止由应用启动的 sherpa-onnx 服务端
+pub fn stop_sherpa_onnx_server() {
+    sherpa_onnx::stop_server();
+}
+
+/// 释放本地识别占用的资源（sherpa-onnx 服务端进程、Vosk 模型），下次识别时重新加载
+pub fn release_local_models() {
+    #[cfg(feature = "sherpa-onnx")]
+    sherpa_onnx::stop_server();
+    #[cfg(feature = "vosk")]
+    vosk::unload_model();
 }
 
 /// 根据配置创建流式 ASR 服务
 pub fn create_streaming_asr_service(
     config: &AsrConfig,
 ) -> Result<Box<dyn StreamingAsrService>, AsrError> {
+    create_streaming_asr_service_with_context(config, None)
+}
+
+/// 解析流式识别实际使用的服务商
+fn resolve_streaming_provider(config: &AsrConfig) -> &str {
+    // 支持流式识别的服务商：Qwen、DashScope、Deepgram、AssemblyAI、Volcengine、Gladia、FunAsr、SherpaOnnx（本地），Auto 模式下优先使用 Qwen
     match config.provider.as_str() {
+        // 仅本机识别时只在已配置的本机服务之间选择，都没有时保持 "Auto"
+        AUTO_PROVIDER if config.local_only => {
+            if config.funasr.is_some() && is_local_provider(config, "FunAsr") {
+                "FunAsr"
+            } else if config.sherpa_onnx.is_some() {
+                "SherpaOnnx"
+            } else {
+                AUTO_PROVIDER
+            }
+        }
+        AUTO_PROVIDER if config.qwen.is_some() => "Qwen",
+        AUTO_PROVIDER if config.dashscope.is_some() => "DashScope",
+        AUTO_PROVIDER if config.deepgram.is_some() => "Deepgram",
+        AUTO_PROVIDER if config.assemblyai.is_some() => "AssemblyAI",
+        AUTO_PROVIDER if config.volcengine.is_some() => "Volcengine",
+        AUTO_PROVIDER if config.gladia.is_some() => "Gladia",
+        AUTO_PROVIDER if config.funasr.is_some() => "FunAsr",
+        provider => provider,
+    }
+}
+
+/// 服务商单个流式连接的时长上限
+///
+/// 连续听写接近上限时管道会提交当前连接并无缝切换到新连接
+pub fn streaming_session_limit(config: &AsrConfig) -> Option<Duration> {
+    match resolve_streaming_provider(config) {
+        // Universal Streaming 单个会话最长 3 小时
+        "AssemblyAI" => Some(Duration::from_secs(3 * 60 * 60)),
+        _ => None,
+    }
+}
+
+/// 根据配置创建流式 ASR 服务，并以最近的识别结果作为识别提示
+pub fn create_streaming_asr_service_with_context(
+    config: &AsrConfig,
+    context: Option<String>,
+) -> Result<Box<dyn StreamingAsrService>, AsrError> {
+    // 只有通义千问使用识别提示
+    #[cfg_attr(not(feature = "qwen"), allow(unused_variables))]
+    let context = prompt_with_vocabulary(config, context);
+    let provider = resolve_streaming_provider(config);
+    if provider == AUTO_PROVIDER {
+        return Err(no_configured_provider(config));
+    }
+    ensure_local_provider(config, provider)?;
+    match provider {
+        #[cfg(feature = "qwen")]
         "Qwen" => {
             let qwen_config = config
                 .qwen
                 .as_ref()
                 .ok_or_else(|| AsrError::Config("通义千问 ASR 配置缺失".to_string()))?;
-            Ok(Box::new(QwenRealtimeAsr::new(
-                qwen_config.api_key.clone(),
-                qwen_config.model.clone(),
+            Ok(Box::new(
+                QwenRealtimeAsr::new(
+                    qwen_config.api_key.clone(),
+                    qwen_config.model.clone(),
+                    asr_language(config),
+                )
+                .with_context(context)
+                .with_url(qwen_config.realtime_url.clone())
+                .with_manual_turn(qwen_config.manual_turn),
+            ))
+        }
+        #[cfg(feature = "dashscope")]
+        "DashScope" => {
+            let dashscope_config = dashscope_config(config)?;
+            Ok(Box::new(DashScopeAsr::new(
+                dashscope_config.api_key.clone(),
+                dashscope_config.model.clone(),
+                dashscope_language_hints(config),
+            )
+            .with_vocabulary(vocabulary(config))
+            .with_punctuation(config.punctuation)
+            .with_inverse_text_normalization(config.inverse_text_normalization)))
+        }
+        #[cfg(feature = "deepgram")]
+        "Deepgram" => {
+            let deepgram_config = deepgram_config(config)?;
+            Ok(Box::new(DeepgramAsr::new(
+                deepgram_config.api_key.clone(),
+                deepgram_config.model.clone(),
+                deepgram_language(config),
+            )
+            .with_vocabulary(vocabulary(config))
+            .with_punctuation(config.punctuation)
+            .with_inverse_text_normalization(config.inverse_text_normalization)))
+        }
+        #[cfg(feature = "assemblyai")]
+        "AssemblyAI" => {
+            let assemblyai_config = assemblyai_config(config)?;
+            Ok(Box::new(AssemblyAiAsr::new(
+                assemblyai_config.api_key.clone(),
+                english_default_language(config),
             )))
         }
+        #[cfg(feature = "volcengine")]
+        "Volcengine" => {
+            let volcengine_config = volcengine_config(config)?;
+            Ok(Box::new(VolcengineAsr::new(
+                volcengine_config.app_id.clone(),
+                volcengine_config.access_token.clone(),
+                volcengine_config.resource_id.clone(),
+            )))
+        }
+        #[cfg(feature = "gladia")]
+        "Gladia" => {
+            let gladia_config = gladia_config(config)?;
+            Ok(Box::new(GladiaAsr::new(
+                gladia_config.api_key.clone(),
+                fixed_language(config),
+                gladia_config.region.clone(),
+            )
+            .with_language_hints(language_hints(config))))
+        }
+        #[cfg(feature = "funasr")]
+        "FunAsr" => {
+            let funasr_config = funasr_config(config)?;
+            Ok(Box::new(
+                FunAsr::new(funasr_config.endpoint.clone(), funasr_config.tls.clone())
+                    .with_inverse_text_normalization(config.inverse_text_normalization),
+            ))
+        }
+        #[cfg(feature = "sherpa-onnx")]
+        "SherpaOnnx" => Ok(Box::new(SherpaOnnxAsr::new(
+            sherpa_onnx_config(config)?.clone(),
+        ))),
+        provider if STREAMING_PROVIDERS.contains(&provider) => Err(unavailable_provider(provider)),
         _ => Err(AsrError::Config(format!(
             "ASR 服务商 {} 不支持流式识别",
             config.provider
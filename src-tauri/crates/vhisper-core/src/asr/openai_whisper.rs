@@ -3,12 +3,19 @@ use reqwest::{multipart, Client};
 use serde::Deserialize;
 
 use super::traits::{AsrError, AsrResult, AsrService};
+use crate::audio::is_ogg;
+use crate::config::settings::AUTO_LANGUAGE;
+use crate::debug::{capture_audio, capture_text, CaptureDirection};
+use crate::net::proxied_client;
 
 /// OpenAI Whisper ASR 服务
 pub struct OpenAiWhisper {
     api_key: String,
     model: String,
+    /// 识别语言，"auto" 表示由服务端检测
     language: String,
+    /// 识别提示（最近的识别结果），帮助识别重复出现的人名、术语
+    prompt: Option<String>,
     client: Client,
 }
 
@@ -18,14 +25,58 @@ impl OpenAiWhisper {
             api_key,
             model,
             language,
-            client: Client::new(),
+            prompt: None,
+            client: proxied_client(),
         }
     }
+
+    /// 使用指定的 HTTP 客户端（如设置了连接超时的客户端）
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 设置识别提示
+    pub fn with_prompt(mut self, prompt: Option<String>) -> Self {
+        self.prompt = prompt;
+        self
+    }
 }
 
 #[derive(Deserialize)]
 struct WhisperResponse {
     text: String,
+    /// verbose_json 中检测到的语言（英文全称，如 "chinese"）
+    #[serde(default)]
+    language: Option<String>,
+}
+
+/// Whisper 返回的语言名转换为 ISO 639-1 代码，已是代码或无法识别时原样返回
+fn language_code(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    let code = match name.as_str() {
+        "chinese" | "mandarin" | "cantonese" => "zh",
+        "english" => "en",
+        "japanese" => "ja",
+        "korean" => "ko",
+        "french" => "fr",
+        "german" => "de",
+        "spanish" => "es",
+        "portuguese" => "pt",
+        "italian" => "it",
+        "russian" => "ru",
+        "arabic" => "ar",
+        "hindi" => "hi",
+        "vietnamese" => "vi",
+        "thai" => "th",
+        "indonesian" => "id",
+        "dutch" => "nl",
+        "turkish" => "tr",
+        "polish" => "pl",
+        "ukrainian" => "uk",
+        _ => return name,
+    };
+    code.to_string()
 }
 
 #[derive(Deserialize)]
@@ -40,19 +91,37 @@ struct WhisperErrorDetail {
 
 #[async_trait]
 impl AsrService for OpenAiWhisper {
-    async fn recognize(&self, audio_data: &[u8], _sample_rate: u32) -> Result<AsrResult, AsrError> {
-        // OpenAI Whisper API 需要 WAV 格式的文件
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        // OpenAI Whisper API 需要带容器的音频文件（WAV 或 Ogg/Opus）
+        let (file_name, mime) = if is_ogg(audio_data) {
+            ("audio.ogg", "audio/ogg")
+        } else {
+            ("audio.wav", "audio/wav")
+        };
         let file_part = multipart::Part::bytes(audio_data.to_vec())
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
+            .file_name(file_name)
+            .mime_str(mime)
             .map_err(|e| AsrError::Encoding(e.to_string()))?;
 
-        let form = multipart::Form::new()
+        // 自动检测时不传语言；只有 whisper 模型的 verbose_json 会返回检测到的语言
+        let detect = self.language == AUTO_LANGUAGE;
+        let response_format = if detect && self.model.starts_with("whisper") {
+            "verbose_json"
+        } else {
+            "json"
+        };
+        let mut form = multipart::Form::new()
             .part("file", file_part)
             .text("model", self.model.clone())
-            .text("language", self.language.clone())
-            .text("response_format", "json");
+            .text("response_format", response_format);
+        if !detect {
+            form = form.text("language", self.language.clone());
+        }
+        if let Some(prompt) = &self.prompt {
+            form = form.text("prompt", prompt.clone());
+        }
 
+        capture_audio("OpenAIWhisper", CaptureDirection::Send, audio_data, sample_rate);
         let response = self
             .client
             .post("https://api.openai.com/v1/audio/transcriptions")
@@ -67,6 +136,7 @@ impl AsrService for OpenAiWhisper {
             .text()
             .await
             .map_err(|e| AsrError::Network(e.to_string()))?;
+        capture_text("OpenAIWhisper", CaptureDirection::Recv, &body);
 
         if !status.is_success() {
             if let Ok(error) = serde_json::from_str::<WhisperError>(&body) {
@@ -81,27 +151,8 @@ impl AsrService for OpenAiWhisper {
         Ok(AsrResult {
             text: result.text,
             is_final: true,
+            language: result.language.as_deref().map(language_code),
+            ..Default::default()
         })
     }
 }
-
-/// 测试 OpenAI API 连接
-pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
-    let client = Client::new();
-
-    let response = client
-        .get("https://api.openai.com/v1/models")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .send()
-        .await
-        .map_err(|e| AsrError::Network(e.to_string()))?;
-
-    if response.status().is_success() {
-        Ok("API Key 验证成功".to_string())
-    } else {
-        Err(AsrError::Api(format!(
-            "API Key 无效: HTTP {}",
-            response.status()
-        )))
-    }
-}
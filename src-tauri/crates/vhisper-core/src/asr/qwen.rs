@@ -2,24 +2,41 @@ use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 fn generate_event_id() -> String {
     format!("event_{}", Uuid::new_v4().to_string().replace("-", "")[..20].to_string())
 }
 
+use super::session_cache;
 use super::traits::{AsrError, AsrResult, AsrService};
+use crate::debug::{capture_text, CaptureDirection};
+use crate::net::connect_async;
 
 /// 通义千问实时语音识别服务
 pub struct QwenAsr {
     api_key: String,
     model: String,
+    language: String,
+    /// 上下文（最近的识别结果），帮助识别重复出现的人名、术语
+    context: Option<String>,
 }
 
 impl QwenAsr {
-    pub fn new(api_key: String, model: String) -> Self {
-        Self { api_key, model }
+    pub fn new(api_key: String, model: String, language: String) -> Self {
+        Self {
+            api_key,
+            model,
+            language,
+            context: None,
+        }
+    }
+
+    /// 设置上下文
+    pub fn with_context(mut self, context: Option<String>) -> Self {
+        self.context = context;
+        self
     }
 }
 
@@ -44,6 +61,14 @@ struct SessionConfig {
 #[derive(Serialize)]
 struct TranscriptionConfig {
     language: String,
+    /// 上下文增强文本
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corpus: Option<Corpus>,
+}
+
+#[derive(Serialize)]
+struct Corpus {
+    text: String,
 }
 
 #[derive(Serialize)]
@@ -124,7 +149,8 @@ impl AsrService for QwenAsr {
                 input_audio_format: "pcm".to_string(),
                 sample_rate: 16000,
                 input_audio_transcription: TranscriptionConfig {
-                    language: "zh".to_string(),
+                    language: self.language.clone(),
+                    corpus: self.context.clone().map(|text| Corpus { text }),
                 },
                 turn_detection: None, // 手动模式，通过 commit 触发
             },
@@ -133,42 +159,49 @@ impl AsrService for QwenAsr {
         let session_json =
             serde_json::to_string(&session_update).map_err(|e| AsrError::Encoding(e.to_string()))?;
 
+        capture_text("Qwen", CaptureDirection::Send, &session_json);
         write
             .send(Message::Text(session_json.into()))
             .await
             .map_err(|e| AsrError::Network(e.to_string()))?;
 
-        // 等待 session.created 或 session.updated 事件
-        let mut session_ready = false;
-        while let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    let response: ResponseEvent = serde_json::from_str(&text)
-                        .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+        // 同一配置最近确认过时不再等待，确认事件在收集结果时忽略
+        let session_key = session_cache::session_key(&[&url, &self.api_key, &self.language]);
+        if !session_cache::is_confirmed(session_key) {
+            // 等待 session.created 或 session.updated 事件
+            let mut session_ready = false;
+            while let Some(msg) = read.next().await {
+                match msg {
+                    Ok(Message::Text(text)) => {
+                        capture_text("Qwen", CaptureDirection::Recv, &text);
+                        let response: ResponseEvent = serde_json::from_str(&text)
+                            .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
+
+                        if let Some(error) = response.error {
+                            return Err(AsrError::Api(error.message));
+                        }
 
-                    if let Some(error) = response.error {
-                        return Err(AsrError::Api(error.message));
+                        if response.event_type == "session.created"
+                            || response.event_type == "session.updated"
+                        {
+                            session_ready = true;
+                            break;
+                        }
                     }
-
-                    if response.event_type == "session.created"
-                        || response.event_type == "session.updated"
-                    {
-                        session_ready = true;
-                        break;
+                    Ok(Message::Close(_)) => {
+                        return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
                     }
+                    Err(e) => {
+                        return Err(AsrError::Network(e.to_string()));
+                    }
+                    _ => {}
                 }
-                Ok(Message::Close(_)) => {
-                    return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
-                }
-                Err(e) => {
-                    return Err(AsrError::Network(e.to_string()));
-                }
-                _ => {}
             }
-        }
 
-        if !session_ready {
-            return Err(AsrError::Api("未收到 session 确认事件".to_string()));
+            if !session_ready {
+                return Err(AsrError::Api("未收到 session 确认事件".to_string()));
+            }
+            session_cache::confirm(session_key);
         }
 
         // 检查音频数据是否为空
@@ -190,6 +223,7 @@ impl AsrService for QwenAsr {
             let audio_json = serde_json::to_string(&audio_append)
                 .map_err(|e| AsrError::Encoding(e.to_string()))?;
 
+            capture_text("Qwen", CaptureDirection::Send, &audio_json);
             write
                 .send(Message::Text(audio_json.into()))
                 .await
@@ -205,6 +239,7 @@ impl AsrService for QwenAsr {
         let commit_json =
             serde_json::to_string(&commit).map_err(|e| AsrError::Encoding(e.to_string()))?;
 
+        capture_text("Qwen", CaptureDirection::Send, &commit_json);
         write
             .send(Message::Text(commit_json.into()))
             .await
@@ -216,10 +251,12 @@ impl AsrService for QwenAsr {
         while let Some(msg) = read.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
+                    capture_text("Qwen", CaptureDirection::Recv, &text);
                     let response: ResponseEvent = serde_json::from_str(&text)
                         .map_err(|e| AsrError::Api(format!("解析响应失败: {}", e)))?;
 
                     if let Some(error) = response.error {
+                        session_cache::invalidate(session_key);
                         return Err(AsrError::Api(error.message));
                     }
 
@@ -238,6 +275,7 @@ impl AsrService for QwenAsr {
                         }
                         "error" => {
                             if let Some(error) = response.error {
+                                session_cache::invalidate(session_key);
                                 return Err(AsrError::Api(error.message));
                             }
                         }
@@ -257,28 +295,7 @@ impl AsrService for QwenAsr {
         Ok(AsrResult {
             text: final_text,
             is_final: true,
+            ..Default::default()
         })
     }
 }
-
-/// 测试通义千问 ASR API 连接
-pub async fn test_api(api_key: &str) -> Result<String, AsrError> {
-    use reqwest::Client;
-
-    let client = Client::new();
-    let response = client
-        .get("https://dashscope.aliyuncs.com/api/v1/models")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .send()
-        .await
-        .map_err(|e| AsrError::Network(e.to_string()))?;
-
-    if response.status().is_success() {
-        Ok("API Key 验证成功".to_string())
-    } else {
-        Err(AsrError::Api(format!(
-            "API Key 无效: HTTP {}",
-            response.status()
-        )))
-    }
-}
@@ -9,15 +9,21 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::timeout;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 /// WebSocket 连接超时时间
 const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 /// Session 确认超时时间
 const SESSION_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+/// 官方实时接口地址
+const DEFAULT_REALTIME_URL: &str = "wss://dashscope.aliyuncs.com/api-ws/v1/realtime";
 
+use super::session_cache;
+use super::streaming_session::StreamingSession;
 use super::traits::{AsrError, StreamingAsrEvent, StreamingAsrService, StreamingControl};
+use crate::debug::{capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::net::connect_async;
 
 fn generate_event_id() -> String {
     format!(
@@ -30,11 +36,45 @@ fn generate_event_id() -> String {
 pub struct QwenRealtimeAsr {
     api_key: String,
     model: String,
+    language: String,
+    /// 上下文（最近的识别结果），帮助识别重复出现的人名、术语
+    context: Option<String>,
+    /// 实时接口地址
+    url: String,
+    /// 手动断句：关闭服务端 VAD，只在提交时（松开快捷键）输出最终结果
+    manual_turn: bool,
 }
 
 impl QwenRealtimeAsr {
-    pub fn new(api_key: String, model: String) -> Self {
-        Self { api_key, model }
+    pub fn new(api_key: String, model: String, language: String) -> Self {
+        Self {
+            api_key,
+            model,
+            language,
+            context: None,
+            url: DEFAULT_REALTIME_URL.to_string(),
+            manual_turn: false,
+        }
+    }
+
+    /// 设置实时接口地址，None 使用官方地址
+    pub fn with_url(mut self, url: Option<String>) -> Self {
+        if let Some(url) = url {
+            self.url = url;
+        }
+        self
+    }
+
+    /// 设置上下文
+    pub fn with_context(mut self, context: Option<String>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// 手动断句：不由服务端按停顿把一段话切成多个最终结果
+    pub fn with_manual_turn(mut self, manual_turn: bool) -> Self {
+        self.manual_turn = manual_turn;
+        self
     }
 }
 
@@ -62,6 +102,14 @@ struct SessionConfig {
 #[derive(Serialize)]
 struct TranscriptionConfig {
     language: String,
+    /// 上下文增强文本
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corpus: Option<Corpus>,
+}
+
+#[derive(Serialize)]
+struct Corpus {
+    text: String,
 }
 
 #[derive(Serialize)]
@@ -116,16 +164,18 @@ impl StreamingAsrService for QwenRealtimeAsr {
     async fn start_streaming(
         &self,
         sample_rate: u32,
-    ) -> Result<(mpsc::Sender<StreamingControl>, mpsc::Receiver<StreamingAsrEvent>), AsrError> {
+    ) -> Result<StreamingSession, AsrError> {
         // 创建通道
         let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
         let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
 
         // 构建 WebSocket URL
-        let url = format!(
-            "wss://dashscope.aliyuncs.com/api-ws/v1/realtime?model={}",
-            self.model
-        );
+        let url = format!("{}?model={}", self.url, self.model);
+        let host = url
+            .parse::<http::Uri>()
+            .ok()
+            .and_then(|uri| uri.authority().map(|a| a.to_string()))
+            .ok_or_else(|| AsrError::Config(format!("无效的实时接口地址: {}", self.url)))?;
 
         // 创建带认证头的请求
         let request = http::Request::builder()
@@ -137,7 +187,7 @@ impl StreamingAsrService for QwenRealtimeAsr {
                 tokio_tungstenite::tungstenite::handshake::client::generate_key(),
             )
             .header("Sec-WebSocket-Version", "13")
-            .header("Host", "dashscope.aliyuncs.com")
+            .header("Host", host)
             .header("Connection", "Upgrade")
             .header("Upgrade", "websocket")
             .body(())
@@ -148,10 +198,11 @@ impl StreamingAsrService for QwenRealtimeAsr {
             .await
             .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))?
             .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        trace_ws_marker("QwenRealtime", "ws.connected");
 
         let (mut write, mut read) = ws_stream.split();
 
-        // 发送 session.update 配置（使用 VAD 模式实现实时识别）
+        // 发送 session.update 配置（默认使用 VAD 模式实现实时识别）
         let session_update = SessionUpdateEvent {
             event_id: generate_event_id(),
             event_type: "session.update".to_string(),
@@ -160,10 +211,11 @@ impl StreamingAsrService for QwenRealtimeAsr {
                 input_audio_format: "pcm".to_string(),
                 sample_rate,
                 input_audio_transcription: TranscriptionConfig {
-                    language: "zh".to_string(),
+                    language: self.language.clone(),
+                    corpus: self.context.clone().map(|text| Corpus { text }),
                 },
-                // VAD 模式：服务端自动检测语音边界
-                turn_detection: Some(TurnDetection {
+                // VAD 模式：服务端自动检测语音边界；手动断句时为 null，只在提交时识别
+                turn_detection: (!self.manual_turn).then(|| TurnDetection {
                     detection_type: "server_vad".to_string(),
                     threshold: 0.5,
                     silence_duration_ms: 500,
@@ -174,42 +226,61 @@ impl StreamingAsrService for QwenRealtimeAsr {
         let session_json =
             serde_json::to_string(&session_update).map_err(|e| AsrError::Encoding(e.to_string()))?;
 
+        capture_text("QwenRealtime", CaptureDirection::Send, &session_json);
+
+        trace_ws_message("QwenRealtime", CaptureDirection::Send, &session_json);
         write
             .send(Message::Text(session_json.into()))
             .await
             .map_err(|e| AsrError::Network(e.to_string()))?;
 
+        // 同一配置最近确认过时不再等待，确认事件和错误由后台任务处理
+        let session_key = session_cache::session_key(&[
+            &url,
+            &self.api_key,
+            &self.language,
+            &sample_rate.to_string(),
+            if self.manual_turn { "manual" } else { "vad" },
+        ]);
+
         // 等待 session 确认（带超时）
-        let session_confirm_result = timeout(SESSION_CONFIRM_TIMEOUT, async {
-            while let Some(msg) = read.next().await {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        if let Ok(response) = serde_json::from_str::<ResponseEvent>(&text) {
-                            if let Some(error) = response.error {
-                                return Err(AsrError::Api(error.message));
-                            }
-                            if response.event_type == "session.created"
-                                || response.event_type == "session.updated"
-                            {
-                                return Ok(());
+        let session_confirm_result = if session_cache::is_confirmed(session_key) {
+            trace_ws_marker("QwenRealtime", "session.cached");
+            Ok(Ok(()))
+        } else {
+            timeout(SESSION_CONFIRM_TIMEOUT, async {
+                while let Some(msg) = read.next().await {
+                    match msg {
+                        Ok(Message::Text(text)) => {
+                            capture_text("QwenRealtime", CaptureDirection::Recv, &text);
+                            trace_ws_message("QwenRealtime", CaptureDirection::Recv, &text);
+                            if let Ok(response) = serde_json::from_str::<ResponseEvent>(&text) {
+                                if let Some(error) = response.error {
+                                    return Err(AsrError::Api(error.message));
+                                }
+                                if response.event_type == "session.created"
+                                    || response.event_type == "session.updated"
+                                {
+                                    return Ok(());
+                                }
                             }
                         }
+                        Ok(Message::Close(_)) => {
+                            return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
+                        }
+                        Err(e) => {
+                            return Err(AsrError::Network(e.to_string()));
+                        }
+                        _ => {}
                     }
-                    Ok(Message::Close(_)) => {
-                        return Err(AsrError::Network("WebSocket 连接被关闭".to_string()));
-                    }
-                    Err(e) => {
-                        return Err(AsrError::Network(e.to_string()));
-                    }
-                    _ => {}
                 }
-            }
-            Err(AsrError::Api("未收到 session 确认事件".to_string()))
-        })
-        .await;
+                Err(AsrError::Api("未收到 session 确认事件".to_string()))
+            })
+            .await
+        };
 
         match session_confirm_result {
-            Ok(Ok(())) => {} // session 确认成功
+            Ok(Ok(())) => session_cache::confirm(session_key),
             Ok(Err(e)) => return Err(e),
             Err(_) => return Err(AsrError::Network("等待 session 确认超时".to_string())),
         }
@@ -232,6 +303,8 @@ impl StreamingAsrService for QwenRealtimeAsr {
                                     audio: BASE64.encode(&data),
                                 };
                                 if let Ok(json) = serde_json::to_string(&audio_append) {
+                                    capture_text("QwenRealtime", CaptureDirection::Send, &json);
+                                    trace_ws_message("QwenRealtime", CaptureDirection::Send, &json);
                                     if write.send(Message::Text(json.into())).await.is_err() {
                                         let _ = event_tx_clone.send(StreamingAsrEvent::Error(
                                             "发送音频失败".to_string()
@@ -247,6 +320,8 @@ impl StreamingAsrService for QwenRealtimeAsr {
                                     event_type: "input_audio_buffer.commit".to_string(),
                                 };
                                 if let Ok(json) = serde_json::to_string(&commit) {
+                                    capture_text("QwenRealtime", CaptureDirection::Send, &json);
+                                    trace_ws_message("QwenRealtime", CaptureDirection::Send, &json);
                                     let _ = write.send(Message::Text(json.into())).await;
                                 }
                             }
@@ -261,8 +336,11 @@ impl StreamingAsrService for QwenRealtimeAsr {
                     Some(msg) = read.next() => {
                         match msg {
                             Ok(Message::Text(text)) => {
+                                capture_text("QwenRealtime", CaptureDirection::Recv, &text);
+                                trace_ws_message("QwenRealtime", CaptureDirection::Recv, &text);
                                 if let Ok(response) = serde_json::from_str::<ResponseEvent>(&text) {
                                     if let Some(error) = response.error {
+                                        session_cache::invalidate(session_key);
                                         let _ = event_tx_clone.send(StreamingAsrEvent::Error(
                                             error.message
                                         )).await;
@@ -296,6 +374,7 @@ impl StreamingAsrService for QwenRealtimeAsr {
                                         }
                                         "error" => {
                                             if let Some(error) = response.error {
+                                                session_cache::invalidate(session_key);
                                                 let _ = event_tx_clone.send(StreamingAsrEvent::Error(
                                                     error.message
                                                 )).await;
@@ -306,9 +385,11 @@ impl StreamingAsrService for QwenRealtimeAsr {
                                 }
                             }
                             Ok(Message::Close(_)) => {
+                                trace_ws_marker("QwenRealtime", "ws.closed");
                                 break;
                             }
                             Err(e) => {
+                                trace_ws_marker("QwenRealtime", "ws.error");
                                 let _ = event_tx_clone.send(StreamingAsrEvent::Error(
                                     e.to_string()
                                 )).await;
@@ -322,6 +403,6 @@ impl StreamingAsrService for QwenRealtimeAsr {
             }
         });
 
-        Ok((control_tx, event_rx))
+        Ok(StreamingSession::new(control_tx, event_rx))
     }
 }
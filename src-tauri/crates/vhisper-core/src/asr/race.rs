@@ -0,0 +1,36 @@
+//! ASR 竞速
+//!
+//! 同一段音频同时发送给多个服务商，取最先返回的成功结果，
+//! 以双倍费用换取更低的延迟和更高的成功率；其余请求随之取消
+
+use async_trait::async_trait;
+use futures_util::future::select_ok;
+
+use super::traits::{AsrError, AsrResult, AsrService};
+
+/// 并发请求的 ASR 服务
+pub(crate) struct RacingAsr {
+    services: Vec<Box<dyn AsrService>>,
+}
+
+impl RacingAsr {
+    pub(crate) fn new(services: Vec<Box<dyn AsrService>>) -> Self {
+        Self { services }
+    }
+}
+
+#[async_trait]
+impl AsrService for RacingAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        if self.services.is_empty() {
+            return Err(AsrError::Config("没有可用的 ASR 服务商".to_string()));
+        }
+        // 全部失败时返回最后一个失败的错误
+        let requests = self
+            .services
+            .iter()
+            .map(|service| service.recognize(audio_data, sample_rate));
+        let (result, _pending) = select_ok(requests).await?;
+        Ok(result)
+    }
+}
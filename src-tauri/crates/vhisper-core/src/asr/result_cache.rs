@@ -0,0 +1,150 @@
+//! ASR 识别结果缓存
+//!
+//! 按音频内容和识别配置（服务商、模型、语言、热词等）的哈希缓存最近的识别结果，
+//! 出错后重试或重复提交同一段录音时直接返回，不再重复请求和计费。
+//! 结果同时保存在配置目录下的 asr_cache.json，重启后仍然有效。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::traits::{AsrError, AsrResult, AsrService};
+use crate::config::settings::AsrConfig;
+
+/// 最多保留的结果数
+const MAX_ENTRIES: usize = 32;
+/// 结果的有效期
+const ENTRY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Entry {
+    key: u64,
+    /// 缓存时间（Unix 时间戳，秒）
+    cached_at: u64,
+    result: AsrResult,
+}
+
+impl Entry {
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.cached_at) < ENTRY_TTL.as_secs()
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 缓存文件路径
+fn cache_path() -> Option<PathBuf> {
+    let app_dir = dirs::config_dir()?.join("com.vhisper.app");
+    fs::create_dir_all(&app_dir).ok()?;
+    Some(app_dir.join("asr_cache.json"))
+}
+
+/// 最近的结果（最新的在后），首次使用时从磁盘加载
+fn entries() -> &'static Mutex<VecDeque<Entry>> {
+    static ENTRIES: OnceLock<Mutex<VecDeque<Entry>>> = OnceLock::new();
+    ENTRIES.get_or_init(|| {
+        let now = now_secs();
+        let entries = cache_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<VecDeque<Entry>>(&content).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| entry.is_fresh(now))
+            .collect();
+        Mutex::new(entries)
+    })
+}
+
+fn lookup(key: u64) -> Option<AsrResult> {
+    let entries = entries().lock().ok()?;
+    let now = now_secs();
+    entries
+        .iter()
+        .find(|entry| entry.key == key && entry.is_fresh(now))
+        .map(|entry| entry.result.clone())
+}
+
+fn store(key: u64, result: &AsrResult) {
+    let Ok(mut entries) = entries().lock() else {
+        return;
+    };
+    let now = now_secs();
+    entries.retain(|entry| entry.key != key && entry.is_fresh(now));
+    entries.push_back(Entry {
+        key,
+        cached_at: now,
+        result: result.clone(),
+    });
+    while entries.len() > MAX_ENTRIES {
+        entries.pop_front();
+    }
+
+    let saved = cache_path().and_then(|path| {
+        let content = serde_json::to_string(&*entries).ok()?;
+        fs::write(path, content).ok()
+    });
+    if saved.is_none() {
+        tracing::debug!("Failed to persist ASR result cache");
+    }
+}
+
+/// 识别配置的哈希，配置的任何变化（换服务商、模型、语言等）都不会命中旧结果
+///
+/// 识别提示（最近的识别结果）不参与：同一段录音重试时提示可能已经变化
+pub(crate) fn config_key(config: &AsrConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(config)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 带结果缓存的 ASR 服务
+pub(crate) struct CachedAsr {
+    config_key: u64,
+    inner: Box<dyn AsrService>,
+}
+
+impl CachedAsr {
+    pub(crate) fn new(config_key: u64, inner: Box<dyn AsrService>) -> Self {
+        Self { config_key, inner }
+    }
+
+    fn key(&self, audio_data: &[u8], sample_rate: u32) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.config_key.hash(&mut hasher);
+        sample_rate.hash(&mut hasher);
+        audio_data.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[async_trait]
+impl AsrService for CachedAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        let key = self.key(audio_data, sample_rate);
+        if let Some(result) = lookup(key) {
+            tracing::info!("ASR result served from cache");
+            return Ok(result);
+        }
+
+        let result = self.inner.recognize(audio_data, sample_rate).await?;
+        // 空结果多半是静音或识别失败，不缓存，重试时重新请求；隐私模式下不写入磁盘
+        if !result.text.trim().is_empty() && !crate::privacy::is_incognito() {
+            store(key, &result);
+        }
+        Ok(result)
+    }
+}
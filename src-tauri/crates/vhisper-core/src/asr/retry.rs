@@ -0,0 +1,87 @@
+//! 识别请求的超时和重试
+//!
+//! 每次请求都有总超时，服务端不响应时不会让管道一直停在处理中；
+//! 网络错误（含超时）按指数退避重试，API 返回的错误（鉴权失败、参数错误等）直接返回
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::traits::{AsrError, AsrResult, AsrService};
+use crate::config::settings::RequestPolicy;
+use crate::net::apply_proxy;
+
+/// 重试等待时间的上限
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// 带超时和重试的 ASR 服务
+pub(crate) struct RetryingAsr {
+    provider: String,
+    policy: RequestPolicy,
+    inner: Box<dyn AsrService>,
+}
+
+impl RetryingAsr {
+    pub(crate) fn new(provider: String, policy: RequestPolicy, inner: Box<dyn AsrService>) -> Self {
+        Self {
+            provider,
+            policy,
+            inner,
+        }
+    }
+
+    /// 第 attempt 次重试前的等待时间
+    fn backoff(&self, attempt: u32) -> Duration {
+        let base = Duration::from_millis(self.policy.retry_backoff_ms);
+        base.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_BACKOFF)
+    }
+}
+
+#[async_trait]
+impl AsrService for RetryingAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        let request_timeout = Duration::from_secs(self.policy.request_timeout_secs.max(1));
+        let mut attempt = 0;
+        loop {
+            let result = tokio::time::timeout(
+                request_timeout,
+                self.inner.recognize(audio_data, sample_rate),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                Err(AsrError::Network(format!(
+                    "请求超时（{} 秒）",
+                    request_timeout.as_secs()
+                )))
+            });
+
+            match result {
+                Err(AsrError::Network(message)) if attempt < self.policy.max_retries => {
+                    let delay = self.backoff(attempt);
+                    attempt += 1;
+                    tracing::warn!(
+                        "ASR provider {} network error, retry {}/{} in {:?}: {}",
+                        self.provider,
+                        attempt,
+                        self.policy.max_retries,
+                        delay,
+                        message
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// 带连接超时的 HTTP 客户端
+pub(crate) fn http_client(policy: &RequestPolicy) -> reqwest::Client {
+    apply_proxy(reqwest::Client::builder())
+        .connect_timeout(Duration::from_secs(policy.connect_timeout_secs.max(1)))
+        .build()
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to build HTTP client with timeouts: {}", e);
+            reqwest::Client::new()
+        })
+}
@@ -0,0 +1,50 @@
+//! 实时识别会话缓存
+//!
+//! 通义千问实时接口每次连接都要发送 session.update 并等待服务端确认。
+//! 同一配置（地址、模型、密钥、识别语言）确认过一次后，一段时间内的后续连接不再等待确认，
+//! 发送配置后直接开始传输音频；配置变化时缓存键随之变化，服务端返回错误时清除缓存。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 确认结果的有效期
+const CONFIRMED_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 缓存键 -> 确认时间
+fn cache() -> &'static Mutex<HashMap<u64, Instant>> {
+    static CACHE: OnceLock<Mutex<HashMap<u64, Instant>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 会话配置的缓存键（只保存哈希，不保存密钥）
+pub(crate) fn session_key(parts: &[&str]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    parts.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 该配置最近是否被服务端确认过
+pub(crate) fn is_confirmed(key: u64) -> bool {
+    cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).copied())
+        .is_some_and(|at| at.elapsed() < CONFIRMED_TTL)
+}
+
+/// 记录服务端确认了该配置
+pub(crate) fn confirm(key: u64) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(key, Instant::now());
+    }
+}
+
+/// 服务端拒绝了该配置，下次连接重新等待确认
+pub(crate) fn invalidate(key: u64) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.remove(&key);
+    }
+}
@@ -0,0 +1,409 @@
+//! sherpa-onnx 本地流式 ASR 服务
+//!
+//! 识别由本机的 sherpa-onnx-online-websocket-server 完成，音频不离开本机。
+//! 首次使用时按配置的模型启动服务端进程，之后的会话复用同一进程。
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::streaming_session::StreamingSession;
+use super::traits::{
+    AsrError, AsrResult, AsrService, StreamingAsrEvent, StreamingAsrService, StreamingControl,
+};
+use crate::config::settings::SherpaOnnxConfig;
+use crate::debug::{capture_audio, capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::net::{connect_async, proxied_client};
+
+/// 模型下载地址
+const MODEL_BASE_URL: &str = "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models";
+/// 等待服务端加载模型、开始监听的最长时间
+const SERVER_START_TIMEOUT: Duration = Duration::from_secs(15);
+/// WebSocket 连接超时时间
+const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 服务端要求的采样率
+const SAMPLE_RATE: u32 = 16000;
+/// 批量识别时每次发送的音频时长（毫秒）
+const BATCH_CHUNK_MS: usize = 100;
+
+/// 由本应用启动的服务端进程
+static SERVER: Mutex<Option<Child>> = Mutex::new(None);
+
+/// sherpa-onnx 本地流式 ASR 服务
+pub struct SherpaOnnxAsr {
+    config: SherpaOnnxConfig,
+}
+
+impl SherpaOnnxAsr {
+    pub fn new(config: SherpaOnnxConfig) -> Self {
+        Self { config }
+    }
+}
+
+// ============================================================================
+// 模型
+// ============================================================================
+
+/// 模型目录
+pub fn model_dir(config: &SherpaOnnxConfig) -> Result<PathBuf, AsrError> {
+    if let Some(dir) = &config.model_dir {
+        return Ok(PathBuf::from(dir));
+    }
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| AsrError::Config("找不到应用数据目录".to_string()))?;
+    Ok(config_dir
+        .join("com.vhisper.app")
+        .join("models")
+        .join(&config.model))
+}
+
+/// 流式 transducer 模型的文件
+struct ModelFiles {
+    tokens: PathBuf,
+    encoder: PathBuf,
+    decoder: PathBuf,
+    joiner: PathBuf,
+}
+
+/// 在模型目录中查找模型文件，编码器和连接器优先使用 int8 量化版本
+fn find_model_files(dir: &Path) -> Result<ModelFiles, AsrError> {
+    let entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| AsrError::Config(format!("无法读取模型目录 {}: {}", dir.display(), e)))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+
+    let find = |prefix: &str, prefer_int8: bool| -> Result<PathBuf, AsrError> {
+        let candidates: Vec<&PathBuf> = entries
+            .iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(prefix) && name.ends_with(".onnx"))
+            })
+            .collect();
+        let is_int8 = |path: &&PathBuf| path.to_string_lossy().contains(".int8.");
+        candidates
+            .iter()
+            .find(|path| is_int8(path) == prefer_int8)
+            .or_else(|| candidates.first())
+            .map(|path| (*path).clone())
+            .ok_or_else(|| {
+                AsrError::Config(format!("模型目录 {} 中缺少 {} 模型", dir.display(), prefix))
+            })
+    };
+
+    let tokens = dir.join("tokens.txt");
+    if !tokens.exists() {
+        return Err(AsrError::Config(format!(
+            "模型目录 {} 中缺少 tokens.txt",
+            dir.display()
+        )));
+    }
+
+    Ok(ModelFiles {
+        tokens,
+        encoder: find("encoder", true)?,
+        decoder: find("decoder", false)?,
+        joiner: find("joiner", true)?,
+    })
+}
+
+/// 检查模型是否已就绪，返回模型目录
+pub fn check_model(config: &SherpaOnnxConfig) -> Result<PathBuf, AsrError> {
+    let dir = model_dir(config)?;
+    find_model_files(&dir)?;
+    Ok(dir)
+}
+
+/// 下载并解压模型到默认模型目录，已存在时直接返回
+pub async fn download_model(config: &SherpaOnnxConfig) -> Result<PathBuf, AsrError> {
+    if config.model_dir.is_some() {
+        return Err(AsrError::Config("自定义模型目录不支持自动下载".to_string()));
+    }
+    if let Ok(dir) = check_model(config) {
+        return Ok(dir);
+    }
+
+    let dir = model_dir(config)?;
+    let models_dir = dir
+        .parent()
+        .ok_or_else(|| AsrError::Config("无效的模型目录".to_string()))?;
+    tokio::fs::create_dir_all(models_dir)
+        .await
+        .map_err(|e| AsrError::Config(format!("无法创建模型目录: {}", e)))?;
+
+    let url = format!("{}/{}.tar.bz2", MODEL_BASE_URL, config.model);
+    tracing::info!("Downloading sherpa-onnx model from {}", url);
+    let response = proxied_client()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(AsrError::Api(format!(
+            "模型下载失败: HTTP {}",
+            response.status()
+        )));
+    }
+    let archive_data = response
+        .bytes()
+        .await
+        .map_err(|e| AsrError::Network(e.to_string()))?;
+
+    let archive = models_dir.join(format!("{}.tar.bz2", config.model));
+    tokio::fs::write(&archive, &archive_data)
+        .await
+        .map_err(|e| AsrError::Config(format!("无法保存模型: {}", e)))?;
+
+    // macOS 和 Windows 10+ 都自带 tar
+    let status = tokio::process::Command::new("tar")
+        .arg("-xjf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(models_dir)
+        .status()
+        .await
+        .map_err(|e| AsrError::Config(format!("无法解压模型: {}", e)))?;
+    let _ = tokio::fs::remove_file(&archive).await;
+    if !status.success() {
+        return Err(AsrError::Config(format!("解压模型失败: {}", status)));
+    }
+
+    check_model(config)
+}
+
+// ============================================================================
+// 服务端进程
+// ============================================================================
+
+async fn server_listening(port: u16) -> bool {
+    tokio::net::TcpStream::connect(("127.0.0.1", port))
+        .await
+        .is_ok()
+}
+
+/// 确保服务端在运行，未运行时按配置启动并等待其开始监听
+async fn ensure_server(config: &SherpaOnnxConfig) -> Result<(), AsrError> {
+    if server_listening(config.port).await {
+        return Ok(());
+    }
+
+    {
+        let mut server = SERVER
+            .lock()
+            .map_err(|e| AsrError::Config(format!("服务端状态锁错误: {}", e)))?;
+
+        // 已启动但仍在加载模型时不重复启动
+        let running = server
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(None)));
+        if !running {
+            let files = find_model_files(&model_dir(config)?)?;
+            tracing::info!("Starting {} on port {}", config.server_binary, config.port);
+            let child = Command::new(&config.server_binary)
+                .arg(format!("--port={}", config.port))
+                .arg(format!("--num-threads={}", config.num_threads))
+                .arg(format!("--tokens={}", files.tokens.display()))
+                .arg(format!("--encoder={}", files.encoder.display()))
+                .arg(format!("--decoder={}", files.decoder.display()))
+                .arg(format!("--joiner={}", files.joiner.display()))
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| {
+                    AsrError::Config(format!("无法启动 {}: {}", config.server_binary, e))
+                })?;
+            *server = Some(child);
+        }
+    }
+
+    let started = timeout(SERVER_START_TIMEOUT, async {
+        while !server_listening(config.port).await {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await;
+
+    started.map_err(|_| AsrError::Network("等待 sherpa-onnx 服务端启动超时".to_string()))
+}
+
+/// 停止由本应用启动的服务端（应用退出时调用）
+pub fn stop_server() {
+    if let Ok(mut server) = SERVER.lock() {
+        if let Some(mut child) = server.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+// ============================================================================
+// 流式服务实现
+// ============================================================================
+
+#[derive(Deserialize)]
+struct RecognitionResult {
+    #[serde(default)]
+    text: String,
+    /// 检测到端点（一句话结束）
+    #[serde(default)]
+    is_final: bool,
+}
+
+/// 16-bit PCM 转为服务端需要的 f32 little-endian
+fn pcm_to_f32_bytes(pcm: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(pcm.len() * 2);
+    for sample in pcm.chunks_exact(2) {
+        let value = i16::from_le_bytes([sample[0], sample[1]]) as f32 / i16::MAX as f32;
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data
+}
+
+#[async_trait]
+impl StreamingAsrService for SherpaOnnxAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<StreamingSession, AsrError> {
+        if sample_rate != SAMPLE_RATE {
+            return Err(AsrError::Config(format!(
+                "sherpa-onnx 只支持 {}Hz 音频",
+                SAMPLE_RATE
+            )));
+        }
+
+        ensure_server(&self.config).await?;
+
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        let url = format!("ws://127.0.0.1:{}", self.config.port);
+        let (ws_stream, _) = timeout(WS_CONNECT_TIMEOUT, connect_async(url.as_str()))
+            .await
+            .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))?
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        trace_ws_marker("SherpaOnnx", "ws.connected");
+
+        let (mut write, mut read) = ws_stream.split();
+
+        tokio::spawn(async move {
+            // 已结束的句子
+            let mut confirmed = String::new();
+            // 当前句子的最新识别结果
+            let mut current = String::new();
+
+            loop {
+                tokio::select! {
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                capture_audio("SherpaOnnx", CaptureDirection::Send, &data, sample_rate);
+                                let samples = pcm_to_f32_bytes(&data);
+                                if write.send(Message::Binary(samples.into())).await.is_err() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(
+                                        "发送音频失败".to_string()
+                                    )).await;
+                                    break;
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                // 输入结束，服务端处理完剩余音频后返回结果并关闭连接
+                                trace_ws_message("SherpaOnnx", CaptureDirection::Send, "Done");
+                                let _ = write.send(Message::Text("Done".into())).await;
+                            }
+                            StreamingControl::Cancel => {
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    msg = read.next() => {
+                        match msg {
+                            Some(Ok(Message::Text(text))) => {
+                                capture_text("SherpaOnnx", CaptureDirection::Recv, &text);
+                                trace_ws_message("SherpaOnnx", CaptureDirection::Recv, &text);
+                                let Ok(result) = serde_json::from_str::<RecognitionResult>(&text) else {
+                                    continue;
+                                };
+
+                                // 端点只表示一句话结束，服务端会自动开始下一句；
+                                // 会话在提交后服务端关闭连接时才结束
+                                if result.is_final {
+                                    confirmed.push_str(&result.text);
+                                    current.clear();
+                                } else {
+                                    current = result.text;
+                                }
+                                let _ = event_tx.send(StreamingAsrEvent::Partial {
+                                    text: confirmed.clone(),
+                                    stash: current.clone(),
+                                }).await;
+                            }
+                            // 服务端处理完提交的音频后关闭连接
+                            Some(Ok(Message::Close(_))) | None => {
+                                trace_ws_marker("SherpaOnnx", "ws.closed");
+                                confirmed.push_str(&current);
+                                let _ = event_tx.send(StreamingAsrEvent::Final {
+                                    text: confirmed,
+                                }).await;
+                                break;
+                            }
+                            Some(Err(e)) => {
+                                trace_ws_marker("SherpaOnnx", "ws.error");
+                                let _ = event_tx.send(StreamingAsrEvent::Error(
+                                    e.to_string()
+                                )).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(StreamingSession::new(control_tx, event_rx))
+    }
+}
+
+/// 非流式识别：一次性送入整段音频，等待最终结果
+#[async_trait]
+impl AsrService for SherpaOnnxAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        let mut session = self.start_streaming(sample_rate).await?;
+
+        let chunk_bytes = sample_rate as usize * 2 * BATCH_CHUNK_MS / 1000;
+        for chunk in audio_data.chunks(chunk_bytes.max(2)) {
+            session
+                .send_audio(chunk.to_vec())
+                .await
+                .map_err(|_| AsrError::Network("识别连接已关闭".to_string()))?;
+        }
+        let _ = session.commit().await;
+
+        // 整段音频中可能检测到多个端点，提交后服务端关闭连接时事件通道才关闭
+        let mut text = String::new();
+        while let Some(event) = session.next_event().await {
+            match event {
+                StreamingAsrEvent::Final { text: sentence } => text.push_str(&sentence),
+                StreamingAsrEvent::Error(message) => return Err(AsrError::Api(message)),
+                StreamingAsrEvent::Partial { .. } => {}
+            }
+        }
+
+        Ok(AsrResult {
+            text,
+            is_final: true,
+            segments: Vec::new(),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,140 @@
+//! 流式识别会话
+//!
+//! 包装服务商返回的控制通道和事件通道，提供暂停、恢复、提交和取消。
+//! 暂停期间音频替换为等长的静音继续发送：连接不会因为没有音频被服务端关闭，
+//! 服务端的时间戳也保持连续
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use tokio::sync::mpsc;
+
+use super::traits::{AsrError, StreamingAsrEvent, StreamingControl};
+
+/// 流式会话状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[repr(u8)]
+pub enum StreamingSessionState {
+    /// 正在发送音频
+    Active = 0,
+    /// 暂停（发送静音）
+    Paused = 1,
+    /// 已提交，等待最终结果
+    Committed = 2,
+    /// 已取消
+    Cancelled = 3,
+    /// 连接已结束
+    Closed = 4,
+}
+
+impl From<u8> for StreamingSessionState {
+    fn from(v: u8) -> Self {
+        match v {
+            1 => StreamingSessionState::Paused,
+            2 => StreamingSessionState::Committed,
+            3 => StreamingSessionState::Cancelled,
+            4 => StreamingSessionState::Closed,
+            _ => StreamingSessionState::Active,
+        }
+    }
+}
+
+/// 流式识别会话
+///
+/// 会话被丢弃时控制通道关闭，服务商结束连接
+pub struct StreamingSession {
+    control_tx: mpsc::Sender<StreamingControl>,
+    events: Option<mpsc::Receiver<StreamingAsrEvent>>,
+    state: AtomicU8,
+    paused: AtomicBool,
+}
+
+impl StreamingSession {
+    /// 由服务商的控制通道和事件通道创建
+    pub fn new(
+        control_tx: mpsc::Sender<StreamingControl>,
+        event_rx: mpsc::Receiver<StreamingAsrEvent>,
+    ) -> Self {
+        Self {
+            control_tx,
+            events: Some(event_rx),
+            state: AtomicU8::new(StreamingSessionState::Active as u8),
+            paused: AtomicBool::new(false),
+        }
+    }
+
+    /// 当前状态
+    pub fn state(&self) -> StreamingSessionState {
+        if self.control_tx.is_closed() {
+            return StreamingSessionState::Closed;
+        }
+        match self.state.load(Ordering::SeqCst).into() {
+            StreamingSessionState::Active if self.is_paused() => StreamingSessionState::Paused,
+            state => state,
+        }
+    }
+
+    /// 是否暂停（提交后等待结果期间也可能处于暂停）
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// 发送 PCM 音频，暂停期间发送等长的静音
+    pub async fn send_audio(&self, pcm: Vec<u8>) -> Result<(), AsrError> {
+        if self.state.load(Ordering::SeqCst) == StreamingSessionState::Cancelled as u8 {
+            return Err(AsrError::Cancelled);
+        }
+        let pcm = if self.is_paused() {
+            vec![0; pcm.len()]
+        } else {
+            pcm
+        };
+        self.send(StreamingControl::Audio(pcm)).await
+    }
+
+    /// 暂停：之后的音频替换为静音，返回是否切换了状态
+    pub fn pause(&self) -> bool {
+        !self.paused.swap(true, Ordering::SeqCst)
+    }
+
+    /// 恢复发送音频，返回是否切换了状态
+    pub fn resume(&self) -> bool {
+        self.paused.swap(false, Ordering::SeqCst)
+    }
+
+    /// 提交缓冲区，触发最终确认
+    pub async fn commit(&self) -> Result<(), AsrError> {
+        if self.state.load(Ordering::SeqCst) == StreamingSessionState::Cancelled as u8 {
+            return Err(AsrError::Cancelled);
+        }
+        self.state
+            .store(StreamingSessionState::Committed as u8, Ordering::SeqCst);
+        self.send(StreamingControl::Commit).await
+    }
+
+    /// 取消会话，不等待结果
+    pub async fn cancel(&self) {
+        let previous = self
+            .state
+            .swap(StreamingSessionState::Cancelled as u8, Ordering::SeqCst);
+        if previous != StreamingSessionState::Cancelled as u8 {
+            let _ = self.control_tx.send(StreamingControl::Cancel).await;
+        }
+    }
+
+    /// 接收下一个识别事件，连接结束或事件接收器已被取出时返回 None
+    pub async fn next_event(&mut self) -> Option<StreamingAsrEvent> {
+        self.events.as_mut()?.recv().await
+    }
+
+    /// 取出事件接收器，在另一个任务中接收事件
+    pub fn take_events(&mut self) -> Option<mpsc::Receiver<StreamingAsrEvent>> {
+        self.events.take()
+    }
+
+    async fn send(&self, control: StreamingControl) -> Result<(), AsrError> {
+        self.control_tx
+            .send(control)
+            .await
+            .map_err(|_| AsrError::Session("连接已结束".to_string()))
+    }
+}
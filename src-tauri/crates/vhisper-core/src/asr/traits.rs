@@ -1,5 +1,9 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use tokio::sync::mpsc;
+
+use super::streaming_session::StreamingSession;
+use crate::net::NetError;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AsrError {
@@ -17,11 +21,39 @@ pub enum AsrError {
     Cancelled,
 }
 
+impl From<NetError> for AsrError {
+    fn from(e: NetError) -> Self {
+        match e {
+            NetError::InvalidUrl(_)
+            | NetError::Certificate(_)
+            | NetError::Tls(_)
+            | NetError::Keychain(_) => AsrError::Config(e.to_string()),
+            NetError::Connect(_) => AsrError::Network(e.to_string()),
+            NetError::Status(_) => AsrError::Api(e.to_string()),
+        }
+    }
+}
+
 /// ASR 识别结果
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct AsrResult {
     pub text: String,
     pub is_final: bool,
+    /// 带时间戳的分句（服务商支持时提供，可用于导出字幕）
+    pub segments: Vec<AsrSegment>,
+    /// 服务商检测到的语言（ISO 639-1，如 "zh"、"en"），自动检测语言时提供
+    pub language: Option<String>,
+}
+
+/// 带时间戳的识别片段
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AsrSegment {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// 说话人编号（从 1 开始），开启说话人分离且服务商支持时提供
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<u32>,
 }
 
 /// 流式识别事件
@@ -60,18 +92,28 @@ pub trait AsrService: Send + Sync {
 pub trait StreamingAsrService: Send + Sync {
     /// 开始流式识别会话
     ///
-    /// 返回:
-    /// - 控制发送器：发送音频数据和控制命令
-    /// - 事件接收器：接收识别事件
-    ///
     /// 使用方式:
-    /// 1. 调用 start_streaming 获取通道
-    /// 2. 通过 control_tx 发送 StreamingControl::Audio(data) 推送音频
-    /// 3. 通过 event_rx 接收 StreamingAsrEvent 获取识别结果
-    /// 4. 发送 StreamingControl::Commit 触发最终确认
+    /// 1. 调用 start_streaming 获取会话
+    /// 2. 通过 send_audio 推送音频，pause / resume 暂停和恢复
+    /// 3. 通过 next_event 接收 StreamingAsrEvent 获取识别结果
+    /// 4. 调用 commit 触发最终确认（或 cancel 取消）
     /// 5. 等待 StreamingAsrEvent::Final 获取最终结果
-    async fn start_streaming(
-        &self,
-        sample_rate: u32,
-    ) -> Result<(mpsc::Sender<StreamingControl>, mpsc::Receiver<StreamingAsrEvent>), AsrError>;
+    ///
+    /// 实现方创建控制通道和事件通道，用 StreamingSession::new 包装后返回
+    async fn start_streaming(&self, sample_rate: u32) -> Result<StreamingSession, AsrError>;
+}
+
+/// 共享的服务实例（如通过 VoicePipelineBuilder 注入的服务）可直接作为 ASR 服务使用
+#[async_trait]
+impl<T: AsrService + ?Sized> AsrService for Arc<T> {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        (**self).recognize(audio_data, sample_rate).await
+    }
+}
+
+#[async_trait]
+impl<T: StreamingAsrService + ?Sized> StreamingAsrService for Arc<T> {
+    async fn start_streaming(&self, sample_rate: u32) -> Result<StreamingSession, AsrError> {
+        (**self).start_streaming(sample_rate).await
+    }
 }
@@ -0,0 +1,363 @@
+//! 火山引擎（豆包）流式 ASR 服务
+//!
+//! 使用大模型流式语音识别的 WebSocket 二进制协议 (wss /api/v3/sauc/bigmodel)：
+//! 每帧由 4 字节头、可选的序号和带长度前缀的负载组成。
+//! 首帧发送 JSON 请求参数，之后发送裸 PCM，最后一包音频带结束标志。
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+use super::streaming_session::StreamingSession;
+use super::traits::{
+    AsrError, AsrResult, AsrService, StreamingAsrEvent, StreamingAsrService, StreamingControl,
+};
+use crate::debug::{capture_audio, capture_text, trace_ws_marker, trace_ws_message, CaptureDirection};
+use crate::net::connect_async;
+
+const API_HOST: &str = "openspeech.bytedance.com";
+const API_PATH: &str = "/api/v3/sauc/bigmodel";
+/// WebSocket 连接超时时间
+const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 批量识别时每次发送的音频时长（毫秒），服务端建议 100~200ms 一包
+const BATCH_CHUNK_MS: usize = 200;
+
+// 协议头字段
+/// 协议版本 1，头长度 1 个 4 字节
+const HEADER_VERSION_SIZE: u8 = 0x11;
+const MSG_FULL_CLIENT_REQUEST: u8 = 0b0001;
+const MSG_AUDIO_ONLY_REQUEST: u8 = 0b0010;
+const MSG_FULL_SERVER_RESPONSE: u8 = 0b1001;
+const MSG_ERROR: u8 = 0b1111;
+/// 头后带有 4 字节序号
+const FLAG_HAS_SEQUENCE: u8 = 0b0001;
+/// 最后一包
+const FLAG_LAST: u8 = 0b0010;
+const SERIALIZATION_NONE: u8 = 0b0000;
+const SERIALIZATION_JSON: u8 = 0b0001;
+const COMPRESSION_NONE: u8 = 0b0000;
+
+/// 火山引擎流式 ASR 服务
+pub struct VolcengineAsr {
+    app_id: String,
+    access_token: String,
+    resource_id: String,
+}
+
+impl VolcengineAsr {
+    pub fn new(app_id: String, access_token: String, resource_id: String) -> Self {
+        Self {
+            app_id,
+            access_token,
+            resource_id,
+        }
+    }
+}
+
+/// 构造客户端帧（不带序号，负载不压缩）
+fn client_frame(message_type: u8, flags: u8, serialization: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(8 + payload.len());
+    frame.push(HEADER_VERSION_SIZE);
+    frame.push((message_type << 4) | flags);
+    frame.push((serialization << 4) | COMPRESSION_NONE);
+    frame.push(0);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// 服务端帧
+enum ServerFrame {
+    /// 识别结果，last 表示这是本次会话的最后一个结果
+    Response { payload: String, last: bool },
+    Error { code: u32, message: String },
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, AsrError> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| AsrError::Api("服务端帧长度不足".to_string()))
+}
+
+fn parse_server_frame(data: &[u8]) -> Result<ServerFrame, AsrError> {
+    if data.len() < 4 {
+        return Err(AsrError::Api("服务端帧长度不足".to_string()));
+    }
+    let header_size = (data[0] & 0x0f) as usize * 4;
+    let message_type = data[1] >> 4;
+    let flags = data[1] & 0x0f;
+    if data[2] & 0x0f != COMPRESSION_NONE {
+        return Err(AsrError::Api("不支持压缩的服务端帧".to_string()));
+    }
+
+    let body = data
+        .get(header_size..)
+        .ok_or_else(|| AsrError::Api("服务端帧长度不足".to_string()))?;
+    match message_type {
+        MSG_FULL_SERVER_RESPONSE => {
+            let mut offset = 0;
+            if flags & FLAG_HAS_SEQUENCE != 0 {
+                offset += 4;
+            }
+            let size = read_u32(body, offset)? as usize;
+            let payload = body
+                .get(offset + 4..offset + 4 + size)
+                .ok_or_else(|| AsrError::Api("服务端帧长度不足".to_string()))?;
+            Ok(ServerFrame::Response {
+                payload: String::from_utf8_lossy(payload).into_owned(),
+                last: flags & FLAG_LAST != 0,
+            })
+        }
+        MSG_ERROR => {
+            let code = read_u32(body, 0)?;
+            let size = read_u32(body, 4)? as usize;
+            let message = body.get(8..8 + size).unwrap_or_default();
+            Ok(ServerFrame::Error {
+                code,
+                message: String::from_utf8_lossy(message).into_owned(),
+            })
+        }
+        other => Err(AsrError::Api(format!("未知的服务端消息类型: {}", other))),
+    }
+}
+
+#[derive(Deserialize)]
+struct RecognitionResponse {
+    #[serde(default)]
+    result: RecognitionResult,
+}
+
+#[derive(Deserialize, Default)]
+struct RecognitionResult {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    utterances: Vec<Utterance>,
+}
+
+#[derive(Deserialize)]
+struct Utterance {
+    text: String,
+    /// 分句已确定，不会再被修正
+    #[serde(default)]
+    definite: bool,
+}
+
+impl RecognitionResult {
+    /// 拆分为已确定的文本和暂定文本
+    fn split(&self) -> (String, String) {
+        if self.utterances.is_empty() {
+            return (String::new(), self.text.clone());
+        }
+        let mut text = String::new();
+        let mut stash = String::new();
+        for utterance in &self.utterances {
+            if utterance.definite {
+                text.push_str(&utterance.text);
+            } else {
+                stash.push_str(&utterance.text);
+            }
+        }
+        (text, stash)
+    }
+}
+
+/// 会话参数（首帧负载）
+fn request_payload(sample_rate: u32) -> String {
+    serde_json::json!({
+        "user": { "uid": "vhisper" },
+        "audio": {
+            "format": "pcm",
+            "codec": "raw",
+            "rate": sample_rate,
+            "bits": 16,
+            "channel": 1,
+        },
+        "request": {
+            "model_name": "bigmodel",
+            "enable_itn": true,
+            "enable_punc": true,
+            "show_utterances": true,
+            "result_type": "full",
+        },
+    })
+    .to_string()
+}
+
+#[async_trait]
+impl StreamingAsrService for VolcengineAsr {
+    async fn start_streaming(
+        &self,
+        sample_rate: u32,
+    ) -> Result<StreamingSession, AsrError> {
+        let (control_tx, mut control_rx) = mpsc::channel::<StreamingControl>(32);
+        let (event_tx, event_rx) = mpsc::channel::<StreamingAsrEvent>(32);
+
+        let request = http::Request::builder()
+            .uri(format!("wss://{}{}", API_HOST, API_PATH))
+            .header("X-Api-App-Key", &self.app_id)
+            .header("X-Api-Access-Key", &self.access_token)
+            .header("X-Api-Resource-Id", &self.resource_id)
+            .header("X-Api-Connect-Id", Uuid::new_v4().to_string())
+            .header(
+                "Sec-WebSocket-Key",
+                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+            )
+            .header("Sec-WebSocket-Version", "13")
+            .header("Host", API_HOST)
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .body(())
+            .map_err(|e| AsrError::Network(e.to_string()))?;
+
+        let (ws_stream, _) = timeout(WS_CONNECT_TIMEOUT, connect_async(request))
+            .await
+            .map_err(|_| AsrError::Network("WebSocket 连接超时".to_string()))?
+            .map_err(|e| AsrError::Network(format!("WebSocket 连接失败: {}", e)))?;
+        trace_ws_marker("Volcengine", "ws.connected");
+
+        let (mut write, mut read) = ws_stream.split();
+
+        let payload = request_payload(sample_rate);
+        trace_ws_message("Volcengine", CaptureDirection::Send, &payload);
+        write
+            .send(Message::Binary(
+                client_frame(MSG_FULL_CLIENT_REQUEST, 0, SERIALIZATION_JSON, payload.as_bytes()).into(),
+            ))
+            .await
+            .map_err(|e| AsrError::Network(format!("发送会话参数失败: {}", e)))?;
+
+        tokio::spawn(async move {
+            // 最新的完整识别文本
+            let mut current = String::new();
+
+            loop {
+                tokio::select! {
+                    Some(control) = control_rx.recv() => {
+                        match control {
+                            StreamingControl::Audio(data) => {
+                                capture_audio("Volcengine", CaptureDirection::Send, &data, sample_rate);
+                                let frame = client_frame(MSG_AUDIO_ONLY_REQUEST, 0, SERIALIZATION_NONE, &data);
+                                if write.send(Message::Binary(frame.into())).await.is_err() {
+                                    let _ = event_tx.send(StreamingAsrEvent::Error(
+                                        "发送音频失败".to_string()
+                                    )).await;
+                                    break;
+                                }
+                            }
+                            StreamingControl::Commit => {
+                                // 空的最后一包：服务端识别完剩余音频后返回带结束标志的结果
+                                trace_ws_marker("Volcengine", "audio.last");
+                                let frame = client_frame(MSG_AUDIO_ONLY_REQUEST, FLAG_LAST, SERIALIZATION_NONE, &[]);
+                                let _ = write.send(Message::Binary(frame.into())).await;
+                            }
+                            StreamingControl::Cancel => {
+                                let _ = write.close().await;
+                                break;
+                            }
+                        }
+                    }
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(Message::Binary(data)) => {
+                                let frame = match parse_server_frame(&data) {
+                                    Ok(frame) => frame,
+                                    Err(e) => {
+                                        tracing::warn!("Volcengine: {}", e);
+                                        continue;
+                                    }
+                                };
+
+                                match frame {
+                                    ServerFrame::Response { payload, last } => {
+                                        capture_text("Volcengine", CaptureDirection::Recv, &payload);
+                                        trace_ws_message("Volcengine", CaptureDirection::Recv, &payload);
+                                        let Ok(response) = serde_json::from_str::<RecognitionResponse>(&payload) else {
+                                            continue;
+                                        };
+
+                                        if last {
+                                            let _ = event_tx.send(StreamingAsrEvent::Final {
+                                                text: response.result.text,
+                                            }).await;
+                                            let _ = write.close().await;
+                                            break;
+                                        }
+
+                                        current = response.result.text.clone();
+                                        let (text, stash) = response.result.split();
+                                        let _ = event_tx.send(StreamingAsrEvent::Partial { text, stash }).await;
+                                    }
+                                    ServerFrame::Error { code, message } => {
+                                        trace_ws_message("Volcengine", CaptureDirection::Recv, &message);
+                                        let _ = event_tx.send(StreamingAsrEvent::Error(
+                                            format!("{} ({})", message, code)
+                                        )).await;
+                                        break;
+                                    }
+                                }
+                            }
+                            Ok(Message::Close(_)) => {
+                                trace_ws_marker("Volcengine", "ws.closed");
+                                let _ = event_tx.send(StreamingAsrEvent::Final {
+                                    text: std::mem::take(&mut current),
+                                }).await;
+                                break;
+                            }
+                            Err(e) => {
+                                trace_ws_marker("Volcengine", "ws.error");
+                                let _ = event_tx.send(StreamingAsrEvent::Error(
+                                    e.to_string()
+                                )).await;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    else => break,
+                }
+            }
+        });
+
+        Ok(StreamingSession::new(control_tx, event_rx))
+    }
+}
+
+/// 非流式识别：一次性送入整段音频，等待最终结果
+#[async_trait]
+impl AsrService for VolcengineAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        let mut session = self.start_streaming(sample_rate).await?;
+
+        let chunk_bytes = sample_rate as usize * 2 * BATCH_CHUNK_MS / 1000;
+        for chunk in audio_data.chunks(chunk_bytes.max(2)) {
+            session
+                .send_audio(chunk.to_vec())
+                .await
+                .map_err(|_| AsrError::Network("识别连接已关闭".to_string()))?;
+        }
+        let _ = session.commit().await;
+
+        while let Some(event) = session.next_event().await {
+            match event {
+                StreamingAsrEvent::Final { text } => {
+                    return Ok(AsrResult {
+                        text,
+                        is_final: true,
+                        segments: Vec::new(),
+                        ..Default::default()
+                    });
+                }
+                StreamingAsrEvent::Error(message) => return Err(AsrError::Api(message)),
+                StreamingAsrEvent::Partial { .. } => {}
+            }
+        }
+
+        Err(AsrError::Network("识别连接已关闭".to_string()))
+    }
+}
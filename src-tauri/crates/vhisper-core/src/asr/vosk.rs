@@ -0,0 +1,228 @@
+//! Vosk 本地识别服务
+//!
+//! 运行时加载 libvosk，整段音频在本机识别，适合配置较低或无法联网的机器。
+//! 模型加载较慢，加载后在进程内缓存，配置变化时重新加载。
+
+use async_trait::async_trait;
+use libloading::Library;
+use serde::Deserialize;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::sync::Mutex;
+
+use super::traits::{AsrError, AsrResult, AsrSegment, AsrService};
+use crate::config::settings::VoskConfig;
+use crate::debug::{capture_audio, capture_text, CaptureDirection};
+
+/// 系统默认的 libvosk 库名
+#[cfg(target_os = "macos")]
+const DEFAULT_LIBRARY: &str = "libvosk.dylib";
+#[cfg(target_os = "windows")]
+const DEFAULT_LIBRARY: &str = "libvosk.dll";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_LIBRARY: &str = "libvosk.so";
+
+/// 每次送入识别器的采样数
+const CHUNK_SAMPLES: usize = 8000;
+
+/// libvosk C 接口
+struct VoskApi {
+    model_new: unsafe extern "C" fn(*const c_char) -> *mut c_void,
+    model_free: unsafe extern "C" fn(*mut c_void),
+    recognizer_new: unsafe extern "C" fn(*mut c_void, f32) -> *mut c_void,
+    recognizer_set_words: unsafe extern "C" fn(*mut c_void, c_int),
+    recognizer_accept_waveform_s: unsafe extern "C" fn(*mut c_void, *const i16, c_int) -> c_int,
+    recognizer_final_result: unsafe extern "C" fn(*mut c_void) -> *const c_char,
+    recognizer_free: unsafe extern "C" fn(*mut c_void),
+    // 函数指针依赖库保持加载
+    _library: Library,
+}
+
+/// 从库中取出函数指针
+///
+/// # Safety
+/// `T` 必须与库中该符号的实际签名一致
+unsafe fn symbol<T: Copy>(library: &Library, name: &str) -> Result<T, AsrError> {
+    library
+        .get::<T>(format!("{}\0", name).as_bytes())
+        .map(|symbol| *symbol)
+        .map_err(|e| AsrError::Config(format!("libvosk 缺少 {}: {}", name, e)))
+}
+
+impl VoskApi {
+    fn load(path: &str) -> Result<Self, AsrError> {
+        // SAFETY: 签名与 vosk_api.h 一致
+        unsafe {
+            let library = Library::new(path)
+                .map_err(|e| AsrError::Config(format!("无法加载 {}: {}", path, e)))?;
+
+            // 关闭 Kaldi 日志输出
+            let set_log_level: unsafe extern "C" fn(c_int) = symbol(&library, "vosk_set_log_level")?;
+            set_log_level(-1);
+
+            Ok(Self {
+                model_new: symbol(&library, "vosk_model_new")?,
+                model_free: symbol(&library, "vosk_model_free")?,
+                recognizer_new: symbol(&library, "vosk_recognizer_new")?,
+                recognizer_set_words: symbol(&library, "vosk_recognizer_set_words")?,
+                recognizer_accept_waveform_s: symbol(&library, "vosk_recognizer_accept_waveform_s")?,
+                recognizer_final_result: symbol(&library, "vosk_recognizer_final_result")?,
+                recognizer_free: symbol(&library, "vosk_recognizer_free")?,
+                _library: library,
+            })
+        }
+    }
+}
+
+/// 已加载的库和模型
+struct LoadedModel {
+    library_path: String,
+    model_dir: String,
+    api: VoskApi,
+    model: *mut c_void,
+}
+
+// SAFETY: Vosk 模型可在线程间共享，识别器在单次识别内创建和释放
+unsafe impl Send for LoadedModel {}
+
+impl Drop for LoadedModel {
+    fn drop(&mut self) {
+        // SAFETY: model 由 vosk_model_new 创建，只释放一次
+        unsafe { (self.api.model_free)(self.model) }
+    }
+}
+
+/// 当前加载的模型（同一时间只保留一个）
+static LOADED: Mutex<Option<LoadedModel>> = Mutex::new(None);
+
+/// 卸载已加载的模型，下次识别时重新加载
+pub fn unload_model() {
+    if let Ok(mut loaded) = LOADED.lock() {
+        if loaded.take().is_some() {
+            tracing::info!("Vosk model unloaded");
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VoskResult {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    result: Vec<VoskWord>,
+}
+
+#[derive(Deserialize)]
+struct VoskWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+/// Vosk 本地识别服务
+pub struct VoskAsr {
+    config: VoskConfig,
+}
+
+impl VoskAsr {
+    pub fn new(config: VoskConfig) -> Self {
+        Self { config }
+    }
+}
+
+/// 在当前线程中识别 16-bit PCM，返回 Vosk 的 JSON 结果
+fn recognize_blocking(config: &VoskConfig, pcm: &[u8], sample_rate: u32) -> Result<String, AsrError> {
+    let library_path = config.library_path.as_deref().unwrap_or(DEFAULT_LIBRARY);
+
+    let mut loaded = LOADED
+        .lock()
+        .map_err(|e| AsrError::Config(format!("Vosk 模型锁错误: {}", e)))?;
+
+    let cached = loaded
+        .as_ref()
+        .is_some_and(|l| l.library_path == library_path && l.model_dir == config.model_dir);
+    if !cached {
+        // 先释放旧模型，避免同时占用两份内存
+        *loaded = None;
+
+        let api = VoskApi::load(library_path)?;
+        let model_dir = CString::new(config.model_dir.as_str())
+            .map_err(|_| AsrError::Config("模型目录包含非法字符".to_string()))?;
+        tracing::info!("Loading Vosk model from {}", config.model_dir);
+        // SAFETY: model_dir 是合法的 C 字符串
+        let model = unsafe { (api.model_new)(model_dir.as_ptr()) };
+        if model.is_null() {
+            return Err(AsrError::Config(format!(
+                "无法加载 Vosk 模型: {}",
+                config.model_dir
+            )));
+        }
+        *loaded = Some(LoadedModel {
+            library_path: library_path.to_string(),
+            model_dir: config.model_dir.clone(),
+            api,
+            model,
+        });
+    }
+    let Some(loaded) = loaded.as_ref() else {
+        return Err(AsrError::Config("Vosk 模型未加载".to_string()));
+    };
+    let api = &loaded.api;
+
+    let samples: Vec<i16> = pcm
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+
+    // SAFETY: 识别器只在本函数内使用，结果字符串在释放识别器前复制
+    unsafe {
+        let recognizer = (api.recognizer_new)(loaded.model, sample_rate as f32);
+        if recognizer.is_null() {
+            return Err(AsrError::Config("无法创建 Vosk 识别器".to_string()));
+        }
+        (api.recognizer_set_words)(recognizer, 1);
+        for chunk in samples.chunks(CHUNK_SAMPLES) {
+            (api.recognizer_accept_waveform_s)(recognizer, chunk.as_ptr(), chunk.len() as c_int);
+        }
+        let json = CStr::from_ptr((api.recognizer_final_result)(recognizer))
+            .to_string_lossy()
+            .into_owned();
+        (api.recognizer_free)(recognizer);
+        Ok(json)
+    }
+}
+
+#[async_trait]
+impl AsrService for VoskAsr {
+    async fn recognize(&self, audio_data: &[u8], sample_rate: u32) -> Result<AsrResult, AsrError> {
+        capture_audio("Vosk", CaptureDirection::Send, audio_data, sample_rate);
+
+        // 识别是 CPU 密集的同步调用，放到阻塞线程池
+        let config = self.config.clone();
+        let pcm = audio_data.to_vec();
+        let json = tokio::task::spawn_blocking(move || recognize_blocking(&config, &pcm, sample_rate))
+            .await
+            .map_err(|e| AsrError::Session(e.to_string()))??;
+        capture_text("Vosk", CaptureDirection::Recv, &json);
+
+        let result: VoskResult =
+            serde_json::from_str(&json).map_err(|e| AsrError::Api(e.to_string()))?;
+
+        let segments = result
+            .result
+            .into_iter()
+            .map(|w| AsrSegment {
+                text: w.word,
+                start_ms: (w.start * 1000.0) as u64,
+                end_ms: (w.end * 1000.0) as u64,
+                speaker: None,
+            })
+            .collect();
+
+        Ok(AsrResult {
+            text: result.text,
+            is_final: true,
+            segments,
+            ..Default::default()
+        })
+    }
+}
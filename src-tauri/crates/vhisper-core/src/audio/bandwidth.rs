@@ -0,0 +1,87 @@
+//! 上传带宽估算
+//!
+//! 根据最近几次上传的实际吞吐量决定上传格式和分块大小
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::settings::UploadEncoding;
+
+/// 16kHz 16bit 单声道 PCM 的码率
+const PCM_KBPS: f64 = 256.0;
+/// 吞吐量低于 PCM 码率的这个倍数时视为低带宽
+const LOW_BANDWIDTH_FACTOR: f64 = 2.0;
+/// 指数平均的权重（新样本占比）
+const EWMA_ALPHA: f64 = 0.3;
+/// 太小的上传测不准，忽略
+const MIN_SAMPLE_BYTES: usize = 16 * 1024;
+
+static ESTIMATE_KBPS: Mutex<Option<f64>> = Mutex::new(None);
+
+/// 上传格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadFormat {
+    /// 无损（PCM / WAV）
+    Lossless,
+    /// Ogg/Opus 压缩
+    Opus,
+}
+
+/// 上传方案
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadPlan {
+    pub format: UploadFormat,
+    /// 流式上传时每块音频的时长
+    pub chunk_ms: u64,
+}
+
+/// 记录一次上传的数据量和耗时
+///
+/// 只在耗时能反映完整传输的地方调用（如 HTTP 上传接口，收到响应时数据已全部送达）；
+/// WebSocket 的 send 只是写入发送缓冲区，识别接口的响应还包含服务端处理时间，都测不准
+pub fn record_upload(bytes: usize, elapsed: Duration) {
+    if bytes < MIN_SAMPLE_BYTES || elapsed.is_zero() {
+        return;
+    }
+
+    let kbps = bytes as f64 * 8.0 / 1000.0 / elapsed.as_secs_f64();
+    let mut estimate = ESTIMATE_KBPS.lock().unwrap_or_else(|e| e.into_inner());
+    let updated = match *estimate {
+        Some(previous) => previous * (1.0 - EWMA_ALPHA) + kbps * EWMA_ALPHA,
+        None => kbps,
+    };
+    tracing::debug!("Upload throughput: {:.0} kbps (estimate {:.0} kbps)", kbps, updated);
+    *estimate = Some(updated);
+}
+
+/// 当前估算的上传带宽 (kbps)，没有记录时返回 None
+pub fn estimated_upload_kbps() -> Option<f64> {
+    *ESTIMATE_KBPS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 是否编译了 Opus 编码支持
+pub fn opus_available() -> bool {
+    cfg!(feature = "opus")
+}
+
+/// 根据配置和带宽估算选择上传方案
+///
+/// - supports_opus: 目标服务商是否接受 Ogg/Opus
+pub fn plan_upload(mode: UploadEncoding, supports_opus: bool) -> UploadPlan {
+    let low_bandwidth = estimated_upload_kbps()
+        .is_some_and(|kbps| kbps < PCM_KBPS * LOW_BANDWIDTH_FACTOR);
+    let can_use_opus = supports_opus && opus_available();
+
+    let format = match mode {
+        UploadEncoding::Lossless => UploadFormat::Lossless,
+        UploadEncoding::Opus if can_use_opus => UploadFormat::Opus,
+        UploadEncoding::Auto if low_bandwidth && can_use_opus => UploadFormat::Opus,
+        _ => UploadFormat::Lossless,
+    };
+
+    UploadPlan {
+        format,
+        // 低带宽时合并成更大的块，减少帧和请求开销
+        chunk_ms: if low_bandwidth { 200 } else { 50 },
+    }
+}
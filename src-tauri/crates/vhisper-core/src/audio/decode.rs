@@ -0,0 +1,94 @@
+//! 音频文件解码
+//!
+//! 使用 symphonia 解码 WAV、MP3、M4A（AAC）文件，混音为单声道并重采样到目标采样率
+
+use std::fs::File;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use super::{AudioError, Resampler};
+
+/// 支持的文件扩展名
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "mp4", "aac"];
+
+/// 解码音频文件为单声道 f32 样本
+pub fn decode_file(path: &Path, target_sample_rate: u32) -> Result<Vec<f32>, AudioError> {
+    let file =
+        File::open(path).map_err(|e| AudioError::Decoding(format!("{}: {}", path.display(), e)))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| AudioError::Decoding(format!("不支持的音频格式: {}", e)))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| AudioError::Decoding("文件中没有音频轨道".to_string()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| AudioError::Decoding(format!("不支持的音频编码: {}", e)))?;
+
+    // 采样率和声道数以第一个解码出的数据包为准
+    let mut resampler: Option<Resampler> = None;
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(AudioError::Decoding(e.to_string())),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // 个别损坏的数据包跳过
+            Err(SymphoniaError::DecodeError(e)) => {
+                tracing::warn!("Skipping undecodable packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(AudioError::Decoding(e.to_string())),
+        };
+
+        let spec = *decoded.spec();
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        resampler
+            .get_or_insert_with(|| {
+                Resampler::new(spec.rate, target_sample_rate, spec.channels.count())
+            })
+            .process(buffer.samples(), &mut samples);
+    }
+
+    tracing::info!(
+        "Decoded {:?}: {} samples at {}Hz",
+        path,
+        samples.len(),
+        target_sample_rate
+    );
+    Ok(samples)
+}
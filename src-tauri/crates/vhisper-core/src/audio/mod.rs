@@ -1,8 +1,33 @@
+mod bandwidth;
+mod decode;
+#[cfg(feature = "opus")]
+mod opus;
+mod playback;
+mod priority;
 mod recorder;
+mod resample;
+mod trim;
+mod vad;
 
-pub use recorder::AudioRecorder;
+pub use bandwidth::{
+    estimated_upload_kbps, opus_available, plan_upload, record_upload, UploadFormat, UploadPlan,
+};
+pub use decode::{decode_file, SUPPORTED_EXTENSIONS};
+#[cfg(feature = "opus")]
+pub use opus::encode_to_ogg_opus;
+pub use playback::{
+    begin_playback, is_capture_suppressed, is_echo_suppression_enabled, set_echo_suppression,
+    suppress_for, PlaybackGuard,
+};
+pub use recorder::{
+    check_capture_device, has_input_device, input_device_names, AudioRecorder, CaptureDevice,
+};
+pub use resample::Resampler;
+pub use trim::{suppress_edge_transients, trim_edges};
+pub use vad::EnergyVad;
 
 use std::io::Cursor;
+use std::sync::Arc;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AudioError {
@@ -14,6 +39,8 @@ pub enum AudioError {
     Encoding(String),
     #[error("Device error: {0}")]
     Device(String),
+    #[error("Decoding error: {0}")]
+    Decoding(String),
 }
 
 /// 将 f32 采样数据编码为 PCM 格式 (16-bit little-endian)
@@ -54,3 +81,107 @@ pub fn encode_to_wav(samples: &[f32], sample_rate: u32, channels: u16) -> Result
 
     Ok(cursor.into_inner())
 }
+
+/// 将 f32 采样数据编码为 Ogg/Opus（未启用 opus feature）
+#[cfg(not(feature = "opus"))]
+pub fn encode_to_ogg_opus(_samples: &[f32], _sample_rate: u32) -> Result<Vec<u8>, AudioError> {
+    Err(AudioError::Encoding("未启用 Opus 编码支持".to_string()))
+}
+
+/// 上传音频的编码格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeFormat {
+    /// 16-bit PCM，无容器
+    Pcm,
+    /// WAV 容器
+    Wav { channels: u16 },
+    /// Ogg/Opus
+    OggOpus,
+}
+
+/// 按指定格式编码
+pub fn encode(samples: &[f32], sample_rate: u32, format: EncodeFormat) -> Result<Vec<u8>, AudioError> {
+    match format {
+        EncodeFormat::Pcm => Ok(encode_to_pcm(samples)),
+        EncodeFormat::Wav { channels } => encode_to_wav(samples, sample_rate, channels),
+        EncodeFormat::OggOpus => encode_to_ogg_opus(samples, sample_rate),
+    }
+}
+
+/// 在阻塞线程池中编码
+///
+/// 长录音的编码耗时可达数百毫秒，直接在异步任务中执行会占住运行时线程，
+/// 拖慢同一运行时上的快捷键、事件转发等任务
+pub async fn encode_blocking(
+    samples: Arc<Vec<f32>>,
+    sample_rate: u32,
+    format: EncodeFormat,
+) -> Result<Vec<u8>, AudioError> {
+    tokio::task::spawn_blocking(move || encode(&samples, sample_rate, format))
+        .await
+        .map_err(|e| AudioError::Encoding(format!("编码任务失败: {}", e)))?
+}
+
+/// 音频电平统计
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelStats {
+    /// 最大振幅
+    pub max: f32,
+    /// 平均振幅
+    pub avg: f32,
+    /// 非零样本数
+    pub non_zero: usize,
+}
+
+/// 统计音频电平，用于检测静音和音量过低
+pub fn level_stats(samples: &[f32]) -> LevelStats {
+    let mut max = 0.0f32;
+    let mut sum = 0.0f32;
+    let mut non_zero = 0;
+    for &sample in samples {
+        let amplitude = sample.abs();
+        max = max.max(amplitude);
+        sum += amplitude;
+        if sample != 0.0 {
+            non_zero += 1;
+        }
+    }
+
+    LevelStats {
+        max,
+        avg: if samples.is_empty() { 0.0 } else { sum / samples.len() as f32 },
+        non_zero,
+    }
+}
+
+/// 在阻塞线程池中统计音频电平
+pub async fn level_stats_blocking(samples: Arc<Vec<f32>>) -> Result<LevelStats, AudioError> {
+    tokio::task::spawn_blocking(move || level_stats(&samples))
+        .await
+        .map_err(|e| AudioError::Encoding(format!("电平统计任务失败: {}", e)))
+}
+
+/// 是否是 Ogg 容器数据
+pub fn is_ogg(data: &[u8]) -> bool {
+    data.starts_with(b"OggS")
+}
+
+/// 取出 WAV 中的 PCM 数据，不是 WAV 时返回 None
+pub fn wav_payload(data: &[u8]) -> Option<&[u8]> {
+    if data.len() < 12 || !data.starts_with(b"RIFF") || &data[8..12] != b"WAVE" {
+        return None;
+    }
+    // 逐个跳过 data 之前的块（fmt、LIST 等）
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let id = &data[offset..offset + 4];
+        let size = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().ok()?) as usize;
+        let start = offset + 8;
+        if id == b"data" {
+            return Some(&data[start..(start + size).min(data.len())]);
+        }
+        // 块按 2 字节对齐
+        offset = start + size + size % 2;
+    }
+    None
+}
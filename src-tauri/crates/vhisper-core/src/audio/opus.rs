@@ -0,0 +1,85 @@
+//! Ogg/Opus 编码
+
+use audiopus::coder::Encoder;
+use audiopus::{Application, Bitrate, Channels, SampleRate};
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+use super::AudioError;
+
+/// 每帧时长 20ms
+const FRAME_MS: usize = 20;
+/// 语音用 24kbps 足够清晰，约为 16kHz PCM 的 1/10
+const BITRATE: i32 = 24_000;
+/// Ogg 逻辑流序列号
+const STREAM_SERIAL: u32 = 1;
+
+fn opus_error(e: audiopus::Error) -> AudioError {
+    AudioError::Encoding(format!("Opus: {}", e))
+}
+
+/// 将单声道 f32 采样编码为 Ogg/Opus
+///
+/// 采样率必须是 8000/12000/16000/24000/48000 之一
+pub fn encode_to_ogg_opus(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, AudioError> {
+    let rate = SampleRate::try_from(sample_rate as i32).map_err(opus_error)?;
+    let mut encoder = Encoder::new(rate, Channels::Mono, Application::Voip).map_err(opus_error)?;
+    encoder
+        .set_bitrate(Bitrate::BitsPerSecond(BITRATE))
+        .map_err(opus_error)?;
+
+    // Ogg 中的时间戳和 pre-skip 统一按 48kHz 计算
+    let scale = 48_000 / sample_rate as u64;
+    let pre_skip = encoder.lookahead().map_err(opus_error)? as u64 * scale;
+    let frame_size = sample_rate as usize * FRAME_MS / 1000;
+
+    let mut writer = PacketWriter::new(Vec::new());
+    let write = |writer: &mut PacketWriter<Vec<u8>>, packet: Vec<u8>, info, granule| {
+        writer
+            .write_packet(packet.into_boxed_slice(), STREAM_SERIAL, info, granule)
+            .map_err(|e| AudioError::Encoding(e.to_string()))
+    };
+
+    // OpusHead
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channel count
+    head.extend_from_slice(&(pre_skip as u16).to_le_bytes());
+    head.extend_from_slice(&sample_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+    write(&mut writer, head, PacketWriteEndInfo::EndPage, 0)?;
+
+    // OpusTags
+    let vendor = b"vhisper";
+    let mut tags = Vec::with_capacity(16 + vendor.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes()); // user comment count
+    write(&mut writer, tags, PacketWriteEndInfo::EndPage, 0)?;
+
+    let frame_count = samples.len().div_ceil(frame_size).max(1);
+    let mut frame = vec![0.0f32; frame_size];
+    let mut output = [0u8; 4000];
+
+    for index in 0..frame_count {
+        let start = index * frame_size;
+        let end = (start + frame_size).min(samples.len());
+        frame.fill(0.0);
+        if start < end {
+            frame[..end - start].copy_from_slice(&samples[start..end]);
+        }
+
+        let len = encoder.encode_float(&frame, &mut output).map_err(opus_error)?;
+        let info = if index + 1 == frame_count {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        let granule = pre_skip + (end as u64) * scale;
+        write(&mut writer, output[..len].to_vec(), info, granule)?;
+    }
+
+    Ok(writer.into_inner())
+}
@@ -0,0 +1,68 @@
+//! 本机播放期间静音录音（回声抑制）
+//!
+//! 提示音、屏幕阅读器播报等从扬声器播放的声音会被麦克风录进去，被识别成多余的字。
+//! 播放方在播放期间持有 `begin_playback` 返回的守卫，或按预计时长调用 `suppress_for`；
+//! 期间（以及结束后的一小段余响时间）输入设备采集的音频替换为静音，保持时间线不变，
+//! 流式识别和 VAD 只会看到静音。外部音频源（系统声音回环）不受影响
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// 播放结束后继续静音的余响时间
+const ECHO_TAIL: Duration = Duration::from_millis(200);
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+/// 正在播放的数量
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+/// 静音截止时间（相对 EPOCH 的毫秒数），录音回调中读取，不加锁
+static SUPPRESS_UNTIL_MS: AtomicU64 = AtomicU64::new(0);
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+fn now_ms() -> u64 {
+    EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+/// 把静音截止时间延后到 now + duration（不会提前）
+fn extend_until(duration: Duration) {
+    let until = now_ms() + duration.as_millis() as u64;
+    SUPPRESS_UNTIL_MS.fetch_max(until, Ordering::SeqCst);
+}
+
+/// 开启或关闭回声抑制
+pub fn set_echo_suppression(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// 回声抑制是否开启
+pub fn is_echo_suppression_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 播放守卫，释放后再静音一小段余响时间
+pub struct PlaybackGuard(());
+
+impl Drop for PlaybackGuard {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+        extend_until(ECHO_TAIL);
+    }
+}
+
+/// 开始播放，守卫存活期间录音静音
+pub fn begin_playback() -> PlaybackGuard {
+    ACTIVE.fetch_add(1, Ordering::SeqCst);
+    PlaybackGuard(())
+}
+
+/// 播放时长已知但无法得知结束时（如屏幕阅读器播报），按预计时长静音
+pub fn suppress_for(duration: Duration) {
+    extend_until(duration + ECHO_TAIL);
+}
+
+/// 当前是否应把录音替换为静音
+pub fn is_capture_suppressed() -> bool {
+    is_echo_suppression_enabled()
+        && (ACTIVE.load(Ordering::SeqCst) > 0
+            || now_ms() < SUPPRESS_UNTIL_MS.load(Ordering::SeqCst))
+}
@@ -0,0 +1,107 @@
+//! 录音线程的优先级
+//!
+//! CPU 繁忙（编译、视频会议等）时普通优先级的音频线程可能来不及取走设备缓冲区，
+//! 造成录音断续。这里按平台提升两类线程：
+//! - 音频回调线程（cpal 创建，负责重采样和 VAD）：Windows 注册 MMCSS「Pro Audio」任务，
+//!   Linux 尝试 SCHED_FIFO；macOS 的回调运行在 HAL IO 线程上，该线程本身就是实时线程
+//!   并已加入设备的 audio workgroup，不需要处理
+//! - 录音控制线程（打开设备、等待停止命令）：macOS 设为 user-interactive QoS，
+//!   Windows 设为较高优先级
+//!
+//! 提升失败（权限不足等）只记录日志，不影响录音
+
+/// 提升当前音频回调线程的优先级
+pub(crate) fn promote_callback_thread() {
+    match platform::promote_callback_thread() {
+        Ok(()) => tracing::debug!("Audio callback thread promoted"),
+        Err(e) => tracing::debug!("Failed to promote audio callback thread: {}", e),
+    }
+}
+
+/// 提升当前录音控制线程的优先级
+pub(crate) fn promote_recorder_thread() {
+    match platform::promote_recorder_thread() {
+        Ok(()) => tracing::debug!("Recorder thread promoted"),
+        Err(e) => tracing::debug!("Failed to promote recorder thread: {}", e),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::System::Threading::{
+        AvSetMmThreadCharacteristicsW, GetCurrentThread, SetThreadPriority,
+        THREAD_PRIORITY_ABOVE_NORMAL,
+    };
+
+    pub(super) fn promote_callback_thread() -> Result<(), String> {
+        // MMCSS 任务在线程退出时自动注销
+        let task: Vec<u16> = "Pro Audio".encode_utf16().chain(Some(0)).collect();
+        let mut task_index = 0u32;
+        let handle = unsafe { AvSetMmThreadCharacteristicsW(task.as_ptr(), &mut task_index) };
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+
+    pub(super) fn promote_recorder_thread() -> Result<(), String> {
+        let ok = unsafe { SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_ABOVE_NORMAL) };
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub(super) fn promote_callback_thread() -> Result<(), String> {
+        // HAL IO 线程已是实时线程，修改 QoS 反而会使其失去实时调度
+        Ok(())
+    }
+
+    pub(super) fn promote_recorder_thread() -> Result<(), String> {
+        let result = unsafe {
+            libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_USER_INTERACTIVE, 0)
+        };
+        if result != 0 {
+            return Err(std::io::Error::from_raw_os_error(result).to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    /// SCHED_FIFO 优先级，低于 PipeWire/JACK 等音频服务的默认值
+    const FIFO_PRIORITY: libc::c_int = 10;
+
+    pub(super) fn promote_callback_thread() -> Result<(), String> {
+        // 普通用户通常没有 CAP_SYS_NICE 或 RLIMIT_RTPRIO，此时返回 EPERM
+        let param = libc::sched_param {
+            sched_priority: FIFO_PRIORITY,
+        };
+        let result =
+            unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+        if result != 0 {
+            return Err(std::io::Error::from_raw_os_error(result).to_string());
+        }
+        Ok(())
+    }
+
+    pub(super) fn promote_recorder_thread() -> Result<(), String> {
+        // 控制线程大部分时间在等待命令，保持默认优先级
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    pub(super) fn promote_callback_thread() -> Result<(), String> {
+        Err("unsupported platform".to_string())
+    }
+
+    pub(super) fn promote_recorder_thread() -> Result<(), String> {
+        Err("unsupported platform".to_string())
+    }
+}
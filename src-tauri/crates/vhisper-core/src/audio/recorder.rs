@@ -1,15 +1,38 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, mpsc};
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
+use super::playback::is_capture_suppressed;
+use super::priority::{promote_callback_thread, promote_recorder_thread};
+use super::resample::Resampler;
+use super::vad::EnergyVad;
 use super::AudioError;
 
+/// 语音活动指示的默认能量阈值（RMS）
+const DEFAULT_SPEECH_THRESHOLD: f32 = 0.02;
+/// 语音结束后保持「正在说话」的时长（毫秒），避免字词间的短暂停顿造成闪烁
+const SPEECH_HANGOVER_MS: u32 = 300;
+
 /// 录音控制命令
 enum RecorderCommand {
     Start,
     Stop,
 }
 
+/// 录音使用的设备
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CaptureDevice {
+    /// 系统默认输入设备
+    #[default]
+    DefaultInput,
+    /// 指定名称的输入设备（如 BlackHole 等系统声音回环虚拟设备）
+    Named(String),
+    /// 默认输出设备的回环，采集系统播放的声音（仅 Windows WASAPI 支持）
+    OutputLoopback,
+}
+
 /// 录音状态
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RecordingState {
@@ -25,6 +48,21 @@ pub struct AudioRecorder {
     state: Arc<Mutex<RecordingState>>,
     command_tx: Option<mpsc::Sender<RecorderCommand>>,
     worker_handle: Option<JoinHandle<()>>,
+    /// 外部音频源：不打开输入设备，音频由 push_samples 写入
+    external: bool,
+    /// 录音设备
+    device: CaptureDevice,
+    /// 最近一块音频的峰值电平（f32 位模式），用于电平表
+    level: Arc<AtomicU32>,
+    /// 本地 VAD 判断当前是否在说话，用于区分「听到说话」和「只有噪音」
+    speech: Arc<AtomicBool>,
+    speech_threshold: f32,
+    /// 提升录音线程和音频回调线程的优先级
+    realtime_priority: bool,
+    /// 外部音频源的语音检测状态（输入设备的在录音线程中）
+    external_activity: Mutex<Option<SpeechActivity>>,
+    /// 本次录音累计的样本数（缓冲区会被流式识别取走，单独计数）
+    recorded: Arc<AtomicUsize>,
 }
 
 impl AudioRecorder {
@@ -37,9 +75,70 @@ impl AudioRecorder {
             state: Arc::new(Mutex::new(RecordingState::Idle)),
             command_tx: None,
             worker_handle: None,
+            external: false,
+            device: CaptureDevice::DefaultInput,
+            level: Arc::new(AtomicU32::new(0)),
+            speech: Arc::new(AtomicBool::new(false)),
+            speech_threshold: DEFAULT_SPEECH_THRESHOLD,
+            realtime_priority: true,
+            external_activity: Mutex::new(None),
+            recorded: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// 创建不使用输入设备的录音器（文件、回环等外部音频源）
+    ///
+    /// 音频通过 push_samples 写入，要求已是 16kHz 单声道
+    pub fn external() -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(Vec::new())),
+            sample_rate: 16000,
+            channels: 1,
+            state: Arc::new(Mutex::new(RecordingState::Idle)),
+            command_tx: None,
+            worker_handle: None,
+            external: true,
+            device: CaptureDevice::DefaultInput,
+            level: Arc::new(AtomicU32::new(0)),
+            speech: Arc::new(AtomicBool::new(false)),
+            speech_threshold: DEFAULT_SPEECH_THRESHOLD,
+            realtime_priority: true,
+            external_activity: Mutex::new(None),
+            recorded: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// 创建使用指定设备的录音器
+    pub fn with_device(device: CaptureDevice) -> Result<Self, AudioError> {
+        Ok(Self {
+            device,
+            ..Self::new()?
         })
     }
 
+    /// 设置语音活动检测的能量阈值（RMS），下次开始录音时生效
+    pub fn set_speech_threshold(&mut self, threshold: f32) {
+        self.speech_threshold = threshold;
+    }
+
+    /// 设置是否提升录音线程的优先级，下次开始录音时生效
+    pub fn set_realtime_priority(&mut self, enabled: bool) {
+        self.realtime_priority = enabled;
+    }
+
+    /// 写入外部音频，未在录音时或使用输入设备时丢弃
+    pub fn push_samples(&self, samples: &[f32]) {
+        if !self.external || *self.state.lock().unwrap() != RecordingState::Recording {
+            return;
+        }
+        store_level(&self.level, samples);
+        if let Some(activity) = self.external_activity.lock().unwrap().as_mut() {
+            activity.process(samples);
+        }
+        self.recorded.fetch_add(samples.len(), Ordering::Relaxed);
+        self.buffer.lock().unwrap().extend_from_slice(samples);
+    }
+
     /// 开始录音
     pub fn start(&mut self) -> Result<(), AudioError> {
         {
@@ -54,19 +153,39 @@ impl AudioRecorder {
             let mut buffer = self.buffer.lock().unwrap();
             buffer.clear();
         }
+        self.recorded.store(0, Ordering::Relaxed);
+
+        let activity =
+            SpeechActivity::new(self.sample_rate, self.speech_threshold, self.speech.clone());
+        if self.external {
+            *self.external_activity.lock().unwrap() = Some(activity);
+            *self.state.lock().unwrap() = RecordingState::Recording;
+            tracing::info!("Recording started (external source)");
+            return Ok(());
+        }
 
         // 创建命令通道
         let (tx, rx) = mpsc::channel::<RecorderCommand>();
         self.command_tx = Some(tx);
 
         // 克隆需要的数据给工作线程
-        let buffer = self.buffer.clone();
+        let sink = TrackSink {
+            sample_rate: self.sample_rate,
+            buffer: self.buffer.clone(),
+            level: self.level.clone(),
+            activity,
+            recorded: self.recorded.clone(),
+        };
         let state = self.state.clone();
-        let target_sample_rate = self.sample_rate;
+        let realtime_priority = self.realtime_priority;
+        let device = self.device.clone();
 
         // 启动工作线程
         let handle = thread::spawn(move || {
-            if let Err(e) = run_recording_loop(rx, buffer, state, target_sample_rate) {
+            if realtime_priority {
+                promote_recorder_thread();
+            }
+            if let Err(e) = run_recording_loop(rx, device, sink, state, realtime_priority) {
                 tracing::error!("Recording thread error: {}", e);
             }
         });
@@ -110,6 +229,9 @@ impl AudioRecorder {
             let mut state = self.state.lock().unwrap();
             *state = RecordingState::Idle;
         }
+        self.level.store(0, Ordering::Relaxed);
+        self.speech.store(false, Ordering::Relaxed);
+        *self.external_activity.lock().unwrap() = None;
 
         // 获取录制的数据
         let buffer = self.buffer.lock().unwrap();
@@ -137,39 +259,175 @@ impl AudioRecorder {
         std::mem::take(&mut *buffer)
     }
 
+    /// 获取从 offset 开始的音频数据（不清空缓冲区）
+    pub fn samples_from(&self, offset: usize) -> Vec<f32> {
+        let buffer = self.buffer.lock().unwrap();
+        buffer.get(offset..).map(|s| s.to_vec()).unwrap_or_default()
+    }
+
+    /// 取出缓冲区开头的 len 个样本，其余数据保留
+    pub fn take_front(&self, len: usize) -> Vec<f32> {
+        let mut buffer = self.buffer.lock().unwrap();
+        let len = len.min(buffer.len());
+        buffer.drain(..len).collect()
+    }
+
+    /// 当前输入电平（0.0 - 1.0 的峰值），未录音时为 0
+    pub fn current_level(&self) -> f32 {
+        f32::from_bits(self.level.load(Ordering::Relaxed))
+    }
+
+    /// 当前是否检测到说话，未录音时为 false
+    pub fn is_speech_detected(&self) -> bool {
+        self.speech.load(Ordering::Relaxed)
+    }
+
+    /// 本次（或最近一次）录音的音频时长
+    pub fn recorded_duration(&self) -> Duration {
+        let samples = self.recorded.load(Ordering::Relaxed) as u64;
+        Duration::from_millis(samples * 1000 / self.sample_rate as u64)
+    }
+
     /// 获取当前缓冲区大小（样本数）
     pub fn buffer_size(&self) -> usize {
         self.buffer.lock().unwrap().len()
     }
 }
 
+/// 是否存在可用的音频输入设备
+pub fn has_input_device() -> bool {
+    cpal::default_host().default_input_device().is_some()
+}
+
+/// 打开录音设备，返回设备和采集格式
+fn open_device(
+    device: &CaptureDevice,
+) -> Result<(cpal::Device, cpal::SupportedStreamConfig), AudioError> {
+    let host = cpal::default_host();
+    match device {
+        CaptureDevice::DefaultInput => {
+            let device = host
+                .default_input_device()
+                .ok_or(AudioError::NoInputDevice)?;
+            let config = device
+                .default_input_config()
+                .map_err(|e| AudioError::Device(e.to_string()))?;
+            Ok((device, config))
+        }
+        CaptureDevice::Named(name) => {
+            let device = host
+                .input_devices()
+                .map_err(|e| AudioError::Device(e.to_string()))?
+                .find(|d| d.name().is_ok_and(|n| n == *name))
+                .ok_or_else(|| AudioError::Device(format!("找不到录音设备: {}", name)))?;
+            let config = device
+                .default_input_config()
+                .map_err(|e| AudioError::Device(e.to_string()))?;
+            Ok((device, config))
+        }
+        CaptureDevice::OutputLoopback => {
+            if !cfg!(target_os = "windows") {
+                return Err(AudioError::Device(
+                    "当前系统不支持直接采集系统声音，请安装回环虚拟设备（如 BlackHole）并在设置中选择"
+                        .to_string(),
+                ));
+            }
+            // WASAPI 在输出设备上建立输入流即为回环采集
+            let device = host
+                .default_output_device()
+                .ok_or_else(|| AudioError::Device("找不到音频输出设备".to_string()))?;
+            let config = device
+                .default_output_config()
+                .map_err(|e| AudioError::Device(e.to_string()))?;
+            Ok((device, config))
+        }
+    }
+}
+
+/// 检查录音设备是否可用
+pub fn check_capture_device(device: &CaptureDevice) -> Result<(), AudioError> {
+    open_device(device).map(|_| ())
+}
+
+/// 所有音频输入设备的名称
+pub fn input_device_names() -> Vec<String> {
+    cpal::default_host()
+        .input_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
 impl Default for AudioRecorder {
     fn default() -> Self {
         Self::new().expect("Failed to create audio recorder")
     }
 }
 
+/// 记录一块音频的峰值电平
+fn store_level(level: &AtomicU32, samples: &[f32]) {
+    let peak = samples.iter().fold(0.0f32, |max, s| max.max(s.abs())).min(1.0);
+    level.store(peak.to_bits(), Ordering::Relaxed);
+}
+
+/// 语音活动检测：句末静音后重置，下一段语音需要重新达到最短时长才算在说话
+struct SpeechActivity {
+    vad: EnergyVad,
+    speech: Arc<AtomicBool>,
+}
+
+impl SpeechActivity {
+    fn new(sample_rate: u32, threshold: f32, speech: Arc<AtomicBool>) -> Self {
+        Self {
+            vad: EnergyVad::new(sample_rate, threshold, SPEECH_HANGOVER_MS),
+            speech,
+        }
+    }
+
+    /// 输入重采样后的音频
+    fn process(&mut self, samples: &[f32]) {
+        let ended = self.vad.process(samples);
+        self.speech.store(self.vad.is_speaking(), Ordering::Relaxed);
+        if ended {
+            self.vad.reset();
+        }
+    }
+}
+
+/// 一条录音轨道的输出：重采样后的音频及其电平、语音活动和已录样本数
+struct TrackSink {
+    /// 输出采样率
+    sample_rate: u32,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    level: Arc<AtomicU32>,
+    activity: SpeechActivity,
+    recorded: Arc<AtomicUsize>,
+}
+
 /// 在单独线程中运行录音循环
 fn run_recording_loop(
     rx: mpsc::Receiver<RecorderCommand>,
-    buffer: Arc<Mutex<Vec<f32>>>,
+    capture_device: CaptureDevice,
+    sink: TrackSink,
     _state: Arc<Mutex<RecordingState>>,
-    target_sample_rate: u32,
+    realtime_priority: bool,
 ) -> Result<(), AudioError> {
+    let TrackSink {
+        sample_rate: target_sample_rate,
+        buffer,
+        level,
+        mut activity,
+        recorded,
+    } = sink;
+
     // 等待开始命令
     match rx.recv() {
         Ok(RecorderCommand::Start) => {}
         _ => return Ok(()),
     }
 
-    let host = cpal::default_host();
-    let device = host
-        .default_input_device()
-        .ok_or(AudioError::NoInputDevice)?;
-
-    let config = device
-        .default_input_config()
-        .map_err(|e| AudioError::Device(e.to_string()))?;
+    let (device, config) = open_device(&capture_device)?;
+    // 系统声音回环录的就是本机播放的声音，不做回声抑制
+    let echo_suppression = capture_device != CaptureDevice::OutputLoopback;
 
     tracing::info!(
         "Using input device: {:?}, config: {:?}",
@@ -180,40 +438,41 @@ fn run_recording_loop(
     let source_sample_rate = config.sample_rate().0;
     let channels = config.channels() as usize;
 
-    // 计算精确的重采样比率
-    let resample_ratio = source_sample_rate as f64 / target_sample_rate as f64;
+    // 转换为单声道并精确重采样
+    let mut resampler = Resampler::new(source_sample_rate, target_sample_rate, channels);
 
     tracing::info!(
         "Resampling: {}Hz -> {}Hz, ratio: {:.4}",
         source_sample_rate,
         target_sample_rate,
-        resample_ratio
+        resampler.ratio()
     );
 
     let buffer_clone = buffer.clone();
-    // 使用浮点累加器实现精确重采样
-    let accumulator = Arc::new(Mutex::new(0.0f64));
-    let accumulator_clone = accumulator.clone();
+    // 回调线程由 cpal 创建，在首次回调时提升
+    let mut promoted = !realtime_priority;
 
     // 构建输入流
     let stream = device
         .build_input_stream(
             &config.into(),
             move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                if !promoted {
+                    promote_callback_thread();
+                    promoted = true;
+                }
                 let mut buffer = buffer_clone.lock().unwrap();
-                let mut acc = accumulator_clone.lock().unwrap();
-
-                // 转换为单声道并精确重采样
-                for frame in data.chunks(channels) {
-                    let mono: f32 = frame.iter().sum::<f32>() / channels as f32;
-
-                    // 当累加器 >= 1.0 时输出一个样本
-                    *acc += 1.0 / resample_ratio;
-                    while *acc >= 1.0 {
-                        buffer.push(mono);
-                        *acc -= 1.0;
-                    }
+                let start = buffer.len();
+                resampler.process(data, &mut buffer);
+                // 本机播放提示音、播报期间替换为静音
+                if echo_suppression && is_capture_suppressed() {
+                    buffer[start..].fill(0.0);
+                    store_level(&level, &[]);
+                } else {
+                    store_level(&level, data);
                 }
+                activity.process(&buffer[start..]);
+                recorded.fetch_add(buffer.len() - start, Ordering::Relaxed);
             },
             |err| {
                 tracing::error!("Audio stream error: {}", err);
@@ -0,0 +1,39 @@
+//! 混音为单声道并重采样
+//!
+//! 使用浮点累加器按比例丢弃或重复样本，计算量小，适合在音频回调中逐块处理
+
+pub struct Resampler {
+    channels: usize,
+    /// 源采样率 / 目标采样率
+    ratio: f64,
+    acc: f64,
+}
+
+impl Resampler {
+    pub fn new(source_sample_rate: u32, target_sample_rate: u32, channels: usize) -> Self {
+        Self {
+            channels: channels.max(1),
+            ratio: source_sample_rate as f64 / target_sample_rate as f64,
+            acc: 0.0,
+        }
+    }
+
+    /// 源采样率 / 目标采样率
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// 处理一块交错的多声道数据，结果追加到 out
+    pub fn process(&mut self, data: &[f32], out: &mut Vec<f32>) {
+        for frame in data.chunks(self.channels) {
+            let mono: f32 = frame.iter().sum::<f32>() / self.channels as f32;
+
+            // 当累加器 >= 1.0 时输出一个样本
+            self.acc += 1.0 / self.ratio;
+            while self.acc >= 1.0 {
+                out.push(mono);
+                self.acc -= 1.0;
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! 去除录音首尾的按键声
+//!
+//! 按下和松开快捷键的按键声会出现在录音的开头和结尾，容易被识别成多余的字。
+//! 先裁掉首尾固定时长，再在靠近首尾的范围内把前后都安静的短促脉冲静音；
+//! 语音起始处后面紧跟持续的能量，不会被当作脉冲
+
+/// 能量计算的块长（毫秒）
+const BLOCK_MS: u32 = 5;
+/// 首尾检测脉冲的范围（毫秒）
+const EDGE_MS: u32 = 200;
+/// 脉冲前后作为参照的间隔和长度（块数），按键声通常短于 20ms
+const CONTEXT_GAP_BLOCKS: usize = 4;
+const CONTEXT_BLOCKS: usize = 4;
+/// 脉冲能量至少是前后参照的多少倍
+const TRANSIENT_RATIO: f32 = 4.0;
+/// 低于此 RMS 的块不处理
+const MIN_TRANSIENT_RMS: f32 = 0.02;
+
+/// 裁掉首尾各 trim_ms 毫秒，录音太短（不到裁剪长度的 4 倍）时不裁剪
+pub fn trim_edges(samples: &[f32], sample_rate: u32, trim_ms: u32) -> &[f32] {
+    let trim = (sample_rate as u64 * trim_ms as u64 / 1000) as usize;
+    if trim == 0 || samples.len() <= trim * 4 {
+        return samples;
+    }
+    &samples[trim..samples.len() - trim]
+}
+
+/// 把首尾范围内前后都安静的短促脉冲静音，返回处理的块数
+pub fn suppress_edge_transients(samples: &mut [f32], sample_rate: u32) -> usize {
+    let block_len = (sample_rate * BLOCK_MS / 1000).max(1) as usize;
+    let rms: Vec<f32> = samples
+        .chunks(block_len)
+        .map(|block| (block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32).sqrt())
+        .collect();
+    let edge_blocks = (EDGE_MS / BLOCK_MS) as usize;
+
+    let mean = |range: std::ops::Range<usize>| -> f32 {
+        let range = range.start.min(rms.len())..range.end.min(rms.len());
+        if range.is_empty() {
+            0.0
+        } else {
+            rms[range.clone()].iter().sum::<f32>() / range.len() as f32
+        }
+    };
+
+    let mut suppressed = 0;
+    for (index, &energy) in rms.iter().enumerate() {
+        let at_edge = index < edge_blocks || index + edge_blocks >= rms.len();
+        if !at_edge || energy < MIN_TRANSIENT_RMS {
+            continue;
+        }
+
+        let before_end = index.saturating_sub(CONTEXT_GAP_BLOCKS);
+        let before = mean(before_end.saturating_sub(CONTEXT_BLOCKS)..before_end);
+        let after_start = index + CONTEXT_GAP_BLOCKS + 1;
+        let after = mean(after_start..after_start + CONTEXT_BLOCKS);
+        if energy > TRANSIENT_RATIO * before.max(after) {
+            let start = index * block_len;
+            let end = (start + block_len).min(samples.len());
+            samples[start..end].fill(0.0);
+            suppressed += 1;
+        }
+    }
+    suppressed
+}
@@ -0,0 +1,74 @@
+//! 基于能量的本地语音活动检测
+//!
+//! 按 20ms 帧计算 RMS 能量，检测到语音后出现足够长的静音即认为一句话结束
+
+/// 每帧时长（毫秒）
+const FRAME_MS: u32 = 20;
+/// 至少检测到多少帧语音才认为有效，避免按键声等瞬时噪音触发
+const MIN_SPEECH_FRAMES: u32 = 5;
+
+pub struct EnergyVad {
+    threshold: f32,
+    frame_len: usize,
+    silence_frames_needed: u32,
+    speech_frames: u32,
+    silent_frames: u32,
+    pending: Vec<f32>,
+}
+
+impl EnergyVad {
+    /// threshold 为语音帧的 RMS 阈值，silence_ms 为判定句末所需的静音时长
+    pub fn new(sample_rate: u32, threshold: f32, silence_ms: u32) -> Self {
+        let frame_len = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+        Self {
+            threshold,
+            frame_len,
+            silence_frames_needed: (silence_ms / FRAME_MS).max(1),
+            speech_frames: 0,
+            silent_frames: 0,
+            pending: Vec::with_capacity(frame_len),
+        }
+    }
+
+    /// 输入新的采样数据，检测到句末时返回 true
+    pub fn process(&mut self, samples: &[f32]) -> bool {
+        let mut end_of_speech = false;
+
+        for &sample in samples {
+            self.pending.push(sample);
+            if self.pending.len() < self.frame_len {
+                continue;
+            }
+
+            let rms = (self.pending.iter().map(|s| s * s).sum::<f32>()
+                / self.pending.len() as f32)
+                .sqrt();
+            self.pending.clear();
+
+            if rms >= self.threshold {
+                self.speech_frames += 1;
+                self.silent_frames = 0;
+            } else if self.speech_frames >= MIN_SPEECH_FRAMES {
+                self.silent_frames += 1;
+                if self.silent_frames >= self.silence_frames_needed {
+                    end_of_speech = true;
+                }
+            }
+        }
+
+        end_of_speech
+    }
+
+    /// 当前是否处于语音中：已检测到有效语音，且之后的静音还不足以判定句末
+    pub fn is_speaking(&self) -> bool {
+        self.speech_frames >= MIN_SPEECH_FRAMES
+            && self.silent_frames < self.silence_frames_needed
+    }
+
+    /// 重置状态，开始检测下一句
+    pub fn reset(&mut self) {
+        self.speech_frames = 0;
+        self.silent_frames = 0;
+        self.pending.clear();
+    }
+}
@@ -0,0 +1,68 @@
+//! 日历会议提醒
+//!
+//! 宿主定期读取本机日历（macOS EventKit、Windows Outlook），交给 `MeetingReminder`
+//! 判断哪些会议即将开始、需要询问用户是否开始会议记录。每个会议只提醒一次，
+//! 全天事件和已经结束的会议不提醒
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// 会议开始后仍然提醒的时长（秒），轮询间隔内开始的会议不会漏掉
+const LATE_GRACE_SECS: u64 = 5 * 60;
+
+/// 日历事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    /// 日历中的事件标识，重复事件的每次发生需带上开始时间区分
+    pub id: String,
+    pub title: String,
+    /// 开始和结束时间（Unix 时间戳，秒）
+    pub start: u64,
+    pub end: u64,
+    pub all_day: bool,
+}
+
+/// 记录已提醒的会议，判断需要提醒的会议
+#[derive(Debug, Default)]
+pub struct MeetingReminder {
+    prompted: HashSet<(String, u64)>,
+}
+
+impl MeetingReminder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回需要提醒的会议（按开始时间），并记为已提醒
+    ///
+    /// 开始前 `lead_secs` 秒到开始后 5 分钟内、还没结束的会议需要提醒
+    pub fn due(
+        &mut self,
+        events: &[CalendarEvent],
+        now: u64,
+        lead_secs: u64,
+    ) -> Vec<CalendarEvent> {
+        let mut due: Vec<CalendarEvent> = events
+            .iter()
+            .filter(|event| {
+                !event.all_day
+                    && event.end > now
+                    && event.start <= now + lead_secs
+                    && event.start + LATE_GRACE_SECS >= now
+            })
+            .filter(|event| self.prompted.insert((event.id.clone(), event.start)))
+            .cloned()
+            .collect();
+        due.sort_by_key(|event| event.start);
+
+        // 已经结束的会议不会再出现，清理掉
+        let active: HashSet<(String, u64)> = events
+            .iter()
+            .filter(|event| event.end > now)
+            .map(|event| (event.id.clone(), event.start))
+            .collect();
+        self.prompted.retain(|key| active.contains(key));
+        due
+    }
+}
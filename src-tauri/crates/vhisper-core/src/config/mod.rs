@@ -1,5 +1,9 @@
+pub mod profile;
 pub mod settings;
 pub mod storage;
 
-pub use settings::{AppConfig, HotkeyBinding, KeyCode};
-pub use storage::{load_config, save_config};
+pub use profile::{find_profile, AppProfile};
+pub use settings::{AppConfig, AutoSubmitConfig, DualKeyChord, HotkeyBinding, KeyCode, KeySide};
+pub use storage::{
+    apply_managed_config, current_managed_paths, load_config, managed_config_path, save_config,
+};
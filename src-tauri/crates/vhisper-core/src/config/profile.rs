@@ -0,0 +1,26 @@
+//! 按前台应用切换的配置覆盖
+
+use serde::{Deserialize, Serialize};
+
+/// 单个应用的配置覆盖
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppProfile {
+    /// 应用标识：macOS 为 bundle id（如 com.tinyspeck.slackmacgap），
+    /// Windows 为可执行文件名（如 WeChat.exe），不区分大小写
+    pub app: String,
+    /// 识别语言（如 "en"、"zh"），None 表示沿用全局配置
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl AppProfile {
+    /// 是否匹配给定的应用标识
+    pub fn matches(&self, app_id: &str) -> bool {
+        self.app.eq_ignore_ascii_case(app_id)
+    }
+}
+
+/// 查找与应用标识匹配的配置（取第一个）
+pub fn find_profile<'a>(profiles: &'a [AppProfile], app_id: &str) -> Option<&'a AppProfile> {
+    profiles.iter().find(|p| p.matches(app_id))
+}
@@ -1,8 +1,22 @@
+//! 配置读写
+//!
+//! 配置分两层，优先级从高到低：
+//! 1. 管理员配置（只读）：团队或企业统一下发，锁定服务商地址、关闭部分功能等
+//! 2. 用户配置：`<配置目录>/com.vhisper.app/config.json`，设置界面读写
+//!
+//! 未出现在两层中的字段使用默认值。管理员配置是与用户配置结构相同的（部分）JSON 对象，
+//! 合并时对象逐字段合并，其他值（字符串、数字、数组等）整体覆盖用户的值
+
 use std::fs;
 use std::path::PathBuf;
 
+use serde_json::Value;
+
 use crate::config::settings::AppConfig;
 
+/// 指定管理员配置路径的环境变量，优先于平台默认路径
+pub const MANAGED_CONFIG_ENV: &str = "VHISPER_MANAGED_CONFIG";
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Config directory not found")]
@@ -11,6 +25,8 @@ pub enum ConfigError {
     Io(#[from] std::io::Error),
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("Managed config must be a JSON object")]
+    InvalidManaged,
 }
 
 /// 获取配置文件路径
@@ -21,25 +37,178 @@ fn get_config_path() -> Result<PathBuf, ConfigError> {
     Ok(app_dir.join("config.json"))
 }
 
-/// 加载配置
+/// 管理员配置路径
+///
+/// - macOS: /Library/Application Support/Vhisper/managed.json
+/// - Windows: %ProgramData%\Vhisper\managed.json
+/// - Linux: /etc/vhisper/managed.json
+pub fn managed_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(MANAGED_CONFIG_ENV).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Some(PathBuf::from(
+            "/Library/Application Support/Vhisper/managed.json",
+        ))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("ProgramData")
+            .map(|dir| PathBuf::from(dir).join("Vhisper").join("managed.json"))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        Some(PathBuf::from("/etc/vhisper/managed.json"))
+    }
+}
+
+/// 读取管理员配置，不存在时返回 None
+pub fn load_managed_layer() -> Result<Option<Value>, ConfigError> {
+    let Some(path) = managed_config_path().filter(|p| p.exists()) else {
+        return Ok(None);
+    };
+    let value: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    if !value.is_object() {
+        return Err(ConfigError::InvalidManaged);
+    }
+    Ok(Some(value))
+}
+
+/// 读取管理员配置，读取失败时记录警告并按没有管理员配置处理
+fn managed_layer() -> Option<Value> {
+    match load_managed_layer() {
+        Ok(layer) => layer,
+        Err(e) => {
+            tracing::warn!("Ignoring invalid managed config: {}", e);
+            None
+        }
+    }
+}
+
+/// 把上层配置合并到下层：对象逐字段合并，其他值整体覆盖
+pub fn merge_layer(base: &mut Value, layer: &Value) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(key) {
+                    Some(existing) => merge_layer(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer.clone(),
+    }
+}
+
+/// 把管理员配置锁定的配置项恢复为用户自己的值，用户没有设置过的删除
+///
+/// 保存时使用，避免管理员的值写入用户配置：管理员配置撤销后仍恢复为用户原来的设置
+pub fn restore_user_values(value: &mut Value, user: Option<&Value>, layer: &Value) {
+    let (Value::Object(map), Value::Object(layer)) = (value, layer) else {
+        return;
+    };
+    for (key, locked) in layer {
+        let user_value = user.and_then(|user| user.get(key));
+        // 与 managed_paths 一致：非空对象逐字段处理，其他值整体锁定
+        let nested = locked.as_object().is_some_and(|locked| !locked.is_empty());
+        match map.get_mut(key) {
+            Some(existing) if nested && existing.is_object() => {
+                restore_user_values(existing, user_value, locked);
+            }
+            _ => match user_value {
+                Some(user_value) => {
+                    map.insert(key.clone(), user_value.clone());
+                }
+                None => {
+                    map.remove(key);
+                }
+            },
+        }
+    }
+}
+
+/// 被管理员配置锁定的配置项（点分隔的路径，如 `asr.funasr.endpoint`）
+pub fn managed_paths(layer: &Value) -> Vec<String> {
+    fn collect(value: &Value, prefix: &str, paths: &mut Vec<String>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    collect(value, &path, paths);
+                }
+            }
+            _ => paths.push(prefix.to_string()),
+        }
+    }
+
+    let mut paths = Vec::new();
+    if layer.is_object() {
+        collect(layer, "", &mut paths);
+    }
+    paths
+}
+
+/// 把管理员配置应用到配置上（锁定的值不能被用户修改）
+pub fn apply_managed_config(config: AppConfig) -> Result<AppConfig, ConfigError> {
+    let Some(layer) = managed_layer() else {
+        return Ok(config);
+    };
+    let mut value = serde_json::to_value(&config)?;
+    merge_layer(&mut value, &layer);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// 当前被管理员锁定的配置项
+pub fn current_managed_paths() -> Vec<String> {
+    managed_layer()
+        .map(|layer| managed_paths(&layer))
+        .unwrap_or_default()
+}
+
+/// 加载配置（用户配置合并管理员配置）
 pub fn load_config() -> Result<AppConfig, ConfigError> {
     let path = get_config_path()?;
 
-    if !path.exists() {
-        return Ok(AppConfig::default());
+    let mut value = if path.exists() {
+        serde_json::from_str(&fs::read_to_string(&path)?)?
+    } else {
+        serde_json::to_value(AppConfig::default())?
+    };
+    if let Some(layer) = managed_layer() {
+        tracing::info!("Applying managed config");
+        merge_layer(&mut value, &layer);
     }
-
-    let content = fs::read_to_string(&path)?;
-    let config: AppConfig = serde_json::from_str(&content)?;
+    let config: AppConfig = serde_json::from_value(value)?;
 
     Ok(config)
 }
 
 /// 保存配置
+///
+/// 只写入用户配置：管理员锁定的配置项保留用户配置文件中原来的值
 pub fn save_config(config: &AppConfig) -> Result<(), ConfigError> {
     let path = get_config_path()?;
     tracing::info!("Saving config to: {:?}", path);
-    let content = serde_json::to_string_pretty(config)?;
+    let mut value = serde_json::to_value(config)?;
+    if let Some(layer) = managed_layer() {
+        let user: Option<Value> = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?).ok()
+        } else {
+            None
+        };
+        restore_user_values(&mut value, user.as_ref(), &layer);
+    }
+    let content = serde_json::to_string_pretty(&value)?;
     fs::write(&path, &content)?;
     tracing::info!("Config saved successfully");
     Ok(())
@@ -0,0 +1,248 @@
+//! 服务商请求/响应抓取
+//!
+//! 开启后在限定时间窗口内把与服务商之间的消息写入调试目录，用于排查解析失败等问题。
+//! 写入前会脱敏：凭据类字段替换为 [REDACTED]，音频只保留哈希、字节数和估算时长，
+//! 过长的字符串截断。开启请求/响应日志（见 `payload_log`）时同样的记录也写入日志
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::payload_log;
+
+/// 单次抓取的最长时间
+const MAX_CAPTURE_DURATION: Duration = Duration::from_secs(30 * 60);
+/// 估算 base64 音频时长时假定的格式（16kHz 16bit 单声道）
+const DEFAULT_SAMPLE_RATE: u32 = 16000;
+
+/// 字符串字段保留的最大字符数
+const MAX_STRING_CHARS: usize = 4096;
+
+/// 需要脱敏的字段名（小写包含匹配，另外 token / *_token 也会脱敏）
+const SENSITIVE_KEYS: &[&str] = &["authorization", "api_key", "apikey", "secret", "password"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum CaptureError {
+    #[error("Debug directory not found")]
+    DirNotFound,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// 消息方向
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureDirection {
+    Send,
+    Recv,
+}
+
+/// 抓取状态
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureStatus {
+    pub dir: PathBuf,
+    pub remaining_secs: u64,
+    pub records: u64,
+}
+
+struct CaptureSession {
+    dir: PathBuf,
+    file: File,
+    started: Instant,
+    until: Instant,
+    seq: u64,
+}
+
+static SESSION: Mutex<Option<CaptureSession>> = Mutex::new(None);
+
+pub(super) fn debug_root() -> Result<PathBuf, CaptureError> {
+    let config_dir = dirs::config_dir().ok_or(CaptureError::DirNotFound)?;
+    Ok(config_dir.join("com.vhisper.app").join("debug"))
+}
+
+/// 开始抓取，返回本次抓取的目录
+///
+/// 时长超过 30 分钟按 30 分钟处理；已在抓取时重新开始一个新目录
+pub fn start_capture(duration: Duration) -> Result<PathBuf, CaptureError> {
+    let duration = duration.min(MAX_CAPTURE_DURATION);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let dir = debug_root()?.join(format!("capture-{}", timestamp));
+    fs::create_dir_all(&dir)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join("capture.jsonl"))?;
+
+    tracing::warn!(
+        "Debug capture enabled for {}s, writing sanitized provider traffic to {}",
+        duration.as_secs(),
+        dir.display()
+    );
+
+    let now = Instant::now();
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    *session = Some(CaptureSession {
+        dir: dir.clone(),
+        file,
+        started: now,
+        until: now + duration,
+        seq: 0,
+    });
+
+    Ok(dir)
+}
+
+/// 停止抓取，返回抓取目录
+pub fn stop_capture() -> Option<PathBuf> {
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = session.take().map(|s| s.dir);
+    if let Some(dir) = &dir {
+        tracing::info!("Debug capture stopped: {}", dir.display());
+    }
+    dir
+}
+
+/// 当前抓取状态，未开启或已过期时返回 None
+pub fn capture_status() -> Option<CaptureStatus> {
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+
+    if session.as_ref().is_some_and(|s| now >= s.until) {
+        *session = None;
+    }
+
+    session.as_ref().map(|s| CaptureStatus {
+        dir: s.dir.clone(),
+        remaining_secs: (s.until - now).as_secs(),
+        records: s.seq,
+    })
+}
+
+/// 记录文本消息（JSON 会被解析并脱敏）
+pub fn capture_text(provider: &str, direction: CaptureDirection, text: &str) {
+    write_record(provider, direction, || redact_payload(text));
+}
+
+/// 脱敏后的消息：JSON 中的凭据字段替换为 [REDACTED]，base64 音频替换为摘要，
+/// 过长的字符串截断；不是 JSON 时按字符串截断
+pub fn redact_payload(text: &str) -> Value {
+    match serde_json::from_str::<Value>(text) {
+        Ok(mut value) => {
+            sanitize(&mut value);
+            value
+        }
+        Err(_) => Value::String(truncate(text)),
+    }
+}
+
+/// 记录二进制音频（只保留摘要）
+pub fn capture_audio(provider: &str, direction: CaptureDirection, data: &[u8], sample_rate: u32) {
+    write_record(provider, direction, || audio_summary(data, sample_rate));
+}
+
+fn write_record(provider: &str, direction: CaptureDirection, payload: impl FnOnce() -> Value) {
+    // 隐私模式下不记录服务商流量
+    if crate::privacy::is_incognito() {
+        return;
+    }
+    let log_enabled = payload_log::is_payload_log_enabled();
+    let mut guard = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+
+    let now = Instant::now();
+    if guard.as_ref().is_some_and(|s| now >= s.until) {
+        if let Some(session) = guard.take() {
+            tracing::info!("Debug capture window elapsed: {}", session.dir.display());
+        }
+    }
+    if guard.is_none() && !log_enabled {
+        return;
+    }
+
+    let payload = payload();
+    if log_enabled {
+        payload_log::write(provider, direction, &payload);
+    }
+    let Some(session) = guard.as_mut() else {
+        return;
+    };
+
+    session.seq += 1;
+    let record = json!({
+        "seq": session.seq,
+        "elapsed_ms": (now - session.started).as_millis() as u64,
+        "provider": provider,
+        "direction": direction,
+        "payload": payload,
+    });
+
+    if let Err(e) = writeln!(session.file, "{}", record) {
+        tracing::warn!("Failed to write debug capture, stopping: {}", e);
+        *guard = None;
+    }
+}
+
+fn audio_summary(data: &[u8], sample_rate: u32) -> Value {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+
+    // 16bit 单声道 PCM
+    let duration_ms = data.len() as u64 * 1000 / (sample_rate.max(1) as u64 * 2);
+    json!({
+        "audio_hash": format!("{:016x}", hasher.finish()),
+        "bytes": data.len(),
+        "duration_ms": duration_ms,
+    })
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key == "token" || key.ends_with("_token") || SENSITIVE_KEYS.iter().any(|k| key.contains(k))
+}
+
+/// 脱敏：凭据字段替换为 [REDACTED]，base64 音频替换为摘要
+fn sanitize(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_sensitive(key) {
+                    *field = Value::String("[REDACTED]".to_string());
+                } else if key == "audio" && field.is_string() {
+                    let data = field
+                        .as_str()
+                        .and_then(|s| BASE64.decode(s).ok())
+                        .unwrap_or_default();
+                    *field = audio_summary(&data, DEFAULT_SAMPLE_RATE);
+                } else {
+                    sanitize(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(sanitize),
+        Value::String(text) if text.chars().count() > MAX_STRING_CHARS => {
+            *text = truncate(text);
+        }
+        _ => {}
+    }
+}
+
+/// 截断过长的字符串，并注明原始长度
+fn truncate(text: &str) -> String {
+    let chars = text.chars().count();
+    if chars <= MAX_STRING_CHARS {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(MAX_STRING_CHARS).collect();
+    format!("{}...[truncated, {} chars]", kept, chars)
+}
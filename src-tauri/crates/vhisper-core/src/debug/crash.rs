@@ -0,0 +1,192 @@
+//! 崩溃报告
+//!
+//! 配置 `debug.crash_reports` 开启后，panic 时把消息、位置和调用栈写入本地的
+//! `crashes` 目录；启动时留下运行标记、正常退出时删除，下次启动仍有标记说明
+//! 上次异常退出（包括 panic 以外的原生崩溃），同样记录一份报告。
+//! 原生崩溃（段错误等）不生成 minidump，只能得到没有调用栈的异常退出报告。
+//! 报告只保存在本地，用户可导出为 issue 文本自行提交
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// 最多保留的报告数，超出时删除最旧的
+const MAX_REPORTS: usize = 20;
+/// 运行标记文件名
+const RUNNING_MARKER: &str = "running";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 崩溃类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    /// 上次运行没有正常退出
+    UncleanShutdown,
+}
+
+/// 崩溃报告
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    pub timestamp_ms: u64,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub message: String,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub thread: Option<String>,
+    #[serde(default)]
+    pub backtrace: Option<String>,
+}
+
+impl CrashReport {
+    fn new(kind: CrashKind, app_version: &str, message: String) -> Self {
+        let timestamp_ms = now_ms();
+        Self {
+            id: format!("{}-{}", timestamp_ms, std::process::id()),
+            kind,
+            timestamp_ms,
+            app_version: app_version.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            message,
+            location: None,
+            thread: None,
+            backtrace: None,
+        }
+    }
+
+    /// panic 报告，记录当前线程和调用栈
+    pub fn panic(app_version: &str, message: String, location: Option<String>) -> Self {
+        Self {
+            location,
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace: Some(std::backtrace::Backtrace::force_capture().to_string()),
+            ..Self::new(CrashKind::Panic, app_version, message)
+        }
+    }
+
+    /// 上次运行异常退出的报告（没有调用栈）
+    pub fn unclean_shutdown(app_version: &str) -> Self {
+        Self::new(
+            CrashKind::UncleanShutdown,
+            app_version,
+            "上次运行没有正常退出".to_string(),
+        )
+    }
+
+    /// 导出为 issue 正文（Markdown）
+    pub fn issue_text(&self) -> String {
+        let mut text = format!(
+            "### 崩溃报告\n\n- 类型: {:?}\n- 版本: {}\n- 系统: {} {}\n- 时间戳: {}\n- 消息: {}\n",
+            self.kind, self.app_version, self.os, self.arch, self.timestamp_ms, self.message
+        );
+        if let Some(location) = &self.location {
+            text.push_str(&format!("- 位置: {}\n", location));
+        }
+        if let Some(thread) = &self.thread {
+            text.push_str(&format!("- 线程: {}\n", thread));
+        }
+        if let Some(backtrace) = &self.backtrace {
+            text.push_str(&format!(
+                "\n<details><summary>调用栈</summary>\n\n```\n{}\n```\n</details>\n",
+                backtrace.trim_end()
+            ));
+        }
+        text
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 开启或关闭崩溃报告
+pub fn set_crash_reports(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// 崩溃报告是否开启
+pub fn is_crash_reports_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 崩溃报告目录
+pub fn crash_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("com.vhisper.app").join("crashes"))
+}
+
+/// 保存报告，超出数量时删除最旧的，返回报告路径
+pub fn save_report(dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", report.id));
+    fs::write(&path, serde_json::to_string_pretty(report)?)?;
+
+    let reports = list_reports(dir);
+    for old in reports.iter().skip(MAX_REPORTS) {
+        let _ = fs::remove_file(dir.join(format!("{}.json", old.id)));
+    }
+    Ok(path)
+}
+
+/// 读取所有报告，最新的在前
+pub fn list_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| serde_json::from_str(&fs::read_to_string(entry.path()).ok()?).ok())
+        .collect();
+    reports.sort_by(|a, b| b.timestamp_ms.cmp(&a.timestamp_ms));
+    reports
+}
+
+/// 删除所有报告
+pub fn clear_reports(dir: &Path) -> std::io::Result<()> {
+    for report in list_reports(dir) {
+        fs::remove_file(dir.join(format!("{}.json", report.id)))?;
+    }
+    Ok(())
+}
+
+/// 启动时写入运行标记，返回上次运行是否留下了标记（异常退出）
+pub fn mark_running(dir: &Path) -> std::io::Result<bool> {
+    fs::create_dir_all(dir)?;
+    let marker = dir.join(RUNNING_MARKER);
+    let previous = marker.exists();
+    fs::write(&marker, now_ms().to_string())?;
+    Ok(previous)
+}
+
+/// 上次运行开始后是否保存过 panic 报告，需在 mark_running 之前调用
+///
+/// panic 后进程退出时运行标记仍在，据此避免再记录一份重复的异常退出报告
+pub fn panic_recorded_since_start(dir: &Path) -> bool {
+    let Some(started) = fs::read_to_string(dir.join(RUNNING_MARKER))
+        .ok()
+        .and_then(|content| content.trim().parse::<u64>().ok())
+    else {
+        return false;
+    };
+    list_reports(dir)
+        .iter()
+        .any(|report| report.kind == CrashKind::Panic && report.timestamp_ms >= started)
+}
+
+/// 正常退出时删除运行标记
+pub fn clear_running(dir: &Path) {
+    let _ = fs::remove_file(dir.join(RUNNING_MARKER));
+}
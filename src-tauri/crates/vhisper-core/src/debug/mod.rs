@@ -0,0 +1,22 @@
+//! 诊断工具
+//!
+//! 默认全部关闭，只有用户显式开启时才会记录
+
+mod capture;
+mod crash;
+mod payload_log;
+mod trace;
+
+pub use capture::{
+    capture_audio, capture_text, capture_status, redact_payload, start_capture, stop_capture,
+    CaptureDirection, CaptureError, CaptureStatus,
+};
+pub use crash::{
+    clear_reports, clear_running, crash_dir, is_crash_reports_enabled, list_reports, mark_running,
+    panic_recorded_since_start, save_report, set_crash_reports, CrashKind, CrashReport,
+};
+pub use payload_log::{is_payload_log_enabled, payload_log_path, set_payload_log};
+pub use trace::{
+    begin_streaming_trace, last_streaming_trace, trace_ws_marker, trace_ws_message,
+    StreamingTrace, TraceEvent,
+};
@@ -0,0 +1,146 @@
+//! 服务商请求/响应日志
+//!
+//! 配置 `debug.payload_log` 开启后，持续把与服务商之间的消息追加到调试目录下的
+//! `payloads.log`（与抓取相同的脱敏：凭据替换为 [REDACTED]，音频只保留摘要）。
+//! 文件超过 5MB 时轮转为 `payloads.log.1`，最多保留 3 个旧文件。
+//! 与限时抓取不同，开启后一直记录，用于排查偶发的识别结果为空等问题
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use super::capture::CaptureDirection;
+
+/// 单个日志文件的最大字节数
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// 保留的旧日志文件数
+const MAX_ROTATED_FILES: usize = 3;
+/// 日志文件名
+const LOG_FILE_NAME: &str = "payloads.log";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+static LOG: Mutex<Option<LogFile>> = Mutex::new(None);
+
+/// 开启或关闭请求/响应日志
+pub fn set_payload_log(enabled: bool) {
+    if ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    if enabled {
+        tracing::warn!(
+            "Provider payload logging enabled, writing sanitized payloads to {}",
+            payload_log_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        );
+    } else {
+        tracing::info!("Provider payload logging disabled");
+        // 关闭文件，之后开启时重新打开
+        *LOG.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// 请求/响应日志是否开启
+pub fn is_payload_log_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 当前日志文件的路径
+pub fn payload_log_path() -> Option<PathBuf> {
+    super::capture::debug_root()
+        .ok()
+        .map(|dir| dir.join(LOG_FILE_NAME))
+}
+
+/// 写入一条已脱敏的记录
+pub(super) fn write(provider: &str, direction: CaptureDirection, payload: &Value) {
+    let mut guard = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        match open_log() {
+            Ok(log) => *guard = Some(log),
+            Err(e) => {
+                tracing::warn!("Failed to open payload log, disabling: {}", e);
+                ENABLED.store(false, Ordering::SeqCst);
+                return;
+            }
+        }
+    }
+    let Some(log) = guard.as_mut() else {
+        return;
+    };
+
+    let record = json!({
+        "timestamp_ms": SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+        "provider": provider,
+        "direction": direction,
+        "payload": payload,
+    });
+    let line = format!("{}\n", record);
+
+    if log.len + line.len() as u64 > MAX_LOG_BYTES {
+        // 先关闭文件再重命名（Windows 上不能重命名打开的文件）
+        let path = log.path.clone();
+        *guard = None;
+        if let Err(e) = rotate(&path) {
+            tracing::warn!("Failed to rotate payload log: {}", e);
+        }
+        match open_log() {
+            Ok(reopened) => *guard = Some(reopened),
+            Err(e) => {
+                tracing::warn!("Failed to reopen payload log, disabling: {}", e);
+                ENABLED.store(false, Ordering::SeqCst);
+                return;
+            }
+        }
+    }
+    let Some(log) = guard.as_mut() else {
+        return;
+    };
+
+    match log.file.write_all(line.as_bytes()) {
+        Ok(()) => log.len += line.len() as u64,
+        Err(e) => {
+            tracing::warn!("Failed to write payload log, disabling: {}", e);
+            ENABLED.store(false, Ordering::SeqCst);
+            *guard = None;
+        }
+    }
+}
+
+fn open_log() -> std::io::Result<LogFile> {
+    let path =
+        payload_log_path().ok_or_else(|| std::io::Error::other("debug directory not found"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let len = file.metadata()?.len();
+    Ok(LogFile { path, file, len })
+}
+
+/// payloads.log -> payloads.log.1 -> ... -> payloads.log.N，最旧的被覆盖
+fn rotate(path: &Path) -> std::io::Result<()> {
+    let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+    for n in (1..MAX_ROTATED_FILES).rev() {
+        let from = rotated(n);
+        if from.exists() {
+            fs::rename(&from, rotated(n + 1))?;
+        }
+    }
+    fs::rename(path, rotated(1))
+}
@@ -0,0 +1,124 @@
+//! 实时识别 WebSocket 消息追踪
+//!
+//! 开启后为每条收发的消息记录序号、时间和事件类型，并保留最近一次流式会话的记录，
+//! 用于排查收不到 Final 等问题。只记录事件类型，不记录识别文本和音频。
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use super::CaptureDirection;
+
+/// 每个会话最多保留的事件数（超出后丢弃最早的）
+const MAX_EVENTS: usize = 4000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TRACE: Mutex<Option<TraceState>> = Mutex::new(None);
+
+/// 单条追踪记录
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    pub seq: u64,
+    pub elapsed_ms: u64,
+    pub provider: String,
+    pub direction: CaptureDirection,
+    pub event_type: String,
+    pub bytes: usize,
+}
+
+/// 一次流式会话的追踪记录
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamingTrace {
+    /// 会话开始时间（Unix 毫秒）
+    pub started_at_ms: u64,
+    /// 因超出上限被丢弃的事件数
+    pub dropped: u64,
+    pub events: VecDeque<TraceEvent>,
+}
+
+struct TraceState {
+    started: Instant,
+    trace: StreamingTrace,
+    seq: u64,
+}
+
+/// 开始新的流式会话追踪，清除上一次的记录
+pub fn begin_streaming_trace(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+
+    let mut state = TRACE.lock().unwrap_or_else(|e| e.into_inner());
+    *state = enabled.then(|| TraceState {
+        started: Instant::now(),
+        trace: StreamingTrace {
+            started_at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            dropped: 0,
+            events: VecDeque::new(),
+        },
+        seq: 0,
+    });
+}
+
+/// 记录一条 WebSocket 文本消息（从 JSON 的 type 字段提取事件类型）
+pub fn trace_ws_message(provider: &str, direction: CaptureDirection, text: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let event_type = serde_json::from_str::<serde_json::Value>(text)
+        .ok()
+        .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+        .unwrap_or_else(|| "<unknown>".to_string());
+    record(provider, direction, event_type, text.len());
+}
+
+/// 记录连接状态变化（连接、关闭、错误等）
+pub fn trace_ws_marker(provider: &str, event_type: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    record(provider, CaptureDirection::Recv, event_type.to_string(), 0);
+}
+
+fn record(provider: &str, direction: CaptureDirection, event_type: String, bytes: usize) {
+    let mut guard = TRACE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(state) = guard.as_mut() else {
+        return;
+    };
+
+    state.seq += 1;
+    let elapsed_ms = state.started.elapsed().as_millis() as u64;
+    tracing::info!(
+        "[ws-trace {} #{} +{}ms] {:?} {} ({} bytes)",
+        provider,
+        state.seq,
+        elapsed_ms,
+        direction,
+        event_type,
+        bytes
+    );
+
+    if state.trace.events.len() == MAX_EVENTS {
+        state.trace.events.pop_front();
+        state.trace.dropped += 1;
+    }
+    state.trace.events.push_back(TraceEvent {
+        seq: state.seq,
+        elapsed_ms,
+        provider: provider.to_string(),
+        direction,
+        event_type,
+        bytes,
+    });
+}
+
+/// 获取最近一次流式会话的追踪记录（未开启追踪时为 None）
+pub fn last_streaming_trace() -> Option<StreamingTrace> {
+    let guard = TRACE.lock().unwrap_or_else(|e| e.into_inner());
+    guard.as_ref().map(|state| state.trace.clone())
+}
@@ -0,0 +1,198 @@
+//! 统一的结构化事件
+//!
+//! 所有状态变化和错误都通过同一个事件通道发布，事件带版本号、会话 ID 和类型化的负载，
+//! 前端（`vhisper://event`）和 FFI 宿主（JSON 回调）共用同一份协议
+
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+
+use crate::pipeline::PipelineError;
+use crate::text::DiffSpan;
+
+/// 事件协议版本，负载结构发生不兼容变化时递增
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Tauri 前端监听的事件名
+pub const EVENT_CHANNEL: &str = "vhisper://event";
+
+/// 事件发生的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStage {
+    Startup,
+    Recording,
+    Asr,
+    PostProcess,
+    Output,
+}
+
+/// 错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Audio,
+    Asr,
+    Llm,
+    Timeout,
+    PasteBlocked,
+    /// 目标窗口权限更高（Windows 管理员窗口），输出降级为剪贴板
+    ElevatedTarget,
+    Output,
+    SecureInput,
+    Other,
+}
+
+/// 连续听写的时长限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionLimit {
+    /// 配置的最长听写时长，到达后自动停止
+    MaxDuration,
+    /// 服务商单个连接的时长上限，到达前自动切换到新连接
+    ProviderSession,
+}
+
+/// 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum EventPayload {
+    RecordingStarted,
+    RecordingStopped,
+    RecordingCancelled,
+    ProcessingComplete {
+        text: String,
+    },
+    /// 流式会话结束，包含整个会话拼接后的文本
+    SessionComplete {
+        text: String,
+    },
+    /// Vhisper 自己的窗口在前台时的识别结果（未粘贴）
+    SelfDictation {
+        text: String,
+    },
+    /// 录音中的输入状态，约每 100ms 发布一次
+    ///
+    /// speech 为本地 VAD 的判断，界面据此区分「听到说话」和「只有噪音」
+    InputActivity {
+        /// 峰值电平（0.0 - 1.0）
+        level: f32,
+        speech: bool,
+    },
+    /// 流式识别中的实时词数和语速
+    DictationProgress {
+        words: usize,
+        /// 每分钟词数，录音不足 2 秒时为 0
+        wpm: f32,
+    },
+    /// 原始识别文本到输出文本的修改（替换规则、LLM 校对等），在 ProcessingComplete /
+    /// SessionComplete 之后发布
+    TextDiff {
+        spans: Vec<DiffSpan>,
+        /// 是否有修改，false 时 spans 只有一个 Equal 片段
+        changed: bool,
+    },
+    /// 会话统计，在 ProcessingComplete / SessionComplete 之后发布
+    SessionStats {
+        words: usize,
+        /// 录音时长
+        duration_ms: u64,
+        wpm: f32,
+    },
+    /// 连续听写即将到达时长限制
+    SessionLimitWarning {
+        limit: SessionLimit,
+        remaining_secs: u64,
+    },
+    Error {
+        stage: EventStage,
+        code: ErrorCode,
+        message: String,
+        /// 是否可以直接重试（状态已恢复为空闲）
+        recoverable: bool,
+    },
+    /// 宿主自定义的扩展事件
+    Custom {
+        name: String,
+        data: serde_json::Value,
+    },
+}
+
+/// 发布的事件
+#[derive(Debug, Clone, Serialize)]
+pub struct VhisperEvent {
+    pub version: u32,
+    /// 录音会话 ID，与会话无关的事件为 None
+    pub session_id: Option<String>,
+    /// 发布时隐私模式是否开启，开启时宿主不应保存事件中的文本
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub incognito: bool,
+    #[serde(flatten)]
+    pub payload: EventPayload,
+}
+
+impl VhisperEvent {
+    pub fn new(session_id: Option<String>, payload: EventPayload) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            session_id,
+            incognito: crate::privacy::is_incognito(),
+            payload,
+        }
+    }
+
+    /// 序列化为 JSON（FFI 回调使用）
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl EventPayload {
+    /// 由管道错误生成错误事件
+    pub fn from_pipeline_error(error: &PipelineError) -> Self {
+        let (stage, code) = match error {
+            PipelineError::Audio(_) => (EventStage::Recording, ErrorCode::Audio),
+            PipelineError::Asr(_) => (EventStage::Asr, ErrorCode::Asr),
+            PipelineError::Llm(_) => (EventStage::PostProcess, ErrorCode::Llm),
+            PipelineError::Timeout(_) => (EventStage::Asr, ErrorCode::Timeout),
+            PipelineError::Other(_) | PipelineError::Cancelled => {
+                (EventStage::Asr, ErrorCode::Other)
+            }
+        };
+        EventPayload::Error {
+            stage,
+            code,
+            message: error.to_string(),
+            recoverable: true,
+        }
+    }
+}
+
+type EventListener = Arc<dyn Fn(&VhisperEvent) + Send + Sync>;
+
+static LISTENER: RwLock<Option<EventListener>> = RwLock::new(None);
+
+/// 设置事件监听器（Tauri 应用或 FFI 宿主），None 表示移除
+pub fn set_event_listener(listener: Option<EventListener>) {
+    if let Ok(mut guard) = LISTENER.write() {
+        *guard = listener;
+    }
+}
+
+/// 发布事件
+pub fn publish(session_id: Option<String>, payload: EventPayload) {
+    emit(&VhisperEvent::new(session_id, payload));
+}
+
+/// 把事件交给监听器
+pub(crate) fn emit(event: &VhisperEvent) {
+    if event.incognito {
+        tracing::debug!("Event (incognito): {:?}", event.session_id);
+    } else {
+        tracing::debug!("Event: {}", event.to_json());
+    }
+
+    let listener = LISTENER.read().ok().and_then(|guard| guard.clone());
+    if let Some(listener) = listener {
+        listener(event);
+    }
+}
@@ -7,28 +7,90 @@
 //! # 线程安全
 //! - 所有函数都是线程安全的
 //! - 回调会在后台线程调用，Swift 侧需要 dispatch 到主线程
+//!
+//! # Runtime
+//! - 默认在首次使用时创建自有的多线程 tokio runtime
+//! - 已经运行 tokio 的 Rust 宿主可以在创建实例前调用 [`set_runtime`] 传入自己的 runtime，
+//!   不再创建第二个 runtime；C 宿主可以用 `vhisper_set_runtime_threads` 限制工作线程数
 
 use std::ffi::{c_char, c_void, CStr, CString};
+use std::future::Future;
 use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, OnceLock};
 
-use tokio::runtime::Runtime;
+use tokio::runtime::{Handle, Runtime};
 use tokio::sync::RwLock;
 
 use crate::asr::StreamingAsrEvent;
 use crate::config::AppConfig;
+use crate::debug::set_payload_log;
+use crate::events;
+use crate::history;
+use crate::net::set_proxy;
 use crate::pipeline::VoicePipeline;
+use crate::text::process_text;
 
 // ============================================================================
 // 全局 Runtime
 // ============================================================================
 
-/// 全局 tokio runtime，懒初始化
+/// 宿主提供的 runtime
+static HOST_RUNTIME: OnceLock<Handle> = OnceLock::new();
+
+/// 自有的 tokio runtime，未提供宿主 runtime 时懒初始化
 static RUNTIME: OnceLock<Runtime> = OnceLock::new();
 
-fn get_runtime() -> &'static Runtime {
-    RUNTIME.get_or_init(|| {
-        Runtime::new().expect("Failed to create tokio runtime")
+/// 自有 runtime 的工作线程数，0 表示使用 tokio 默认值（CPU 核数）
+static WORKER_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// 使用宿主的 tokio runtime 执行所有异步任务
+///
+/// 必须在首次调用其他 FFI 函数之前调用；已经设置过宿主 runtime 或自有 runtime 已创建时返回 false
+pub fn set_runtime(handle: Handle) -> bool {
+    if RUNTIME.get().is_some() {
+        return false;
+    }
+    HOST_RUNTIME.set(handle).is_ok()
+}
+
+fn get_runtime() -> Handle {
+    if let Some(handle) = HOST_RUNTIME.get() {
+        return handle.clone();
+    }
+    RUNTIME
+        .get_or_init(|| {
+            let mut builder = tokio::runtime::Builder::new_multi_thread();
+            let threads = WORKER_THREADS.load(Ordering::SeqCst);
+            if threads > 0 {
+                builder.worker_threads(threads);
+            }
+            builder
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime")
+        })
+        .handle()
+        .clone()
+}
+
+/// 同步等待异步任务完成
+///
+/// 宿主在自己的 runtime 中调用时不能直接 block_on（会 panic），改在临时线程中等待
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    let runtime = get_runtime();
+    if Handle::try_current().is_err() {
+        return runtime.block_on(future);
+    }
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| runtime.block_on(future))
+            .join()
+            .expect("FFI blocking task panicked")
     })
 }
 
@@ -78,10 +140,23 @@ pub type VhisperStreamingCallback = extern "C" fn(
     error: *const c_char,
 );
 
+/// 结构化事件回调函数类型
+/// - context: 用户传入的上下文指针
+/// - event_json: 事件 JSON（UTF-8），包含 version、session_id、type、data 字段
+pub type VhisperEventCallback = extern "C" fn(context: *mut c_void, event_json: *const c_char);
+
 // ============================================================================
 // FFI 函数
 // ============================================================================
 
+/// 应用进程级的设置（网络代理、请求日志），创建实例和更新配置时调用
+fn apply_global_settings(config: &AppConfig) {
+    if let Err(e) = set_proxy(config.network.proxy.as_deref()) {
+        tracing::warn!("Ignoring invalid proxy setting: {}", e);
+    }
+    set_payload_log(config.debug.payload_log);
+}
+
 /// 创建 Vhisper 实例
 ///
 /// # 参数
@@ -111,6 +186,7 @@ pub extern "C" fn vhisper_create(config_json: *const c_char) -> *mut VhisperHand
         }
     };
 
+    apply_global_settings(&config);
     let config_arc = Arc::new(RwLock::new(config));
 
     match VoicePipeline::new(config_arc.clone()) {
@@ -257,6 +333,64 @@ pub extern "C" fn vhisper_stop_recording(
     0
 }
 
+/// 对任意文本执行后处理流程（异步）
+///
+/// 只运行替换、规范化、LLM 校对等文本处理，不涉及录音
+///
+/// # 参数
+/// - handle: Vhisper 实例
+/// - text: 待处理的文本（UTF-8）
+/// - profile: 应用标识（如 bundle id），可以为 NULL
+/// - callback: 结果回调函数
+/// - context: 传递给回调的用户上下文
+///
+/// # 返回
+/// - 0: 任务已提交
+/// - -1: handle 或 text 无效
+/// - -2: 字符串不是有效的 UTF-8
+#[no_mangle]
+pub extern "C" fn vhisper_process_text(
+    handle: *mut VhisperHandle,
+    text: *const c_char,
+    profile: *const c_char,
+    callback: VhisperResultCallback,
+    context: *mut c_void,
+) -> i32 {
+    if handle.is_null() || text.is_null() {
+        return -1;
+    }
+
+    let handle = unsafe { &*handle };
+    let config = handle.config.clone();
+
+    let text = match unsafe { CStr::from_ptr(text) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(_) => return -2,
+    };
+    let profile = if profile.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(profile) }.to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => return -2,
+        }
+    };
+
+    // context 指针转为 usize 以满足 Send 约束
+    let context_usize = context as usize;
+
+    get_runtime().spawn(async move {
+        let config = config.read().await.clone();
+        let result = process_text(&config, &text, profile.as_deref()).await;
+
+        let ctx = context_usize as *mut c_void;
+        let c_text = CString::new(result).unwrap_or_default();
+        callback(ctx, c_text.as_ptr(), ptr::null());
+    });
+
+    0
+}
+
 /// 更新配置
 ///
 /// # 参数
@@ -292,9 +426,11 @@ pub extern "C" fn vhisper_update_config(
         }
     };
 
-    get_runtime().block_on(async {
-        let mut config = handle.config.write().await;
-        *config = new_config;
+    apply_global_settings(&new_config);
+    block_on(async {
+        *handle.config.write().await = new_config;
+        // 流式会话进行中时切换到新的 ASR 配置
+        handle.pipeline.reload_streaming_asr().await;
     });
 
     0
@@ -313,6 +449,63 @@ pub extern "C" fn vhisper_string_free(s: *mut c_char) {
     }
 }
 
+/// 获取听写统计历史
+///
+/// # 返回
+/// - JSON 数组（每个会话的词数、时长和语速），调用方需要用 vhisper_string_free 释放
+/// - NULL: 读取失败
+#[no_mangle]
+pub extern "C" fn vhisper_get_history() -> *mut c_char {
+    let records = match history::load_history() {
+        Ok(records) => records,
+        Err(e) => {
+            tracing::error!("Failed to load history: {}", e);
+            return ptr::null_mut();
+        }
+    };
+    let json = serde_json::to_string(&records).unwrap_or_else(|_| "[]".to_string());
+    CString::new(json)
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut())
+}
+
+/// 空闲超过节能配置的时长时释放本地模型和保活连接
+///
+/// 宿主定时调用（如每 30 秒），下次开始录音时按需重新建立
+///
+/// # 返回
+/// - 1: 已释放
+/// - 0: 未到空闲时长、正在使用或已经释放过
+/// - -1: handle 无效
+#[no_mangle]
+pub extern "C" fn vhisper_release_if_idle(handle: *mut VhisperHandle) -> i32 {
+    if handle.is_null() {
+        return -1;
+    }
+
+    let handle = unsafe { &*handle };
+    handle.pipeline.release_if_idle() as i32
+}
+
+/// 设置自有 tokio runtime 的工作线程数
+///
+/// 必须在首次调用其他 FFI 函数之前调用
+///
+/// # 参数
+/// - worker_threads: 工作线程数，0 表示使用默认值（CPU 核数）
+///
+/// # 返回
+/// - 0: 成功
+/// - -1: runtime 已创建或宿主已通过 set_runtime 提供 runtime
+#[no_mangle]
+pub extern "C" fn vhisper_set_runtime_threads(worker_threads: u32) -> i32 {
+    if RUNTIME.get().is_some() || HOST_RUNTIME.get().is_some() {
+        return -1;
+    }
+    WORKER_THREADS.store(worker_threads as usize, Ordering::SeqCst);
+    0
+}
+
 /// 获取版本号
 #[no_mangle]
 pub extern "C" fn vhisper_version() -> *const c_char {
@@ -502,3 +695,24 @@ pub extern "C" fn vhisper_is_streaming(handle: *mut VhisperHandle) -> i32 {
         0
     }
 }
+
+/// 设置结构化事件回调
+///
+/// 回调是全局的，后设置的覆盖先前的；callback 为 NULL 时移除
+#[no_mangle]
+pub extern "C" fn vhisper_set_event_callback(
+    callback: Option<VhisperEventCallback>,
+    context: *mut c_void,
+) {
+    let Some(callback) = callback else {
+        events::set_event_listener(None);
+        return;
+    };
+
+    // context 指针转为 usize 以满足 Send 约束
+    let context_usize = context as usize;
+    events::set_event_listener(Some(Arc::new(move |event| {
+        let c_json = CString::new(event.to_json()).unwrap_or_default();
+        callback(context_usize as *mut c_void, c_json.as_ptr());
+    })));
+}
@@ -0,0 +1,194 @@
+//! 轻量 Markdown 转换
+//!
+//! 只支持听写结果中常见的语法：标题、无序/有序列表、**粗体**、*斜体*、`代码`
+
+/// 行级结构
+enum Block<'a> {
+    Heading(usize, &'a str),
+    Bullet(&'a str),
+    Ordered(&'a str),
+    Paragraph(&'a str),
+    Blank,
+}
+
+fn parse_line(line: &str) -> Block<'_> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Block::Blank;
+    }
+
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+        return Block::Heading(hashes, trimmed[hashes..].trim());
+    }
+
+    for marker in ["- ", "* ", "• "] {
+        if let Some(rest) = trimmed.strip_prefix(marker) {
+            return Block::Bullet(rest.trim());
+        }
+    }
+
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let rest = &trimmed[digits..];
+        if let Some(item) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix("、")) {
+            return Block::Ordered(item.trim());
+        }
+    }
+
+    Block::Paragraph(trimmed)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 处理行内强调标记
+///
+/// `html` 为 true 时输出标签，否则只去掉标记
+fn render_inline(text: &str, html: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let (marker, open, close) = if rest.starts_with("**") {
+            ("**", "<strong>", "</strong>")
+        } else if rest.starts_with("__") {
+            ("__", "<strong>", "</strong>")
+        } else if rest.starts_with('*') {
+            ("*", "<em>", "</em>")
+        } else if rest.starts_with('`') {
+            ("`", "<code>", "</code>")
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            let len = c.len_utf8();
+            if html {
+                out.push_str(&escape_html(&rest[..len]));
+            } else {
+                out.push(c);
+            }
+            rest = &rest[len..];
+            continue;
+        };
+
+        let body = &rest[marker.len()..];
+        match body.find(marker) {
+            Some(end) if end > 0 => {
+                let inner = &body[..end];
+                if html {
+                    out.push_str(open);
+                    if marker == "`" {
+                        out.push_str(&escape_html(inner));
+                    } else {
+                        out.push_str(&render_inline(inner, true));
+                    }
+                    out.push_str(close);
+                } else {
+                    out.push_str(&render_inline(inner, false));
+                }
+                rest = &body[end + marker.len()..];
+            }
+            _ => {
+                // 没有闭合标记，按原样输出
+                if html {
+                    out.push_str(&escape_html(marker));
+                } else {
+                    out.push_str(marker);
+                }
+                rest = body;
+            }
+        }
+    }
+
+    out
+}
+
+/// 将 Markdown 转换为 HTML 片段
+pub fn markdown_to_html(text: &str) -> String {
+    let mut html = String::new();
+    // 当前打开的列表标签
+    let mut open_list: Option<&str> = None;
+
+    for line in text.lines() {
+        let block = parse_line(line);
+
+        let list_tag = match block {
+            Block::Bullet(_) => Some("ul"),
+            Block::Ordered(_) => Some("ol"),
+            _ => None,
+        };
+        if open_list != list_tag {
+            if let Some(tag) = open_list {
+                html.push_str(&format!("</{}>", tag));
+            }
+            if let Some(tag) = list_tag {
+                html.push_str(&format!("<{}>", tag));
+            }
+            open_list = list_tag;
+        }
+
+        match block {
+            Block::Heading(level, content) => {
+                html.push_str(&format!(
+                    "<h{level}>{}</h{level}>",
+                    render_inline(content, true)
+                ));
+            }
+            Block::Bullet(content) | Block::Ordered(content) => {
+                html.push_str(&format!("<li>{}</li>", render_inline(content, true)));
+            }
+            Block::Paragraph(content) => {
+                html.push_str(&format!("<p>{}</p>", render_inline(content, true)));
+            }
+            Block::Blank => {}
+        }
+    }
+
+    if let Some(tag) = open_list {
+        html.push_str(&format!("</{}>", tag));
+    }
+
+    html
+}
+
+/// 去掉 Markdown 标记，得到纯文本版本
+///
+/// 列表项保留 "- " / "1. " 前缀，便于在纯文本编辑器中阅读
+pub fn strip_markdown(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut index = 0;
+
+    for line in text.lines() {
+        let stripped = match parse_line(line) {
+            Block::Heading(_, content) | Block::Paragraph(content) => {
+                index = 0;
+                render_inline(content, false)
+            }
+            Block::Bullet(content) => {
+                index = 0;
+                format!("- {}", render_inline(content, false))
+            }
+            Block::Ordered(content) => {
+                index += 1;
+                format!("{}. {}", index, render_inline(content, false))
+            }
+            Block::Blank => {
+                index = 0;
+                String::new()
+            }
+        };
+        lines.push(stripped);
+    }
+
+    lines.join("\n")
+}
@@ -0,0 +1,11 @@
+//! 文本格式化
+//!
+//! 将 LLM 输出的轻量 Markdown 转换为剪贴板可用的多种格式，以及字幕导出、按说话人分行
+
+mod markdown;
+mod speakers;
+mod subtitles;
+
+pub use markdown::{markdown_to_html, strip_markdown};
+pub use speakers::label_speakers;
+pub use subtitles::segments_to_srt;
@@ -0,0 +1,49 @@
+//! 按说话人分行
+
+use crate::asr::AsrSegment;
+use crate::text::is_cjk;
+
+/// 中日韩文字或全角标点
+fn is_wide(c: char) -> bool {
+    is_cjk(c) || matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF)
+}
+
+/// 按说话人把片段整理为多行文本，每行以 "Speaker N:" 开头
+///
+/// 同一说话人的连续片段合并为一行；没有任何片段带说话人标签时返回 None
+pub fn label_speakers(segments: &[AsrSegment]) -> Option<String> {
+    if segments.iter().all(|s| s.speaker.is_none()) {
+        return None;
+    }
+
+    let mut lines: Vec<(Option<u32>, String)> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match lines.last_mut() {
+            Some((speaker, line)) if *speaker == segment.speaker => {
+                // 中文之间不加空格
+                let cjk_join = line.chars().last().is_some_and(is_wide)
+                    && text.chars().next().is_some_and(is_wide);
+                if !cjk_join {
+                    line.push(' ');
+                }
+                line.push_str(text);
+            }
+            _ => lines.push((segment.speaker, text.to_string())),
+        }
+    }
+
+    Some(
+        lines
+            .into_iter()
+            .map(|(speaker, line)| match speaker {
+                Some(n) => format!("Speaker {}: {}", n, line),
+                None => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
@@ -0,0 +1,31 @@
+//! 字幕导出
+
+use crate::asr::AsrSegment;
+
+fn srt_timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// 将带时间戳的片段转换为 SRT 字幕
+pub fn segments_to_srt(segments: &[AsrSegment]) -> String {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                srt_timestamp(segment.start_ms),
+                srt_timestamp(segment.end_ms),
+                segment.text.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,95 @@
+//! 每日目标和连续天数
+//!
+//! 根据听写历史按本地日期汇总词数：设置了每日目标时，达到目标的日子计入连续天数；
+//! 没有目标时有听写的日子都计入
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::SessionRecord;
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// 今日进度和连续天数
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GoalProgress {
+    /// 每日目标词数，0 表示未设置
+    pub daily_goal: usize,
+    pub today_words: usize,
+    pub goal_met_today: bool,
+    /// 截至今天（今天还没达标时截至昨天）的连续达标天数
+    pub current_streak: u32,
+    pub longest_streak: u32,
+}
+
+/// Unix 时间戳所在的本地日期（从 1970-01-01 起的天数）
+fn local_day(timestamp: u64, utc_offset_secs: i64) -> i64 {
+    (timestamp as i64 + utc_offset_secs).div_euclid(SECS_PER_DAY)
+}
+
+/// 计算目标进度
+///
+/// `now` 为当前 Unix 时间戳，`utc_offset_secs` 为本地时区相对 UTC 的偏移
+pub fn goal_progress(
+    records: &[SessionRecord],
+    daily_goal: usize,
+    now: u64,
+    utc_offset_secs: i64,
+) -> GoalProgress {
+    let mut words_by_day: BTreeMap<i64, usize> = BTreeMap::new();
+    for record in records {
+        *words_by_day
+            .entry(local_day(record.finished_at, utc_offset_secs))
+            .or_default() += record.words;
+    }
+    let met = |words: usize| words > 0 && words >= daily_goal;
+
+    let today = local_day(now, utc_offset_secs);
+    let today_words = words_by_day.get(&today).copied().unwrap_or_default();
+    let goal_met_today = met(today_words);
+
+    let mut longest_streak = 0;
+    let mut streak = 0;
+    let mut previous_day = None;
+    for (&day, &words) in &words_by_day {
+        if !met(words) {
+            streak = 0;
+            continue;
+        }
+        streak = if previous_day == Some(day - 1) {
+            streak + 1
+        } else {
+            1
+        };
+        previous_day = Some(day);
+        longest_streak = longest_streak.max(streak);
+    }
+
+    // 今天还没达标不算中断，从昨天往前数
+    let mut current_streak = 0;
+    let mut day = if goal_met_today { today } else { today - 1 };
+    while words_by_day.get(&day).is_some_and(|&words| met(words)) {
+        current_streak += 1;
+        day -= 1;
+    }
+
+    GoalProgress {
+        daily_goal,
+        today_words,
+        goal_met_today,
+        current_streak,
+        longest_streak,
+    }
+}
+
+/// 按当前时间和本地时区计算目标进度
+pub fn current_goal_progress(records: &[SessionRecord], daily_goal: usize) -> GoalProgress {
+    let now = chrono::Local::now();
+    goal_progress(
+        records,
+        daily_goal,
+        now.timestamp().max(0) as u64,
+        now.offset().local_minus_utc() as i64,
+    )
+}
@@ -0,0 +1,85 @@
+//! 听写历史统计
+//!
+//! 每个会话结束后记录词数、录音时长和语速（不记录文本），供统计面板使用。
+//! 保存在配置目录下的 history.jsonl，每行一条记录，只追加不改写。
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+mod goals;
+mod transcripts;
+
+pub use goals::{current_goal_progress, goal_progress, GoalProgress};
+pub use transcripts::{
+    load_session_transcript, load_transcripts, read_session_transcript, read_transcripts,
+    save_session_transcript, save_transcript, write_session_transcript, write_transcript,
+    DialogueLine, DialogueTranscript, Speaker, TimedTranscript, TranscriptSegment,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryError {
+    #[error("Config directory not found")]
+    DirNotFound,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid id: {0}")]
+    InvalidId(String),
+}
+
+/// 一次听写会话的统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub session_id: String,
+    /// 结束时间（Unix 时间戳，秒）
+    pub finished_at: u64,
+    pub words: usize,
+    /// 录音时长
+    pub duration_ms: u64,
+    /// 每分钟词数
+    pub wpm: f32,
+}
+
+/// 历史文件路径
+fn history_path() -> Result<PathBuf, HistoryError> {
+    let config_dir = dirs::config_dir().ok_or(HistoryError::DirNotFound)?;
+    let app_dir = config_dir.join("com.vhisper.app");
+    fs::create_dir_all(&app_dir)?;
+    Ok(app_dir.join("history.jsonl"))
+}
+
+/// 追加一条会话记录
+pub fn record_session(record: &SessionRecord) -> Result<(), HistoryError> {
+    append_record(&history_path()?, record)
+}
+
+/// 读取全部会话记录（按时间先后）
+pub fn load_history() -> Result<Vec<SessionRecord>, HistoryError> {
+    read_records(&history_path()?)
+}
+
+/// 追加记录到指定文件
+pub fn append_record(path: &Path, record: &SessionRecord) -> Result<(), HistoryError> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// 从指定文件读取记录，文件不存在时为空；写入中断留下的残缺行会被跳过
+pub fn read_records(path: &Path) -> Result<Vec<SessionRecord>, HistoryError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
@@ -0,0 +1,197 @@
+//! 访谈对话和长会话的带时间戳文本
+//!
+//! 麦克风和系统声音分轨识别后按时间交错成对话，保存在配置目录下的 `transcripts` 目录；
+//! 流式会话的分段文本和时间保存在其下的 `sessions` 目录，供文本面板按段查看。
+//! 每个记录一个 JSON 文件。与听写统计不同，这里保存文本
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::HistoryError;
+
+/// 说话人（按音轨区分）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Speaker {
+    /// 麦克风（本人）
+    Me,
+    /// 系统声音（对方）
+    Other,
+}
+
+impl Speaker {
+    pub fn label(self) -> &'static str {
+        match self {
+            Speaker::Me => "我",
+            Speaker::Other => "对方",
+        }
+    }
+}
+
+/// 对话中的一句
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueLine {
+    pub speaker: Speaker,
+    /// 相对录音开始的时间
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// 一次访谈的对话记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueTranscript {
+    pub id: String,
+    /// 开始时间（Unix 时间戳，秒）
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub lines: Vec<DialogueLine>,
+}
+
+impl DialogueTranscript {
+    /// 按开始时间交错两条音轨的句子
+    pub fn new(
+        id: String,
+        started_at: u64,
+        duration_ms: u64,
+        mut lines: Vec<DialogueLine>,
+    ) -> Self {
+        lines.sort_by_key(|line| line.start_ms);
+        Self {
+            id,
+            started_at,
+            duration_ms,
+            lines,
+        }
+    }
+
+    /// 纯文本对话，每句一行：`[mm:ss] 说话人: 文本`
+    pub fn to_text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| {
+                let secs = line.start_ms / 1000;
+                format!(
+                    "[{:02}:{:02}] {}: {}",
+                    secs / 60,
+                    secs % 60,
+                    line.speaker.label(),
+                    line.text
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// 会话中的一段识别结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    /// 相对录音开始的时间
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// 一次流式会话的分段文本（ASR 原始结果，未经文本后处理）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedTranscript {
+    pub session_id: String,
+    /// 结束时间（Unix 时间戳，秒）
+    pub finished_at: u64,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// 对话记录目录
+fn transcripts_dir() -> Result<PathBuf, HistoryError> {
+    let config_dir = dirs::config_dir().ok_or(HistoryError::DirNotFound)?;
+    Ok(config_dir.join("com.vhisper.app").join("transcripts"))
+}
+
+/// 会话文本目录
+fn sessions_dir() -> Result<PathBuf, HistoryError> {
+    Ok(transcripts_dir()?.join("sessions"))
+}
+
+/// 会话 ID 只允许字母、数字和连字符，避免拼出目录外的路径
+fn is_valid_session_id(session_id: &str) -> bool {
+    !session_id.is_empty()
+        && session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// 保存对话记录
+pub fn save_transcript(transcript: &DialogueTranscript) -> Result<(), HistoryError> {
+    write_transcript(&transcripts_dir()?, transcript)
+}
+
+/// 读取所有对话记录，最新的在前
+pub fn load_transcripts() -> Result<Vec<DialogueTranscript>, HistoryError> {
+    read_transcripts(&transcripts_dir()?)
+}
+
+/// 写入对话记录到指定目录
+pub fn write_transcript(dir: &Path, transcript: &DialogueTranscript) -> Result<(), HistoryError> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", transcript.id));
+    fs::write(path, serde_json::to_string_pretty(transcript)?)?;
+    Ok(())
+}
+
+/// 从指定目录读取对话记录，目录不存在时为空
+pub fn read_transcripts(dir: &Path) -> Result<Vec<DialogueTranscript>, HistoryError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut transcripts: Vec<DialogueTranscript> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| serde_json::from_str(&fs::read_to_string(entry.path()).ok()?).ok())
+        .collect();
+    transcripts.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(transcripts)
+}
+
+/// 保存会话的分段文本
+pub fn save_session_transcript(transcript: &TimedTranscript) -> Result<(), HistoryError> {
+    write_session_transcript(&sessions_dir()?, transcript)
+}
+
+/// 读取会话的分段文本，没有记录时为 None
+pub fn load_session_transcript(session_id: &str) -> Result<Option<TimedTranscript>, HistoryError> {
+    read_session_transcript(&sessions_dir()?, session_id)
+}
+
+/// 写入会话分段文本到指定目录
+pub fn write_session_transcript(
+    dir: &Path,
+    transcript: &TimedTranscript,
+) -> Result<(), HistoryError> {
+    if !is_valid_session_id(&transcript.session_id) {
+        return Err(HistoryError::InvalidId(transcript.session_id.clone()));
+    }
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", transcript.session_id));
+    fs::write(path, serde_json::to_string(transcript)?)?;
+    Ok(())
+}
+
+/// 从指定目录读取会话分段文本
+pub fn read_session_transcript(
+    dir: &Path,
+    session_id: &str,
+) -> Result<Option<TimedTranscript>, HistoryError> {
+    if !is_valid_session_id(session_id) {
+        return Err(HistoryError::InvalidId(session_id.to_string()));
+    }
+    match fs::read_to_string(dir.join(format!("{}.json", session_id))) {
+        Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
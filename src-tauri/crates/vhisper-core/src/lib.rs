@@ -1,13 +1,39 @@
 pub mod asr;
 pub mod audio;
+pub mod calendar;
 pub mod config;
+pub mod debug;
+pub mod events;
+#[cfg(feature = "ffi")]
 pub mod ffi;
+pub mod format;
+pub mod history;
 pub mod llm;
+pub mod net;
 pub mod pipeline;
+pub mod privacy;
+pub mod telemetry;
+pub mod text;
+pub mod update;
 
-pub use asr::{create_asr_service, AsrError, AsrResult, AsrService};
-pub use asr::{test_qwen_api, test_dashscope_api, test_openai_api, test_funasr_api};
-pub use audio::{encode_to_pcm, encode_to_wav, AudioError, AudioRecorder};
-pub use config::{load_config, save_config, AppConfig, HotkeyBinding, KeyCode};
-pub use llm::{create_llm_service, LlmError, LlmService, test_ollama_api};
-pub use pipeline::{PipelineError, VoicePipeline};
+pub use asr::{check_provider_reachable, create_asr_service, AsrError, AsrResult, AsrSegment, AsrService};
+pub use asr::{list_asr_models, provider_stats, ProviderStats};
+pub use asr::test_asr_api;
+#[cfg(feature = "sherpa-onnx")]
+pub use asr::{download_sherpa_onnx_model, stop_sherpa_onnx_server};
+pub use audio::{encode_to_pcm, encode_to_wav, has_input_device, AudioError, AudioRecorder};
+pub use config::{load_config, save_config, AppConfig, DualKeyChord, HotkeyBinding, KeyCode, KeySide};
+pub use events::{EventPayload, VhisperEvent, EVENT_CHANNEL, EVENT_SCHEMA_VERSION};
+pub use format::{label_speakers, markdown_to_html, segments_to_srt, strip_markdown};
+pub use llm::{create_llm_service, list_llm_models, LlmError, LlmService};
+#[cfg(feature = "ollama")]
+pub use llm::test_ollama_api;
+pub use pipeline::{
+    last_dictation, release_idle_resources, take_last_dictation, Dictation, OutputSink, PipelineError, PipelineEvent, PipelineRegistry,
+    PipelineSource, PipelineState, VoicePipeline, VoicePipelineBuilder,
+};
+pub use privacy::{is_incognito, set_incognito};
+pub use text::{
+    available_dictionaries, count_words, diff_text, process_text, words_per_minute, DiffSpan,
+    Spellchecker, TextPipeline,
+};
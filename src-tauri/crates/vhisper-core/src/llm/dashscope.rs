@@ -2,23 +2,35 @@ use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use super::traits::{LlmError, LlmService, REFINE_PROMPT};
+use super::shared_client;
+use super::traits::{LlmError, LlmService, ModePrompts};
+use crate::config::settings::PostProcessMode;
+
+const API_URL: &str = "https://dashscope.aliyuncs.com/api/v1/services/aigc/text-generation/generation";
 
 /// DashScope LLM 服务 (通义千问)
 pub struct DashScopeLlm {
     api_key: String,
     model: String,
+    prompts: ModePrompts,
     client: Client,
 }
 
 impl DashScopeLlm {
-    pub fn new(api_key: String, model: String) -> Self {
+    pub fn new(api_key: String, model: String, language: Option<String>) -> Self {
         Self {
             api_key,
             model,
-            client: Client::new(),
+            prompts: ModePrompts::new(language),
+            client: shared_client(),
         }
     }
+
+    /// 使用配置中的各模式提示词
+    pub fn with_prompts(mut self, prompts: ModePrompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
 }
 
 #[derive(Serialize)]
@@ -62,14 +74,14 @@ struct ChoiceMessage {
 
 #[async_trait]
 impl LlmService for DashScopeLlm {
-    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError> {
         let request = DashScopeRequest {
             model: self.model.clone(),
             input: DashScopeInput {
                 messages: vec![
                     Message {
                         role: "system".to_string(),
-                        content: REFINE_PROMPT.to_string(),
+                        content: self.prompts.system_prompt(mode),
                     },
                     Message {
                         role: "user".to_string(),
@@ -81,7 +93,7 @@ impl LlmService for DashScopeLlm {
 
         let response = self
             .client
-            .post("https://dashscope.aliyuncs.com/api/v1/services/aigc/text-generation/generation")
+            .post(API_URL)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&request)
@@ -121,4 +133,14 @@ impl LlmService for DashScopeLlm {
 
         Ok(output_text.trim().to_string())
     }
+
+    /// HEAD 请求只为建立 TLS 连接放入连接池，忽略响应状态
+    async fn warm_up(&self) -> Result<(), LlmError> {
+        self.client
+            .head(API_URL)
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+        Ok(())
+    }
 }
@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::shared_client;
+use super::traits::{LlmError, LlmService, ModePrompts};
+use crate::config::settings::PostProcessMode;
+
+/// Groq 的 OpenAI 兼容接口
+const API_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
+
+/// Groq LLM 服务（托管的 Llama 等开源模型，推理延迟很低）
+pub struct GroqLlm {
+    api_key: String,
+    model: String,
+    temperature: f32,
+    max_tokens: u32,
+    prompts: ModePrompts,
+    client: Client,
+}
+
+impl GroqLlm {
+    pub fn new(
+        api_key: String,
+        model: String,
+        temperature: f32,
+        max_tokens: u32,
+        language: Option<String>,
+    ) -> Self {
+        Self {
+            api_key,
+            model,
+            temperature,
+            max_tokens,
+            prompts: ModePrompts::new(language),
+            client: shared_client(),
+        }
+    }
+
+    /// 使用配置中的各模式提示词
+    pub fn with_prompts(mut self, prompts: ModePrompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct GroqRequest {
+    model: String,
+    messages: Vec<Message>,
+    temperature: f32,
+    max_tokens: u32,
+}
+
+#[derive(Serialize)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct GroqResponse {
+    choices: Option<Vec<Choice>>,
+    error: Option<GroqError>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChoiceMessage {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct GroqError {
+    message: String,
+}
+
+#[async_trait]
+impl LlmService for GroqLlm {
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError> {
+        let request = GroqRequest {
+            model: self.model.clone(),
+            messages: vec![
+                Message {
+                    role: "system".to_string(),
+                    content: self.prompts.system_prompt(mode),
+                },
+                Message {
+                    role: "user".to_string(),
+                    content: text.to_string(),
+                },
+            ],
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+        };
+
+        let response = self
+            .client
+            .post(API_URL)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(LlmError::Api(format!("HTTP {}: {}", status, body)));
+        }
+
+        let result: GroqResponse =
+            serde_json::from_str(&body).map_err(|e| LlmError::Api(e.to_string()))?;
+
+        if let Some(error) = result.error {
+            return Err(LlmError::Api(error.message));
+        }
+
+        let output_text = result
+            .choices
+            .and_then(|c| c.into_iter().next().map(|choice| choice.message.content))
+            .unwrap_or_else(|| text.to_string());
+
+        Ok(output_text.trim().to_string())
+    }
+
+    /// HEAD 请求只为建立 TLS 连接放入连接池，忽略响应状态
+    async fn warm_up(&self) -> Result<(), LlmError> {
+        self.client
+            .head(API_URL)
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+        Ok(())
+    }
+}
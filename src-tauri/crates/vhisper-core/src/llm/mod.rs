@@ -1,14 +1,68 @@
+// 裁剪服务商（--no-default-features）后，部分服务商共用的辅助函数不再使用
+#![cfg_attr(
+    not(all(
+        feature = "dashscope",
+        feature = "openai",
+        feature = "groq",
+        feature = "ollama"
+    )),
+    allow(dead_code)
+)]
+
+#[cfg(feature = "dashscope")]
 mod dashscope;
+#[cfg(feature = "groq")]
+mod groq;
+#[cfg(feature = "ollama")]
 mod ollama;
+#[cfg(feature = "openai")]
 mod openai;
 mod traits;
 
+#[cfg(feature = "dashscope")]
 pub use dashscope::DashScopeLlm;
+#[cfg(feature = "groq")]
+pub use groq::GroqLlm;
+#[cfg(feature = "ollama")]
 pub use ollama::OllamaLlm;
+#[cfg(feature = "openai")]
 pub use openai::OpenAiLlm;
-pub use traits::{LlmError, LlmService};
+pub use traits::{LlmError, LlmService, ModePrompts};
+
+use std::sync::Mutex;
+
+use reqwest::Client;
 
+#[cfg(feature = "openai")]
+use crate::config::settings::default_openai_base_url;
 use crate::config::settings::LlmConfig;
+use crate::net::{openai_compatible_models, proxied_client};
+
+/// DashScope 的 OpenAI 兼容接口
+#[cfg(feature = "dashscope")]
+const DASHSCOPE_COMPATIBLE_URL: &str = "https://dashscope.aliyuncs.com/compatible-mode/v1";
+#[cfg(feature = "groq")]
+const GROQ_API_URL: &str = "https://api.groq.com/openai/v1";
+
+/// 云端 LLM 共用的 HTTP 客户端（连接池）
+static CLIENT: Mutex<Option<Client>> = Mutex::new(None);
+
+/// 云端 LLM 共用的 HTTP 客户端
+///
+/// 连接池跨服务实例复用，预热时建立的连接在随后的修正请求中直接使用
+pub(crate) fn shared_client() -> Client {
+    let mut client = CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    client.get_or_insert_with(proxied_client).clone()
+}
+
+/// 丢弃共用客户端及其保活连接，下次请求时重新创建
+///
+/// 已创建的服务实例持有的客户端不受影响，随实例释放
+pub(crate) fn release_connections() {
+    if let Ok(mut client) = CLIENT.lock() {
+        *client = None;
+    }
+}
 
 /// 根据配置创建 LLM 服务
 pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmService>>, LlmError> {
@@ -17,6 +71,7 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
     }
 
     match config.provider.as_str() {
+        #[cfg(feature = "dashscope")]
         "DashScope" => {
             let dashscope_config = config
                 .dashscope
@@ -25,8 +80,11 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
             Ok(Some(Box::new(DashScopeLlm::new(
                 dashscope_config.api_key.clone(),
                 dashscope_config.model.clone(),
-            ))))
+                config.language.clone(),
+            )
+            .with_prompts(ModePrompts::from_config(config)))))
         }
+        #[cfg(feature = "openai")]
         "OpenAI" => {
             let openai_config = config
                 .openai
@@ -34,11 +92,30 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
                 .ok_or_else(|| LlmError::Config("OpenAI LLM 配置缺失".to_string()))?;
             Ok(Some(Box::new(OpenAiLlm::new(
                 openai_config.api_key.clone(),
+                &openai_config.base_url,
                 openai_config.model.clone(),
                 openai_config.temperature,
                 openai_config.max_tokens,
-            ))))
+                config.language.clone(),
+            )
+            .with_prompts(ModePrompts::from_config(config)))))
         }
+        #[cfg(feature = "groq")]
+        "Groq" => {
+            let groq_config = config
+                .groq
+                .as_ref()
+                .ok_or_else(|| LlmError::Config("Groq LLM 配置缺失".to_string()))?;
+            Ok(Some(Box::new(GroqLlm::new(
+                groq_config.api_key.clone(),
+                groq_config.model.clone(),
+                groq_config.temperature,
+                groq_config.max_tokens,
+                config.language.clone(),
+            )
+            .with_prompts(ModePrompts::from_config(config)))))
+        }
+        #[cfg(feature = "ollama")]
         "Ollama" => {
             let ollama_config = config
                 .ollama
@@ -47,8 +124,15 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
             Ok(Some(Box::new(OllamaLlm::new(
                 ollama_config.endpoint.clone(),
                 ollama_config.model.clone(),
-            ))))
+                config.language.clone(),
+                &ollama_config.tls,
+            )?
+            .with_prompts(ModePrompts::from_config(config)))))
         }
+        // 已知的服务商未编译进当前版本（见 Cargo.toml 中的 feature）
+        provider if matches!(provider, "DashScope" | "OpenAI" | "Groq" | "Ollama") => Err(
+            LlmError::Config(format!("当前版本未包含 LLM 服务商 {}", provider)),
+        ),
         _ => Err(LlmError::Config(format!(
             "未知的 LLM 服务商: {}",
             config.provider
@@ -56,6 +140,78 @@ pub fn create_llm_service(config: &LlmConfig) -> Result<Option<Box<dyn LlmServic
     }
 }
 
+/// 预热配置的 LLM 服务，失败只记录日志（修正时会重新连接）
+pub async fn warm_up_llm(config: &LlmConfig) {
+    let llm = match create_llm_service(config) {
+        Ok(Some(llm)) => llm,
+        _ => return,
+    };
+    let started = std::time::Instant::now();
+    match llm.warm_up().await {
+        Ok(()) => tracing::debug!("LLM warmed up in {:?}", started.elapsed()),
+        Err(e) => tracing::debug!("LLM warm-up failed: {}", e),
+    }
+}
+
+/// 查询服务商可用的对话模型，供设置界面选择
+///
+/// DashScope、OpenAI 和 Groq 使用 api_key 查询，Ollama 查询 endpoint 上已下载的模型；
+/// OpenAI 的 endpoint 为接口地址，为空时使用官方地址
+pub async fn list_llm_models(
+    provider: &str,
+    api_key: &str,
+    endpoint: &str,
+) -> Result<Vec<String>, LlmError> {
+    #[cfg(not(any(feature = "ollama", feature = "openai")))]
+    let _ = endpoint;
+    #[cfg(feature = "openai")]
+    let openai_base_url = if endpoint.is_empty() {
+        default_openai_base_url()
+    } else {
+        endpoint.trim_end_matches('/').to_string()
+    };
+    let (base_url, is_chat_model): (&str, fn(&str) -> bool) = match provider {
+        #[cfg(feature = "dashscope")]
+        "DashScope" => (DASHSCOPE_COMPATIBLE_URL, |id| {
+            id.starts_with("qwen")
+                && !["asr", "tts", "embedding", "omni"]
+                    .iter()
+                    .any(|kind| id.contains(kind))
+        }),
+        // 兼容服务的模型命名各不相同，不过滤
+        #[cfg(feature = "openai")]
+        "OpenAI" if openai_base_url != default_openai_base_url() => {
+            return Ok(openai_compatible_models(&openai_base_url, api_key).await?);
+        }
+        #[cfg(feature = "openai")]
+        "OpenAI" => (openai_base_url.as_str(), |id| {
+            let chat = id.starts_with("gpt-")
+                || (id.starts_with('o') && id[1..].starts_with(|c: char| c.is_ascii_digit()));
+            chat && !["audio", "realtime", "tts", "transcribe", "image", "search"]
+                .iter()
+                .any(|kind| id.contains(kind))
+        }),
+        #[cfg(feature = "groq")]
+        "Groq" => (GROQ_API_URL, |id| {
+            !["whisper", "tts", "guard"]
+                .iter()
+                .any(|kind| id.contains(kind))
+        }),
+        #[cfg(feature = "ollama")]
+        "Ollama" => return ollama::list_models(endpoint).await,
+        _ => {
+            return Err(LlmError::Config(format!(
+                "LLM 服务商 {} 不支持查询模型列表",
+                provider
+            )))
+        }
+    };
+
+    let models = openai_compatible_models(base_url, api_key).await?;
+    Ok(models.into_iter().filter(|id| is_chat_model(id)).collect())
+}
+
+#[cfg(feature = "ollama")]
 /// 测试 Ollama API
 pub async fn test_ollama_api(endpoint: &str, model: &str) -> Result<String, LlmError> {
     ollama::test_api(endpoint, model).await
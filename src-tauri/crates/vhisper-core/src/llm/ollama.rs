@@ -2,22 +2,39 @@ use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use super::traits::{LlmError, LlmService, REFINE_PROMPT};
+use super::traits::{LlmError, LlmService, ModePrompts};
+use crate::config::settings::{PostProcessMode, TlsOptions};
+use crate::net::{http_client, proxied_client};
 
 /// Ollama 本地 LLM 服务
 pub struct OllamaLlm {
     endpoint: String,
     model: String,
+    prompts: ModePrompts,
     client: Client,
 }
 
 impl OllamaLlm {
-    pub fn new(endpoint: String, model: String) -> Self {
-        Self {
+    pub fn new(
+        endpoint: String,
+        model: String,
+        language: Option<String>,
+        tls: &TlsOptions,
+    ) -> Result<Self, LlmError> {
+        let (client, endpoint) =
+            http_client(&endpoint, tls).map_err(|e| LlmError::Config(e.to_string()))?;
+        Ok(Self {
             endpoint,
             model,
-            client: Client::new(),
-        }
+            prompts: ModePrompts::new(language),
+            client,
+        })
+    }
+
+    /// 使用配置中的各模式提示词
+    pub fn with_prompts(mut self, prompts: ModePrompts) -> Self {
+        self.prompts = prompts;
+        self
     }
 }
 
@@ -42,7 +59,7 @@ struct OllamaChatResponse {
 
 #[async_trait]
 impl LlmService for OllamaLlm {
-    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError> {
         let url = format!("{}/api/chat", self.endpoint.trim_end_matches('/'));
 
         let request = OllamaChatRequest {
@@ -50,7 +67,7 @@ impl LlmService for OllamaLlm {
             messages: vec![
                 Message {
                     role: "system".to_string(),
-                    content: REFINE_PROMPT.to_string(),
+                    content: self.prompts.system_prompt(mode),
                 },
                 Message {
                     role: "user".to_string(),
@@ -92,11 +109,68 @@ impl LlmService for OllamaLlm {
 
         Ok(output_text.trim().to_string())
     }
+
+    /// 不带 prompt 的 generate 请求让 Ollama 把模型加载到内存
+    async fn warm_up(&self) -> Result<(), LlmError> {
+        let url = format!("{}/api/generate", self.endpoint.trim_end_matches('/'));
+        let response = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "model": self.model }))
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(LlmError::Api(format!("HTTP {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    models: Option<Vec<ModelInfo>>,
+}
+
+#[derive(Deserialize)]
+struct ModelInfo {
+    name: String,
+}
+
+/// 已下载到 Ollama 的模型（按名称排序）
+pub async fn list_models(endpoint: &str) -> Result<Vec<String>, LlmError> {
+    let url = format!("{}/api/tags", endpoint.trim_end_matches('/'));
+    let response = proxied_client()
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| LlmError::Network(format!("无法连接到 Ollama: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(LlmError::Api(format!(
+            "Ollama 服务错误: HTTP {}",
+            response.status()
+        )));
+    }
+
+    let tags: TagsResponse = response
+        .json()
+        .await
+        .map_err(|e| LlmError::Api(e.to_string()))?;
+    let mut names: Vec<String> = tags
+        .models
+        .unwrap_or_default()
+        .into_iter()
+        .map(|model| model.name)
+        .collect();
+    names.sort();
+    Ok(names)
 }
 
 /// 测试 Ollama 服务连接
 pub async fn test_api(endpoint: &str, model: &str) -> Result<String, LlmError> {
-    let client = Client::new();
+    let client = proxied_client();
     let url = format!("{}/api/tags", endpoint.trim_end_matches('/'));
 
     let response = client
@@ -119,16 +193,6 @@ pub async fn test_api(endpoint: &str, model: &str) -> Result<String, LlmError> {
         .await
         .map_err(|e| LlmError::Network(e.to_string()))?;
 
-    #[derive(Deserialize)]
-    struct TagsResponse {
-        models: Option<Vec<ModelInfo>>,
-    }
-
-    #[derive(Deserialize)]
-    struct ModelInfo {
-        name: String,
-    }
-
     let tags: TagsResponse =
         serde_json::from_str(&body).map_err(|e| LlmError::Api(e.to_string()))?;
 
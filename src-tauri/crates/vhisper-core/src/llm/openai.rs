@@ -2,27 +2,48 @@ use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use super::traits::{LlmError, LlmService, REFINE_PROMPT};
+use super::shared_client;
+use super::traits::{LlmError, LlmService, ModePrompts};
+use crate::config::settings::PostProcessMode;
 
-/// OpenAI LLM 服务
+/// OpenAI 及兼容接口的 LLM 服务
 pub struct OpenAiLlm {
     api_key: String,
+    /// 对话接口完整地址
+    url: String,
     model: String,
     temperature: f32,
     max_tokens: u32,
+    prompts: ModePrompts,
     client: Client,
 }
 
 impl OpenAiLlm {
-    pub fn new(api_key: String, model: String, temperature: f32, max_tokens: u32) -> Self {
+    /// `base_url` 为接口根地址（如 `https://api.openai.com/v1`）
+    pub fn new(
+        api_key: String,
+        base_url: &str,
+        model: String,
+        temperature: f32,
+        max_tokens: u32,
+        language: Option<String>,
+    ) -> Self {
         Self {
             api_key,
+            url: format!("{}/chat/completions", base_url.trim_end_matches('/')),
             model,
             temperature,
             max_tokens,
-            client: Client::new(),
+            prompts: ModePrompts::new(language),
+            client: shared_client(),
         }
     }
+
+    /// 使用配置中的各模式提示词
+    pub fn with_prompts(mut self, prompts: ModePrompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
 }
 
 #[derive(Serialize)]
@@ -62,13 +83,13 @@ struct OpenAiError {
 
 #[async_trait]
 impl LlmService for OpenAiLlm {
-    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError> {
         let request = OpenAiRequest {
             model: self.model.clone(),
             messages: vec![
                 Message {
                     role: "system".to_string(),
-                    content: REFINE_PROMPT.to_string(),
+                    content: self.prompts.system_prompt(mode),
                 },
                 Message {
                     role: "user".to_string(),
@@ -79,11 +100,16 @@ impl LlmService for OpenAiLlm {
             max_tokens: self.max_tokens,
         };
 
-        let response = self
+        let mut request_builder = self
             .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        // 本地兼容服务通常不需要 API Key
+        if !self.api_key.is_empty() {
+            request_builder =
+                request_builder.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+        let response = request_builder
             .json(&request)
             .send()
             .await
@@ -113,4 +139,14 @@ impl LlmService for OpenAiLlm {
 
         Ok(output_text.trim().to_string())
     }
+
+    /// HEAD 请求只为建立 TLS 连接放入连接池，忽略响应状态
+    async fn warm_up(&self) -> Result<(), LlmError> {
+        self.client
+            .head(&self.url)
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+        Ok(())
+    }
 }
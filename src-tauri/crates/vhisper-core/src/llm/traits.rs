@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
+use crate::config::settings::{LlmConfig, PostProcessMode};
+use crate::net::NetError;
+
 #[derive(Debug, thiserror::Error)]
 pub enum LlmError {
     #[error("API error: {0}")]
@@ -10,11 +16,43 @@ pub enum LlmError {
     Config(String),
 }
 
+impl From<NetError> for LlmError {
+    fn from(e: NetError) -> Self {
+        match e {
+            NetError::Connect(_) => LlmError::Network(e.to_string()),
+            NetError::Status(_) => LlmError::Api(e.to_string()),
+            _ => LlmError::Config(e.to_string()),
+        }
+    }
+}
+
 /// LLM 服务 trait
 #[async_trait]
 pub trait LlmService: Send + Sync {
-    /// 优化文本
-    async fn refine_text(&self, text: &str) -> Result<String, LlmError>;
+    /// 按模式处理文本（校对、翻译、总结、整理格式）
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError>;
+
+    /// 校对文本
+    async fn refine_text(&self, text: &str) -> Result<String, LlmError> {
+        self.process(text, PostProcessMode::Refine).await
+    }
+
+    /// 预热：提前建立连接（TLS 握手）或加载模型，与 ASR 请求并行执行
+    async fn warm_up(&self) -> Result<(), LlmError> {
+        Ok(())
+    }
+}
+
+/// 共享的服务实例可直接作为 LLM 服务使用
+#[async_trait]
+impl<T: LlmService + ?Sized> LlmService for Arc<T> {
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError> {
+        (**self).process(text, mode).await
+    }
+
+    async fn warm_up(&self) -> Result<(), LlmError> {
+        (**self).warm_up().await
+    }
 }
 
 /// 用于文本修正的系统提示词
@@ -34,3 +72,98 @@ pub const REFINE_PROMPT: &str = r#"你是一个语音识别文本校对助手。
 只输出修正后的文本，不要添加任何解释。如果输入文本没有错误，原样输出。
 
 输入文本："#;
+
+/// 根据文本语言生成修正提示词
+///
+/// 非中文文本额外要求保持原语言，避免模型顺手翻译成中文
+pub fn refine_prompt(language: Option<&str>) -> String {
+    match language {
+        Some(language) if !language.starts_with("zh") => REFINE_PROMPT.replacen(
+            "只输出修正后的文本",
+            &format!("文本语言为 {}，请保持原语言输出，不要翻译。\n\n只输出修正后的文本", language),
+            1,
+        ),
+        _ => REFINE_PROMPT.to_string(),
+    }
+}
+
+/// 翻译模式的系统提示词，{language} 替换为目标语言
+pub const TRANSLATE_PROMPT: &str = r#"你是一个翻译助手。以下是一段语音识别文本，请将它翻译为 {language}。
+
+规则：
+1. 先按上下文修正明显的同音字和识别错误，再翻译
+2. 保持原文的意思和语气，不要添加解释或评论
+3. 专业术语、产品名和代码保持通用写法
+4. 如果原文已经是目标语言，只修正错误后输出
+
+只输出翻译后的文本，不要添加任何解释。
+
+输入文本："#;
+
+/// 总结模式的系统提示词
+pub const SUMMARIZE_PROMPT: &str = r#"你是一个总结助手。以下是一段口述内容的语音识别文本，请总结其要点。
+
+规则：
+1. 用简洁的要点列表（每行以"- "开头）列出关键信息、结论和待办事项
+2. 保留人名、时间、数字等关键细节
+3. 忽略口头禅、重复和语气词
+4. 使用与原文相同的语言
+
+只输出总结，不要添加任何解释。
+
+输入文本："#;
+
+/// 整理格式模式的系统提示词
+pub const FORMAT_PROMPT: &str = r#"你是一个文本整理助手。以下是一段口述内容的语音识别文本，请整理为结构清晰的书面文本。
+
+规则：
+1. 修正错别字和标点，去掉口头禅、重复和语气词
+2. 按内容分段；列举的内容整理为列表，步骤使用编号列表
+3. 不要改变原文的意思，不要添加原文没有的内容
+4. 使用与原文相同的语言
+
+只输出整理后的文本，不要添加任何解释。
+
+输入文本："#;
+
+/// 各后处理模式的系统提示词
+#[derive(Debug, Clone)]
+pub struct ModePrompts {
+    /// 文本语言，用于调整校对提示词
+    language: Option<String>,
+    /// 用户自定义的提示词
+    custom: HashMap<PostProcessMode, String>,
+    translate_target: String,
+}
+
+impl ModePrompts {
+    /// 只使用内置提示词
+    pub fn new(language: Option<String>) -> Self {
+        Self::from_config(&LlmConfig {
+            language,
+            ..LlmConfig::default()
+        })
+    }
+
+    pub fn from_config(config: &LlmConfig) -> Self {
+        Self {
+            language: config.language.clone(),
+            custom: config.prompts.clone(),
+            translate_target: config.translate_target.clone(),
+        }
+    }
+
+    /// 模式对应的系统提示词，自定义提示词中的 {language} 同样替换为翻译目标语言
+    pub fn system_prompt(&self, mode: PostProcessMode) -> String {
+        let prompt = match self.custom.get(&mode).filter(|p| !p.trim().is_empty()) {
+            Some(custom) => custom.clone(),
+            None => match mode {
+                PostProcessMode::Refine => return refine_prompt(self.language.as_deref()),
+                PostProcessMode::Translate => TRANSLATE_PROMPT.to_string(),
+                PostProcessMode::Summarize => SUMMARIZE_PROMPT.to_string(),
+                PostProcessMode::Format => FORMAT_PROMPT.to_string(),
+            },
+        };
+        prompt.replace("{language}", &self.translate_target)
+    }
+}
@@ -0,0 +1,18 @@
+//! 网络连接工具
+//!
+//! 按端点配置构建 HTTP / WebSocket 客户端（自签名证书、自定义 CA、SNI 覆盖、客户端证书），
+//! 以及全局的代理设置
+
+mod models;
+mod proxy;
+mod tls;
+
+pub(crate) use models::openai_compatible_models;
+pub use proxy::{proxied_client, set_proxy};
+#[cfg(feature = "streaming")]
+pub use proxy::connect_async;
+pub(crate) use proxy::{apply_proxy, connect_address, is_local};
+
+pub use tls::{http_client, store_certificate_passphrase, NetError};
+#[cfg(feature = "streaming")]
+pub use tls::{connect_websocket, WsStream};
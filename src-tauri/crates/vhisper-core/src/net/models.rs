@@ -0,0 +1,50 @@
+//! 查询服务商提供的模型列表
+
+use serde::Deserialize;
+
+use super::{proxied_client, NetError};
+
+/// OpenAI 兼容的 `GET /models` 响应
+#[derive(Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+/// 查询 OpenAI 兼容接口的模型 ID（去重并按名称排序）
+#[cfg_attr(
+    not(any(feature = "qwen", feature = "dashscope", feature = "openai")),
+    allow(dead_code)
+)]
+pub(crate) async fn openai_compatible_models(
+    base_url: &str,
+    api_key: &str,
+) -> Result<Vec<String>, NetError> {
+    let url = format!("{}/models", base_url.trim_end_matches('/'));
+    let response = proxied_client()
+        .get(&url)
+        .bearer_auth(api_key)
+        .send()
+        .await
+        .map_err(|e| NetError::Connect(e.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(NetError::Status(format!("HTTP {}: {}", status, body)));
+    }
+
+    let list: ModelList = response
+        .json()
+        .await
+        .map_err(|e| NetError::Status(format!("无法解析模型列表: {}", e)))?;
+    let mut ids: Vec<String> = list.data.into_iter().map(|model| model.id).collect();
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
@@ -0,0 +1,221 @@
+//! 代理设置
+//!
+//! 全局的 HTTP 代理（`network.proxy`），供 ASR / LLM 的 HTTP 请求和 WebSocket 连接使用。
+//! WebSocket 通过代理的 CONNECT 隧道连接。本机地址（本地部署的 FunASR、sherpa-onnx、
+//! Ollama 等）始终直连。
+
+use std::sync::RwLock;
+
+#[cfg(feature = "streaming")]
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use reqwest::{ClientBuilder, Url};
+#[cfg(feature = "streaming")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "streaming")]
+use tokio::net::TcpStream;
+#[cfg(feature = "streaming")]
+use tokio_tungstenite::tungstenite::{
+    client::IntoClientRequest, handshake::client::Response, Error as WsError,
+};
+
+use super::NetError;
+#[cfg(feature = "streaming")]
+use super::WsStream;
+
+/// 始终直连的本机地址
+const LOCAL_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1", "[::1]"];
+/// 代理 CONNECT 响应头的最大长度
+#[cfg(feature = "streaming")]
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+
+/// 当前使用的代理
+static PROXY: RwLock<Option<Url>> = RwLock::new(None);
+
+/// 设置全局代理，None 或空字符串表示直连
+///
+/// 支持 `http://[用户名:密码@]主机:端口`，之后创建的客户端和连接生效
+pub fn set_proxy(proxy: Option<&str>) -> Result<(), NetError> {
+    let url = match proxy.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(proxy) => {
+            let url = Url::parse(proxy)
+                .map_err(|e| NetError::InvalidUrl(format!("{}: {}", proxy, e)))?;
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                return Err(NetError::InvalidUrl(format!("不支持的代理地址: {}", proxy)));
+            }
+            Some(url)
+        }
+        None => None,
+    };
+
+    let mut current = PROXY.write().unwrap_or_else(|e| e.into_inner());
+    if *current != url {
+        match &url {
+            Some(url) => tracing::info!(
+                "Using proxy {}:{}",
+                url.host_str().unwrap_or_default(),
+                proxy_port(url)
+            ),
+            None => tracing::info!("Proxy disabled"),
+        }
+        *current = url;
+        // 共用的 LLM 客户端按新设置重建
+        crate::llm::release_connections();
+    }
+    Ok(())
+}
+
+/// 当前代理
+fn current_proxy() -> Option<Url> {
+    PROXY.read().ok().and_then(|proxy| proxy.clone())
+}
+
+fn proxy_port(url: &Url) -> u16 {
+    url.port_or_known_default().unwrap_or(80)
+}
+
+/// 是否为本机地址
+pub(crate) fn is_local(host: &str) -> bool {
+    LOCAL_HOSTS.contains(&host)
+}
+
+/// 实际建立 TCP 连接的地址：设置了代理且目标不是本机时为代理地址
+pub(crate) fn connect_address(host: &str, port: u16) -> (String, u16) {
+    match current_proxy().filter(|_| !is_local(host)) {
+        Some(proxy) => (proxy.host_str().unwrap_or_default().to_string(), proxy_port(&proxy)),
+        None => (host.to_string(), port),
+    }
+}
+
+/// 为 HTTP 客户端应用代理设置
+pub(crate) fn apply_proxy(builder: ClientBuilder) -> ClientBuilder {
+    let Some(url) = current_proxy() else {
+        return builder;
+    };
+    match reqwest::Proxy::all(url.as_str()) {
+        Ok(proxy) => {
+            let no_proxy = reqwest::NoProxy::from_string(&LOCAL_HOSTS.join(","));
+            builder.proxy(proxy.no_proxy(no_proxy))
+        }
+        Err(e) => {
+            tracing::warn!("Invalid proxy, connecting directly: {}", e);
+            builder
+        }
+    }
+}
+
+/// 创建应用代理设置的 HTTP 客户端
+pub fn proxied_client() -> reqwest::Client {
+    apply_proxy(reqwest::Client::builder())
+        .build()
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to build HTTP client with proxy: {}", e);
+            reqwest::Client::new()
+        })
+}
+
+/// 建立 WebSocket 连接，设置了代理时经由代理隧道
+///
+/// 与 `tokio_tungstenite::connect_async` 的用法一致
+#[cfg(feature = "streaming")]
+pub async fn connect_async<R>(request: R) -> Result<(WsStream, Response), WsError>
+where
+    R: IntoClientRequest + Unpin,
+{
+    let request = request.into_client_request()?;
+    let host = request.uri().host().unwrap_or_default().to_string();
+    let proxy = current_proxy().filter(|_| !is_local(&host));
+    let Some(proxy) = proxy else {
+        return tokio_tungstenite::connect_async(request).await;
+    };
+
+    let port = request
+        .uri()
+        .port_u16()
+        .unwrap_or(if request.uri().scheme_str() == Some("wss") { 443 } else { 80 });
+    let stream = open_tunnel(&proxy, &host, port)
+        .await
+        .map_err(|e| WsError::Io(std::io::Error::other(e.to_string())))?;
+    tokio_tungstenite::client_async_tls(request, stream).await
+}
+
+/// 设置了代理且目标不是本机时，经由代理连接到目标地址
+#[cfg(feature = "streaming")]
+pub(crate) async fn tunnel_if_proxied(host: &str, port: u16) -> Result<Option<TcpStream>, NetError> {
+    match current_proxy().filter(|_| !is_local(host)) {
+        Some(proxy) => open_tunnel(&proxy, host, port).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 通过 HTTP CONNECT 建立到目标地址的隧道
+#[cfg(feature = "streaming")]
+async fn open_tunnel(proxy: &Url, host: &str, port: u16) -> Result<TcpStream, NetError> {
+    let proxy_host = proxy
+        .host_str()
+        .ok_or_else(|| NetError::InvalidUrl(proxy.to_string()))?;
+    let mut stream = TcpStream::connect((proxy_host, proxy_port(proxy)))
+        .await
+        .map_err(|e| NetError::Connect(format!("代理 {}: {}", proxy_host, e)))?;
+
+    let target = format!("{}:{}", host, port);
+    let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+    if !proxy.username().is_empty() {
+        let credentials = format!(
+            "{}:{}",
+            percent_decode(proxy.username()),
+            percent_decode(proxy.password().unwrap_or_default())
+        );
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", BASE64.encode(credentials)));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| NetError::Connect(format!("代理 {}: {}", proxy_host, e)))?;
+
+    // 逐字节读取响应头，避免读走隧道中的后续数据
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        let read = stream
+            .read(&mut byte)
+            .await
+            .map_err(|e| NetError::Connect(format!("代理 {}: {}", proxy_host, e)))?;
+        if read == 0 || response.len() >= MAX_CONNECT_RESPONSE {
+            return Err(NetError::Connect(format!("代理 {} 响应无效", proxy_host)));
+        }
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(NetError::Connect(format!("代理拒绝连接 {}: {}", target, status_line)));
+    }
+    Ok(stream)
+}
+
+/// 解码 URL 中的用户名和密码
+#[cfg(feature = "streaming")]
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
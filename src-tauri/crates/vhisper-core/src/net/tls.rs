@@ -0,0 +1,240 @@
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use reqwest::Url;
+#[cfg(feature = "streaming")]
+use tokio::net::TcpStream;
+#[cfg(feature = "streaming")]
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+use super::proxy::apply_proxy;
+#[cfg(feature = "streaming")]
+use super::proxy::tunnel_if_proxied;
+use crate::config::settings::{ClientCertificate, TlsOptions};
+
+/// 钥匙串中保存客户端证书密码的服务名
+const KEYCHAIN_SERVICE: &str = "vhisper-client-certificate";
+
+#[derive(Debug, thiserror::Error)]
+pub enum NetError {
+    #[error("无效的地址: {0}")]
+    InvalidUrl(String),
+    #[error("读取证书失败: {0}")]
+    Certificate(String),
+    #[error("TLS 配置失败: {0}")]
+    Tls(String),
+    #[error("连接失败: {0}")]
+    Connect(String),
+    #[error("钥匙串访问失败: {0}")]
+    Keychain(String),
+    #[error("服务端返回错误: {0}")]
+    Status(String),
+}
+
+/// WebSocket 连接
+#[cfg(feature = "streaming")]
+pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// 读取 PEM 文件中的所有证书
+fn read_pem_certificates(path: &str) -> Result<Vec<Vec<u8>>, NetError> {
+    const END: &str = "-----END CERTIFICATE-----";
+
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| NetError::Certificate(format!("{}: {}", path, e)))?;
+    let certificates: Vec<Vec<u8>> = data
+        .split_inclusive(END)
+        .filter(|block| block.contains(END))
+        .map(|block| block.trim().as_bytes().to_vec())
+        .collect();
+
+    if certificates.is_empty() {
+        return Err(NetError::Certificate(format!("{}: 未找到 PEM 证书", path)));
+    }
+    Ok(certificates)
+}
+
+/// 保存客户端证书密码到系统钥匙串
+pub fn store_certificate_passphrase(account: &str, passphrase: &str) -> Result<(), NetError> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .and_then(|entry| entry.set_password(passphrase))
+        .map_err(|e| NetError::Keychain(e.to_string()))
+}
+
+/// 读取客户端证书的 PKCS#12 数据和密码
+fn load_client_certificate(certificate: &ClientCertificate) -> Result<(Vec<u8>, String), NetError> {
+    let der = std::fs::read(&certificate.pkcs12_path)
+        .map_err(|e| NetError::Certificate(format!("{}: {}", certificate.pkcs12_path, e)))?;
+
+    let passphrase = match &certificate.passphrase_account {
+        Some(account) => keyring::Entry::new(KEYCHAIN_SERVICE, account)
+            .and_then(|entry| entry.get_password())
+            .map_err(|e| NetError::Keychain(format!("{}: {}", account, e)))?,
+        None => String::new(),
+    };
+
+    Ok((der, passphrase))
+}
+
+fn warn_insecure(endpoint: &str) {
+    tracing::warn!(
+        "!!! TLS certificate verification is DISABLED for {} - traffic can be intercepted. \
+         Prefer ca_bundle for self-signed certificates !!!",
+        endpoint
+    );
+}
+
+/// 按 SNI 覆盖改写地址
+///
+/// 返回握手用主机名替换后的 URL，以及需要解析的原主机和端口
+fn sni_target(endpoint: &str, sni: &str) -> Result<(Url, String, u16), NetError> {
+    let mut url = Url::parse(endpoint).map_err(|e| NetError::InvalidUrl(e.to_string()))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| NetError::InvalidUrl(endpoint.to_string()))?
+        .to_string();
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| NetError::InvalidUrl(endpoint.to_string()))?;
+
+    url.set_host(Some(sni))
+        .map_err(|e| NetError::InvalidUrl(e.to_string()))?;
+
+    Ok((url, host, port))
+}
+
+/// 取解析结果中的第一个地址
+fn first_addr(
+    host: &str,
+    addrs: std::io::Result<impl Iterator<Item = SocketAddr>>,
+) -> Result<SocketAddr, NetError> {
+    addrs
+        .map_err(|e| NetError::Connect(format!("{}: {}", host, e)))?
+        .next()
+        .ok_or_else(|| NetError::Connect(format!("无法解析 {}", host)))
+}
+
+/// 构建 HTTP 客户端
+///
+/// 返回客户端和实际请求时使用的基础地址（设置 SNI 覆盖时主机名会被替换）
+pub fn http_client(
+    endpoint: &str,
+    options: &TlsOptions,
+) -> Result<(reqwest::Client, String), NetError> {
+    let mut builder = apply_proxy(reqwest::Client::builder());
+    let mut base_url = endpoint.to_string();
+
+    if options.accept_invalid_certs {
+        warn_insecure(endpoint);
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    if let Some(path) = &options.ca_bundle {
+        for pem in read_pem_certificates(path)? {
+            let certificate = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| NetError::Certificate(format!("{}: {}", path, e)))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    if let Some(certificate) = &options.client_certificate {
+        let (der, passphrase) = load_client_certificate(certificate)?;
+        let identity = reqwest::Identity::from_pkcs12_der(&der, &passphrase)
+            .map_err(|e| NetError::Certificate(format!("{}: {}", certificate.pkcs12_path, e)))?;
+        builder = builder.identity(identity);
+    }
+
+    if let Some(sni) = &options.sni_override {
+        let (url, host, port) = sni_target(endpoint, sni)?;
+        let addr = first_addr(&host, (host.as_str(), port).to_socket_addrs())?;
+        tracing::info!("TLS SNI override for {}: {} -> {}", endpoint, sni, addr);
+        builder = builder.resolve(sni, addr);
+        base_url = url.as_str().trim_end_matches('/').to_string();
+    }
+
+    let client = builder.build().map_err(|e| NetError::Tls(e.to_string()))?;
+    Ok((client, base_url))
+}
+
+/// 构建 WebSocket 使用的 TLS 连接器
+#[cfg(feature = "streaming")]
+fn tls_connector(endpoint: &str, options: &TlsOptions) -> Result<Connector, NetError> {
+    let mut builder = native_tls::TlsConnector::builder();
+
+    if options.accept_invalid_certs {
+        warn_insecure(endpoint);
+        builder
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true);
+    }
+
+    if let Some(path) = &options.ca_bundle {
+        for pem in read_pem_certificates(path)? {
+            let certificate = native_tls::Certificate::from_pem(&pem)
+                .map_err(|e| NetError::Certificate(format!("{}: {}", path, e)))?;
+            builder.add_root_certificate(certificate);
+        }
+    }
+
+    if let Some(certificate) = &options.client_certificate {
+        let (der, passphrase) = load_client_certificate(certificate)?;
+        let identity = native_tls::Identity::from_pkcs12(&der, &passphrase)
+            .map_err(|e| NetError::Certificate(format!("{}: {}", certificate.pkcs12_path, e)))?;
+        builder.identity(identity);
+    }
+
+    let connector = builder.build().map_err(|e| NetError::Tls(e.to_string()))?;
+    Ok(Connector::NativeTls(connector))
+}
+
+/// 建立 WebSocket 连接
+#[cfg(feature = "streaming")]
+pub async fn connect_websocket(url: &str, options: &TlsOptions) -> Result<WsStream, NetError> {
+    let connector = tls_connector(url, options)?;
+
+    let result = match &options.sni_override {
+        Some(sni) => {
+            let (target, host, port) = sni_target(url, sni)?;
+            // 异步解析，避免 DNS 查询阻塞运行时线程
+            let addr = first_addr(&host, tokio::net::lookup_host((host.as_str(), port)).await)?;
+            tracing::info!("TLS SNI override for {}: {} -> {}", url, sni, addr);
+            let stream = TcpStream::connect(addr)
+                .await
+                .map_err(|e| NetError::Connect(e.to_string()))?;
+            tokio_tungstenite::client_async_tls_with_config(
+                target.as_str(),
+                stream,
+                None,
+                Some(connector),
+            )
+            .await
+        }
+        None => {
+            let target = Url::parse(url).map_err(|e| NetError::InvalidUrl(e.to_string()))?;
+            let host = target.host_str().unwrap_or_default();
+            let port = target.port_or_known_default().unwrap_or(80);
+            match tunnel_if_proxied(host, port).await? {
+                Some(stream) => {
+                    tokio_tungstenite::client_async_tls_with_config(
+                        url,
+                        stream,
+                        None,
+                        Some(connector),
+                    )
+                    .await
+                }
+                None => {
+                    tokio_tungstenite::connect_async_tls_with_config(
+                        url,
+                        None,
+                        false,
+                        Some(connector),
+                    )
+                    .await
+                }
+            }
+        }
+    };
+
+    let (stream, _) =
+        result.map_err(|e| NetError::Connect(format!("WebSocket 连接失败: {}", e)))?;
+    Ok(stream)
+}
@@ -0,0 +1,158 @@
+//! 流式会话的文本拼接
+//!
+//! 流式模式下每次 VAD Final 都会开始新的 ASR 会话，一次按住说话会产生多段结果，
+//! 这里把它们拼成完整的一段文本。
+//!
+//! 重连时会补发上一段末尾的音频，新一段开头可能重复上一段结尾的几个字/词，拼接时去掉重复部分。
+//! 用 `push_at` 追加时同时记录每段的时间，供长会话的文本面板使用
+
+use std::time::Duration;
+
+use crate::history::TranscriptSegment;
+use crate::text::is_cjk;
+
+/// 补发音频最多对应的重复词元数（中日韩文字按字，其他按单词）
+const MAX_OVERLAP_TOKENS: usize = 6;
+
+/// 一次按住说话的识别结果
+#[derive(Debug, Default)]
+pub struct SessionTranscript {
+    text: String,
+    /// 去掉段首与上一段结尾重复的部分
+    dedup: bool,
+    /// 带时间的分段（只记录 push_at 追加的段）
+    segments: Vec<TranscriptSegment>,
+}
+
+impl SessionTranscript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 拼接时去掉段首与上一段结尾重复的部分（重连补发音频时使用）
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// 追加一段 Final 结果，返回实际追加的文本（去掉重复部分后）
+    pub fn push(&mut self, segment: &str) -> String {
+        let mut segment = segment.trim();
+        if self.dedup {
+            segment = strip_overlap(&self.text, segment);
+        }
+        if segment.is_empty() {
+            return String::new();
+        }
+
+        if let (Some(last), Some(first)) = (self.text.chars().last(), segment.chars().next()) {
+            if needs_space(last, first) {
+                self.text.push(' ');
+            }
+        }
+        self.text.push_str(segment);
+        segment.to_string()
+    }
+
+    /// 追加一段 Final 结果并记录时间，`elapsed` 为收到结果时的录音时长
+    ///
+    /// 段的开始时间取上一段的结束时间；结束时间包含识别延迟，只是近似值
+    pub fn push_at(&mut self, segment: &str, elapsed: Duration) -> String {
+        let appended = self.push(segment);
+        if !appended.is_empty() {
+            let start_ms = self.segments.last().map_or(0, |last| last.end_ms);
+            self.segments.push(TranscriptSegment {
+                start_ms,
+                end_ms: (elapsed.as_millis() as u64).max(start_ms),
+                text: appended.clone(),
+            });
+        }
+        appended
+    }
+
+    /// 带时间的分段，按时间先后
+    pub fn segments(&self) -> &[TranscriptSegment] {
+        &self.segments
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+/// 两段之间是否需要空格：中日韩文字和全角标点前后不加空格
+fn needs_space(last: char, first: char) -> bool {
+    if is_cjk(last) || is_cjk(first) || is_fullwidth_punct(last) || is_fullwidth_punct(first) {
+        return false;
+    }
+    // 下一段以标点开头时直接相连
+    !matches!(first, ',' | '.' | '!' | '?' | ';' | ':' | ')')
+}
+
+fn is_fullwidth_punct(c: char) -> bool {
+    matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF)
+}
+
+/// 比较用的词元
+struct Token {
+    /// 小写形式
+    text: String,
+    /// 中日韩单字（单字重复很常见，不足以判断为补发造成的重复）
+    cjk: bool,
+    /// 词元在原文中的结束位置（字节）
+    end: usize,
+}
+
+/// 切分词元：中日韩文字逐字，字母数字按单词，忽略标点和空白
+fn tokens(text: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut word: Option<Token> = None;
+    for (i, c) in text.char_indices() {
+        let end = i + c.len_utf8();
+        if is_cjk(c) {
+            tokens.extend(word.take());
+            tokens.push(Token {
+                text: c.to_string(),
+                cjk: true,
+                end,
+            });
+        } else if c.is_alphanumeric() || c == '\'' {
+            let token = word.get_or_insert_with(|| Token {
+                text: String::new(),
+                cjk: false,
+                end,
+            });
+            token.text.extend(c.to_lowercase());
+            token.end = end;
+        } else {
+            tokens.extend(word.take());
+        }
+    }
+    tokens.extend(word);
+    tokens
+}
+
+/// 去掉 segment 开头与 previous 结尾重复的词元（取最长的重复），以及紧随其后的标点
+fn strip_overlap<'a>(previous: &str, segment: &'a str) -> &'a str {
+    let previous = tokens(previous);
+    let current = tokens(segment);
+    let max = MAX_OVERLAP_TOKENS.min(previous.len()).min(current.len());
+
+    let overlap = (1..=max).rev().find(|&n| {
+        (n > 1 || !current[0].cjk)
+            && previous[previous.len() - n..]
+                .iter()
+                .zip(&current[..n])
+                .all(|(a, b)| a.text == b.text)
+    });
+    match overlap {
+        Some(n) => segment[current[n - 1].end..].trim_start_matches(|c: char| {
+            c.is_whitespace() || c.is_ascii_punctuation() || is_fullwidth_punct(c)
+        }),
+        None => segment,
+    }
+}
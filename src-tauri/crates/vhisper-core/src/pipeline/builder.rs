@@ -0,0 +1,181 @@
+//! 注入自定义服务构建语音管道
+//!
+//! 默认情况下管道按配置中的服务商名称创建 ASR、LLM 服务并使用系统麦克风；
+//! 嵌入其他应用或编写确定性测试时，可以通过 VoicePipelineBuilder 直接传入服务实例、
+//! 录音器和输出目标，绕过基于配置的工厂函数。
+
+use std::sync::Arc;
+
+use tokio::sync::RwLock as TokioRwLock;
+
+use super::voice::{PipelineError, VoicePipeline};
+use crate::asr::{
+    create_asr_service_with_context, create_streaming_asr_service_with_context, AsrError,
+    AsrService, StreamingAsrService,
+};
+use crate::audio::AudioRecorder;
+use crate::config::settings::AsrConfig;
+use crate::config::AppConfig;
+use crate::llm::{warm_up_llm, LlmService};
+use crate::text::TextPipeline;
+
+/// 识别结果的输出目标
+///
+/// 每次识别完成（批量识别或流式会话结束）且结果非空时调用
+pub trait OutputSink: Send + Sync {
+    fn output(&self, text: &str);
+}
+
+/// 注入的服务，未注入的按配置创建
+#[derive(Clone, Default)]
+pub(crate) struct PipelineServices {
+    asr: Option<Arc<dyn AsrService>>,
+    streaming_asr: Option<Arc<dyn StreamingAsrService>>,
+    llm: Option<Arc<dyn LlmService>>,
+    output: Option<Arc<dyn OutputSink>>,
+}
+
+impl PipelineServices {
+    /// 批量识别服务
+    pub(crate) fn asr_service(
+        &self,
+        config: &AsrConfig,
+        context: Option<String>,
+    ) -> Result<Box<dyn AsrService>, AsrError> {
+        match &self.asr {
+            Some(asr) => Ok(Box::new(asr.clone())),
+            None => create_asr_service_with_context(config, context),
+        }
+    }
+
+    /// 流式识别服务
+    pub(crate) fn streaming_asr_service(
+        &self,
+        config: &AsrConfig,
+        context: Option<String>,
+    ) -> Result<Box<dyn StreamingAsrService>, AsrError> {
+        match &self.streaming_asr {
+            Some(asr) => Ok(Box::new(asr.clone())),
+            None => create_streaming_asr_service_with_context(config, context),
+        }
+    }
+
+    /// 文本后处理管道，注入的 LLM 服务替代配置中的服务商
+    pub(crate) fn text_pipeline(&self, config: &AppConfig) -> TextPipeline {
+        let pipeline = TextPipeline::from_config(config);
+        match &self.llm {
+            Some(llm) => pipeline.with_llm(Box::new(llm.clone())),
+            None => pipeline,
+        }
+    }
+
+    /// 注入的 LLM 服务
+    pub(crate) fn llm(&self) -> Option<Arc<dyn LlmService>> {
+        self.llm.clone()
+    }
+
+    /// 后台预热 LLM
+    pub(crate) fn spawn_llm_warm_up(&self, config: &AppConfig) {
+        match self.llm.clone() {
+            Some(llm) => {
+                tokio::spawn(async move {
+                    if let Err(e) = llm.warm_up().await {
+                        tracing::debug!("LLM warm-up failed: {}", e);
+                    }
+                });
+            }
+            None if TextPipeline::uses_llm(config) => {
+                let llm_config = config.llm.clone();
+                tokio::spawn(async move { warm_up_llm(&llm_config).await });
+            }
+            None => {}
+        }
+    }
+
+    /// 把识别结果交给输出目标
+    pub(crate) fn output(&self, text: &str) {
+        if let Some(output) = &self.output {
+            if !text.is_empty() {
+                output.output(text);
+            }
+        }
+    }
+}
+
+/// 语音管道构建器
+///
+/// ```ignore
+/// let pipeline = VoicePipeline::builder()
+///     .config(config)
+///     .recorder(AudioRecorder::external())
+///     .asr(Box::new(MyAsr))
+///     .llm(Box::new(MyLlm))
+///     .output(Box::new(MySink))
+///     .build()?;
+/// ```
+#[derive(Default)]
+pub struct VoicePipelineBuilder {
+    config: Option<Arc<TokioRwLock<AppConfig>>>,
+    recorder: Option<AudioRecorder>,
+    services: PipelineServices,
+}
+
+impl VoicePipelineBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用指定配置（未设置时使用默认配置）
+    pub fn config(self, config: AppConfig) -> Self {
+        self.shared_config(Arc::new(TokioRwLock::new(config)))
+    }
+
+    /// 使用与宿主共享的配置，宿主修改后下次识别生效
+    pub fn shared_config(mut self, config: Arc<TokioRwLock<AppConfig>>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// 使用指定的录音器（如外部音频源），未设置时使用系统默认麦克风
+    pub fn recorder(mut self, recorder: AudioRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// 批量识别使用的 ASR 服务，替代配置中的服务商
+    pub fn asr(mut self, asr: Box<dyn AsrService>) -> Self {
+        self.services.asr = Some(Arc::from(asr));
+        self
+    }
+
+    /// 流式识别使用的 ASR 服务，替代配置中的服务商
+    pub fn streaming_asr(mut self, asr: Box<dyn StreamingAsrService>) -> Self {
+        self.services.streaming_asr = Some(Arc::from(asr));
+        self
+    }
+
+    /// 文本修正使用的 LLM 服务，替代配置中的服务商
+    ///
+    /// 仍受文本管道中 LLM 阶段的开关控制
+    pub fn llm(mut self, llm: Box<dyn LlmService>) -> Self {
+        self.services.llm = Some(Arc::from(llm));
+        self
+    }
+
+    /// 识别结果的输出目标
+    pub fn output(mut self, output: Box<dyn OutputSink>) -> Self {
+        self.services.output = Some(Arc::from(output));
+        self
+    }
+
+    pub fn build(self) -> Result<VoicePipeline, PipelineError> {
+        let config = self
+            .config
+            .unwrap_or_else(|| Arc::new(TokioRwLock::new(AppConfig::default())));
+        let recorder = match self.recorder {
+            Some(recorder) => recorder,
+            None => AudioRecorder::new()?,
+        };
+        Ok(VoicePipeline::with_recorder(config, recorder).with_services(self.services))
+    }
+}
@@ -0,0 +1,55 @@
+//! 最近识别结果的上下文
+//!
+//! 作为下一次识别的提示（Whisper prompt 等），提高连续口述中重复出现的人名、术语的识别率
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// 保留的最近识别结果条数
+const MAX_ENTRIES: usize = 5;
+
+#[derive(Default)]
+pub struct TranscriptContext {
+    entries: Mutex<VecDeque<String>>,
+}
+
+impl TranscriptContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一条最终识别结果
+    pub fn push(&self, text: &str) {
+        let text = text.trim();
+        // 隐私模式下的内容不作为之后识别的上下文
+        if text.is_empty() || crate::privacy::is_incognito() {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(text.to_string());
+    }
+
+    /// 生成提示文本，只保留最后 max_chars 个字符；为 0 或没有记录时返回 None
+    pub fn prompt(&self, max_chars: usize) -> Option<String> {
+        if max_chars == 0 {
+            return None;
+        }
+
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.is_empty() {
+            return None;
+        }
+
+        let joined = entries.iter().cloned().collect::<Vec<_>>().join(" ");
+        let skip = joined.chars().count().saturating_sub(max_chars);
+        Some(joined.chars().skip(skip).collect())
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
@@ -0,0 +1,149 @@
+//! 双轨对话转写
+//!
+//! 访谈录音时麦克风和系统声音分别录成两条音轨，各自按静音切分成句子后独立识别，
+//! 再按句子开始时间交错成对话。两条音轨同时开始录制，时间戳可以直接比较
+
+use crate::history::{DialogueLine, DialogueTranscript, Speaker};
+
+use super::voice::{PipelineError, VoicePipeline};
+
+/// 能量计算的帧长（毫秒）
+const FRAME_MS: u32 = 20;
+/// 句子前后保留的余量（毫秒），避免切掉字的开头和结尾
+const PADDING_MS: u32 = 200;
+/// 短于此时长的片段视为噪声
+const MIN_UTTERANCE_MS: u32 = 300;
+/// 单个片段的最大时长（毫秒），持续说话时强制切分，避免单次请求过长
+const MAX_UTTERANCE_MS: u32 = 60_000;
+/// 默认语音能量阈值（RMS）
+pub const DEFAULT_SPEECH_THRESHOLD: f32 = 0.01;
+/// 默认句间静音时长（毫秒）
+pub const DEFAULT_SILENCE_MS: u32 = 700;
+
+/// 音轨中的一段语音
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Utterance {
+    /// 样本范围
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Utterance {
+    pub fn start_ms(&self, sample_rate: u32) -> u64 {
+        self.start as u64 * 1000 / sample_rate as u64
+    }
+
+    pub fn end_ms(&self, sample_rate: u32) -> u64 {
+        self.end as u64 * 1000 / sample_rate as u64
+    }
+}
+
+/// 按静音把单声道音轨切分成语音片段
+///
+/// 帧 RMS 不低于 `threshold` 视为语音，连续 `silence_ms` 以上的静音作为句子分隔
+pub fn split_utterances(
+    samples: &[f32],
+    sample_rate: u32,
+    threshold: f32,
+    silence_ms: u32,
+) -> Vec<Utterance> {
+    let frame_len = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+    let ms_to_samples = |ms: u32| (sample_rate as u64 * ms as u64 / 1000) as usize;
+    let silence_frames = (silence_ms / FRAME_MS).max(1) as usize;
+    let max_frames = (MAX_UTTERANCE_MS / FRAME_MS) as usize;
+
+    let voiced: Vec<bool> = samples
+        .chunks(frame_len)
+        .map(|frame| {
+            (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt() >= threshold
+        })
+        .collect();
+
+    // 帧范围 [start, end)
+    let mut segments = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    for (index, &is_voiced) in voiced.iter().enumerate() {
+        match current {
+            Some((start, last_voiced)) => {
+                if is_voiced {
+                    if index - start >= max_frames {
+                        segments.push((start, index));
+                        current = Some((index, index));
+                    } else {
+                        current = Some((start, index));
+                    }
+                } else if index - last_voiced > silence_frames {
+                    segments.push((start, last_voiced + 1));
+                    current = None;
+                }
+            }
+            None if is_voiced => current = Some((index, index)),
+            None => {}
+        }
+    }
+    if let Some((start, last_voiced)) = current {
+        segments.push((start, last_voiced + 1));
+    }
+
+    let padding = ms_to_samples(PADDING_MS);
+    let min_len = ms_to_samples(MIN_UTTERANCE_MS);
+    segments
+        .into_iter()
+        .filter(|(start, end)| (end - start) * frame_len >= min_len)
+        .map(|(start, end)| Utterance {
+            start: (start * frame_len).saturating_sub(padding),
+            end: (end * frame_len + padding).min(samples.len()),
+        })
+        .collect()
+}
+
+impl VoicePipeline {
+    /// 分别识别麦克风和系统声音两条音轨，按时间交错成对话
+    ///
+    /// 两条音轨的采样率须与录音器一致（16kHz 单声道）；单句识别失败时跳过该句
+    pub async fn transcribe_dialogue(
+        &self,
+        id: String,
+        started_at: u64,
+        mic: Vec<f32>,
+        system: Vec<f32>,
+    ) -> Result<DialogueTranscript, PipelineError> {
+        let sample_rate = self.sample_rate()?;
+        let duration_ms = mic.len().max(system.len()) as u64 * 1000 / sample_rate as u64;
+
+        let mut lines = Vec::new();
+        for (speaker, samples) in [(Speaker::Me, mic), (Speaker::Other, system)] {
+            let utterances = split_utterances(
+                &samples,
+                sample_rate,
+                DEFAULT_SPEECH_THRESHOLD,
+                DEFAULT_SILENCE_MS,
+            );
+            tracing::info!(
+                "Dialogue track {:?}: {} utterances",
+                speaker,
+                utterances.len()
+            );
+
+            for utterance in utterances {
+                let chunk = samples[utterance.start..utterance.end].to_vec();
+                match self.recognize_samples(chunk).await {
+                    Ok(text) if !text.trim().is_empty() => lines.push(DialogueLine {
+                        speaker,
+                        start_ms: utterance.start_ms(sample_rate),
+                        end_ms: utterance.end_ms(sample_rate),
+                        text: text.trim().to_string(),
+                    }),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(
+                        "Dialogue utterance at {}ms skipped: {}",
+                        utterance.start_ms(sample_rate),
+                        e
+                    ),
+                }
+            }
+        }
+
+        Ok(DialogueTranscript::new(id, started_at, duration_ms, lines))
+    }
+}
@@ -0,0 +1,86 @@
+//! 管道事件流
+//!
+//! 把状态变化、流式识别结果和结构化事件汇总到一个广播通道，
+//! 库的使用方（CLI、Python 绑定、测试）可以用 `while let Some(event) = stream.next().await`
+//! 处理整个会话，不必为每次会话分别读取 mpsc 接收器
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use futures_util::Stream;
+use tokio::sync::broadcast;
+
+use super::voice::PipelineState;
+use crate::asr::StreamingAsrEvent;
+use crate::events::{self, EventPayload, VhisperEvent};
+
+/// 事件流的缓冲容量，订阅者落后超过该数量时丢弃最旧的事件
+const EVENT_BUFFER: usize = 256;
+
+/// 管道事件
+#[derive(Debug, Clone)]
+pub enum PipelineEvent {
+    /// 状态变化
+    StateChanged(PipelineState),
+    /// 流式识别的中间结果
+    Partial { text: String, stash: String },
+    /// 流式识别的一句最终结果（已去掉与上一句重复的部分）
+    Final { text: String },
+    /// 结构化事件（录音开始/结束、结果、统计、错误等），与事件监听器收到的相同
+    Event(VhisperEvent),
+}
+
+/// 管道事件的发布端
+#[derive(Clone)]
+pub(crate) struct EventHub {
+    tx: broadcast::Sender<PipelineEvent>,
+}
+
+impl EventHub {
+    pub(crate) fn new() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_BUFFER);
+        Self { tx }
+    }
+
+    /// 订阅之后发布的事件
+    pub(crate) fn subscribe(&self) -> impl Stream<Item = PipelineEvent> + Send + 'static {
+        futures_util::stream::unfold(self.tx.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Pipeline event stream lagged, {} events dropped", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// 发布结构化事件：交给事件监听器，同时发送给事件流
+    pub(crate) fn publish(&self, session_id: Option<String>, payload: EventPayload) {
+        let event = VhisperEvent::new(session_id, payload);
+        events::emit(&event);
+        let _ = self.tx.send(PipelineEvent::Event(event));
+    }
+
+    /// 更新状态，状态变化时发送给事件流
+    pub(crate) fn set_state(&self, state: &AtomicU8, new_state: PipelineState) {
+        let previous = state.swap(new_state as u8, Ordering::SeqCst);
+        if previous != new_state as u8 {
+            let _ = self.tx.send(PipelineEvent::StateChanged(new_state));
+        }
+    }
+
+    /// 发送流式识别结果（错误通过会话结束的 Error 事件发布）
+    pub(crate) fn asr_event(&self, event: &StreamingAsrEvent) {
+        let event = match event {
+            StreamingAsrEvent::Partial { text, stash } => PipelineEvent::Partial {
+                text: text.clone(),
+                stash: stash.clone(),
+            },
+            StreamingAsrEvent::Final { text } => PipelineEvent::Final { text: text.clone() },
+            StreamingAsrEvent::Error(_) => return,
+        };
+        let _ = self.tx.send(event);
+    }
+}
@@ -0,0 +1,30 @@
+//! 空闲时释放资源
+//!
+//! 录音结束后麦克风随即关闭，但本地模型、sherpa-onnx 服务端进程和 LLM 的保活连接
+//! 会一直保留。空闲超过配置的时长（或锁屏、休眠）时统一释放，下次听写时按需重新建立。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::asr::release_local_models;
+use crate::llm::release_connections;
+
+/// 资源是否已释放且之后没有新的会话
+static RELEASED: AtomicBool = AtomicBool::new(false);
+
+/// 释放空闲资源，返回是否实际释放（上次释放后没有新会话时直接返回 false）
+///
+/// 调用方需要保证没有正在录音或处理的会话
+pub fn release_idle_resources() -> bool {
+    if RELEASED.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    release_local_models();
+    release_connections();
+    tracing::info!("Idle resources released");
+    true
+}
+
+/// 开始新会话时调用，之后的空闲可以再次释放
+pub(crate) fn mark_active() {
+    RELEASED.store(false, Ordering::SeqCst);
+}
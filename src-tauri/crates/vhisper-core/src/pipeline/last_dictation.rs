@@ -0,0 +1,38 @@
+//! 最近一次听写的原始识别文本和后处理后的文本
+//!
+//! LLM 校对改错了内容时，宿主可以把刚刚输出的文本替换为原始识别文本。
+//! 只保存在内存中
+
+use std::sync::Mutex;
+
+static LAST_DICTATION: Mutex<Option<Dictation>> = Mutex::new(None);
+
+/// 一次听写的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictation {
+    /// ASR 原始识别文本
+    pub raw: String,
+    /// 经过文本后处理（替换、LLM 校对等）输出的文本
+    pub refined: String,
+}
+
+/// 记录最近一次听写，返回输出的文本；空结果不覆盖之前的记录
+pub fn remember_dictation(dictation: Dictation) -> String {
+    let refined = dictation.refined.clone();
+    if !refined.is_empty() {
+        if let Ok(mut last) = LAST_DICTATION.lock() {
+            *last = Some(dictation);
+        }
+    }
+    refined
+}
+
+/// 最近一次听写
+pub fn last_dictation() -> Option<Dictation> {
+    LAST_DICTATION.lock().ok().and_then(|last| last.clone())
+}
+
+/// 取出最近一次听写，替换为原始文本后不能再次替换
+pub fn take_last_dictation() -> Option<Dictation> {
+    LAST_DICTATION.lock().ok().and_then(|mut last| last.take())
+}
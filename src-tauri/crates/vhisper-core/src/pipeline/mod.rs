@@ -1,3 +1,21 @@
+mod assembly;
+mod builder;
+mod context;
+mod dialogue;
+mod event_stream;
+mod idle;
+mod last_dictation;
+mod refine;
+mod registry;
 mod voice;
 
+pub use assembly::SessionTranscript;
+pub use builder::{OutputSink, VoicePipelineBuilder};
+pub use context::TranscriptContext;
+pub use dialogue::{split_utterances, Utterance, DEFAULT_SILENCE_MS, DEFAULT_SPEECH_THRESHOLD};
+pub use event_stream::PipelineEvent;
+pub use idle::release_idle_resources;
+pub use last_dictation::{last_dictation, remember_dictation, take_last_dictation, Dictation};
+pub use refine::SentenceRefiner;
+pub use registry::{PipelineRegistry, PipelineSource};
 pub use voice::{PipelineError, PipelineState, VoicePipeline};
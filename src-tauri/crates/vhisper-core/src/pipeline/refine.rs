@@ -0,0 +1,86 @@
+//! 流式会话的逐句修正
+//!
+//! 每句 Final 到达后立即在后台执行文本后处理（含 LLM 修正），用户继续说下一句时
+//! 前面的句子已经处理完成，松开快捷键后只需等待最后几句。
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+use super::assembly::SessionTranscript;
+use crate::config::AppConfig;
+use crate::llm::LlmService;
+use crate::text::TextPipeline;
+
+/// 逐句修正器：并发数受限，按提交顺序输出
+pub struct SentenceRefiner {
+    config: Arc<AppConfig>,
+    /// 注入的 LLM 服务，未设置时按配置创建
+    llm: Option<Arc<dyn LlmService>>,
+    permits: Arc<Semaphore>,
+    pending: VecDeque<JoinHandle<String>>,
+}
+
+impl SentenceRefiner {
+    pub fn new(config: AppConfig) -> Self {
+        let concurrency = config.llm.refine_concurrency.max(1);
+        Self {
+            config: Arc::new(config),
+            llm: None,
+            permits: Arc::new(Semaphore::new(concurrency)),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// 使用指定的 LLM 服务修正
+    pub fn with_llm(mut self, llm: Option<Arc<dyn LlmService>>) -> Self {
+        self.llm = llm;
+        self
+    }
+
+    /// 提交一句识别结果，立即返回
+    pub fn push(&mut self, sentence: &str) {
+        let sentence = sentence.trim().to_string();
+        if sentence.is_empty() {
+            return;
+        }
+
+        let config = self.config.clone();
+        let permits = self.permits.clone();
+        let llm = self.llm.clone();
+        self.pending.push_back(tokio::spawn(async move {
+            let Ok(_permit) = permits.acquire_owned().await else {
+                return sentence;
+            };
+            let pipeline = TextPipeline::from_config(&config);
+            let pipeline = match llm {
+                Some(llm) => pipeline.with_llm(Box::new(llm)),
+                None => pipeline,
+            };
+            pipeline.process(&sentence).await
+        }));
+    }
+
+    /// 等待所有句子处理完成，按提交顺序拼接
+    pub async fn finish(mut self) -> String {
+        let mut transcript = SessionTranscript::new();
+        while let Some(handle) = self.pending.pop_front() {
+            match handle.await {
+                Ok(text) => {
+                    transcript.push(&text);
+                }
+                Err(e) => tracing::warn!("Sentence refinement task failed: {}", e),
+            }
+        }
+        transcript.text().to_string()
+    }
+
+    /// 丢弃尚未完成的句子
+    pub fn abort(self) {
+        for handle in self.pending {
+            handle.abort();
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! 按音频来源管理多个语音管道
+//!
+//! 每个来源拥有独立的 VoicePipeline（独立的录音器和状态机），
+//! 会议录制等长时间会话可以与快捷键听写同时进行而互不干扰
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock as TokioRwLock;
+
+use super::idle::release_idle_resources;
+use super::voice::{PipelineError, PipelineState, VoicePipeline};
+use crate::config::AppConfig;
+
+/// 音频来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PipelineSource {
+    /// 麦克风（快捷键听写）
+    Microphone,
+    /// 系统音频回环（会议录制）
+    Loopback,
+    /// 文件转写队列
+    FileQueue,
+}
+
+/// 管道注册表
+pub struct PipelineRegistry {
+    config: Arc<TokioRwLock<AppConfig>>,
+    pipelines: Mutex<HashMap<PipelineSource, Arc<VoicePipeline>>>,
+}
+
+impl PipelineRegistry {
+    pub fn new(config: Arc<TokioRwLock<AppConfig>>) -> Self {
+        Self {
+            config,
+            pipelines: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取指定来源的管道（未创建时返回 None）
+    pub fn get(&self, source: PipelineSource) -> Option<Arc<VoicePipeline>> {
+        self.pipelines.lock().ok()?.get(&source).cloned()
+    }
+
+    /// 获取指定来源的管道，不存在时创建
+    pub fn get_or_create(&self, source: PipelineSource) -> Result<Arc<VoicePipeline>, PipelineError> {
+        let mut pipelines = self
+            .pipelines
+            .lock()
+            .map_err(|e| PipelineError::Other(format!("Failed to acquire registry lock: {}", e)))?;
+
+        if let Some(pipeline) = pipelines.get(&source) {
+            return Ok(pipeline.clone());
+        }
+
+        let pipeline = Arc::new(VoicePipeline::new(self.config.clone())?);
+        pipelines.insert(source, pipeline.clone());
+        tracing::info!("Pipeline created for source {:?}", source);
+        Ok(pipeline)
+    }
+
+    /// 移除空闲的管道，正在录音或处理的管道不会被移除
+    pub fn remove(&self, source: PipelineSource) -> bool {
+        let Ok(mut pipelines) = self.pipelines.lock() else {
+            return false;
+        };
+        match pipelines.get(&source) {
+            Some(pipeline) if pipeline.get_state() == PipelineState::Idle => {
+                pipelines.remove(&source);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 所有已创建的管道
+    pub fn all(&self) -> Vec<Arc<VoicePipeline>> {
+        self.pipelines
+            .lock()
+            .map(|pipelines| pipelines.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 所有已创建管道的状态
+    pub fn states(&self) -> Vec<(PipelineSource, PipelineState)> {
+        self.pipelines
+            .lock()
+            .map(|pipelines| {
+                pipelines
+                    .iter()
+                    .map(|(source, pipeline)| (*source, pipeline.get_state()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 所有管道都空闲超过节能配置的时长时释放资源，返回是否实际释放
+    ///
+    /// 由宿主定时调用；资源在管道间共用，任一管道在使用时都不释放
+    pub fn release_if_idle(&self) -> bool {
+        let Ok(config) = self.config.try_read() else {
+            return false;
+        };
+        if !config.energy_saver.enabled {
+            return false;
+        }
+        let idle_for = Duration::from_secs(config.energy_saver.idle_release_secs);
+        drop(config);
+        self.release_when(|idle| idle >= idle_for)
+    }
+
+    /// 没有管道在使用时立即释放资源（锁屏、休眠时调用），返回是否实际释放
+    pub fn release_resources(&self) -> bool {
+        self.release_when(|_| true)
+    }
+
+    fn release_when(&self, ready: impl Fn(Duration) -> bool) -> bool {
+        let all_idle = self
+            .all()
+            .iter()
+            .all(|pipeline| pipeline.idle_duration().is_some_and(&ready));
+        all_idle && release_idle_resources()
+    }
+}
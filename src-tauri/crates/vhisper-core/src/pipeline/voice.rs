@@ -1,15 +1,49 @@
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use futures_util::Stream;
 use tokio::sync::mpsc;
-use tokio::sync::RwLock as TokioRwLock;
+use uuid::Uuid;
+use tokio::sync::{Mutex as TokioMutex, RwLock as TokioRwLock};
 
 use crate::asr::{
-    create_asr_service, create_streaming_asr_service, StreamingAsrEvent, StreamingControl,
+    accepts_opus, requires_wav, provider_chain, race_provider, resolve_provider, streaming_session_limit, StreamingAsrEvent, StreamingSession,
+    StreamingSessionState,
 };
-use crate::audio::{encode_to_pcm, encode_to_wav, AudioRecorder};
-use crate::config::AppConfig;
-use crate::llm::create_llm_service;
+use crate::audio::{
+    decode_file, encode_blocking, encode_to_pcm, level_stats_blocking, plan_upload,
+    set_echo_suppression, suppress_edge_transients, trim_edges, AudioRecorder, EncodeFormat,
+    EnergyVad, LevelStats, UploadFormat,
+};
+use crate::config::settings::{default_processing_timeout_secs, AsrConfig, PostProcessMode};
+use crate::config::{AppConfig, AutoSubmitConfig};
+use crate::debug::begin_streaming_trace;
+use crate::events::{ErrorCode, EventPayload, EventStage, SessionLimit};
+use crate::format::label_speakers;
+use crate::history::{self, SessionRecord, TimedTranscript};
+use crate::text::{count_words, diff_text, words_per_minute};
+
+use super::assembly::SessionTranscript;
+use super::builder::{PipelineServices, VoicePipelineBuilder};
+use super::context::TranscriptContext;
+use super::event_stream::{EventHub, PipelineEvent};
+use super::idle;
+use super::last_dictation::{remember_dictation, Dictation};
+use super::refine::SentenceRefiner;
+
+/// 到达时长限制前多久发出提醒
+const LIMIT_WARNING_LEAD: Duration = Duration::from_secs(10);
+/// 在服务商连接上限前多久切换到新连接，留出等待最终结果的时间
+const ROLLOVER_MARGIN: Duration = Duration::from_secs(2);
+/// 检查时长限制的间隔
+const LIMIT_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+/// 发布输入状态（电平、是否在说话）的间隔
+const ACTIVITY_INTERVAL: Duration = Duration::from_millis(100);
+
+type SessionHolder = TokioRwLock<Option<StreamingSession>>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum PipelineError {
@@ -23,10 +57,12 @@ pub enum PipelineError {
     Other(String),
     #[error("Operation cancelled")]
     Cancelled,
+    #[error("Processing timed out after {0}s")]
+    Timeout(u64),
 }
 
 /// Pipeline 状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 #[repr(u8)]
 pub enum PipelineState {
     Idle = 0,
@@ -55,30 +91,105 @@ pub struct VoicePipeline {
     cancelled: Arc<AtomicBool>,
     /// 流式模式标志（Arc 包装以便后台任务共享）
     streaming_mode: Arc<AtomicBool>,
-    /// 流式 ASR 控制通道（用于发送音频和控制命令）
-    /// 音频发送任务从这里读取当前活跃的 control_tx
-    streaming_control_tx: Arc<TokioRwLock<Option<mpsc::Sender<StreamingControl>>>>,
+    /// 当前活跃的流式 ASR 会话（用于发送音频和控制命令）
+    /// 音频发送任务从这里读取，重连时替换
+    streaming_session: Arc<SessionHolder>,
+    /// 流式识别是否暂停（按住静音），重连后的新会话同样暂停
+    streaming_paused: Arc<AtomicBool>,
     /// 流式任务取消标志（每次会话独立，用于通知后台任务停止）
     streaming_task_cancelled: Arc<TokioRwLock<Option<Arc<AtomicBool>>>>,
     /// 是否应该完全停止（热键松开时设为 true，区别于 VAD Final）
     should_stop: Arc<AtomicBool>,
+    /// 本次会话的语言覆盖（由前台应用的配置决定）
+    language_override: RwLock<Option<String>>,
+    /// 本次会话的后处理模式覆盖（由触发的快捷键决定），会话结束时清除
+    mode_override: Arc<RwLock<Option<PostProcessMode>>>,
+    /// 最近的识别结果，作为下一次识别的提示
+    transcript_context: Arc<TranscriptContext>,
+    /// 自动提交分句与停止处理互斥，保证分句先于剩余部分输出
+    segment_lock: TokioMutex<()>,
+    /// 进入 Processing 状态的时间，用于看门狗检测卡住的处理
+    processing_since: RwLock<Option<Instant>>,
+    /// 会话代数，开始新会话或看门狗强制恢复时递增，过期的处理任务不再写入状态
+    generation: AtomicU64,
+    /// 最近一次录音会话的 ID（Arc 包装以便输入状态线程判断会话是否已切换）
+    session_id: Arc<RwLock<Option<String>>>,
+    /// 会话是否尚未发布结束事件
+    session_open: Arc<AtomicBool>,
+    /// 最近一次会话开始或结束的时间，用于判断空闲时长
+    last_active: RwLock<Instant>,
+    /// 流式会话使用的 ASR 配置，重连时读取；配置变更后在当前句结束时切换
+    streaming_asr_config: Arc<RwLock<Option<AsrConfig>>>,
+    /// 事件流（状态变化、流式识别结果和结构化事件）
+    events: EventHub,
+    /// 通过 VoicePipelineBuilder 注入的服务
+    services: PipelineServices,
 }
 
 impl VoicePipeline {
     /// 创建新的语音管道
     pub fn new(config: Arc<TokioRwLock<AppConfig>>) -> Result<Self, PipelineError> {
-        let recorder = AudioRecorder::new()?;
+        Ok(Self::with_recorder(config, AudioRecorder::new()?))
+    }
 
-        Ok(Self {
+    /// 使用指定的录音器创建（如外部音频源）
+    pub fn with_recorder(config: Arc<TokioRwLock<AppConfig>>, recorder: AudioRecorder) -> Self {
+        Self {
             config,
             recorder: Arc::new(RwLock::new(recorder)),
             state: Arc::new(AtomicU8::new(PipelineState::Idle as u8)),
             cancelled: Arc::new(AtomicBool::new(false)),
             streaming_mode: Arc::new(AtomicBool::new(false)),
-            streaming_control_tx: Arc::new(TokioRwLock::new(None)),
+            streaming_session: Arc::new(TokioRwLock::new(None)),
+            streaming_paused: Arc::new(AtomicBool::new(false)),
             streaming_task_cancelled: Arc::new(TokioRwLock::new(None)),
             should_stop: Arc::new(AtomicBool::new(false)),
-        })
+            language_override: RwLock::new(None),
+            mode_override: Arc::new(RwLock::new(None)),
+            transcript_context: Arc::new(TranscriptContext::new()),
+            segment_lock: TokioMutex::new(()),
+            processing_since: RwLock::new(None),
+            generation: AtomicU64::new(0),
+            session_id: Arc::new(RwLock::new(None)),
+            session_open: Arc::new(AtomicBool::new(false)),
+            last_active: RwLock::new(Instant::now()),
+            streaming_asr_config: Arc::new(RwLock::new(None)),
+            events: EventHub::new(),
+            services: PipelineServices::default(),
+        }
+    }
+
+    /// 构建注入自定义服务的管道
+    pub fn builder() -> VoicePipelineBuilder {
+        VoicePipelineBuilder::new()
+    }
+
+    pub(crate) fn with_services(mut self, services: PipelineServices) -> Self {
+        self.services = services;
+        self
+    }
+
+    /// 写入外部音频（仅对外部音频源的录音器有效）
+    pub fn feed_audio(&self, samples: &[f32]) {
+        if let Ok(recorder) = self.recorder.read() {
+            recorder.push_samples(samples);
+        }
+    }
+
+    /// 当前输入电平（0.0 - 1.0），用于外部电平表
+    pub fn input_level(&self) -> f32 {
+        self.recorder
+            .read()
+            .map(|recorder| recorder.current_level())
+            .unwrap_or(0.0)
+    }
+
+    /// 本地 VAD 是否检测到正在说话
+    pub fn speech_detected(&self) -> bool {
+        self.recorder
+            .read()
+            .map(|recorder| recorder.is_speech_detected())
+            .unwrap_or(false)
     }
 
     /// 获取当前状态
@@ -91,6 +202,197 @@ impl VoicePipeline {
         self.get_state() == PipelineState::Recording
     }
 
+    /// 设置后续会话使用的识别语言，None 表示使用配置中的语言
+    pub fn set_language_override(&self, language: Option<String>) {
+        if let Ok(mut guard) = self.language_override.write() {
+            *guard = language;
+        }
+    }
+
+    /// 设置下一次会话使用的后处理模式，None 表示使用配置中的模式
+    ///
+    /// 只对下一次会话生效，会话结束（包括取消）后恢复为配置中的模式
+    pub fn set_mode_override(&self, mode: Option<PostProcessMode>) {
+        if let Ok(mut guard) = self.mode_override.write() {
+            *guard = mode;
+        }
+    }
+
+    /// 读取配置快照，并应用语言和后处理模式覆盖
+    async fn effective_config(&self) -> AppConfig {
+        let mut config = self.config.read().await.clone();
+        let language = self
+            .language_override
+            .read()
+            .ok()
+            .and_then(|guard| guard.clone());
+        if let Some(language) = language {
+            config.apply_language(&language);
+        }
+        if let Some(mode) = self.mode_override.read().ok().and_then(|guard| *guard) {
+            config.llm.mode = mode;
+        }
+        config
+    }
+
+    /// 最近一次录音会话的 ID
+    pub fn session_id(&self) -> Option<String> {
+        self.session_id.read().ok().and_then(|guard| guard.clone())
+    }
+
+    /// 开始新的会话
+    fn begin_session(&self) {
+        let session_id = Uuid::new_v4().to_string();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut guard) = self.session_id.write() {
+            *guard = Some(session_id.clone());
+        }
+        self.session_open.store(true, Ordering::SeqCst);
+        self.touch();
+        idle::mark_active();
+        self.spawn_activity_monitor(session_id);
+    }
+
+    /// 记录活动时间
+    fn touch(&self) {
+        if let Ok(mut last_active) = self.last_active.write() {
+            *last_active = Instant::now();
+        }
+    }
+
+    /// 空闲时长，正在录音或处理时返回 None
+    pub fn idle_duration(&self) -> Option<Duration> {
+        if self.get_state() != PipelineState::Idle {
+            return None;
+        }
+        self.last_active.read().ok().map(|since| since.elapsed())
+    }
+
+    /// 空闲超过节能配置的时长时释放资源，返回是否实际释放
+    ///
+    /// 只适用于单个管道的宿主（如 FFI）；多个管道共用资源时使用 PipelineRegistry::release_if_idle
+    pub fn release_if_idle(&self) -> bool {
+        let Ok(config) = self.config.try_read() else {
+            return false;
+        };
+        let energy_saver = &config.energy_saver;
+        let idle_for = Duration::from_secs(energy_saver.idle_release_secs);
+        if !energy_saver.enabled || self.idle_duration().is_none_or(|idle| idle < idle_for) {
+            return false;
+        }
+        drop(config);
+        idle::release_idle_resources()
+    }
+
+    /// 录音期间定时发布输入状态，录音结束或会话切换后退出
+    ///
+    /// 使用独立线程：开始录音可能在快捷键回调等没有 tokio 运行时的线程中调用
+    fn spawn_activity_monitor(&self, session_id: String) {
+        let recorder = self.recorder.clone();
+        let state = self.state.clone();
+        let current_session = self.session_id.clone();
+        let hub = self.events.clone();
+
+        thread::spawn(move || loop {
+            thread::sleep(ACTIVITY_INTERVAL);
+            let same_session = current_session
+                .read()
+                .is_ok_and(|guard| guard.as_deref() == Some(session_id.as_str()));
+            if !same_session || state.load(Ordering::SeqCst) != PipelineState::Recording as u8 {
+                break;
+            }
+            let Ok((level, speech)) = recorder
+                .read()
+                .map(|recorder| (recorder.current_level(), recorder.is_speech_detected()))
+            else {
+                break;
+            };
+            hub.publish(
+                Some(session_id.clone()),
+                EventPayload::InputActivity { level, speech },
+            );
+        });
+    }
+
+    /// 发布当前会话的事件
+    fn publish(&self, payload: EventPayload) {
+        self.events.publish(self.session_id(), payload);
+    }
+
+    /// 更新状态
+    fn set_state(&self, state: PipelineState) {
+        self.events.set_state(&self.state, state);
+    }
+
+    /// 会话代数未变化时更新状态
+    ///
+    /// 看门狗强制恢复后，原处理任务仍可能完成，此时不能覆盖新会话的状态
+    fn set_state_if_current(&self, generation: u64, state: PipelineState) {
+        if self.generation.load(Ordering::SeqCst) == generation {
+            self.set_state(state);
+        } else {
+            tracing::warn!("Stale processing task finished after reset, state left unchanged");
+        }
+    }
+
+    /// 订阅管道事件
+    ///
+    /// 返回之后发生的状态变化、流式识别的中间和最终结果，以及所有结构化事件（包括错误），
+    /// 跨会话持续有效，可以代替 start_streaming 返回的接收器
+    pub fn events(&self) -> impl Stream<Item = PipelineEvent> + Send + 'static {
+        self.events.subscribe()
+    }
+
+    /// 发布会话结束事件（每个会话只发布一次），返回是否发布
+    fn finish_session(&self, payload: EventPayload) -> bool {
+        let open = self.session_open.swap(false, Ordering::SeqCst);
+        if open {
+            self.touch();
+            self.set_mode_override(None);
+            self.publish(payload);
+        }
+        open
+    }
+
+    /// 处理超时时间
+    fn processing_timeout(&self) -> Duration {
+        let secs = self
+            .config
+            .try_read()
+            .map(|config| config.asr.processing_timeout_secs)
+            .unwrap_or_else(|_| default_processing_timeout_secs());
+        Duration::from_secs(secs)
+    }
+
+    /// 看门狗：处理状态持续超过超时时间时强制恢复为 Idle
+    ///
+    /// 处理任务被中途丢弃（未走到恢复状态的代码）时，状态会一直停留在 Processing，
+    /// 导致快捷键失效。返回是否进行了恢复。
+    pub fn recover_if_stuck(&self) -> bool {
+        if self.get_state() != PipelineState::Processing {
+            return false;
+        }
+
+        let stuck = self
+            .processing_since
+            .read()
+            .ok()
+            .and_then(|since| *since)
+            .is_some_and(|since| since.elapsed() > self.processing_timeout());
+        if !stuck {
+            return false;
+        }
+
+        tracing::warn!("Pipeline stuck in Processing, forcing reset to Idle");
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.cancelled.store(false, Ordering::SeqCst);
+        self.set_state(PipelineState::Idle);
+        if let Ok(mut since) = self.processing_since.write() {
+            *since = None;
+        }
+        true
+    }
+
     /// 取消当前操作
     ///
     /// - 如果正在录音，停止录音并丢弃数据
@@ -111,8 +413,9 @@ impl VoicePipeline {
                     PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
                 })?;
                 let _ = recorder.stop(); // 忽略数据
-                self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+                self.set_state(PipelineState::Idle);
                 self.cancelled.store(false, Ordering::SeqCst);
+                self.finish_session(EventPayload::RecordingCancelled);
                 tracing::info!("Recording cancelled");
                 Ok(())
             }
@@ -127,6 +430,25 @@ impl VoicePipeline {
 
     /// 开始录音
     pub fn start_recording(&self) -> Result<(), PipelineError> {
+        let result = self.start_recording_inner();
+        match &result {
+            Ok(()) => self.publish(EventPayload::RecordingStarted),
+            Err(e) => self.events.publish(
+                None,
+                EventPayload::Error {
+                    stage: EventStage::Recording,
+                    code: ErrorCode::Audio,
+                    message: e.to_string(),
+                    recoverable: true,
+                },
+            ),
+        }
+        result
+    }
+
+    fn start_recording_inner(&self) -> Result<(), PipelineError> {
+        self.recover_if_stuck();
+
         // 检查状态，只有 Idle 才能开始
         let current = self.state.load(Ordering::SeqCst);
         if current != PipelineState::Idle as u8 {
@@ -140,9 +462,16 @@ impl VoicePipeline {
         let mut recorder = self.recorder.write().map_err(|e| {
             PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
         })?;
+        if let Ok(config) = self.config.try_read() {
+            recorder.set_speech_threshold(config.hotkey.auto_submit.threshold);
+            recorder.set_realtime_priority(config.audio.realtime_priority);
+            set_echo_suppression(config.audio.echo_suppression);
+        }
         recorder.start()?;
+        drop(recorder);
 
-        self.state.store(PipelineState::Recording as u8, Ordering::SeqCst);
+        self.set_state(PipelineState::Recording);
+        self.begin_session();
         Ok(())
     }
 
@@ -152,46 +481,154 @@ impl VoicePipeline {
     /// - 如果不在录音状态，直接返回空字符串
     /// - 如果已取消，返回 Cancelled 错误
     pub async fn stop_and_process(&self) -> Result<String, PipelineError> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        let result = self.stop_and_process_inner(generation).await;
+        // 看门狗已强制恢复：会话可能已被新录音接替，不再发布结束事件和输出
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return result.map(remember_dictation);
+        }
+        let finished = self.finish_session(match &result {
+            Ok(dictation) => EventPayload::ProcessingComplete {
+                text: dictation.refined.clone(),
+            },
+            Err(PipelineError::Cancelled) => EventPayload::RecordingCancelled,
+            Err(e) => EventPayload::from_pipeline_error(e),
+        });
+        if let (true, Ok(dictation)) = (finished, &result) {
+            publish_text_diff(&self.events, self.session_id(), dictation);
+            self.services.output(&dictation.refined);
+            let record_history = self.config.read().await.history.enabled;
+            publish_session_stats(
+                &self.events,
+                self.session_id(),
+                &dictation.refined,
+                recorded_duration(&self.recorder),
+                record_history,
+            );
+        }
+        result.map(remember_dictation)
+    }
+
+    async fn stop_and_process_inner(&self, generation: u64) -> Result<Dictation, PipelineError> {
         // 检查是否已取消
         if self.cancelled.load(Ordering::SeqCst) {
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+            self.set_state_if_current(generation, PipelineState::Idle);
             self.cancelled.store(false, Ordering::SeqCst);
             return Err(PipelineError::Cancelled);
         }
 
+        // 等待正在处理的自动提交分句完成，保证输出顺序
+        let _segment_guard = self.segment_lock.lock().await;
+
         // 幂等检查：非录音状态直接返回
         let current = self.state.load(Ordering::SeqCst);
         if current != PipelineState::Recording as u8 {
             tracing::warn!("stop_and_process called but not recording, state={:?}", PipelineState::from(current));
-            return Ok(String::new());
+            return Ok(Dictation::default());
         }
 
         // 转换到 Processing 状态
-        self.state.store(PipelineState::Processing as u8, Ordering::SeqCst);
+        self.set_state(PipelineState::Processing);
+        if let Ok(mut since) = self.processing_since.write() {
+            *since = Some(Instant::now());
+        }
+        self.publish(EventPayload::RecordingStopped);
 
         // 停止录音 - 使用同步锁，快速获取并释放
-        let samples = {
+        let (samples, sample_rate) = {
             let mut recorder = self.recorder.write().map_err(|e| {
-                self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+                self.set_state_if_current(generation, PipelineState::Idle);
                 PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
             })?;
-            recorder.stop()?
+            (recorder.stop()?, recorder.sample_rate())
         };
 
         // 检查是否在停止后被取消
         if self.cancelled.load(Ordering::SeqCst) {
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+            self.set_state_if_current(generation, PipelineState::Idle);
             self.cancelled.store(false, Ordering::SeqCst);
             return Err(PipelineError::Cancelled);
         }
 
         if samples.is_empty() {
             tracing::warn!("No audio data recorded");
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+            self.set_state_if_current(generation, PipelineState::Idle);
+            return Ok(Dictation::default());
+        }
+
+        // 去掉首尾的按键声
+        let audio_config = self.config.read().await.audio.clone();
+        let mut samples = trim_edges(&samples, sample_rate, audio_config.edge_trim_ms).to_vec();
+        if audio_config.suppress_edge_clicks {
+            let suppressed = suppress_edge_transients(&mut samples, sample_rate);
+            if suppressed > 0 {
+                tracing::debug!("Suppressed {} click blocks at recording edges", suppressed);
+            }
+        }
+
+        let timeout = self.processing_timeout();
+        let result = tokio::time::timeout(timeout, self.recognize_dictation(samples))
+            .await
+            .unwrap_or_else(|_| {
+                tracing::error!("Processing timed out after {:?}, force cancelling", timeout);
+                self.cancelled.store(false, Ordering::SeqCst);
+                Err(PipelineError::Timeout(timeout.as_secs()))
+            });
+
+        // 完成，恢复 Idle 状态
+        self.set_state_if_current(generation, PipelineState::Idle);
+        if result.is_ok() {
+            tracing::info!("stop_and_process completed successfully");
+        }
+        result
+    }
+
+    /// 转写音频文件（WAV/MP3/M4A），经过与录音相同的 ASR 和文本后处理
+    ///
+    /// 不影响录音状态，也不写入历史记录；结果由调用方决定如何输出
+    pub async fn transcribe_file(&self, path: impl AsRef<Path>) -> Result<String, PipelineError> {
+        let path = path.as_ref().to_path_buf();
+        let sample_rate = {
+            let recorder = self.recorder.read().map_err(|e| {
+                PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
+            })?;
+            recorder.sample_rate()
+        };
+        tracing::info!("Transcribing file: {:?}", path);
+
+        // 解码和重采样在阻塞线程池中执行
+        let samples = tokio::task::spawn_blocking(move || decode_file(&path, sample_rate))
+            .await
+            .map_err(|e| PipelineError::Other(format!("Decoding task failed: {}", e)))??;
+        if samples.is_empty() {
             return Ok(String::new());
         }
 
-        let config = self.config.read().await.clone();
+        self.recognize_samples(samples).await
+    }
+
+    /// 录音器的采样率
+    pub(super) fn sample_rate(&self) -> Result<u32, PipelineError> {
+        let recorder = self.recorder.read().map_err(|e| {
+            PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
+        })?;
+        Ok(recorder.sample_rate())
+    }
+
+    /// 识别一段音频并完成文本后处理
+    pub(super) async fn recognize_samples(&self, samples: Vec<f32>) -> Result<String, PipelineError> {
+        self.recognize_dictation(samples)
+            .await
+            .map(|dictation| dictation.refined)
+    }
+
+    /// 识别一段音频并完成文本后处理，同时返回原始识别文本
+    ///
+    /// 电平统计和编码在阻塞线程池中执行，不占用异步运行时线程
+    async fn recognize_dictation(&self, samples: Vec<f32>) -> Result<Dictation, PipelineError> {
+        let mut config = self.effective_config().await;
+        // Auto 模式下先确定服务商，音频编码格式取决于具体服务商
+        config.asr.provider = resolve_provider(&config.asr);
         let sample_rate = {
             let recorder = self.recorder.read().map_err(|e| {
                 PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
@@ -200,11 +637,14 @@ impl VoicePipeline {
         };
 
         tracing::info!("Processing {} samples at {}Hz", samples.len(), sample_rate);
+        let samples = Arc::new(samples);
 
         // 检测是否全静音
-        let max_amplitude = samples.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
-        let avg_amplitude = samples.iter().map(|s| s.abs()).sum::<f32>() / samples.len() as f32;
-        let non_zero_count = samples.iter().filter(|&&s| s != 0.0).count();
+        let LevelStats {
+            max: max_amplitude,
+            avg: avg_amplitude,
+            non_zero: non_zero_count,
+        } = level_stats_blocking(samples.clone()).await?;
 
         tracing::info!(
             "Audio stats: max={:.6}, avg={:.6}, non_zero={}/{}, threshold=0.001",
@@ -217,7 +657,6 @@ impl VoicePipeline {
         // >= 0.05 = 正常语音
         if max_amplitude < 0.001 {
             tracing::warn!(">>> SILENT (amplitude={:.6}) - likely permission issue <<<", max_amplitude);
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
             return Err(PipelineError::Other(
                 "录音无声音，请检查麦克风权限是否已授予当前应用".to_string()
             ));
@@ -225,7 +664,6 @@ impl VoicePipeline {
 
         if max_amplitude < 0.05 {
             tracing::warn!(">>> AUDIO TOO QUIET (amplitude={:.6}) - speak louder or closer <<<", max_amplitude);
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
             return Err(PipelineError::Other(
                 "录音音量太低，请靠近麦克风或大声说话".to_string()
             ));
@@ -233,74 +671,189 @@ impl VoicePipeline {
 
         tracing::info!("Audio OK, proceeding to ASR...");
 
-        // 编码音频数据
-        let audio_data = if config.asr.provider == "OpenAIWhisper" {
-            // OpenAI Whisper 需要 WAV 格式
+        // LLM 预热（TLS 握手、Ollama 加载模型）与编码和 ASR 请求并行，修正时不再等待连接
+        self.services.spawn_llm_warm_up(&config);
+
+        // 编码音频数据：带宽不足且服务商支持时使用 Opus
+        // 配置了备用或竞速服务商时按其中要求最严格的服务商选择格式
+        let mut providers = provider_chain(&config.asr);
+        providers.extend(race_provider(&config.asr));
+        let plan = plan_upload(
+            config.asr.upload_encoding,
+            providers.iter().all(|p| accepts_opus(p)),
+        );
+        let opus_data = if plan.format == UploadFormat::Opus {
+            match encode_blocking(samples.clone(), sample_rate, EncodeFormat::OggOpus).await {
+                Ok(data) => {
+                    tracing::info!("Using Opus upload: {} bytes", data.len());
+                    Some(data)
+                }
+                Err(e) => {
+                    tracing::warn!("Opus encoding failed, falling back to lossless: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let audio_data = if let Some(data) = opus_data {
+            data
+        } else if providers.iter().any(|p| requires_wav(p)) {
+            // OpenAI Whisper、AssemblyAI 需要 WAV 格式
             let channels = {
                 let recorder = self.recorder.read().map_err(|e| {
                     PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
                 })?;
                 recorder.channels()
             };
-            encode_to_wav(&samples, sample_rate, channels)?
+            encode_blocking(samples, sample_rate, EncodeFormat::Wav { channels }).await?
         } else {
             // 其他服务使用 PCM
-            encode_to_pcm(&samples)
+            encode_blocking(samples, sample_rate, EncodeFormat::Pcm).await?
         };
 
         // 检查取消标志
         if self.cancelled.load(Ordering::SeqCst) {
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
             self.cancelled.store(false, Ordering::SeqCst);
             return Err(PipelineError::Cancelled);
         }
 
         // 创建 ASR 服务并识别
-        let asr_service = create_asr_service(&config.asr)?;
-        let asr_result = match asr_service.recognize(&audio_data, sample_rate).await {
-            Ok(r) => r,
-            Err(e) => {
-                self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-                return Err(e.into());
-            }
-        };
+        let context = self.transcript_context.prompt(config.asr.context_chars);
+        let asr_service = self.services.asr_service(&config.asr, context)?;
+        let mut asr_result = asr_service.recognize(&audio_data, sample_rate).await?;
 
         tracing::info!("ASR result: {}", asr_result.text);
+        self.transcript_context.push(&asr_result.text);
+
+        // 说话人分离：按说话人分行
+        if config.asr.diarization {
+            if let Some(labeled) = label_speakers(&asr_result.segments) {
+                asr_result.text = labeled;
+            }
+        }
 
         // 再次检查取消标志
         if self.cancelled.load(Ordering::SeqCst) {
-            self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
             self.cancelled.store(false, Ordering::SeqCst);
             return Err(PipelineError::Cancelled);
         }
 
-        let mut final_text = asr_result.text.clone();
+        // 自动检测语言时，文本修正使用检测到的语言
+        if let Some(language) = &asr_result.language {
+            tracing::info!("Detected language: {}", language);
+        }
+        config.apply_detected_language(asr_result.language.as_deref());
+
+        // 文本后处理（替换、规范化、LLM 校对等）
+        let final_text = self
+            .services
+            .text_pipeline(&config)
+            .process(&asr_result.text)
+            .await;
+
+        Ok(Dictation {
+            raw: asr_result.text,
+            refined: final_text,
+        })
+    }
 
-        // 如果启用了 LLM，进行文本优化
-        if config.llm.enabled && !final_text.is_empty() {
-            if let Ok(Some(llm_service)) = create_llm_service(&config.llm) {
-                match llm_service.refine_text(&final_text).await {
-                    Ok(refined) => {
-                        tracing::info!("LLM refined: {} -> {}", final_text, refined);
-                        final_text = refined;
-                    }
-                    Err(e) => {
-                        tracing::warn!("LLM refinement failed, using original: {}", e);
-                    }
+    /// 按住快捷键录音时，检测到停顿自动提交已说完的句子
+    ///
+    /// 后台监听录音缓冲区，本地 VAD 检测到句末静音后识别该段音频并调用 on_segment，
+    /// 录音继续进行；松开快捷键时 stop_and_process 只处理剩余部分。
+    /// on_segment 在持有分句锁时调用，其中的输出会先于 stop_and_process 的结果完成。
+    pub fn start_auto_submit<F>(self: &Arc<Self>, config: AutoSubmitConfig, on_segment: F)
+    where
+        F: Fn(Result<String, PipelineError>) + Send + Sync + 'static,
+    {
+        let pipeline = self.clone();
+
+        tokio::spawn(async move {
+            let sample_rate = match pipeline.recorder.read() {
+                Ok(recorder) => recorder.sample_rate(),
+                Err(_) => return,
+            };
+            let mut vad = EnergyVad::new(sample_rate, config.threshold, config.silence_ms);
+            // 已经送入 VAD 的样本数
+            let mut scanned = 0usize;
+
+            loop {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+
+                let _segment_guard = pipeline.segment_lock.lock().await;
+                if pipeline.get_state() != PipelineState::Recording {
+                    break;
                 }
+
+                let samples = match pipeline.recorder.read() {
+                    Ok(recorder) => recorder.samples_from(scanned),
+                    Err(_) => break,
+                };
+                scanned += samples.len();
+                if !vad.process(&samples) {
+                    continue;
+                }
+
+                let segment = match pipeline.recorder.read() {
+                    Ok(recorder) => recorder.take_front(scanned),
+                    Err(_) => break,
+                };
+                scanned = 0;
+                vad.reset();
+
+                tracing::info!("End of speech detected, submitting {} samples", segment.len());
+                on_segment(
+                    pipeline
+                        .recognize_dictation(segment)
+                        .await
+                        .map(remember_dictation),
+                );
             }
-        }
 
-        // 完成，恢复 Idle 状态
-        self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-        tracing::info!("stop_and_process completed successfully");
-        Ok(final_text)
+            tracing::info!("Auto submit watcher stopped");
+        });
     }
 
     // ========================================================================
     // 流式识别方法
     // ========================================================================
 
+    /// 停止录音，把剩余音频和 commit 发送给当前 ASR 连接
+    async fn commit_streaming_audio(
+        recorder: &RwLock<AudioRecorder>,
+        session_holder: &SessionHolder,
+    ) -> Result<(), PipelineError> {
+        // 停止录音
+        {
+            let mut recorder = recorder.write().map_err(|e| {
+                PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
+            })?;
+            let _ = recorder.stop();
+        }
+
+        // 发送最后一批音频和 commit（写锁等待音频发送任务正在发送的一批完成）
+        if let Some(session) = session_holder.write().await.as_ref() {
+            // 获取剩余音频
+            let samples = {
+                let recorder = recorder.read().map_err(|e| {
+                    PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
+                })?;
+                recorder.drain_buffer()
+            };
+
+            if !samples.is_empty() {
+                let pcm_data = encode_to_pcm(&samples);
+                let _ = session.send_audio(pcm_data).await;
+            }
+
+            // 提交
+            let _ = session.commit().await;
+        }
+        Ok(())
+    }
+
     /// 清理流式会话资源
     async fn cleanup_streaming(&self) {
         // 设置任务取消标志
@@ -310,7 +863,7 @@ impl VoicePipeline {
 
         // 清理控制通道
         {
-            let mut tx_guard = self.streaming_control_tx.write().await;
+            let mut tx_guard = self.streaming_session.write().await;
             *tx_guard = None;
         }
 
@@ -342,7 +895,7 @@ impl VoicePipeline {
             task_cancelled.store(true, Ordering::SeqCst);
         }
         {
-            let mut tx_guard = self.streaming_control_tx.write().await;
+            let mut tx_guard = self.streaming_session.write().await;
             *tx_guard = None;
         }
         {
@@ -365,7 +918,7 @@ impl VoicePipeline {
         self.streaming_mode.store(true, Ordering::SeqCst);
 
         // 获取配置和采样率
-        let config = self.config.read().await.clone();
+        let config = self.effective_config().await;
         let sample_rate = {
             let recorder = self.recorder.read().map_err(|e| {
                 PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
@@ -374,13 +927,22 @@ impl VoicePipeline {
         };
 
         // 创建首个 ASR 连接
-        let streaming_service = create_streaming_asr_service(&config.asr)?;
-        let (control_tx, event_rx) = streaming_service.start_streaming(sample_rate).await?;
-
-        // 保存控制通道
+        begin_streaming_trace(config.debug.streaming_trace && !crate::privacy::is_incognito());
+        if let Ok(mut guard) = self.streaming_asr_config.write() {
+            *guard = Some(config.asr.clone());
+        }
+        let context = self.transcript_context.prompt(config.asr.context_chars);
+        let streaming_service = self.services.streaming_asr_service(&config.asr, context)?;
+        let mut session = streaming_service.start_streaming(sample_rate).await?;
+        let event_rx = session
+            .take_events()
+            .ok_or_else(|| PipelineError::Other("Streaming session has no event receiver".to_string()))?;
+        self.streaming_paused.store(false, Ordering::SeqCst);
+
+        // 保存会话（事件由会话管理任务接收）
         {
-            let mut tx_guard = self.streaming_control_tx.write().await;
-            *tx_guard = Some(control_tx);
+            let mut tx_guard = self.streaming_session.write().await;
+            *tx_guard = Some(session);
         }
 
         // 启动录音
@@ -388,22 +950,36 @@ impl VoicePipeline {
             let mut recorder = self.recorder.write().map_err(|e| {
                 PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
             })?;
+            recorder.set_speech_threshold(config.hotkey.auto_submit.threshold);
+            recorder.set_realtime_priority(config.audio.realtime_priority);
+            set_echo_suppression(config.audio.echo_suppression);
             recorder.start()?;
         }
 
-        self.state.store(PipelineState::Recording as u8, Ordering::SeqCst);
+        self.set_state(PipelineState::Recording);
+        self.begin_session();
+        self.publish(EventPayload::RecordingStarted);
 
         // 创建事件转发通道
         let (forward_tx, forward_rx) = mpsc::channel::<StreamingAsrEvent>(32);
 
+        // 最近发送的音频，重连时补发给新连接：服务端断句到新连接建立之间的音频
+        // 发给了已结束的连接，下一句开头的字会丢失
+        let overlap_samples =
+            (config.asr.reconnect_overlap_ms * sample_rate as u64 / 1000) as usize;
+        let recent_audio = Arc::new(Mutex::new(VecDeque::<f32>::with_capacity(overlap_samples)));
+
         // === 音频发送任务 ===
-        // 持续运行，从 streaming_control_tx 读取当前活跃的 control_tx
+        // 持续运行，从 streaming_session 读取当前活跃的会话
         let recorder = self.recorder.clone();
+        let recent_audio_for_audio = recent_audio.clone();
         let should_stop_for_audio = self.should_stop.clone();
-        let control_tx_holder = self.streaming_control_tx.clone();
+        let session_holder = self.streaming_session.clone();
+
+        let chunk_interval =
+            Duration::from_millis(plan_upload(config.asr.upload_encoding, false).chunk_ms);
 
         tokio::spawn(async move {
-            let chunk_interval = Duration::from_millis(50);
 
             loop {
                 // 检查是否应该停止
@@ -412,59 +988,262 @@ impl VoicePipeline {
                     break;
                 }
 
-                // 获取音频数据
-                let samples = {
-                    let recorder_guard = match recorder.read() {
-                        Ok(r) => r,
-                        Err(_) => break,
+                // 取出和发送都在读锁内完成，stop_streaming 持写锁发送的 commit
+                // 一定排在已取出的音频之后
+                let session = session_holder.read().await;
+
+                // 切换连接期间没有活跃连接，音频留在录音缓冲区，新连接建立后一并发送
+                if let Some(session) = session.as_ref() {
+                    let samples = {
+                        let recorder_guard = match recorder.read() {
+                            Ok(r) => r,
+                            Err(_) => break,
+                        };
+                        recorder_guard.drain_buffer()
                     };
-                    recorder_guard.drain_buffer()
-                };
 
-                // 发送到当前活跃的 ASR 连接
-                if !samples.is_empty() {
-                    let pcm_data = encode_to_pcm(&samples);
-                    if let Some(tx) = control_tx_holder.read().await.as_ref() {
+                    if !samples.is_empty() {
                         // 忽略发送错误（ASR 可能在重连中）
-                        let _ = tx.send(StreamingControl::Audio(pcm_data)).await;
+                        let _ = session.send_audio(encode_to_pcm(&samples)).await;
+
+                        if overlap_samples > 0 {
+                            if let Ok(mut recent) = recent_audio_for_audio.lock() {
+                                // 暂停期间的音频不能在重连时补发出去
+                                if session.is_paused() {
+                                    recent.clear();
+                                } else {
+                                    recent.extend(samples.iter().copied());
+                                    let excess = recent.len().saturating_sub(overlap_samples);
+                                    recent.drain(..excess);
+                                }
+                            }
+                        }
                     }
                 }
+                drop(session);
 
                 tokio::time::sleep(chunk_interval).await;
             }
         });
 
+        // 当前 ASR 连接的建立时间，用于服务商连接时长上限
+        let connection_started = Arc::new(Mutex::new(Instant::now()));
+
+        // === 时长限制任务 ===
+        // 到达最长听写时长时自动停止；接近服务商连接上限时提交当前连接，由会话管理任务重连
+        {
+            let recorder = self.recorder.clone();
+            let should_stop = self.should_stop.clone();
+            let session_holder = self.streaming_session.clone();
+            let state = self.state.clone();
+            let hub = self.events.clone();
+            let streaming_asr_config = self.streaming_asr_config.clone();
+            let connection_started = connection_started.clone();
+            let session_id = self.session_id();
+            let max_duration = (config.asr.max_streaming_secs > 0)
+                .then(|| Duration::from_secs(config.asr.max_streaming_secs));
+            let recording_started = Instant::now();
+
+            tokio::spawn(async move {
+                let mut duration_warned = false;
+                // 已提醒过的连接（按建立时间区分）
+                let mut warned_connection: Option<Instant> = None;
+
+                loop {
+                    tokio::time::sleep(LIMIT_CHECK_INTERVAL).await;
+                    if should_stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    if let Some(max_duration) = max_duration {
+                        let remaining = max_duration.saturating_sub(recording_started.elapsed());
+                        if !duration_warned && remaining <= LIMIT_WARNING_LEAD {
+                            duration_warned = true;
+                            hub.publish(
+                                session_id.clone(),
+                                EventPayload::SessionLimitWarning {
+                                    limit: SessionLimit::MaxDuration,
+                                    remaining_secs: remaining.as_secs(),
+                                },
+                            );
+                        }
+                        if remaining.is_zero() {
+                            tracing::info!("Max streaming duration reached, stopping");
+                            should_stop.store(true, Ordering::SeqCst);
+                            if let Err(e) =
+                                Self::commit_streaming_audio(&recorder, &session_holder).await
+                            {
+                                tracing::error!("Failed to stop streaming: {}", e);
+                            }
+                            hub.set_state(&state, PipelineState::Processing);
+                            hub.publish(session_id.clone(), EventPayload::RecordingStopped);
+                            break;
+                        }
+                    }
+
+                    let limit = streaming_asr_config
+                        .read()
+                        .ok()
+                        .and_then(|guard| guard.as_ref().and_then(streaming_session_limit));
+                    let Some(limit) = limit else {
+                        continue;
+                    };
+                    let Ok(started) = connection_started.lock().map(|s| *s) else {
+                        continue;
+                    };
+                    let remaining = limit.saturating_sub(started.elapsed());
+                    if warned_connection != Some(started) && remaining <= LIMIT_WARNING_LEAD {
+                        warned_connection = Some(started);
+                        hub.publish(
+                            session_id.clone(),
+                            EventPayload::SessionLimitWarning {
+                                limit: SessionLimit::ProviderSession,
+                                remaining_secs: remaining.as_secs(),
+                            },
+                        );
+                    }
+                    if remaining <= ROLLOVER_MARGIN {
+                        // 取走控制通道再提交：切换期间的音频留在录音缓冲区，新连接建立后发送
+                        if let Some(session) = session_holder.write().await.take() {
+                            tracing::info!("Provider session limit approaching, rolling over");
+                            let _ = session.commit().await;
+                        }
+                        if let Ok(mut started) = connection_started.lock() {
+                            *started = Instant::now();
+                        }
+                    }
+                }
+            });
+        }
+
         // === ASR 会话管理任务 ===
         // Final 后自动重连，直到 should_stop 为 true
         let should_stop_for_asr = self.should_stop.clone();
-        let control_tx_holder_for_asr = self.streaming_control_tx.clone();
+        let session_holder_for_asr = self.streaming_session.clone();
+        let streaming_paused = self.streaming_paused.clone();
         let state = self.state.clone();
+        let hub = self.events.clone();
         let streaming_mode = self.streaming_mode.clone();
         let config_for_asr = config.clone();
+        let transcript_context = self.transcript_context.clone();
+        let session_id = self.session_id();
+        let session_open = self.session_open.clone();
+        let streaming_asr_config = self.streaming_asr_config.clone();
+        let recorder_for_asr = self.recorder.clone();
+        let progress_session_id = session_id.clone();
+        let transcript_session_id = session_id.clone();
+        let services = self.services.clone();
+
+        // 会话结束时发布一次结束事件，正常结束（Ok）时再发布修改差异和会话统计
+        let finish_session = {
+            let recorder = recorder_for_asr.clone();
+            let hub = hub.clone();
+            let services = services.clone();
+            let mode_override = self.mode_override.clone();
+            let record_history = config.history.enabled;
+            move |outcome: Result<Dictation, EventPayload>| {
+                if !session_open.swap(false, Ordering::SeqCst) {
+                    return;
+                }
+                if let Ok(mut mode) = mode_override.write() {
+                    *mode = None;
+                }
+                let dictation = match outcome {
+                    Ok(dictation) => dictation,
+                    Err(payload) => {
+                        hub.publish(session_id.clone(), payload);
+                        return;
+                    }
+                };
+                hub.publish(
+                    session_id.clone(),
+                    EventPayload::SessionComplete {
+                        text: dictation.refined.clone(),
+                    },
+                );
+                publish_text_diff(&hub, session_id.clone(), &dictation);
+                services.output(&dictation.refined);
+                publish_session_stats(
+                    &hub,
+                    session_id.clone(),
+                    &dictation.refined,
+                    recorded_duration(&recorder),
+                    record_history,
+                );
+                remember_dictation(dictation);
+            }
+        };
 
         tokio::spawn(async move {
             let mut current_event_rx = event_rx;
+            // 重连补发了音频时，去掉新一段开头与上一段结尾重复的文字
+            let mut transcript =
+                SessionTranscript::new().with_dedup(config_for_asr.asr.reconnect_overlap_ms > 0);
+            // 开启逐句修正时，每句 Final 立即在后台处理
+            let mut refiner = (config_for_asr.llm.refine_per_sentence
+                && config_for_asr.llm.mode.works_per_sentence())
+                .then(|| {
+                    SentenceRefiner::new(config_for_asr.clone()).with_llm(services.llm())
+                });
+            // 最近发布的实时词数
+            let mut last_words = 0;
+            // 切换连接期间停止时，由本任务提交的会话（保持控制通道到连接结束）
+            let mut _stopping_session = None;
 
             loop {
                 // 处理当前 ASR 连接的事件
                 // 注意：不在这里检查 should_stop，必须等到 Final/Error 才能退出
-                while let Some(event) = current_event_rx.recv().await {
+                while let Some(mut event) = current_event_rx.recv().await {
+                    if let StreamingAsrEvent::Final { text } = &mut event {
+                        // 转发给宿主的也是去重后的文本
+                        *text = transcript.push_at(text, recorded_duration(&recorder_for_asr));
+                        transcript_context.push(text);
+                        if let Some(refiner) = refiner.as_mut() {
+                            refiner.push(text);
+                        }
+                    }
+                    // 实时词数和语速：已完成的句子加上当前句的识别结果
+                    let words = match &event {
+                        StreamingAsrEvent::Partial { text, stash } => Some(
+                            count_words(transcript.text()) + count_words(text) + count_words(stash),
+                        ),
+                        StreamingAsrEvent::Final { .. } => Some(count_words(transcript.text())),
+                        StreamingAsrEvent::Error(_) => None,
+                    };
+                    if let Some(words) = words.filter(|&words| words != last_words) {
+                        last_words = words;
+                        let wpm = words_per_minute(words, recorded_duration(&recorder_for_asr));
+                        hub.publish(
+                            progress_session_id.clone(),
+                            EventPayload::DictationProgress { words, wpm },
+                        );
+                    }
+
+                    let error_message = match &event {
+                        StreamingAsrEvent::Error(message) => Some(message.clone()),
+                        _ => None,
+                    };
                     let is_final = matches!(event, StreamingAsrEvent::Final { .. });
-                    let is_error = matches!(event, StreamingAsrEvent::Error(_));
 
-                    // 转发事件
-                    if forward_tx.send(event).await.is_err() {
-                        tracing::info!("ASR task stopping: forward channel closed");
-                        return;
-                    }
+                    // 转发事件；宿主可能只通过 events() 接收，丢弃了接收器时会话照常进行
+                    hub.asr_event(&event);
+                    let _ = forward_tx.send(event).await;
 
                     // Final 事件：检查是否应该重连
                     if is_final {
                         if should_stop_for_asr.load(Ordering::SeqCst) {
                             // 热键已松开，不再重连，正常退出
                             tracing::info!("Final received, should_stop=true, stopping");
-                            state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+                            let dictation =
+                                complete_transcript(&config_for_asr, &services, &transcript, refiner.take()).await;
+                            save_timed_transcript(
+                                transcript_session_id.as_deref(),
+                                &transcript,
+                                config_for_asr.history.enabled,
+                            );
+                            hub.set_state(&state, PipelineState::Idle);
                             streaming_mode.store(false, Ordering::SeqCst);
+                            finish_session(Ok(dictation));
                             return;
                         } else {
                             // 热键还按着，VAD Final，自动重连
@@ -474,10 +1253,19 @@ impl VoicePipeline {
                     }
 
                     // 错误：停止
-                    if is_error {
+                    if let Some(message) = error_message {
                         tracing::error!("ASR error, stopping");
-                        state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+                        if let Some(refiner) = refiner.take() {
+                            refiner.abort();
+                        }
+                        hub.set_state(&state, PipelineState::Idle);
                         streaming_mode.store(false, Ordering::SeqCst);
+                        finish_session(Err(EventPayload::Error {
+                            stage: EventStage::Asr,
+                            code: ErrorCode::Asr,
+                            message,
+                            recoverable: true,
+                        }));
                         return;
                     }
                 }
@@ -486,37 +1274,88 @@ impl VoicePipeline {
                 // 检查是否应该停止
                 if should_stop_for_asr.load(Ordering::SeqCst) {
                     tracing::info!("ASR task stopping: channel closed, should_stop=true");
-                    state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+                    let dictation =
+                        complete_transcript(&config_for_asr, &services, &transcript, refiner.take()).await;
+                    save_timed_transcript(
+                        transcript_session_id.as_deref(),
+                        &transcript,
+                        config_for_asr.history.enabled,
+                    );
+                    hub.set_state(&state, PipelineState::Idle);
                     streaming_mode.store(false, Ordering::SeqCst);
+                    finish_session(Ok(dictation));
                     return;
                 }
 
-                // 重新创建 ASR 连接
+                // 重新创建 ASR 连接（使用最新的 ASR 配置）
                 tracing::info!("Creating new ASR connection...");
-                let new_service = match create_streaming_asr_service(&config_for_asr.asr) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        tracing::error!("Failed to create ASR service: {}", e);
-                        state.store(PipelineState::Idle as u8, Ordering::SeqCst);
-                        streaming_mode.store(false, Ordering::SeqCst);
-                        return;
-                    }
-                };
-
-                let (new_control_tx, new_event_rx) = match new_service.start_streaming(sample_rate).await {
-                    Ok(r) => r,
+                let asr_config = streaming_asr_config
+                    .read()
+                    .ok()
+                    .and_then(|guard| guard.clone())
+                    .unwrap_or_else(|| config_for_asr.asr.clone());
+                let context = transcript_context.prompt(asr_config.context_chars);
+                let reconnected = match services.streaming_asr_service(&asr_config, context) {
+                    Ok(service) => service.start_streaming(sample_rate).await,
+                    Err(e) => Err(e),
+                }
+                .map_err(PipelineError::from)
+                .and_then(|mut session| match session.take_events() {
+                    Some(event_rx) => Ok((session, event_rx)),
+                    None => Err(PipelineError::Other(
+                        "Streaming session has no event receiver".to_string(),
+                    )),
+                });
+                // 重连失败与识别出错一样结束会话，发布错误事件
+                let (mut new_session, new_event_rx) = match reconnected {
+                    Ok(reconnected) => reconnected,
                     Err(e) => {
-                        tracing::error!("Failed to start ASR streaming: {}", e);
-                        state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+                        tracing::error!("Failed to reconnect ASR: {}", e);
+                        if let Some(refiner) = refiner.take() {
+                            refiner.abort();
+                        }
+                        hub.set_state(&state, PipelineState::Idle);
                         streaming_mode.store(false, Ordering::SeqCst);
+                        finish_session(Err(EventPayload::from_pipeline_error(&e)));
                         return;
                     }
                 };
+                // 暂停中重连时新会话同样暂停
+                if streaming_paused.load(Ordering::SeqCst) {
+                    new_session.pause();
+                }
 
-                // 更新共享的 control_tx（音频发送任务会自动使用新的）
+                // 先补发最近的音频，再更新共享的会话（音频发送任务会自动使用新的）
+                // 持有写锁期间音频发送任务不会发送，补发的音频一定在新音频之前
                 {
-                    let mut tx_guard = control_tx_holder_for_asr.write().await;
-                    *tx_guard = Some(new_control_tx);
+                    let mut tx_guard = session_holder_for_asr.write().await;
+                    let replay: Vec<f32> = recent_audio
+                        .lock()
+                        .map(|recent| recent.iter().copied().collect())
+                        .unwrap_or_default();
+                    if !replay.is_empty() {
+                        tracing::debug!("Replaying {} samples into new ASR connection", replay.len());
+                        let _ = new_session.send_audio(encode_to_pcm(&replay)).await;
+                    }
+                    if should_stop_for_asr.load(Ordering::SeqCst) {
+                        // 建立连接期间已经停止：停止时没有可提交的连接，在这里提交剩余音频，
+                        // 不放入共享的会话，避免重复提交
+                        tracing::info!("Stopped while reconnecting, committing new connection");
+                        let samples = recorder_for_asr
+                            .read()
+                            .map(|recorder| recorder.drain_buffer())
+                            .unwrap_or_default();
+                        if !samples.is_empty() {
+                            let _ = new_session.send_audio(encode_to_pcm(&samples)).await;
+                        }
+                        let _ = new_session.commit().await;
+                        _stopping_session = Some(new_session);
+                    } else {
+                        *tx_guard = Some(new_session);
+                    }
+                }
+                if let Ok(mut started) = connection_started.lock() {
+                    *started = Instant::now();
                 }
 
                 current_event_rx = new_event_rx;
@@ -536,39 +1375,16 @@ impl VoicePipeline {
             return Ok(());
         }
 
-        tracing::info!("stop_streaming: setting should_stop=true");
-
-        // 设置停止标志，通知后台任务停止
-        self.should_stop.store(true, Ordering::SeqCst);
-
-        // 停止录音
-        {
-            let mut recorder = self.recorder.write().map_err(|e| {
-                PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
-            })?;
-            let _ = recorder.stop();
+        // 设置停止标志，通知后台任务停止；已在停止中（如到达最长听写时长）时不再重复提交
+        if self.should_stop.swap(true, Ordering::SeqCst) {
+            return Ok(());
         }
+        tracing::info!("stop_streaming: should_stop=true");
 
-        // 发送最后一批音频和 commit
-        if let Some(control_tx) = self.streaming_control_tx.read().await.as_ref() {
-            // 获取剩余音频
-            let samples = {
-                let recorder = self.recorder.read().map_err(|e| {
-                    PipelineError::Other(format!("Failed to acquire recorder lock: {}", e))
-                })?;
-                recorder.drain_buffer()
-            };
-
-            if !samples.is_empty() {
-                let pcm_data = encode_to_pcm(&samples);
-                let _ = control_tx.send(StreamingControl::Audio(pcm_data)).await;
-            }
-
-            // 提交
-            let _ = control_tx.send(StreamingControl::Commit).await;
-        }
+        Self::commit_streaming_audio(&self.recorder, &self.streaming_session).await?;
 
-        self.state.store(PipelineState::Processing as u8, Ordering::SeqCst);
+        self.set_state(PipelineState::Processing);
+        self.publish(EventPayload::RecordingStopped);
 
         Ok(())
     }
@@ -593,8 +1409,8 @@ impl VoicePipeline {
         }
 
         // 发送取消命令
-        if let Some(control_tx) = self.streaming_control_tx.read().await.as_ref() {
-            let _ = control_tx.send(StreamingControl::Cancel).await;
+        if let Some(session) = self.streaming_session.read().await.as_ref() {
+            session.cancel().await;
         }
 
         // 清理所有资源
@@ -602,13 +1418,195 @@ impl VoicePipeline {
 
         self.streaming_mode.store(false, Ordering::SeqCst);
         self.cancelled.store(true, Ordering::SeqCst);
-        self.state.store(PipelineState::Idle as u8, Ordering::SeqCst);
+        self.set_state(PipelineState::Idle);
+        self.finish_session(EventPayload::RecordingCancelled);
 
         Ok(())
     }
 
+    /// 配置变更后切换流式会话的 ASR 服务商，不中断会话
+    ///
+    /// 提交当前音频让旧服务商完成正在说的这句，收到 Final 后后台任务用新配置重连。
+    /// 不在流式会话中或 ASR 配置未变化时不做任何事。
+    pub async fn reload_streaming_asr(&self) {
+        if !self.streaming_mode.load(Ordering::SeqCst) || self.should_stop.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let new_config = self.effective_config().await.asr;
+        {
+            let Ok(mut guard) = self.streaming_asr_config.write() else {
+                return;
+            };
+            if guard.as_ref() == Some(&new_config) {
+                return;
+            }
+            tracing::info!(
+                "ASR config changed mid-session, switching to {} after current utterance",
+                new_config.provider
+            );
+            *guard = Some(new_config);
+        }
+
+        if let Some(session) = self.streaming_session.read().await.as_ref() {
+            let _ = session.commit().await;
+        }
+    }
+
     /// 是否在流式模式
     pub fn is_streaming(&self) -> bool {
         self.streaming_mode.load(Ordering::SeqCst)
     }
+
+    /// 暂停流式识别（按住静音）
+    ///
+    /// 录音和连接保持，期间向服务商发送静音，恢复后继续识别。
+    /// 不在流式会话中或已在停止时返回 false
+    pub async fn pause_streaming(&self) -> bool {
+        if !self.streaming_mode.load(Ordering::SeqCst) || self.should_stop.load(Ordering::SeqCst) {
+            return false;
+        }
+        if self.streaming_paused.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        // 写锁等待音频发送任务正在发送的一批完成，之后的音频都是静音
+        if let Some(session) = self.streaming_session.write().await.as_ref() {
+            session.pause();
+        }
+        tracing::info!("Streaming paused");
+        true
+    }
+
+    /// 恢复暂停的流式识别，未暂停时返回 false
+    pub async fn resume_streaming(&self) -> bool {
+        if !self.streaming_paused.swap(false, Ordering::SeqCst) {
+            return false;
+        }
+        if let Some(session) = self.streaming_session.read().await.as_ref() {
+            session.resume();
+        }
+        tracing::info!("Streaming resumed");
+        true
+    }
+
+    /// 流式识别是否暂停
+    pub fn is_streaming_paused(&self) -> bool {
+        self.streaming_paused.load(Ordering::SeqCst)
+    }
+
+    /// 当前流式连接的状态，切换连接期间或不在流式会话中时为 None
+    pub async fn streaming_session_state(&self) -> Option<StreamingSessionState> {
+        self.streaming_session
+            .read()
+            .await
+            .as_ref()
+            .map(StreamingSession::state)
+    }
+}
+
+/// 录音器记录的本次录音时长
+fn recorded_duration(recorder: &RwLock<AudioRecorder>) -> Duration {
+    recorder
+        .read()
+        .map(|recorder| recorder.recorded_duration())
+        .unwrap_or_default()
+}
+
+/// 发布会话统计，并按配置写入历史；没有识别出文字的会话不统计
+fn publish_session_stats(
+    hub: &EventHub,
+    session_id: Option<String>,
+    text: &str,
+    duration: Duration,
+    record_history: bool,
+) {
+    let words = count_words(text);
+    if words == 0 {
+        return;
+    }
+    let wpm = words_per_minute(words, duration);
+    let duration_ms = duration.as_millis() as u64;
+
+    // 先写入历史，收到统计事件的宿主读取历史（如每日目标进度）时已包含本次会话
+    if record_history && !crate::privacy::is_incognito() {
+        let record = SessionRecord {
+            session_id: session_id.clone().unwrap_or_default(),
+            finished_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            words,
+            duration_ms,
+            wpm,
+        };
+        if let Err(e) = history::record_session(&record) {
+            tracing::warn!("Failed to record dictation history: {}", e);
+        }
+    }
+
+    hub.publish(
+        session_id,
+        EventPayload::SessionStats {
+            words,
+            duration_ms,
+            wpm,
+        },
+    );
+}
+
+/// 发布原始识别文本到输出文本的修改，没有输出文本时不发布
+fn publish_text_diff(hub: &EventHub, session_id: Option<String>, dictation: &Dictation) {
+    if dictation.refined.is_empty() {
+        return;
+    }
+    let spans = diff_text(&dictation.raw, &dictation.refined);
+    let changed = dictation.raw != dictation.refined;
+    hub.publish(session_id, EventPayload::TextDiff { spans, changed });
+}
+
+/// 按配置保存会话的分段文本，会话结束事件发出前写入，宿主收到事件后即可读取
+fn save_timed_transcript(
+    session_id: Option<&str>,
+    transcript: &SessionTranscript,
+    record_history: bool,
+) {
+    let Some(session_id) = session_id else {
+        return;
+    };
+    if !record_history || crate::privacy::is_incognito() || transcript.segments().is_empty() {
+        return;
+    }
+    let record = TimedTranscript {
+        session_id: session_id.to_string(),
+        finished_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        segments: transcript.segments().to_vec(),
+    };
+    if let Err(e) = history::save_session_transcript(&record) {
+        tracing::warn!("Failed to save session transcript: {}", e);
+    }
+}
+
+/// 对拼接后的会话文本做后处理
+///
+/// 开启逐句修正时各句已在后台处理，只需按顺序等待结果
+async fn complete_transcript(
+    config: &AppConfig,
+    services: &PipelineServices,
+    transcript: &SessionTranscript,
+    refiner: Option<SentenceRefiner>,
+) -> Dictation {
+    let refined = if let Some(refiner) = refiner {
+        refiner.finish().await
+    } else if transcript.is_empty() {
+        String::new()
+    } else {
+        services.text_pipeline(config).process(transcript.text()).await
+    };
+    Dictation {
+        raw: transcript.text().to_string(),
+        refined,
+    }
 }
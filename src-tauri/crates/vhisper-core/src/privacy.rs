@@ -0,0 +1,26 @@
+//! 隐私模式（无痕听写）
+//!
+//! 开启后不写入听写历史和识别结果缓存，不记录调试抓取和流式追踪，
+//! 也不把识别结果作为下一次识别的上下文；发布的事件带 `incognito` 标记。
+//! 状态只在内存中，重启后恢复为关闭
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INCOGNITO: AtomicBool = AtomicBool::new(false);
+
+/// 开启或关闭隐私模式，返回是否切换了状态
+pub fn set_incognito(enabled: bool) -> bool {
+    let changed = INCOGNITO.swap(enabled, Ordering::SeqCst) != enabled;
+    if changed {
+        tracing::info!(
+            "Incognito dictation {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+    changed
+}
+
+/// 隐私模式是否开启
+pub fn is_incognito() -> bool {
+    INCOGNITO.load(Ordering::SeqCst)
+}
@@ -0,0 +1,264 @@
+//! 匿名使用统计（默认关闭）
+//!
+//! 用户在设置中开启后，只累计各 ASR 服务商的请求数、失败数和延迟分段，
+//! 上报时附带应用版本和操作系统，不包含文本、音频、配置或任何设备标识。
+//! 累计数据保存在本地 `telemetry.json`（定期和退出时批量写入），上报成功后清零；
+//! 上报内容可先在设置界面预览
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::net::proxied_client;
+
+/// 上报数据格式版本
+pub const TELEMETRY_SCHEMA_VERSION: u32 = 1;
+/// 上报请求超时时间
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+/// 延迟分段上限（毫秒）和名称，超过最后一段的计入 ">5s"
+const LATENCY_BUCKETS: &[(u64, &str)] = &[
+    (500, "<0.5s"),
+    (1000, "0.5-1s"),
+    (2000, "1-2s"),
+    (5000, "2-5s"),
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// 内存中的累计数据，首次使用时从文件加载
+static COUNTERS: Mutex<Option<TelemetryCounters>> = Mutex::new(None);
+/// 内存中的累计数据是否有尚未写入文件的修改
+static DIRTY: AtomicBool = AtomicBool::new(false);
+/// 写入文件时持有，保证较新的数据不会被较旧的覆盖
+static FLUSH_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("API error: {0}")]
+    Api(String),
+}
+
+/// 单个服务商的累计数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProviderCounts {
+    pub requests: u64,
+    pub failures: u64,
+    /// 成功请求按延迟分段的次数
+    pub latency_buckets: BTreeMap<String, u64>,
+}
+
+/// 累计数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TelemetryCounters {
+    pub providers: BTreeMap<String, ProviderCounts>,
+    /// 上次上报（或到期时没有数据可报）的时间，Unix 毫秒；不包含在上报内容中
+    #[serde(default)]
+    pub last_report_ms: Option<u64>,
+}
+
+/// 上报内容（预览和实际发送的是同一份）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TelemetryReport {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub providers: BTreeMap<String, ProviderCounts>,
+}
+
+/// 延迟所在的分段
+pub fn latency_bucket(latency: Duration) -> &'static str {
+    let ms = latency.as_millis() as u64;
+    LATENCY_BUCKETS
+        .iter()
+        .find(|(limit, _)| ms < *limit)
+        .map(|(_, name)| *name)
+        .unwrap_or(">5s")
+}
+
+impl TelemetryCounters {
+    /// 记录一次识别请求
+    pub fn record(&mut self, provider: &str, success: bool, latency: Duration) {
+        let counts = self.providers.entry(provider.to_string()).or_default();
+        counts.requests += 1;
+        if success {
+            *counts
+                .latency_buckets
+                .entry(latency_bucket(latency).to_string())
+                .or_default() += 1;
+        } else {
+            counts.failures += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    /// 距离下次上报的时间，从未上报过时立即上报
+    pub fn next_report_in(&self, interval: Duration, now_ms: u64) -> Duration {
+        let Some(last) = self.last_report_ms else {
+            return Duration::ZERO;
+        };
+        let elapsed = Duration::from_millis(now_ms.saturating_sub(last));
+        interval.saturating_sub(elapsed)
+    }
+
+    /// 生成上报内容
+    pub fn report(&self, app_version: &str) -> TelemetryReport {
+        TelemetryReport {
+            schema_version: TELEMETRY_SCHEMA_VERSION,
+            app_version: app_version.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            providers: self.providers.clone(),
+        }
+    }
+}
+
+/// 累计数据文件路径
+fn counters_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("com.vhisper.app")
+            .join("telemetry.json"),
+    )
+}
+
+fn load_counters() -> TelemetryCounters {
+    counters_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_counters(counters: &TelemetryCounters) {
+    let Some(path) = counters_path() else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, serde_json::to_string(counters).unwrap_or_default()));
+    if let Err(e) = result {
+        tracing::warn!("Failed to save telemetry counters: {}", e);
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 在累计数据上执行操作，需要时从文件加载
+fn with_counters<T>(f: impl FnOnce(&mut TelemetryCounters) -> T) -> T {
+    let mut counters = COUNTERS.lock().unwrap_or_else(|e| e.into_inner());
+    f(counters.get_or_insert_with(load_counters))
+}
+
+/// 开启或关闭统计，关闭时删除已累计的数据
+pub fn set_telemetry(enabled: bool) {
+    let was_enabled = ENABLED.swap(enabled, Ordering::SeqCst);
+    if was_enabled && !enabled {
+        clear_counters();
+    }
+}
+
+/// 统计是否开启
+pub fn is_telemetry_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 记录一次识别请求（未开启时忽略）
+pub fn record_asr(provider: &str, success: bool, latency: Duration) {
+    if !is_telemetry_enabled() {
+        return;
+    }
+    with_counters(|counters| counters.record(provider, success, latency));
+    DIRTY.store(true, Ordering::SeqCst);
+}
+
+/// 把尚未保存的累计数据写入文件，在锁外写入，不阻塞正在记录的请求
+pub fn flush_counters() {
+    let _flush = FLUSH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if !DIRTY.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let counters = with_counters(|counters| counters.clone());
+    save_counters(&counters);
+}
+
+/// 距离下次上报的时间（按上次上报时间计算，重启后不会重新等待一整个周期）
+pub fn next_report_in(interval: Duration) -> Duration {
+    with_counters(|counters| counters.next_report_in(interval, now_ms()))
+}
+
+/// 删除已累计的数据
+pub fn clear_counters() {
+    let _flush = FLUSH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    with_counters(|counters| *counters = TelemetryCounters::default());
+    DIRTY.store(false, Ordering::SeqCst);
+    if let Some(path) = counters_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 预览下次上报的内容
+pub fn preview_report(app_version: &str) -> TelemetryReport {
+    with_counters(|counters| counters.report(app_version))
+}
+
+/// 上报累计数据，成功后清零；没有数据时不发送，返回是否发送
+///
+/// 发送成功或没有数据时记录上报时间
+pub async fn send_report(endpoint: &str, app_version: &str) -> Result<bool, TelemetryError> {
+    let counters = with_counters(|counters| counters.clone());
+    if counters.is_empty() {
+        with_counters(|current| current.last_report_ms = Some(now_ms()));
+        DIRTY.store(true, Ordering::SeqCst);
+        flush_counters();
+        return Ok(false);
+    }
+
+    let response = proxied_client()
+        .post(endpoint)
+        .json(&counters.report(app_version))
+        .timeout(SEND_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| TelemetryError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(TelemetryError::Api(format!("HTTP {}", response.status())));
+    }
+
+    // 只扣除已发送的部分，发送期间新增的记录保留到下次
+    with_counters(|current| {
+        for (provider, sent) in &counters.providers {
+            let Some(counts) = current.providers.get_mut(provider) else {
+                continue;
+            };
+            counts.requests = counts.requests.saturating_sub(sent.requests);
+            counts.failures = counts.failures.saturating_sub(sent.failures);
+            for (bucket, count) in &sent.latency_buckets {
+                if let Some(current) = counts.latency_buckets.get_mut(bucket) {
+                    *current = current.saturating_sub(*count);
+                }
+            }
+            counts.latency_buckets.retain(|_, count| *count > 0);
+        }
+        current.providers.retain(|_, counts| counts.requests > 0);
+        current.last_report_ms = Some(now_ms());
+    });
+    DIRTY.store(true, Ordering::SeqCst);
+    flush_counters();
+    tracing::info!("Telemetry report sent");
+    Ok(true)
+}
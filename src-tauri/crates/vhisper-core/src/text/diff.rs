@@ -0,0 +1,129 @@
+//! 原始识别文本与后处理后文本的差异
+//!
+//! 按词比较：连续的 ASCII 字母数字算一个词，连续空白算一个词，其余字符（汉字、标点等）逐字比较
+
+use serde::Serialize;
+
+/// 逐词比较的最大表格大小，超过时只比较首尾相同的部分
+const MAX_TABLE_CELLS: usize = 4_000_000;
+
+/// 差异片段
+///
+/// 按顺序拼接 Equal 和 Delete 得到原始文本，拼接 Equal 和 Insert 得到修改后的文本；
+/// 同一处的替换先给出 Delete 再给出 Insert
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "op", content = "text", rename_all = "snake_case")]
+pub enum DiffSpan {
+    Equal(String),
+    Insert(String),
+    Delete(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// 比较原始文本和修改后的文本，返回相邻同类合并后的差异片段
+pub fn diff_text(original: &str, revised: &str) -> Vec<DiffSpan> {
+    let old = tokenize(original);
+    let new = tokenize(revised);
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(Op, &str)> = Vec::new();
+    ops.extend(old[..prefix].iter().map(|t| (Op::Equal, *t)));
+    if (old_mid.len() + 1) * (new_mid.len() + 1) > MAX_TABLE_CELLS {
+        ops.extend(old_mid.iter().map(|t| (Op::Delete, *t)));
+        ops.extend(new_mid.iter().map(|t| (Op::Insert, *t)));
+    } else {
+        ops.extend(lcs_ops(old_mid, new_mid));
+    }
+    ops.extend(old[old.len() - suffix..].iter().map(|t| (Op::Equal, *t)));
+
+    let mut spans: Vec<(Op, String)> = Vec::new();
+    for (op, token) in ops {
+        match spans.last_mut() {
+            Some((last, text)) if *last == op => text.push_str(token),
+            _ => spans.push((op, token.to_string())),
+        }
+    }
+    spans
+        .into_iter()
+        .map(|(op, text)| match op {
+            Op::Equal => DiffSpan::Equal(text),
+            Op::Insert => DiffSpan::Insert(text),
+            Op::Delete => DiffSpan::Delete(text),
+        })
+        .collect()
+}
+
+/// 最长公共子序列对齐
+fn lcs_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let width = new.len() + 1;
+    // table[i * width + j] 为 old[i..] 与 new[j..] 的最长公共子序列长度
+    let mut table = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            table[i * width + j] = if old[i] == new[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push((Op::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            ops.push((Op::Delete, old[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|t| (Op::Delete, *t)));
+    ops.extend(new[j..].iter().map(|t| (Op::Insert, *t)));
+    ops
+}
+
+/// 切分为比较单位
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let same_kind: fn(char) -> bool = if c.is_ascii_alphanumeric() {
+            |c| c.is_ascii_alphanumeric()
+        } else if c.is_whitespace() {
+            char::is_whitespace
+        } else {
+            |_| false
+        };
+        let mut end = index + c.len_utf8();
+        while let Some(&(next_index, next)) = chars.peek() {
+            if !same_kind(next) {
+                break;
+            }
+            end = next_index + next.len_utf8();
+            chars.next();
+        }
+        tokens.push(&text[index..end]);
+    }
+    tokens
+}
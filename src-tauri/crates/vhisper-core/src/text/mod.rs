@@ -0,0 +1,13 @@
+//! 识别文本的后处理工具
+
+mod diff;
+mod pipeline;
+pub mod spellcheck;
+pub mod stages;
+mod stats;
+
+pub use diff::{diff_text, DiffSpan};
+pub use pipeline::{process_text, TextPipeline};
+pub use spellcheck::{available_dictionaries, Spellchecker};
+pub use stats::{count_words, words_per_minute};
+pub(crate) use stats::is_cjk;
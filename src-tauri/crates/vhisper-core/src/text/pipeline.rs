@@ -0,0 +1,170 @@
+//! 文本处理流程
+
+use super::spellcheck::Spellchecker;
+use super::stages::{apply_replacements, format_plain, normalize, punctuate, within_guardrails};
+use crate::config::settings::{PostProcessMode, TextPipelineConfig, TextStageKind};
+use crate::config::AppConfig;
+use crate::llm::{create_llm_service, LlmService};
+
+/// 识别后的文本处理流程
+///
+/// 按配置顺序执行启用的阶段，任何阶段失败都保留上一步的文本，不会中断输出
+pub struct TextPipeline {
+    config: TextPipelineConfig,
+    rich_text: bool,
+    /// LLM 阶段的处理模式
+    mode: PostProcessMode,
+    llm: Option<Box<dyn LlmService>>,
+    spellchecker: Option<Spellchecker>,
+}
+
+impl TextPipeline {
+    pub fn new(config: TextPipelineConfig) -> Self {
+        Self {
+            config,
+            rich_text: false,
+            mode: PostProcessMode::default(),
+            llm: None,
+            spellchecker: None,
+        }
+    }
+
+    /// 根据应用配置创建（LLM 未启用或配置缺失时跳过 LLM 阶段）
+    pub fn from_config(config: &AppConfig) -> Self {
+        let llm = match create_llm_service(&config.llm) {
+            Ok(llm) => llm,
+            Err(e) => {
+                tracing::warn!("LLM unavailable, skipping LLM stage: {}", e);
+                None
+            }
+        };
+
+        // 拼写检查只在没有 LLM 时执行，避免无谓地加载词典
+        let spellchecker = match llm {
+            Some(_) => None,
+            None => Spellchecker::from_config(config),
+        };
+
+        Self {
+            config: config.text_pipeline.clone(),
+            rich_text: config.output.rich_text,
+            mode: config.llm.mode,
+            llm,
+            spellchecker,
+        }
+    }
+
+    /// 配置的流程中是否会执行 LLM 阶段
+    pub fn uses_llm(config: &AppConfig) -> bool {
+        config.llm.enabled
+            && config
+                .text_pipeline
+                .stages
+                .iter()
+                .any(|stage| stage.enabled && matches!(stage.kind, TextStageKind::Llm))
+    }
+
+    /// 设置 LLM 服务
+    pub fn with_llm(mut self, llm: Box<dyn LlmService>) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    /// 设置拼写检查器
+    pub fn with_spellchecker(mut self, spellchecker: Spellchecker) -> Self {
+        self.spellchecker = Some(spellchecker);
+        self
+    }
+
+    /// 输出富文本时保留 Markdown，Formatter 阶段不做处理
+    pub fn with_rich_text(mut self, rich_text: bool) -> Self {
+        self.rich_text = rich_text;
+        self
+    }
+
+    /// 设置 LLM 阶段的处理模式
+    pub fn with_mode(mut self, mode: PostProcessMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// LLM 输出相对输入的最大长度比例
+    ///
+    /// 翻译、整理格式等模式的输出长度本就与原文不同，只检查输出是否为空
+    fn max_length_ratio(&self) -> f32 {
+        match self.mode {
+            PostProcessMode::Refine => self.config.max_length_ratio,
+            _ => f32::MAX,
+        }
+    }
+
+    /// 处理文本
+    pub async fn process(&self, text: &str) -> String {
+        let mut current = text.to_string();
+        // 进入 LLM 阶段前的文本，供 Guardrails 回退
+        let mut llm_input: Option<String> = None;
+
+        for stage in self.config.stages.iter().filter(|s| s.enabled) {
+            if current.is_empty() {
+                break;
+            }
+
+            current = match stage.kind {
+                TextStageKind::Replacements => apply_replacements(&current, &self.config.replacements),
+                TextStageKind::Normalize => normalize(&current),
+                TextStageKind::Punctuation => punctuate(&current),
+                // LLM 校对会处理拼写，启用 LLM 时跳过
+                TextStageKind::Spellcheck => match (&self.spellchecker, &self.llm) {
+                    (Some(spellchecker), None) => spellchecker.correct(&current),
+                    _ => current,
+                },
+                TextStageKind::Llm => match &self.llm {
+                    Some(llm) => match llm.process(&current, self.mode).await {
+                        Ok(refined) => {
+                            tracing::info!("LLM {:?}: {} -> {}", self.mode, current, refined);
+                            llm_input = Some(current);
+                            refined
+                        }
+                        Err(e) => {
+                            tracing::warn!("LLM {:?} failed, using original: {}", self.mode, e);
+                            current
+                        }
+                    },
+                    None => current,
+                },
+                TextStageKind::Formatter => {
+                    if self.rich_text {
+                        current
+                    } else {
+                        format_plain(&current)
+                    }
+                }
+                TextStageKind::Guardrails => match llm_input.take() {
+                    Some(input) if !within_guardrails(&input, &current, self.max_length_ratio()) => {
+                        tracing::warn!("LLM output rejected by guardrails, reverting: {}", current);
+                        input
+                    }
+                    _ => current,
+                },
+            };
+        }
+
+        current
+    }
+}
+
+/// 对任意文本执行后处理流程
+///
+/// - profile: 应用标识（如 bundle id），匹配到应用配置时使用其语言设置
+pub async fn process_text(config: &AppConfig, text: &str, profile: Option<&str>) -> String {
+    let language = profile.and_then(|app_id| config.language_for_app(app_id));
+
+    match language {
+        Some(language) => {
+            let mut config = config.clone();
+            config.apply_language(&language);
+            TextPipeline::from_config(&config).process(text).await
+        }
+        None => TextPipeline::from_config(config).process(text).await,
+    }
+}
@@ -0,0 +1,291 @@
+//! 基于 Hunspell 词典的拼写检查
+//!
+//! 只修正有把握的拼错：词典首个建议与原词只差一两处编辑时才替换，
+//! 中文、缩写、驼峰词和用户词典中的词保持原样。
+//! 词典文件（`<名称>.aff` 和 `<名称>.dic`，UTF-8 编码）放在配置目录的 `dictionaries`
+//! 下，Linux 还会查找系统安装的 Hunspell 词典。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use spellbook::Dictionary;
+
+use super::stats::is_cjk;
+use crate::config::settings::AUTO_LANGUAGE;
+use crate::config::AppConfig;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SpellcheckError {
+    #[error("Config directory not found")]
+    DirNotFound,
+    #[error("Dictionary not found for language: {0}")]
+    NotFound(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid dictionary: {0}")]
+    Parse(String),
+}
+
+/// 系统 Hunspell 词典目录
+#[cfg(target_os = "linux")]
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &["/usr/share/hunspell", "/usr/share/myspell/dicts"];
+#[cfg(not(target_os = "linux"))]
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &[];
+
+/// 已加载的词典，按文件路径缓存（解析词典较慢，每次听写都会创建文本处理流程）
+fn loaded_dictionaries() -> &'static Mutex<HashMap<PathBuf, Arc<Dictionary>>> {
+    static LOADED: OnceLock<Mutex<HashMap<PathBuf, Arc<Dictionary>>>> = OnceLock::new();
+    LOADED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 用户词典目录
+pub fn dictionaries_dir() -> Result<PathBuf, SpellcheckError> {
+    let config_dir = dirs::config_dir().ok_or(SpellcheckError::DirNotFound)?;
+    Ok(config_dir.join("com.vhisper.app").join("dictionaries"))
+}
+
+/// 查找词典的目录，用户目录优先
+fn search_dirs() -> Vec<PathBuf> {
+    dictionaries_dir()
+        .into_iter()
+        .chain(SYSTEM_DICTIONARY_DIRS.iter().map(PathBuf::from))
+        .collect()
+}
+
+/// 目录中同时有 .aff 和 .dic 文件的词典名称
+fn dictionaries_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "dic" {
+                return None;
+            }
+            path.with_extension("aff")
+                .is_file()
+                .then(|| path.file_stem()?.to_str().map(str::to_string))?
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// 可用的词典名称（如 "en_US"、"de_DE"），供设置界面选择
+pub fn available_dictionaries() -> Vec<String> {
+    let mut names: Vec<String> = search_dirs()
+        .iter()
+        .flat_map(|dir| dictionaries_in(dir))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// 按语言选择词典名称
+///
+/// 先找完全匹配的名称（"en-US" 视同 "en_US"），再找同一语言的词典（"en" 匹配 "en_GB"、"en_US"）
+pub fn resolve_dictionary<'a>(language: &str, available: &'a [String]) -> Option<&'a str> {
+    let language = language.replace('-', "_");
+    let primary = language.split('_').next().unwrap_or_default();
+
+    available
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(&language))
+        .or_else(|| {
+            available.iter().find(|name| {
+                name.split(['_', '-'])
+                    .next()
+                    .is_some_and(|p| p.eq_ignore_ascii_case(primary))
+            })
+        })
+        .map(String::as_str)
+}
+
+/// 拼写检查器
+#[derive(Clone)]
+pub struct Spellchecker {
+    dictionary: Arc<Dictionary>,
+    /// 用户词典，小写保存
+    user_words: HashSet<String>,
+}
+
+impl Spellchecker {
+    /// 从 .aff 和 .dic 文件内容创建
+    pub fn new(aff: &str, dic: &str) -> Result<Self, SpellcheckError> {
+        let dictionary =
+            Dictionary::new(aff, dic).map_err(|e| SpellcheckError::Parse(e.to_string()))?;
+        Ok(Self {
+            dictionary: Arc::new(dictionary),
+            user_words: HashSet::new(),
+        })
+    }
+
+    /// 加载语言对应的词典
+    pub fn load(language: &str) -> Result<Self, SpellcheckError> {
+        let path = search_dirs()
+            .into_iter()
+            .find_map(|dir| {
+                let available = dictionaries_in(&dir);
+                resolve_dictionary(language, &available).map(|name| dir.join(name))
+            })
+            .ok_or_else(|| SpellcheckError::NotFound(language.to_string()))?;
+
+        if let Some(dictionary) = loaded_dictionaries().lock().unwrap().get(&path) {
+            return Ok(Self {
+                dictionary: dictionary.clone(),
+                user_words: HashSet::new(),
+            });
+        }
+
+        let aff = std::fs::read_to_string(path.with_extension("aff"))?;
+        let dic = std::fs::read_to_string(path.with_extension("dic"))?;
+        let checker = Self::new(&aff, &dic)?;
+        tracing::info!("Loaded spellcheck dictionary: {}", path.display());
+        loaded_dictionaries()
+            .lock()
+            .unwrap()
+            .insert(path, checker.dictionary.clone());
+        Ok(checker)
+    }
+
+    /// 根据应用配置创建，未启用或找不到词典时返回 None
+    ///
+    /// 未指定词典语言时使用识别语言（包括应用配置和自动检测到的语言）
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let spellcheck = &config.text_pipeline.spellcheck;
+        if !spellcheck.enabled {
+            return None;
+        }
+
+        let language = spellcheck
+            .language
+            .as_deref()
+            .or(config.llm.language.as_deref())
+            .or(config.asr.language.as_deref())
+            .filter(|language| *language != AUTO_LANGUAGE)?;
+
+        match Self::load(language) {
+            Ok(checker) => Some(checker.with_user_words(&spellcheck.user_words)),
+            Err(e) => {
+                tracing::warn!("Spellcheck unavailable: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 添加用户词典中的词，这些词不会被修正
+    pub fn with_user_words(mut self, words: &[String]) -> Self {
+        self.user_words.extend(
+            words
+                .iter()
+                .map(|w| w.trim().to_lowercase())
+                .filter(|w| !w.is_empty()),
+        );
+        self
+    }
+
+    /// 修正文本中的拼写错误，非单词部分原样保留
+    pub fn correct(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut word = String::new();
+
+        for c in text.chars() {
+            // 单词中间的撇号（如 don't）属于单词
+            if is_word_char(c) || (c == '\'' && !word.is_empty()) {
+                word.push(c);
+                continue;
+            }
+            self.push_word(&mut result, &mut word);
+            result.push(c);
+        }
+        self.push_word(&mut result, &mut word);
+
+        result
+    }
+
+    fn push_word(&self, result: &mut String, word: &mut String) {
+        // 结尾的撇号不属于单词
+        let trailing = word.len() - word.trim_end_matches('\'').len();
+        let (core, apostrophes) = word.split_at(word.len() - trailing);
+        match self.correct_word(core) {
+            Some(corrected) => result.push_str(&corrected),
+            None => result.push_str(core),
+        }
+        result.push_str(apostrophes);
+        word.clear();
+    }
+
+    /// 拼错且有把握修正时返回修正后的词
+    fn correct_word(&self, word: &str) -> Option<String> {
+        let len = word.chars().count();
+        if len < 3 || !should_check(word) || self.user_words.contains(&word.to_lowercase()) {
+            return None;
+        }
+        if self.dictionary.check(word) {
+            return None;
+        }
+
+        let mut suggestions = Vec::new();
+        self.dictionary.suggest(word, &mut suggestions);
+        let suggestion = suggestions.into_iter().next()?;
+        if suggestion.contains([' ', '-']) {
+            return None;
+        }
+
+        let max_distance = if len >= 8 { 2 } else { 1 };
+        let distance = edit_distance(&word.to_lowercase(), &suggestion.to_lowercase());
+        (distance <= max_distance).then(|| match_case(word, &suggestion))
+    }
+}
+
+/// 参与拼写检查的字符：字母（不含中文）
+fn is_word_char(c: char) -> bool {
+    c.is_alphabetic() && !is_cjk(c)
+}
+
+/// 全大写（缩写）和首字母之后有大写（驼峰、品牌名）的词不检查
+fn should_check(word: &str) -> bool {
+    !word.chars().skip(1).any(char::is_uppercase)
+}
+
+/// 按原词首字母大小写调整建议
+fn match_case(original: &str, suggestion: &str) -> String {
+    let capitalized = original.chars().next().is_some_and(char::is_uppercase);
+    let mut chars = suggestion.chars();
+    match chars.next() {
+        Some(first) if capitalized => first.to_uppercase().chain(chars).collect(),
+        _ => suggestion.to_string(),
+    }
+}
+
+/// 编辑距离（相邻字符交换算一次编辑）
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
@@ -0,0 +1,171 @@
+//! 文本处理的各个阶段
+//!
+//! 每个阶段都是纯函数，由 [`TextPipeline`](super::TextPipeline) 按配置顺序调用
+
+use super::stats::is_cjk;
+use crate::config::settings::TextReplacement;
+use crate::format::strip_markdown;
+
+/// 是否是中文标点
+fn is_cjk_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '，' | '。' | '！' | '？' | '、' | '；' | '：' | '“' | '”' | '‘' | '’' | '（' | '）' | '《'
+            | '》' | '【' | '】' | '…'
+    )
+}
+
+/// 全角字母、数字、空格转半角
+fn to_half_width(c: char) -> char {
+    match c {
+        '\u{FF10}'..='\u{FF19}' | '\u{FF21}'..='\u{FF3A}' | '\u{FF41}'..='\u{FF5A}' => {
+            char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+        }
+        '\u{3000}' => ' ',
+        _ => c,
+    }
+}
+
+/// 半角标点对应的中文标点
+fn to_full_width_punctuation(c: char) -> Option<char> {
+    match c {
+        ',' => Some('，'),
+        '.' => Some('。'),
+        '?' => Some('？'),
+        '!' => Some('！'),
+        ':' => Some('：'),
+        ';' => Some('；'),
+        _ => None,
+    }
+}
+
+/// 应用替换规则
+pub fn apply_replacements(text: &str, rules: &[TextReplacement]) -> String {
+    let mut result = text.to_string();
+
+    for rule in rules.iter().filter(|r| !r.from.is_empty()) {
+        result = if rule.case_sensitive {
+            result.replace(&rule.from, &rule.to)
+        } else {
+            replace_ignore_ascii_case(&result, &rule.from, &rule.to)
+        };
+    }
+
+    result
+}
+
+fn replace_ignore_ascii_case(text: &str, from: &str, to: &str) -> String {
+    // ASCII 大小写转换不改变字节长度，位置可以直接对应回原文
+    let lower_text = text.to_ascii_lowercase();
+    let lower_from = from.to_ascii_lowercase();
+
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in lower_text.match_indices(&lower_from) {
+        result.push_str(&text[last..start]);
+        result.push_str(to);
+        last = start + from.len();
+    }
+    result.push_str(&text[last..]);
+
+    result
+}
+
+/// 规范化文本
+///
+/// - 去除首尾空白，连续空白合并为一个空格；保留换行（说话人标签、列表等按行分隔），
+///   换行两侧的空格去掉
+/// - 全角字母、数字转半角
+/// - 去掉中文字符之间多余的空格（部分 ASR 会逐字加空格）
+pub fn normalize(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut pending_space = false;
+    let mut pending_newlines = 0;
+
+    for c in text.chars().map(to_half_width) {
+        if c == '\n' {
+            if !result.is_empty() {
+                pending_newlines += 1;
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = !result.is_empty();
+            continue;
+        }
+
+        if pending_newlines > 0 {
+            result.push_str(&"\n".repeat(pending_newlines));
+            pending_newlines = 0;
+            pending_space = false;
+        } else if pending_space {
+            let prev_is_cjk = result
+                .chars()
+                .last()
+                .is_some_and(|p| is_cjk(p) || is_cjk_punctuation(p));
+            if !(prev_is_cjk && (is_cjk(c) || is_cjk_punctuation(c))) {
+                result.push(' ');
+            }
+            pending_space = false;
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+/// 修正中文语境下的标点
+///
+/// - 紧跟中文字符的半角标点转为中文标点（后面紧跟字母数字的除外，如 "文件.txt"）
+/// - 合并重复的中文标点
+/// - 去掉中文标点后的空格（保留换行）
+pub fn punctuate(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = result.chars().last();
+
+        let c = match to_full_width_punctuation(c) {
+            Some(full)
+                if prev.is_some_and(is_cjk)
+                    && !chars.get(i + 1).is_some_and(|n| n.is_ascii_alphanumeric()) =>
+            {
+                full
+            }
+            _ => c,
+        };
+
+        if c.is_whitespace() && !matches!(c, '\n' | '\r') && prev.is_some_and(is_cjk_punctuation) {
+            continue;
+        }
+        if is_cjk_punctuation(c) && c != '…' && prev == Some(c) {
+            continue;
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+/// 去除 Markdown 标记，输出纯文本
+pub fn format_plain(text: &str) -> String {
+    strip_markdown(text)
+}
+
+/// 检查 LLM 输出是否可信
+///
+/// 输入非空而输出为空，或输出长度超过输入的 `max_length_ratio` 倍（额外允许少量标点）
+/// 时视为异常，通常是模型在回答问题或补充解释
+pub fn within_guardrails(input: &str, output: &str, max_length_ratio: f32) -> bool {
+    let input_len = input.trim().chars().count();
+    let output_len = output.trim().chars().count();
+
+    if input_len > 0 && output_len == 0 {
+        return false;
+    }
+
+    output_len as f32 <= input_len as f32 * max_length_ratio + 4.0
+}
@@ -0,0 +1,49 @@
+//! 文本统计
+
+use std::time::Duration;
+
+/// 计算语速所需的最短时长，太短时估算值波动过大
+const MIN_WPM_ELAPSED: Duration = Duration::from_secs(2);
+
+/// 是否是按单字计数的字符（中日韩文字）
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // 平假名、片假名
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x4E00..=0x9FFF   // CJK 统一表意文字
+        | 0xAC00..=0xD7AF   // 韩文音节
+        | 0xF900..=0xFAFF   // CJK 兼容表意文字
+    )
+}
+
+/// 统计词数
+///
+/// 中日韩文字每个字计为一个词，其他文字按空白和标点分隔的单词计数
+pub fn count_words(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                count += 1;
+                in_word = true;
+            }
+        } else if c != '\'' && c != '-' {
+            in_word = false;
+        }
+    }
+
+    count
+}
+
+/// 估算语速（每分钟词数），时长不足时返回 0
+pub fn words_per_minute(words: usize, elapsed: Duration) -> f32 {
+    if elapsed < MIN_WPM_ELAPSED {
+        return 0.0;
+    }
+    words as f32 * 60.0 / elapsed.as_secs_f32()
+}
@@ -0,0 +1,298 @@
+//! 检查更新
+//!
+//! 从 GitHub Releases 查询新版本：稳定通道只看正式版，测试通道同时包含预发布版本。
+//! 发布页只提供完整安装包（macOS dmg、Windows 安装程序），下载后由用户运行安装
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::settings::UpdateChannel;
+use crate::net::proxied_client;
+
+/// GitHub Releases 接口
+pub const RELEASES_URL: &str = "https://api.github.com/repos/JobinJia/Vhisper/releases";
+/// 查询版本列表的超时时间
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateError {
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("API error: {0}")]
+    Api(String),
+    #[error("No installer for this platform in release {0}")]
+    NoAsset(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// 发布页上的文件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// GitHub 返回的发布信息（只取用到的字段）
+#[derive(Debug, Clone, Deserialize)]
+pub struct GithubRelease {
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub html_url: String,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// 可用的新版本
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UpdateInfo {
+    /// 新版本号（不带 v 前缀）
+    pub version: String,
+    pub current_version: String,
+    pub name: String,
+    /// 更新说明（Markdown）
+    pub notes: String,
+    /// 发布页地址
+    pub url: String,
+    pub prerelease: bool,
+    pub published_at: Option<String>,
+    /// 当前平台的安装包，发布页没有时为 None（只能打开发布页手动下载）
+    pub asset: Option<ReleaseAsset>,
+}
+
+/// 语义化版本（忽略构建元数据）
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    core: [u64; 3],
+    pre: Vec<String>,
+}
+
+fn parse_version(text: &str) -> Option<Version> {
+    let text = text.trim().trim_start_matches(['v', 'V']);
+    let text = text.split('+').next()?;
+    let (core, pre) = match text.split_once('-') {
+        Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+        None => (text, Vec::new()),
+    };
+
+    let mut parts = core.split('.');
+    let mut numbers = [0; 3];
+    for number in &mut numbers {
+        *number = parts.next()?.parse().ok()?;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Version { core: numbers, pre })
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core.cmp(&other.core).then_with(|| {
+            // 正式版高于同版本号的预发布版本
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_pre(&self.pre, &other.pre),
+            }
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 预发布标识逐段比较：数字按大小，数字低于字母，其余按字典序，前缀相同时段少的较低
+fn compare_pre(a: &[String], b: &[String]) -> Ordering {
+    for (a, b) in a.iter().zip(b) {
+        let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => a.cmp(b),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// 比较两个版本号，无法解析时返回 None
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    Some(parse_version(a)?.cmp(&parse_version(b)?))
+}
+
+/// 当前平台安装包的文件名后缀，按优先顺序
+fn platform_suffixes() -> &'static [&'static str] {
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    {
+        &["_aarch64.dmg"]
+    }
+
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    {
+        &["_x64.dmg"]
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        &["-setup.exe", ".msi"]
+    }
+
+    #[cfg(not(any(
+        all(
+            target_os = "macos",
+            any(target_arch = "aarch64", target_arch = "x86_64")
+        ),
+        target_os = "windows"
+    )))]
+    {
+        &[]
+    }
+}
+
+/// 选出当前平台的安装包
+pub fn platform_asset(assets: &[ReleaseAsset]) -> Option<&ReleaseAsset> {
+    platform_suffixes()
+        .iter()
+        .find_map(|suffix| assets.iter().find(|asset| asset.name.ends_with(suffix)))
+}
+
+/// 从发布列表中选出通道内比当前版本新的最高版本
+pub fn select_update(
+    releases: &[GithubRelease],
+    channel: UpdateChannel,
+    current_version: &str,
+) -> Option<UpdateInfo> {
+    let current = parse_version(current_version)?;
+    let (_, release) = releases
+        .iter()
+        .filter(|release| !release.draft)
+        .filter(|release| channel == UpdateChannel::Beta || !release.prerelease)
+        .filter_map(|release| Some((parse_version(&release.tag_name)?, release)))
+        .filter(|(version, _)| *version > current)
+        .max_by(|(a, _), (b, _)| a.cmp(b))?;
+
+    Some(UpdateInfo {
+        name: release
+            .name
+            .clone()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| release.tag_name.clone()),
+        version: release.tag_name.trim_start_matches(['v', 'V']).to_string(),
+        current_version: current_version.to_string(),
+        notes: release.body.clone().unwrap_or_default(),
+        url: release.html_url.clone(),
+        prerelease: release.prerelease,
+        published_at: release.published_at.clone(),
+        asset: platform_asset(&release.assets).cloned(),
+    })
+}
+
+/// 查询通道内是否有新版本
+pub async fn check_for_update(
+    channel: UpdateChannel,
+    current_version: &str,
+) -> Result<Option<UpdateInfo>, UpdateError> {
+    let response = proxied_client()
+        .get(format!("{}?per_page=20", RELEASES_URL))
+        // GitHub 要求带 User-Agent
+        .header("User-Agent", "Vhisper")
+        .header("Accept", "application/vnd.github+json")
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| UpdateError::Network(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(UpdateError::Api(format!("HTTP {}", response.status())));
+    }
+    let releases: Vec<GithubRelease> = response
+        .json()
+        .await
+        .map_err(|e| UpdateError::Api(e.to_string()))?;
+
+    let update = select_update(&releases, channel, current_version);
+    match &update {
+        Some(update) => tracing::info!(
+            "Update available: {} -> {}",
+            current_version,
+            update.version
+        ),
+        None => tracing::info!("No update available ({:?} channel)", channel),
+    }
+    Ok(update)
+}
+
+/// 下载安装包到指定目录，返回文件路径
+///
+/// 已下载过的（大小一致）直接返回；下载过程中写入 `.part` 文件，完成后再重命名
+pub async fn download_update(update: &UpdateInfo, dir: &Path) -> Result<PathBuf, UpdateError> {
+    let asset = update
+        .asset
+        .as_ref()
+        .ok_or_else(|| UpdateError::NoAsset(update.version.clone()))?;
+
+    tokio::fs::create_dir_all(dir).await?;
+    let path = dir.join(&asset.name);
+    if let Ok(metadata) = tokio::fs::metadata(&path).await {
+        if asset.size > 0 && metadata.len() == asset.size {
+            return Ok(path);
+        }
+    }
+
+    let mut response = proxied_client()
+        .get(&asset.browser_download_url)
+        .header("User-Agent", "Vhisper")
+        .send()
+        .await
+        .map_err(|e| UpdateError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(UpdateError::Api(format!("HTTP {}", response.status())));
+    }
+
+    let partial = dir.join(format!("{}.part", asset.name));
+    let mut file = tokio::fs::File::create(&partial).await?;
+    let mut written = 0u64;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| UpdateError::Network(e.to_string()))?
+    {
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    file.flush().await?;
+    drop(file);
+
+    if asset.size > 0 && written != asset.size {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(UpdateError::Network(format!(
+            "下载不完整: {} / {} 字节",
+            written, asset.size
+        )));
+    }
+    tokio::fs::rename(&partial, &path).await?;
+    tracing::info!("Downloaded update {} to {:?}", update.version, path);
+    Ok(path)
+}
@@ -0,0 +1,77 @@
+//! ASR 备用服务商：主服务商不可用时按顺序尝试备用服务商
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vhisper_core::config::settings::{FasterWhisperConfig, FunAsrConfig, TlsOptions};
+use vhisper_core::{create_asr_service, encode_to_wav, AppConfig};
+
+/// 模拟 faster-whisper-server：接受一个转写请求，返回固定结果和收到的原始请求
+async fn serve_transcription(listener: TcpListener) -> Vec<u8> {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, _)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if request.len() - head.len() - 4 >= length {
+                break;
+            }
+        }
+        if n == 0 {
+            break;
+        }
+    }
+    let body = r#"{"text":"备用服务商的结果","segments":[]}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    request
+}
+
+#[tokio::test]
+async fn falls_back_when_primary_is_unreachable() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve_transcription(listener));
+
+    let mut config = AppConfig::default();
+    config.asr.result_cache = false;
+    // 主服务商连接被拒绝
+    config.asr.provider = "FunAsr".to_string();
+    config.asr.funasr = Some(FunAsrConfig {
+        endpoint: "http://127.0.0.1:1".to_string(),
+        tls: TlsOptions::default(),
+    });
+    // 未配置的备用服务商被跳过
+    config.asr.fallback_providers = vec!["Deepgram".to_string(), "FasterWhisper".to_string()];
+    config.asr.faster_whisper = Some(FasterWhisperConfig {
+        endpoint,
+        model: "mock-model".to_string(),
+        api_key: None,
+        vad_filter: false,
+        beam_size: None,
+        initial_prompt: None,
+    });
+
+    let service = create_asr_service(&config.asr).unwrap();
+    let audio = encode_to_wav(&[0.1; 1600], 16000, 1).unwrap();
+    let result = service.recognize(&audio, 16000).await.unwrap();
+    assert_eq!(result.text, "备用服务商的结果");
+
+    // 需要 WAV 的服务商收到完整的 WAV 文件
+    let request = server.await.unwrap();
+    assert!(request.windows(4).any(|w| w == b"RIFF"));
+}
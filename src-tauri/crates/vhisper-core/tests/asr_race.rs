@@ -0,0 +1,102 @@
+//! ASR 竞速：同时请求主服务商和竞速服务商，取先返回的成功结果
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vhisper_core::config::settings::{FasterWhisperConfig, FunAsrConfig, TlsOptions};
+use vhisper_core::{create_asr_service, encode_to_wav, AppConfig};
+
+/// 模拟 faster-whisper-server：接受一个转写请求，返回固定结果和收到的原始请求
+async fn serve_transcription(listener: TcpListener) -> Vec<u8> {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, _)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if request.len() - head.len() - 4 >= length {
+                break;
+            }
+        }
+        if n == 0 {
+            break;
+        }
+    }
+    let body = r#"{"text":"竞速服务商的结果","segments":[]}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    request
+}
+
+fn race_config(primary_endpoint: String, racer_endpoint: String) -> AppConfig {
+    let mut config = AppConfig::default();
+    config.asr.result_cache = false;
+    config.asr.provider = "FunAsr".to_string();
+    config.asr.funasr = Some(FunAsrConfig {
+        endpoint: primary_endpoint,
+        tls: TlsOptions::default(),
+    });
+    config.asr.race_provider = Some("FasterWhisper".to_string());
+    config.asr.faster_whisper = Some(FasterWhisperConfig {
+        endpoint: racer_endpoint,
+        model: "mock-model".to_string(),
+        api_key: None,
+        vad_filter: false,
+        beam_size: None,
+        initial_prompt: None,
+    });
+    config
+}
+
+#[tokio::test]
+async fn racer_wins_over_stalled_primary() {
+    // 主服务商接受连接但从不响应
+    let stalled = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let primary_endpoint = format!("http://{}", stalled.local_addr().unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let racer_endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve_transcription(listener));
+
+    let config = race_config(primary_endpoint, racer_endpoint);
+    let service = create_asr_service(&config.asr).unwrap();
+    let audio = encode_to_wav(&[0.1; 1600], 16000, 1).unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), service.recognize(&audio, 16000))
+        .await
+        .expect("racing should not wait for the stalled primary")
+        .unwrap();
+    assert_eq!(result.text, "竞速服务商的结果");
+
+    // 需要 WAV 的竞速服务商收到完整的 WAV 文件
+    let request = server.await.unwrap();
+    assert!(request.windows(4).any(|w| w == b"RIFF"));
+    drop(stalled);
+}
+
+#[tokio::test]
+async fn racer_covers_failed_primary() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let racer_endpoint = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve_transcription(listener));
+
+    // 主服务商连接被拒绝
+    let config = race_config("http://127.0.0.1:1".to_string(), racer_endpoint);
+    let service = create_asr_service(&config.asr).unwrap();
+    let audio = encode_to_wav(&[0.1; 1600], 16000, 1).unwrap();
+    let result = service.recognize(&audio, 16000).await.unwrap();
+    assert_eq!(result.text, "竞速服务商的结果");
+}
@@ -0,0 +1,80 @@
+//! ASR 结果缓存：同一段音频以相同配置再次识别时不再请求服务商
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vhisper_core::config::settings::FasterWhisperConfig;
+use vhisper_core::{create_asr_service, encode_to_wav, AppConfig};
+
+/// 模拟 faster-whisper-server：只处理一个转写请求
+async fn serve_once(listener: TcpListener) {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, _)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if request.len() - head.len() - 4 >= length {
+                break;
+            }
+        }
+        if n == 0 {
+            break;
+        }
+    }
+    let body = r#"{"text":"缓存的结果","segments":[]}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+}
+
+#[tokio::test]
+async fn repeated_audio_is_served_from_cache() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve_once(listener));
+
+    let mut config = AppConfig::default();
+    config.asr.result_cache = true;
+    config.asr.provider = "FasterWhisper".to_string();
+    config.asr.faster_whisper = Some(FasterWhisperConfig {
+        endpoint,
+        model: "mock-model".to_string(),
+        api_key: None,
+        vad_filter: false,
+        beam_size: None,
+        initial_prompt: None,
+    });
+
+    // 每次运行使用不同的音频，不命中之前运行留在磁盘上的结果
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+    let amplitude = 0.1 + (nanos % 1000) as f32 / 100_000.0;
+    let audio = encode_to_wav(&[amplitude; 1600], 16000, 1).unwrap();
+
+    let first = create_asr_service(&config.asr).unwrap();
+    assert_eq!(first.recognize(&audio, 16000).await.unwrap().text, "缓存的结果");
+    server.await.unwrap();
+
+    // 服务已关闭，新建的服务实例仍返回缓存的结果
+    let second = create_asr_service(&config.asr).unwrap();
+    assert_eq!(second.recognize(&audio, 16000).await.unwrap().text, "缓存的结果");
+
+    // 配置变化后不命中缓存
+    config.asr.language = Some("en".to_string());
+    let changed = create_asr_service(&config.asr).unwrap();
+    assert!(changed.recognize(&audio, 16000).await.is_err());
+}
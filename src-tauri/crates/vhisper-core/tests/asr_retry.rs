@@ -0,0 +1,128 @@
+//! ASR 请求超时和重试：服务端无响应时超时重试，API 错误不重试
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use vhisper_core::config::settings::{FasterWhisperConfig, RequestPolicy};
+use vhisper_core::{create_asr_service, encode_to_wav, AppConfig};
+
+/// 服务端对每个连接的处理方式
+#[derive(Clone, Copy)]
+enum Reply {
+    /// 读完请求后不响应
+    Hang,
+    Ok,
+    Unauthorized,
+}
+
+async fn read_request(stream: &mut TcpStream) {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, _)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if request.len() - head.len() - 4 >= length {
+                break;
+            }
+        }
+        if n == 0 {
+            break;
+        }
+    }
+}
+
+/// 模拟 faster-whisper-server，按顺序对每个连接使用 replies 中的处理方式
+async fn serve(listener: TcpListener, replies: Vec<Reply>, connections: Arc<AtomicUsize>) {
+    for reply in replies {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        connections.fetch_add(1, Ordering::SeqCst);
+        tokio::spawn(async move {
+            read_request(&mut stream).await;
+            let (status, body) = match reply {
+                Reply::Hang => {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    return;
+                }
+                Reply::Ok => ("200 OK", r#"{"text":"重试后的结果","segments":[]}"#),
+                Reply::Unauthorized => ("401 Unauthorized", r#"{"detail":"invalid api key"}"#),
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+    }
+}
+
+async fn config_with_server(replies: Vec<Reply>, connections: Arc<AtomicUsize>) -> AppConfig {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve(listener, replies, connections));
+
+    let mut config = AppConfig::default();
+    config.asr.result_cache = false;
+    config.asr.provider = "FasterWhisper".to_string();
+    config.asr.faster_whisper = Some(FasterWhisperConfig {
+        endpoint,
+        model: "mock-model".to_string(),
+        api_key: None,
+        vad_filter: false,
+        beam_size: None,
+        initial_prompt: None,
+    });
+    // 按服务商覆盖默认策略
+    config.asr.provider_request_policy.insert(
+        "FasterWhisper".to_string(),
+        RequestPolicy {
+            connect_timeout_secs: 1,
+            request_timeout_secs: 1,
+            max_retries: 2,
+            retry_backoff_ms: 10,
+        },
+    );
+    config
+}
+
+#[tokio::test]
+async fn hung_request_times_out_and_is_retried() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let config = config_with_server(vec![Reply::Hang, Reply::Ok], connections.clone()).await;
+
+    let service = create_asr_service(&config.asr).unwrap();
+    let audio = encode_to_wav(&[0.1; 1600], 16000, 1).unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(10), service.recognize(&audio, 16000))
+        .await
+        .expect("request timeout should stop the hung request")
+        .unwrap();
+
+    assert_eq!(result.text, "重试后的结果");
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn api_errors_are_not_retried() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let config =
+        config_with_server(vec![Reply::Unauthorized, Reply::Ok], connections.clone()).await;
+
+    let service = create_asr_service(&config.asr).unwrap();
+    let audio = encode_to_wav(&[0.1; 1600], 16000, 1).unwrap();
+    assert!(service.recognize(&audio, 16000).await.is_err());
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
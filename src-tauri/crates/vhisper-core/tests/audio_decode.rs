@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use vhisper_core::audio::{decode_file, AudioError};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("vhisper-{}-{}", name, std::process::id()))
+}
+
+/// 写入 48kHz 立体声 WAV：左声道 0.5，右声道 -0.25
+fn write_stereo_wav(path: &PathBuf, frames: usize) {
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 48000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for _ in 0..frames {
+        writer.write_sample((0.5 * i16::MAX as f32) as i16).unwrap();
+        writer
+            .write_sample((-0.25 * i16::MAX as f32) as i16)
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+#[test]
+fn decodes_wav_to_mono_16k() {
+    let path = temp_path("decode.wav");
+    write_stereo_wav(&path, 48000);
+
+    let samples = decode_file(&path, 16000).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    // 1 秒音频重采样后约 16000 个样本
+    assert!(
+        (15990..=16010).contains(&samples.len()),
+        "{}",
+        samples.len()
+    );
+    // 左右声道取平均
+    assert!(samples.iter().all(|s| (s - 0.125).abs() < 0.001));
+}
+
+#[test]
+fn rejects_unsupported_file() {
+    let path = temp_path("decode.txt");
+    std::fs::write(&path, "not audio").unwrap();
+
+    let result = decode_file(&path, 16000);
+    let _ = std::fs::remove_file(&path);
+
+    assert!(matches!(result, Err(AudioError::Decoding(_))));
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    let result = decode_file(&temp_path("missing.wav"), 16000);
+    assert!(matches!(result, Err(AudioError::Decoding(_))));
+}
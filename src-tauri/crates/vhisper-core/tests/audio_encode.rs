@@ -0,0 +1,81 @@
+use proptest::prelude::*;
+use vhisper_core::{encode_to_pcm, encode_to_wav};
+
+/// 16-bit 量化的最大误差
+const QUANTIZATION_ERROR: f32 = 1.0 / i16::MAX as f32;
+
+fn decode_pcm(data: &[u8]) -> Vec<i16> {
+    data.chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect()
+}
+
+fn to_f32(sample: i16) -> f32 {
+    sample as f32 / i16::MAX as f32
+}
+
+proptest! {
+    #[test]
+    fn pcm_round_trip_within_quantization_error(
+        samples in prop::collection::vec(-1.0f32..=1.0, 0..2048),
+    ) {
+        let data = encode_to_pcm(&samples);
+        prop_assert_eq!(data.len(), samples.len() * 2);
+
+        for (original, decoded) in samples.iter().zip(decode_pcm(&data)) {
+            prop_assert!((original - to_f32(decoded)).abs() <= QUANTIZATION_ERROR);
+        }
+    }
+
+    #[test]
+    fn pcm_clamps_out_of_range_samples(
+        samples in prop::collection::vec(-100.0f32..100.0, 1..512),
+    ) {
+        let decoded = decode_pcm(&encode_to_pcm(&samples));
+
+        for (original, decoded) in samples.iter().zip(decoded) {
+            if *original >= 1.0 {
+                prop_assert_eq!(decoded, i16::MAX);
+            } else if *original <= -1.0 {
+                prop_assert_eq!(decoded, -i16::MAX);
+            } else {
+                prop_assert!((original - to_f32(decoded)).abs() <= QUANTIZATION_ERROR);
+            }
+        }
+    }
+
+    #[test]
+    fn wav_header_matches_format(
+        sample_rate in prop::sample::select(vec![8000u32, 16000, 22050, 24000, 44100, 48000]),
+        channels in 1u16..=2,
+        frames in 0usize..1024,
+    ) {
+        let samples = vec![0.25f32; frames * channels as usize];
+        let data = encode_to_wav(&samples, sample_rate, channels).unwrap();
+
+        prop_assert_eq!(&data[0..4], b"RIFF");
+        prop_assert_eq!(&data[8..12], b"WAVE");
+        // RIFF 块大小 = 文件长度 - 8
+        let riff_size = u32::from_le_bytes(data[4..8].try_into().unwrap());
+        prop_assert_eq!(riff_size as usize, data.len() - 8);
+
+        let reader = hound::WavReader::new(std::io::Cursor::new(&data)).unwrap();
+        let spec = reader.spec();
+        prop_assert_eq!(spec.sample_rate, sample_rate);
+        prop_assert_eq!(spec.channels, channels);
+        prop_assert_eq!(spec.bits_per_sample, 16);
+        prop_assert_eq!(spec.sample_format, hound::SampleFormat::Int);
+        prop_assert_eq!(reader.len() as usize, samples.len());
+    }
+
+    #[test]
+    fn wav_samples_match_pcm(
+        samples in prop::collection::vec(-2.0f32..2.0, 0..2048),
+    ) {
+        let wav = encode_to_wav(&samples, 16000, 1).unwrap();
+        let mut reader = hound::WavReader::new(std::io::Cursor::new(&wav)).unwrap();
+        let decoded: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+
+        prop_assert_eq!(decoded, decode_pcm(&encode_to_pcm(&samples)));
+    }
+}
@@ -0,0 +1,65 @@
+//! 日历会议提醒：即将开始的会议只提醒一次，全天事件和已结束的会议不提醒
+
+use vhisper_core::calendar::{CalendarEvent, MeetingReminder};
+
+const NOW: u64 = 1_700_000_000;
+
+fn event(id: &str, start: u64, end: u64) -> CalendarEvent {
+    CalendarEvent {
+        id: id.to_string(),
+        title: format!("会议 {}", id),
+        start,
+        end,
+        all_day: false,
+    }
+}
+
+fn due_ids(reminder: &mut MeetingReminder, events: &[CalendarEvent], now: u64) -> Vec<String> {
+    reminder
+        .due(events, now, 60)
+        .into_iter()
+        .map(|event| event.id)
+        .collect()
+}
+
+#[test]
+fn prompts_meetings_within_lead_time_once() {
+    let mut reminder = MeetingReminder::new();
+    let events = vec![
+        event("later", NOW + 600, NOW + 3600),
+        event("soon", NOW + 30, NOW + 1800),
+        event("started", NOW - 120, NOW + 1800),
+    ];
+    assert_eq!(
+        due_ids(&mut reminder, &events, NOW),
+        vec!["started", "soon"]
+    );
+    assert!(due_ids(&mut reminder, &events, NOW + 30).is_empty());
+    assert_eq!(due_ids(&mut reminder, &events, NOW + 560), vec!["later"]);
+}
+
+#[test]
+fn skips_all_day_long_started_and_finished_events() {
+    let mut reminder = MeetingReminder::new();
+    let mut all_day = event("all-day", NOW - 3600, NOW + 20 * 3600);
+    all_day.all_day = true;
+    let events = vec![
+        all_day,
+        event("long-started", NOW - 1800, NOW + 1800),
+        event("finished", NOW - 60, NOW),
+    ];
+    assert!(due_ids(&mut reminder, &events, NOW).is_empty());
+}
+
+#[test]
+fn distinguishes_occurrences_of_recurring_meetings() {
+    let mut reminder = MeetingReminder::new();
+    let today = event("standup", NOW + 30, NOW + 900);
+    assert_eq!(due_ids(&mut reminder, &[today], NOW), vec!["standup"]);
+
+    let tomorrow = event("standup", NOW + 86_400 + 30, NOW + 86_400 + 900);
+    assert_eq!(
+        due_ids(&mut reminder, &[tomorrow], NOW + 86_400),
+        vec!["standup"]
+    );
+}
@@ -0,0 +1,199 @@
+//! 模拟通义千问实时识别服务（只实现管道用到的协议子集）
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+/// 单个连接的行为
+#[derive(Debug, Clone)]
+pub enum Turn {
+    /// 收到 commit 后返回最终结果
+    OnCommit(&'static str),
+    /// 收到第一块音频后立即返回最终结果（模拟服务端 VAD 断句）
+    AfterAudio(&'static str),
+    /// 收到第一块音频后返回错误
+    Error(&'static str),
+}
+
+/// 服务端收到的客户端消息
+#[derive(Debug, Clone, PartialEq)]
+pub enum Received {
+    SessionUpdate,
+    Audio(usize),
+    Commit,
+}
+
+#[derive(Default)]
+struct State {
+    turns: VecDeque<Turn>,
+    /// 每个连接收到的消息
+    connections: Vec<Vec<Received>>,
+    /// 每个连接 session.update 中的识别提示
+    corpus: Vec<Option<String>>,
+    /// 每个连接 session.update 中的断句配置
+    turn_detection: Vec<Value>,
+    /// 回复 session.updated 前的延迟
+    confirm_delay: Duration,
+}
+
+pub struct MockQwen {
+    pub url: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockQwen {
+    /// 启动服务，按连接顺序使用 turns 中的行为（用完后默认 OnCommit("")）
+    pub async fn start(turns: Vec<Turn>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/realtime", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State {
+            turns: turns.into(),
+            connections: Vec::new(),
+            corpus: Vec::new(),
+            turn_detection: Vec::new(),
+            confirm_delay: Duration::ZERO,
+        }));
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    if let Ok(ws) = tokio_tungstenite::accept_async(stream).await {
+                        handle_connection(ws, state).await;
+                    }
+                });
+            }
+        });
+
+        Self { url, state }
+    }
+
+    /// 已建立的连接数
+    pub fn connection_count(&self) -> usize {
+        self.state.lock().unwrap().connections.len()
+    }
+
+    /// 指定连接收到的消息
+    pub fn received(&self, connection: usize) -> Vec<Received> {
+        self.state.lock().unwrap().connections[connection].clone()
+    }
+
+    /// 设置回复 session.updated 前的延迟（模拟服务端初始化会话）
+    pub fn set_confirm_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().confirm_delay = delay;
+    }
+
+    /// 指定连接的识别提示
+    pub fn corpus(&self, connection: usize) -> Option<String> {
+        self.state.lock().unwrap().corpus[connection].clone()
+    }
+
+    /// 指定连接的断句配置（手动断句时为 null）
+    pub fn turn_detection(&self, connection: usize) -> Value {
+        self.state.lock().unwrap().turn_detection[connection].clone()
+    }
+
+    /// 所有连接收到的音频字节数
+    pub fn audio_bytes(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .connections
+            .iter()
+            .flatten()
+            .map(|r| match r {
+                Received::Audio(len) => *len,
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+async fn handle_connection(
+    ws: tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
+    state: Arc<Mutex<State>>,
+) {
+    let (turn, index) = {
+        let mut state = state.lock().unwrap();
+        state.connections.push(Vec::new());
+        state.corpus.push(None);
+        state.turn_detection.push(Value::Null);
+        (
+            state.turns.pop_front().unwrap_or(Turn::OnCommit("")),
+            state.connections.len() - 1,
+        )
+    };
+    let record = |received: Received| state.lock().unwrap().connections[index].push(received);
+
+    let (mut write, mut read) = ws.split();
+    let mut finished = false;
+
+    while let Some(Ok(Message::Text(text))) = read.next().await {
+        let message: Value = serde_json::from_str(&text).unwrap();
+        let reply = match message["type"].as_str().unwrap_or_default() {
+            "session.update" => {
+                record(Received::SessionUpdate);
+                let corpus = &message["session"]["input_audio_transcription"]["corpus"]["text"];
+                let delay = {
+                    let mut state = state.lock().unwrap();
+                    state.corpus[index] = corpus.as_str().map(str::to_string);
+                    state.turn_detection[index] = message["session"]["turn_detection"].clone();
+                    state.confirm_delay
+                };
+                tokio::time::sleep(delay).await;
+                json!({ "type": "session.updated" })
+            }
+            "input_audio_buffer.append" => {
+                let audio = message["audio"].as_str().unwrap_or_default();
+                // base64 长度换算为字节数
+                record(Received::Audio(audio.len() / 4 * 3 - audio.matches('=').count()));
+                if finished {
+                    continue;
+                }
+                match &turn {
+                    Turn::AfterAudio(text) => {
+                        finished = true;
+                        completed(text)
+                    }
+                    Turn::Error(message) => {
+                        finished = true;
+                        json!({ "type": "error", "error": { "message": message } })
+                    }
+                    Turn::OnCommit(_) => json!({
+                        "type": "conversation.item.input_audio_transcription.text",
+                        "text": "",
+                        "stash": "…",
+                    }),
+                }
+            }
+            "input_audio_buffer.commit" => {
+                record(Received::Commit);
+                match &turn {
+                    Turn::OnCommit(text) if !finished => {
+                        finished = true;
+                        completed(text)
+                    }
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        if write.send(Message::Text(reply.to_string().into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+fn completed(text: &str) -> Value {
+    json!({
+        "type": "conversation.item.input_audio_transcription.completed",
+        "transcript": text,
+    })
+}
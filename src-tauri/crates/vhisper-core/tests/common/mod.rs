@@ -0,0 +1,82 @@
+//! 集成测试共用的模拟服务和辅助函数
+
+pub mod mock_qwen;
+
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::RwLock as TokioRwLock;
+use vhisper_core::config::settings::QwenAsrConfig;
+use vhisper_core::events::{set_event_listener, EventPayload, VhisperEvent};
+use vhisper_core::{AppConfig, AudioRecorder, VoicePipeline};
+
+/// 每个音频块 50ms（16kHz）
+pub const CHUNK_SAMPLES: usize = 800;
+
+/// 连接到模拟服务、关闭文本后处理的配置
+pub fn mock_config(realtime_url: &str) -> AppConfig {
+    let mut config = AppConfig::default();
+    config.asr.provider = "Qwen".to_string();
+    config.asr.qwen = Some(QwenAsrConfig {
+        api_key: "test-key".to_string(),
+        model: "mock-model".to_string(),
+        realtime_url: Some(realtime_url.to_string()),
+        manual_turn: false,
+    });
+    config.asr.context_chars = 0;
+    config.asr.result_cache = false;
+    config.llm.enabled = false;
+    config.text_pipeline.stages.clear();
+    config.history.enabled = false;
+    config
+}
+
+/// 使用外部音频源的管道
+pub fn mock_pipeline(realtime_url: &str) -> Arc<VoicePipeline> {
+    let config = Arc::new(TokioRwLock::new(mock_config(realtime_url)));
+    Arc::new(VoicePipeline::with_recorder(config, AudioRecorder::external()))
+}
+
+/// 一个 50ms 的音频块
+pub fn chunk() -> Vec<f32> {
+    vec![0.1; CHUNK_SAMPLES]
+}
+
+/// 所有测试共享的事件记录（事件监听器是全局的，按 session_id 区分）
+fn recorded_events() -> &'static Mutex<Vec<VhisperEvent>> {
+    static EVENTS: OnceLock<Mutex<Vec<VhisperEvent>>> = OnceLock::new();
+    EVENTS.get_or_init(|| {
+        set_event_listener(Some(Arc::new(|event: &VhisperEvent| {
+            recorded_events().lock().unwrap().push(event.clone());
+        })));
+        Mutex::new(Vec::new())
+    })
+}
+
+/// 开始记录事件（在启动管道之前调用）
+pub fn record_events() {
+    recorded_events();
+}
+
+/// 指定会话的事件
+pub fn session_events(session_id: &str) -> Vec<EventPayload> {
+    recorded_events()
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.session_id.as_deref() == Some(session_id))
+        .map(|e| e.payload.clone())
+        .collect()
+}
+
+/// 等待条件成立，超时返回 false
+pub async fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    condition()
+}
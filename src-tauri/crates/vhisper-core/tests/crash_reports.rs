@@ -0,0 +1,73 @@
+//! 崩溃报告：本地保存、列表和异常退出检测
+
+use vhisper_core::debug::{
+    clear_reports, clear_running, list_reports, mark_running, panic_recorded_since_start,
+    save_report, CrashKind, CrashReport,
+};
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("vhisper-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn saves_and_exports_panic_report() {
+    let dir = temp_dir("crash-reports");
+    let report = CrashReport::panic(
+        "1.0.7",
+        "index out of bounds".to_string(),
+        Some("src/lib.rs:1:1".to_string()),
+    );
+    save_report(&dir, &report).unwrap();
+
+    let reports = list_reports(&dir);
+    assert_eq!(reports, vec![report.clone()]);
+    assert_eq!(reports[0].kind, CrashKind::Panic);
+    assert!(reports[0].backtrace.is_some());
+
+    let text = report.issue_text();
+    assert!(text.contains("1.0.7"));
+    assert!(text.contains("index out of bounds"));
+    assert!(text.contains("src/lib.rs:1:1"));
+
+    clear_reports(&dir).unwrap();
+    assert!(list_reports(&dir).is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn detects_unclean_shutdown() {
+    let dir = temp_dir("crash-marker");
+
+    // 首次启动没有标记
+    assert!(!mark_running(&dir).unwrap());
+    // 没有正常退出就再次启动
+    assert!(mark_running(&dir).unwrap());
+    // 正常退出后再启动
+    clear_running(&dir);
+    assert!(!mark_running(&dir).unwrap());
+
+    // 运行标记不会被当成报告
+    assert!(list_reports(&dir).is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn panic_during_run_is_not_reported_twice() {
+    let dir = temp_dir("crash-panic-marker");
+
+    mark_running(&dir).unwrap();
+    assert!(!panic_recorded_since_start(&dir));
+
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    let report = CrashReport::panic("1.0.7", "boom".to_string(), None);
+    save_report(&dir, &report).unwrap();
+    assert!(panic_recorded_since_start(&dir));
+
+    // 新的一次运行开始后，之前的 panic 不再算在内
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    mark_running(&dir).unwrap();
+    assert!(!panic_recorded_since_start(&dir));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,41 @@
+//! 服务商消息脱敏
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::json;
+use vhisper_core::debug::redact_payload;
+
+#[test]
+fn redacts_credentials_and_audio() {
+    let audio = BASE64.encode(vec![0u8; 32000]);
+    let message = json!({
+        "header": { "Authorization": "Bearer sk-secret", "task_id": "t1" },
+        "payload": { "api_key": "sk-secret", "access_token": "abc", "audio": audio },
+        "text": "你好",
+    });
+
+    let redacted = redact_payload(&message.to_string());
+    assert_eq!(redacted["header"]["Authorization"], "[REDACTED]");
+    assert_eq!(redacted["header"]["task_id"], "t1");
+    assert_eq!(redacted["payload"]["api_key"], "[REDACTED]");
+    assert_eq!(redacted["payload"]["access_token"], "[REDACTED]");
+    // 16kHz 16bit 单声道 1 秒
+    assert_eq!(redacted["payload"]["audio"]["bytes"], 32000);
+    assert_eq!(redacted["payload"]["audio"]["duration_ms"], 1000);
+    assert_eq!(redacted["text"], "你好");
+    assert!(!redacted.to_string().contains("sk-secret"));
+}
+
+#[test]
+fn truncates_long_strings() {
+    let long = "a".repeat(10_000);
+    let redacted = redact_payload(&json!({ "image": long }).to_string());
+    let image = redacted["image"].as_str().unwrap();
+    assert!(image.len() < 5000);
+    assert!(image.ends_with("[truncated, 10000 chars]"));
+
+    let plain = redact_payload(&long);
+    assert!(plain
+        .as_str()
+        .unwrap()
+        .ends_with("[truncated, 10000 chars]"));
+}
@@ -0,0 +1,111 @@
+//! 访谈对话：按静音切分音轨、按时间交错和对话记录读写
+
+use std::path::PathBuf;
+
+use vhisper_core::history::{
+    read_transcripts, write_transcript, DialogueLine, DialogueTranscript, Speaker,
+};
+use vhisper_core::pipeline::{split_utterances, Utterance};
+
+const RATE: u32 = 16000;
+
+/// 按 (时长毫秒, 是否有声) 拼接音轨
+fn track(parts: &[(u32, bool)]) -> Vec<f32> {
+    parts
+        .iter()
+        .flat_map(|&(ms, voiced)| {
+            let len = (RATE * ms / 1000) as usize;
+            (0..len).map(move |i| {
+                if voiced {
+                    0.3 * (i as f32 * 0.1).sin()
+                } else {
+                    0.0
+                }
+            })
+        })
+        .collect()
+}
+
+fn line(speaker: Speaker, start_ms: u64, text: &str) -> DialogueLine {
+    DialogueLine {
+        speaker,
+        start_ms,
+        end_ms: start_ms + 1000,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn splits_track_at_long_silences() {
+    let samples = track(&[
+        (500, false),
+        (1000, true),
+        (1000, false),
+        (800, true),
+        (500, false),
+    ]);
+    let utterances = split_utterances(&samples, RATE, 0.01, 700);
+    assert_eq!(utterances.len(), 2);
+    // 前后各保留 200ms 余量
+    assert_eq!(utterances[0].start_ms(RATE), 300);
+    assert_eq!(utterances[0].end_ms(RATE), 1700);
+    assert_eq!(utterances[1].start_ms(RATE), 2300);
+    assert_eq!(utterances[1].end_ms(RATE), 3500);
+}
+
+#[test]
+fn keeps_short_pauses_within_utterance() {
+    let samples = track(&[(1000, true), (300, false), (1000, true)]);
+    assert_eq!(
+        split_utterances(&samples, RATE, 0.01, 700),
+        vec![Utterance {
+            start: 0,
+            end: samples.len(),
+        }]
+    );
+}
+
+#[test]
+fn drops_short_noise_and_silent_tracks() {
+    assert!(split_utterances(&track(&[(2000, false)]), RATE, 0.01, 700).is_empty());
+    let samples = track(&[(1000, false), (100, true), (1000, false)]);
+    assert!(split_utterances(&samples, RATE, 0.01, 700).is_empty());
+}
+
+#[test]
+fn interleaves_lines_by_start_time() {
+    let transcript = DialogueTranscript::new(
+        "interview-1".to_string(),
+        1_700_000_000,
+        90_000,
+        vec![
+            line(Speaker::Me, 0, "你好，先做个自我介绍吧"),
+            line(Speaker::Me, 65_000, "明白了"),
+            line(Speaker::Other, 3_000, "好的，我叫张三"),
+        ],
+    );
+    assert_eq!(
+        transcript.to_text(),
+        "[00:00] 我: 你好，先做个自我介绍吧\n[00:03] 对方: 好的，我叫张三\n[01:05] 我: 明白了"
+    );
+}
+
+#[test]
+fn writes_and_reads_transcripts_newest_first() {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("vhisper-transcripts-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(read_transcripts(&dir).unwrap().is_empty());
+
+    let older = DialogueTranscript::new(
+        "interview-1".to_string(),
+        1_700_000_000,
+        1000,
+        vec![line(Speaker::Other, 0, "喂")],
+    );
+    let newer = DialogueTranscript::new("interview-2".to_string(), 1_700_000_100, 0, Vec::new());
+    write_transcript(&dir, &older).unwrap();
+    write_transcript(&dir, &newer).unwrap();
+    assert_eq!(read_transcripts(&dir).unwrap(), vec![newer, older]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
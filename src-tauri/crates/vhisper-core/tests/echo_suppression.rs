@@ -0,0 +1,36 @@
+//! 本机播放期间静音录音
+//!
+//! 状态是全局的，放在同一个测试中按顺序检查
+
+use std::time::Duration;
+
+use vhisper_core::audio::{
+    begin_playback, is_capture_suppressed, set_echo_suppression, suppress_for,
+};
+
+#[test]
+fn suppresses_capture_during_playback() {
+    set_echo_suppression(true);
+    assert!(!is_capture_suppressed());
+
+    // 守卫存活期间静音，释放后还有一小段余响时间
+    let guard = begin_playback();
+    assert!(is_capture_suppressed());
+    drop(guard);
+    assert!(is_capture_suppressed());
+    std::thread::sleep(Duration::from_millis(400));
+    assert!(!is_capture_suppressed());
+
+    // 按预计时长静音
+    suppress_for(Duration::from_millis(100));
+    assert!(is_capture_suppressed());
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(!is_capture_suppressed());
+
+    // 关闭后不静音
+    set_echo_suppression(false);
+    let _guard = begin_playback();
+    assert!(!is_capture_suppressed());
+    set_echo_suppression(true);
+    assert!(is_capture_suppressed());
+}
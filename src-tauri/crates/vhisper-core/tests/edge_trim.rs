@@ -0,0 +1,67 @@
+//! 录音首尾按键声的裁剪和脉冲静音
+
+use vhisper_core::audio::{suppress_edge_transients, trim_edges};
+
+const SAMPLE_RATE: u32 = 16000;
+
+fn ms(ms: usize) -> usize {
+    SAMPLE_RATE as usize * ms / 1000
+}
+
+/// 持续的"语音"（正弦波）
+fn tone(len: usize, amplitude: f32) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 0.2).sin())
+        .collect()
+}
+
+#[test]
+fn trims_both_edges() {
+    let samples: Vec<f32> = (0..ms(1000)).map(|i| i as f32).collect();
+    let trimmed = trim_edges(&samples, SAMPLE_RATE, 80);
+    assert_eq!(trimmed.len(), ms(1000) - 2 * ms(80));
+    assert_eq!(trimmed[0], ms(80) as f32);
+}
+
+#[test]
+fn short_recordings_are_not_trimmed() {
+    let samples = vec![0.1; ms(300)];
+    assert_eq!(trim_edges(&samples, SAMPLE_RATE, 80).len(), samples.len());
+    assert_eq!(trim_edges(&samples, SAMPLE_RATE, 0).len(), samples.len());
+}
+
+#[test]
+fn silences_isolated_click_at_start() {
+    // 20ms 静音，10ms 按键声，80ms 静音，然后是 1 秒语音
+    let mut samples = vec![0.0; ms(20)];
+    samples.extend(vec![0.8; ms(10)]);
+    samples.extend(vec![0.0; ms(80)]);
+    samples.extend(tone(ms(1000), 0.5));
+    let speech = samples[ms(110)..].to_vec();
+
+    assert!(suppress_edge_transients(&mut samples, SAMPLE_RATE) > 0);
+    assert!(samples[..ms(110)].iter().all(|s| *s == 0.0));
+    assert_eq!(&samples[ms(110)..], &speech[..]);
+}
+
+#[test]
+fn silences_isolated_click_at_end() {
+    let mut samples = tone(ms(1000), 0.5);
+    samples.extend(vec![0.0; ms(100)]);
+    samples.extend(vec![-0.9; ms(5)]);
+    samples.extend(vec![0.0; ms(30)]);
+
+    assert!(suppress_edge_transients(&mut samples, SAMPLE_RATE) > 0);
+    assert!(samples[ms(1000)..].iter().all(|s| *s == 0.0));
+}
+
+#[test]
+fn keeps_speech_onset_and_ending() {
+    let mut samples = vec![0.0; ms(50)];
+    samples.extend(tone(ms(1000), 0.5));
+    samples.extend(vec![0.0; ms(50)]);
+    let original = samples.clone();
+
+    assert_eq!(suppress_edge_transients(&mut samples, SAMPLE_RATE), 0);
+    assert_eq!(samples, original);
+}
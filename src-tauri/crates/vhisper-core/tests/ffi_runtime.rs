@@ -0,0 +1,19 @@
+//! FFI 使用宿主提供的 tokio runtime
+#![cfg(feature = "ffi")]
+
+use vhisper_core::ffi::{set_runtime, vhisper_set_runtime_threads};
+
+#[test]
+fn host_runtime_replaces_own_runtime() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    assert!(set_runtime(runtime.handle().clone()));
+    // 只能设置一次
+    assert!(!set_runtime(runtime.handle().clone()));
+    // 使用宿主 runtime 时不再创建自有 runtime，线程数设置无效
+    assert_eq!(vhisper_set_runtime_threads(2), -1);
+}
@@ -0,0 +1,73 @@
+//! 每日目标：按本地日期汇总词数并计算连续天数
+
+use vhisper_core::history::{goal_progress, SessionRecord};
+
+const DAY: u64 = 24 * 60 * 60;
+/// 某天 UTC 00:00
+const BASE: u64 = 1_700_006_400;
+
+fn record(day: u64, hour: u64, words: usize) -> SessionRecord {
+    SessionRecord {
+        session_id: format!("{}-{}", day, hour),
+        finished_at: BASE + day * DAY + hour * 60 * 60,
+        words,
+        duration_ms: 10_000,
+        wpm: 0.0,
+    }
+}
+
+#[test]
+fn counts_consecutive_days_meeting_goal() {
+    let records = vec![
+        record(0, 9, 600),
+        // 第 1 天未达标，中断连续
+        record(1, 9, 100),
+        record(2, 9, 300),
+        record(2, 18, 300),
+        record(3, 9, 500),
+        record(4, 9, 800),
+    ];
+
+    let progress = goal_progress(&records, 500, BASE + 4 * DAY + 20 * 60 * 60, 0);
+    assert_eq!(progress.today_words, 800);
+    assert!(progress.goal_met_today);
+    assert_eq!(progress.current_streak, 3);
+    assert_eq!(progress.longest_streak, 3);
+}
+
+#[test]
+fn unmet_today_does_not_break_streak() {
+    let records = vec![record(0, 9, 500), record(1, 9, 500), record(2, 9, 50)];
+
+    let progress = goal_progress(&records, 500, BASE + 2 * DAY + 12 * 60 * 60, 0);
+    assert_eq!(progress.today_words, 50);
+    assert!(!progress.goal_met_today);
+    assert_eq!(progress.current_streak, 2);
+
+    // 隔了一整天没达标则中断
+    let progress = goal_progress(&records, 500, BASE + 3 * DAY, 0);
+    assert_eq!(progress.current_streak, 0);
+    assert_eq!(progress.longest_streak, 2);
+}
+
+#[test]
+fn without_goal_any_dictation_counts() {
+    let records = vec![record(0, 9, 1), record(1, 9, 1)];
+
+    let progress = goal_progress(&records, 0, BASE + DAY + 10 * 60 * 60, 0);
+    assert!(progress.goal_met_today);
+    assert_eq!(progress.current_streak, 2);
+}
+
+#[test]
+fn groups_by_local_date() {
+    // UTC 23:00 在 UTC+8 是第二天 07:00
+    let records = vec![record(0, 23, 400), record(1, 9, 400)];
+
+    let utc = goal_progress(&records, 500, BASE + DAY + 12 * 60 * 60, 0);
+    assert_eq!(utc.today_words, 400);
+
+    let beijing = goal_progress(&records, 500, BASE + DAY + 12 * 60 * 60, 8 * 60 * 60);
+    assert_eq!(beijing.today_words, 800);
+    assert!(beijing.goal_met_today);
+}
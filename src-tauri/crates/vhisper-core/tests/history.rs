@@ -0,0 +1,53 @@
+//! 听写历史：追加记录和读取
+
+use std::path::PathBuf;
+
+use vhisper_core::history::{append_record, read_records, SessionRecord};
+
+fn temp_history(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "vhisper-history-{}-{}.jsonl",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn record(session_id: &str, words: usize) -> SessionRecord {
+    SessionRecord {
+        session_id: session_id.to_string(),
+        finished_at: 1_700_000_000,
+        words,
+        duration_ms: 30_000,
+        wpm: words as f32 * 2.0,
+    }
+}
+
+#[test]
+fn appends_and_reads_records_in_order() {
+    let path = temp_history("order");
+    assert!(read_records(&path).unwrap().is_empty());
+
+    append_record(&path, &record("a", 10)).unwrap();
+    append_record(&path, &record("b", 20)).unwrap();
+    assert_eq!(
+        read_records(&path).unwrap(),
+        vec![record("a", 10), record("b", 20)]
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn skips_truncated_lines() {
+    let path = temp_history("truncated");
+    append_record(&path, &record("a", 10)).unwrap();
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, b"{\"session_id\":\"b\",\"fin"))
+        .unwrap();
+
+    assert_eq!(read_records(&path).unwrap(), vec![record("a", 10)]);
+    let _ = std::fs::remove_file(&path);
+}
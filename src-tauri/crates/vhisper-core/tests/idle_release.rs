@@ -0,0 +1,39 @@
+//! 空闲时释放资源
+
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use vhisper_core::{AppConfig, AudioRecorder, PipelineRegistry, VoicePipeline};
+
+fn config(enabled: bool, idle_release_secs: u64) -> Arc<RwLock<AppConfig>> {
+    let mut config = AppConfig::default();
+    config.energy_saver.enabled = enabled;
+    config.energy_saver.idle_release_secs = idle_release_secs;
+    Arc::new(RwLock::new(config))
+}
+
+#[test]
+fn idle_pipeline_releases_once_until_next_session() {
+    let pipeline = VoicePipeline::with_recorder(config(true, 0), AudioRecorder::external());
+    assert!(pipeline.idle_duration().is_some());
+
+    assert!(pipeline.release_if_idle());
+    // 没有新会话时不重复释放
+    assert!(!pipeline.release_if_idle());
+
+    pipeline.start_recording().unwrap();
+    assert!(pipeline.idle_duration().is_none());
+    assert!(!pipeline.release_if_idle());
+
+    pipeline.cancel().unwrap();
+    assert!(pipeline.release_if_idle());
+}
+
+#[test]
+fn release_respects_timeout_and_opt_out() {
+    let pipeline = VoicePipeline::with_recorder(config(true, 3600), AudioRecorder::external());
+    assert!(!pipeline.release_if_idle());
+
+    let registry = PipelineRegistry::new(config(false, 0));
+    assert!(!registry.release_if_idle());
+}
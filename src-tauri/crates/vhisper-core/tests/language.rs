@@ -0,0 +1,41 @@
+//! 识别语言：自动检测时文本修正按检测结果选择语言
+
+use vhisper_core::config::settings::AUTO_LANGUAGE;
+use vhisper_core::AppConfig;
+
+#[test]
+fn auto_language_leaves_llm_language_unset() {
+    let mut config = AppConfig::default();
+    config.apply_language(AUTO_LANGUAGE);
+    assert_eq!(config.asr.language.as_deref(), Some(AUTO_LANGUAGE));
+    assert_eq!(config.llm.language, None);
+
+    config.apply_detected_language(Some("en"));
+    assert_eq!(config.llm.language.as_deref(), Some("en"));
+}
+
+#[test]
+fn detected_language_does_not_override_configured_language() {
+    let mut config = AppConfig::default();
+    config.apply_language("ja");
+    config.apply_detected_language(Some("en"));
+    assert_eq!(config.llm.language.as_deref(), Some("ja"));
+
+    // 服务商未返回语言时保持不变
+    let mut config = AppConfig::default();
+    config.apply_detected_language(None);
+    assert_eq!(config.llm.language, None);
+}
+
+#[test]
+fn language_hints_are_optional_in_saved_config() {
+    // 旧版本保存的配置没有 language_hints
+    let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+    value["asr"].as_object_mut().unwrap().remove("language_hints");
+    let config: AppConfig = serde_json::from_value(value.clone()).unwrap();
+    assert!(config.asr.language_hints.is_empty());
+
+    value["asr"]["language_hints"] = serde_json::json!(["zh", "en"]);
+    let config: AppConfig = serde_json::from_value(value).unwrap();
+    assert_eq!(config.asr.language_hints, vec!["zh", "en"]);
+}
@@ -0,0 +1,37 @@
+//! 最近一次听写：保留原始文本和输出文本，空结果不覆盖，取出后不能再次替换
+
+use vhisper_core::pipeline::{last_dictation, remember_dictation, take_last_dictation, Dictation};
+
+fn dictation(raw: &str, refined: &str) -> Dictation {
+    Dictation {
+        raw: raw.to_string(),
+        refined: refined.to_string(),
+    }
+}
+
+// 记录是进程内全局状态，放在同一个测试中按顺序验证
+#[test]
+fn keeps_raw_and_refined_text_of_last_dictation() {
+    assert_eq!(
+        remember_dictation(dictation("嗯 我们明天开会", "我们明天开会。")),
+        "我们明天开会。"
+    );
+    assert_eq!(
+        last_dictation(),
+        Some(dictation("嗯 我们明天开会", "我们明天开会。"))
+    );
+
+    // 没有识别到内容时保留上一次的记录
+    assert_eq!(remember_dictation(dictation("", "")), "");
+    assert_eq!(
+        last_dictation().map(|d| d.raw),
+        Some("嗯 我们明天开会".to_string())
+    );
+
+    assert_eq!(
+        take_last_dictation(),
+        Some(dictation("嗯 我们明天开会", "我们明天开会。"))
+    );
+    assert_eq!(take_last_dictation(), None);
+    assert_eq!(last_dictation(), None);
+}
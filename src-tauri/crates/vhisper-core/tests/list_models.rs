@@ -0,0 +1,43 @@
+//! 查询服务商可用的模型：Ollama 通过 /api/tags 列出已安装的模型
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vhisper_core::list_llm_models;
+
+/// 接受一个请求，返回给定的 JSON 响应和收到的请求行
+async fn serve_json_once(listener: TcpListener, body: &'static str) -> String {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.ends_with(b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+    }
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    let request = String::from_utf8_lossy(&request).into_owned();
+    request.lines().next().unwrap_or_default().to_string()
+}
+
+#[tokio::test]
+async fn lists_installed_ollama_models() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve_json_once(
+        listener,
+        r#"{"models":[{"name":"qwen3:8b"},{"name":"llama3.2:3b"}]}"#,
+    ));
+
+    let models = list_llm_models("Ollama", "", &endpoint).await.unwrap();
+    assert_eq!(models, vec!["llama3.2:3b", "qwen3:8b"]);
+    assert_eq!(server.await.unwrap(), "GET /api/tags HTTP/1.1");
+}
+
+#[tokio::test]
+async fn rejects_unknown_provider() {
+    assert!(list_llm_models("Unknown", "key", "").await.is_err());
+}
@@ -0,0 +1,60 @@
+//! LLM 预热：Ollama 发送不带 prompt 的 generate 请求加载模型
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vhisper_core::config::settings::TlsOptions;
+use vhisper_core::llm::OllamaLlm;
+use vhisper_core::LlmService;
+
+/// 接受一个请求，返回 200 和收到的原始请求
+async fn serve_once(listener: TcpListener) -> String {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if body.len() >= length {
+                break;
+            }
+        }
+        if n == 0 {
+            break;
+        }
+    }
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}")
+        .await
+        .unwrap();
+    String::from_utf8_lossy(&request).into_owned()
+}
+
+#[tokio::test]
+async fn ollama_warm_up_loads_model() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve_once(listener));
+
+    let llm = OllamaLlm::new(
+        endpoint,
+        "qwen2.5:7b".to_string(),
+        None,
+        &TlsOptions::default(),
+    )
+    .unwrap();
+    llm.warm_up().await.unwrap();
+
+    let request = server.await.unwrap();
+    assert!(request.starts_with("POST /api/generate "));
+    assert!(request.ends_with(r#"{"model":"qwen2.5:7b"}"#));
+}
@@ -0,0 +1,91 @@
+//! 仅本机识别：只能使用在本机处理音频的服务商
+
+use vhisper_core::asr::{create_streaming_asr_service, is_local_provider, resolve_provider};
+use vhisper_core::config::settings::{
+    DeepgramAsrConfig, FunAsrConfig, SherpaOnnxConfig, TlsOptions,
+};
+use vhisper_core::{create_asr_service, AppConfig};
+
+fn funasr(endpoint: &str) -> Option<FunAsrConfig> {
+    Some(FunAsrConfig {
+        endpoint: endpoint.to_string(),
+        tls: TlsOptions::default(),
+    })
+}
+
+#[test]
+fn only_local_endpoints_count_as_local() {
+    let mut config = AppConfig::default();
+    config.asr.funasr = funasr("ws://localhost:10096");
+    assert!(is_local_provider(&config.asr, "FunAsr"));
+    assert!(is_local_provider(&config.asr, "SherpaOnnx"));
+    assert!(!is_local_provider(&config.asr, "Qwen"));
+
+    config.asr.funasr = funasr("ws://192.168.1.20:10096");
+    assert!(!is_local_provider(&config.asr, "FunAsr"));
+}
+
+#[test]
+fn rejects_cloud_provider_when_local_only() {
+    let mut config = AppConfig::default();
+    config.asr.local_only = true;
+    config.asr.provider = "Deepgram".to_string();
+    config.asr.deepgram = Some(DeepgramAsrConfig {
+        api_key: "key".to_string(),
+        model: "nova-2".to_string(),
+    });
+
+    let error = create_asr_service(&config.asr).err().unwrap();
+    assert!(error.to_string().contains("仅本机识别"));
+
+    // 关闭后可以正常创建
+    config.asr.local_only = false;
+    assert!(create_asr_service(&config.asr).is_ok());
+}
+
+#[test]
+fn skips_cloud_fallbacks_and_remote_deployments() {
+    let mut config = AppConfig::default();
+    config.asr.local_only = true;
+    config.asr.provider = "FunAsr".to_string();
+    config.asr.funasr = funasr("ws://127.0.0.1:10096");
+    config.asr.fallback_providers = vec!["Deepgram".to_string()];
+    config.asr.deepgram = Some(DeepgramAsrConfig {
+        api_key: "key".to_string(),
+        model: "nova-2".to_string(),
+    });
+    assert!(create_asr_service(&config.asr).is_ok());
+
+    // 远程部署的 FunASR 不算本机
+    config.asr.funasr = funasr("ws://asr.example.com:10096");
+    assert!(create_asr_service(&config.asr).is_err());
+}
+
+#[test]
+fn auto_considers_configured_local_models() {
+    let mut config = AppConfig::default();
+    config.asr.provider = "Auto".to_string();
+    config.asr.sherpa_onnx = Some(SherpaOnnxConfig::default());
+    assert_eq!(resolve_provider(&config.asr), "SherpaOnnx");
+}
+
+#[test]
+fn auto_falls_back_to_local_models_when_local_only() {
+    let mut config = AppConfig::default();
+    config.asr.local_only = true;
+    config.asr.provider = "Auto".to_string();
+    config.asr.deepgram = Some(DeepgramAsrConfig {
+        api_key: "key".to_string(),
+        model: "nova-2".to_string(),
+    });
+
+    // 没有本机服务商时明确提示
+    let error = create_asr_service(&config.asr).err().unwrap();
+    assert!(error.to_string().contains("没有配置本机服务商"));
+    let error = create_streaming_asr_service(&config.asr).err().unwrap();
+    assert!(error.to_string().contains("没有配置本机服务商"));
+
+    config.asr.sherpa_onnx = Some(SherpaOnnxConfig::default());
+    assert_eq!(resolve_provider(&config.asr), "SherpaOnnx");
+    assert!(create_asr_service(&config.asr).is_ok());
+}
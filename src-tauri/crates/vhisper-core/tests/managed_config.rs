@@ -0,0 +1,102 @@
+//! 管理员配置：合并到用户配置上并锁定对应的配置项
+
+use serde_json::json;
+use vhisper_core::config::storage::{
+    load_managed_layer, managed_paths, merge_layer, restore_user_values, MANAGED_CONFIG_ENV,
+};
+use vhisper_core::AppConfig;
+
+#[test]
+fn managed_layer_overrides_user_values() {
+    let mut user = serde_json::to_value(AppConfig::default()).unwrap();
+    user["asr"]["provider"] = json!("Qwen");
+    user["asr"]["vocabulary"] = json!(["Vhisper", "Tauri"]);
+    user["remote_trigger"]["enabled"] = json!(true);
+
+    let managed = json!({
+        "asr": {
+            "provider": "FunAsr",
+            "funasr": { "endpoint": "ws://asr.corp.example:10096" },
+            "vocabulary": ["Acme"],
+        },
+        "remote_trigger": { "enabled": false },
+    });
+    merge_layer(&mut user, &managed);
+
+    let config: AppConfig = serde_json::from_value(user).unwrap();
+    assert_eq!(config.asr.provider, "FunAsr");
+    // 用户没有配置过的对象由管理员配置补全，其余字段使用默认值
+    assert_eq!(
+        config.asr.funasr.unwrap().endpoint,
+        "ws://asr.corp.example:10096"
+    );
+    // 数组整体覆盖
+    assert_eq!(config.asr.vocabulary, vec!["Acme"]);
+    assert!(!config.remote_trigger.enabled);
+    // 未锁定的值保持用户的设置
+    assert_eq!(
+        config.remote_trigger.port,
+        AppConfig::default().remote_trigger.port
+    );
+
+    let mut paths = managed_paths(&managed);
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            "asr.funasr.endpoint",
+            "asr.provider",
+            "asr.vocabulary",
+            "remote_trigger.enabled",
+        ]
+    );
+}
+
+#[test]
+fn saving_keeps_user_values_for_locked_paths() {
+    let saved_user = json!({
+        "asr": { "provider": "Qwen", "vocabulary": ["Vhisper"] },
+        "remote_trigger": { "enabled": true, "port": 9000 },
+    });
+    let managed = json!({
+        "asr": {
+            "provider": "FunAsr",
+            "funasr": { "endpoint": "ws://asr.corp.example:10096" },
+        },
+        "remote_trigger": { "enabled": false },
+    });
+
+    let mut effective = saved_user.clone();
+    merge_layer(&mut effective, &managed);
+    // 设置界面上修改了未锁定的值
+    effective["asr"]["vocabulary"] = json!(["Vhisper", "Tauri"]);
+
+    restore_user_values(&mut effective, Some(&saved_user), &managed);
+    assert_eq!(effective["asr"]["provider"], "Qwen");
+    assert_eq!(effective["asr"]["vocabulary"], json!(["Vhisper", "Tauri"]));
+    // 用户没有设置过的锁定项不写入
+    assert!(effective["asr"]["funasr"].get("endpoint").is_none());
+    assert_eq!(effective["remote_trigger"]["enabled"], true);
+    assert_eq!(effective["remote_trigger"]["port"], 9000);
+}
+
+#[test]
+fn loads_managed_layer_from_env_path() {
+    let path = std::env::temp_dir().join(format!("vhisper-managed-{}.json", std::process::id()));
+    std::env::set_var(MANAGED_CONFIG_ENV, &path);
+
+    // 文件不存在时没有管理员配置
+    let _ = std::fs::remove_file(&path);
+    assert!(load_managed_layer().unwrap().is_none());
+
+    std::fs::write(&path, r#"{"asr":{"local_only":true}}"#).unwrap();
+    let layer = load_managed_layer().unwrap().unwrap();
+    assert_eq!(layer["asr"]["local_only"], true);
+
+    // 不是对象时报错
+    std::fs::write(&path, "[1, 2]").unwrap();
+    assert!(load_managed_layer().is_err());
+
+    let _ = std::fs::remove_file(&path);
+    std::env::remove_var(MANAGED_CONFIG_ENV);
+}
@@ -0,0 +1,84 @@
+//! 经由 HTTP 代理的 WebSocket 连接
+
+use std::sync::{Arc, Mutex};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use vhisper_core::net::{connect_async, set_proxy};
+
+/// 回显文本消息的 WebSocket 服务，返回地址
+async fn start_echo_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    ws.send(Message::Text(text)).await.unwrap();
+                }
+            });
+        }
+    });
+    addr
+}
+
+/// 模拟 HTTP 代理：记录 CONNECT 请求头，之后把隧道转发到 upstream（忽略请求的目标）
+async fn start_proxy(upstream: String, requests: Arc<Mutex<Vec<String>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        while let Ok((mut client, _)) = listener.accept().await {
+            let upstream = upstream.clone();
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                let mut head = Vec::new();
+                let mut byte = [0u8; 1];
+                while !head.ends_with(b"\r\n\r\n") {
+                    client.read_exact(&mut byte).await.unwrap();
+                    head.push(byte[0]);
+                }
+                requests
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&head).into_owned());
+                client
+                    .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                    .await
+                    .unwrap();
+                let mut server = TcpStream::connect(upstream).await.unwrap();
+                let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn websocket_tunnels_through_proxy_with_credentials() {
+    let upstream = start_echo_server().await;
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let proxy = start_proxy(upstream, requests.clone()).await;
+
+    set_proxy(Some(&format!("http://user:p%40ss@{}", proxy))).unwrap();
+    let (mut ws, _) = connect_async("ws://asr.example.com:8080/stream").await.unwrap();
+    ws.send(Message::text("hello")).await.unwrap();
+    let reply = ws.next().await.unwrap().unwrap();
+    assert_eq!(reply.into_text().unwrap().as_str(), "hello");
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("CONNECT asr.example.com:8080 HTTP/1.1\r\n"));
+    // user:p@ss
+    assert!(requests[0].contains("Proxy-Authorization: Basic dXNlcjpwQHNz\r\n"));
+
+    set_proxy(None).unwrap();
+}
+
+#[test]
+fn rejects_unsupported_proxy_address() {
+    assert!(set_proxy(Some("socks5://127.0.0.1:1080")).is_err());
+    assert!(set_proxy(Some("not a url")).is_err());
+}
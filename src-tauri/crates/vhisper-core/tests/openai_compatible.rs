@@ -0,0 +1,89 @@
+//! OpenAI 兼容接口：通过 base_url 接入 LM Studio、vLLM 等服务
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vhisper_core::config::settings::{LlmConfig, OpenAiLlmConfig};
+use vhisper_core::{create_llm_service, list_llm_models};
+
+/// 接受一个请求，返回给定的 JSON 响应和收到的请求头
+async fn serve_json_once(listener: TcpListener, body: &'static str) -> String {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, _)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if request.len() - head.len() - 4 >= length {
+                break;
+            }
+        }
+        if n == 0 {
+            break;
+        }
+    }
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    let request = String::from_utf8_lossy(&request).into_owned();
+    request
+        .split_once("\r\n\r\n")
+        .map(|(head, _)| head.to_string())
+        .unwrap_or(request)
+}
+
+#[tokio::test]
+async fn refines_through_custom_base_url_without_api_key() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}/v1/", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve_json_once(
+        listener,
+        r#"{"choices":[{"message":{"content":" 修正后的文本 "}}]}"#,
+    ));
+
+    let config = LlmConfig {
+        enabled: true,
+        provider: "OpenAI".to_string(),
+        openai: Some(OpenAiLlmConfig {
+            api_key: String::new(),
+            base_url,
+            model: "local-model".to_string(),
+            temperature: 0.3,
+            max_tokens: 2000,
+        }),
+        ..LlmConfig::default()
+    };
+    let llm = create_llm_service(&config).unwrap().unwrap();
+    assert_eq!(llm.refine_text("原始文本").await.unwrap(), "修正后的文本");
+
+    let head = server.await.unwrap();
+    assert!(head.starts_with("POST /v1/chat/completions HTTP/1.1"));
+    assert!(!head.to_ascii_lowercase().contains("authorization:"));
+}
+
+#[tokio::test]
+async fn lists_all_models_from_compatible_endpoint() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve_json_once(
+        listener,
+        r#"{"data":[{"id":"qwen2.5-7b-instruct"},{"id":"deepseek-chat"}]}"#,
+    ));
+
+    // 兼容服务的模型不按 OpenAI 的命名过滤
+    let models = list_llm_models("OpenAI", "", &base_url).await.unwrap();
+    assert_eq!(models, vec!["deepseek-chat", "qwen2.5-7b-instruct"]);
+    assert!(server.await.unwrap().starts_with("GET /v1/models HTTP/1.1"));
+}
@@ -0,0 +1,115 @@
+//! 通过 VoicePipelineBuilder 注入 ASR、LLM 服务和输出目标
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use vhisper_core::config::settings::{PostProcessMode, TextStageConfig, TextStageKind};
+use vhisper_core::{
+    AppConfig, AsrError, AsrResult, AsrService, AudioRecorder, LlmError, LlmService, OutputSink,
+    VoicePipeline,
+};
+
+/// 返回固定文本，记录收到的音频长度
+struct FixedAsr {
+    received: Arc<Mutex<Vec<usize>>>,
+}
+
+#[async_trait]
+impl AsrService for FixedAsr {
+    async fn recognize(&self, audio_data: &[u8], _sample_rate: u32) -> Result<AsrResult, AsrError> {
+        self.received.lock().unwrap().push(audio_data.len());
+        Ok(AsrResult {
+            text: "hello world".to_string(),
+            is_final: true,
+            segments: Vec::new(),
+            language: None,
+        })
+    }
+}
+
+/// 首字母大写并补句号，非校对模式在前面标出模式
+struct CapitalizeLlm;
+
+#[async_trait]
+impl LlmService for CapitalizeLlm {
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError> {
+        if mode != PostProcessMode::Refine {
+            return Ok(format!("[{:?}] {}", mode, text));
+        }
+        let mut chars = text.chars();
+        let first = chars.next().map(|c| c.to_uppercase().collect::<String>());
+        Ok(format!("{}{}.", first.unwrap_or_default(), chars.as_str()))
+    }
+}
+
+struct CollectSink(Arc<Mutex<Vec<String>>>);
+
+impl OutputSink for CollectSink {
+    fn output(&self, text: &str) {
+        self.0.lock().unwrap().push(text.to_string());
+    }
+}
+
+#[tokio::test]
+async fn injected_services_replace_configured_providers() {
+    // 配置中的服务商不可用（未配置 API Key、LLM 关闭），只有注入的服务参与识别
+    let mut config = AppConfig::default();
+    config.llm.enabled = false;
+    config.history.enabled = false;
+    config.text_pipeline.stages = vec![TextStageConfig::new(TextStageKind::Llm, true)];
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let outputs = Arc::new(Mutex::new(Vec::new()));
+    let pipeline = VoicePipeline::builder()
+        .config(config)
+        .recorder(AudioRecorder::external())
+        .asr(Box::new(FixedAsr {
+            received: received.clone(),
+        }))
+        .llm(Box::new(CapitalizeLlm))
+        .output(Box::new(CollectSink(outputs.clone())))
+        .build()
+        .unwrap();
+
+    pipeline.start_recording().unwrap();
+    // 1 秒音频（16kHz）
+    for _ in 0..20 {
+        pipeline.feed_audio(&[0.1; 800]);
+    }
+    let text = pipeline.stop_and_process().await.unwrap();
+
+    assert_eq!(text, "Hello world.");
+    assert_eq!(received.lock().unwrap().len(), 1);
+    assert_eq!(*outputs.lock().unwrap(), vec!["Hello world.".to_string()]);
+}
+
+#[tokio::test]
+async fn mode_override_applies_to_next_session_only() {
+    let mut config = AppConfig::default();
+    config.llm.enabled = false;
+    config.history.enabled = false;
+    config.text_pipeline.stages = vec![TextStageConfig::new(TextStageKind::Llm, true)];
+
+    let pipeline = VoicePipeline::builder()
+        .config(config)
+        .recorder(AudioRecorder::external())
+        .asr(Box::new(FixedAsr {
+            received: Arc::new(Mutex::new(Vec::new())),
+        }))
+        .llm(Box::new(CapitalizeLlm))
+        .build()
+        .unwrap();
+
+    async fn dictate(pipeline: &VoicePipeline) -> String {
+        pipeline.start_recording().unwrap();
+        for _ in 0..20 {
+            pipeline.feed_audio(&[0.1; 800]);
+        }
+        pipeline.stop_and_process().await.unwrap()
+    }
+
+    pipeline.set_mode_override(Some(PostProcessMode::Summarize));
+    assert_eq!(dictate(&pipeline).await, "[Summarize] hello world");
+    assert_eq!(dictate(&pipeline).await, "Hello world.");
+}
+
@@ -0,0 +1,107 @@
+//! 后处理模式：各模式的提示词、自定义提示词，以及非校对模式下的 Guardrails
+
+use async_trait::async_trait;
+use vhisper_core::config::settings::{
+    LlmConfig, PostProcessMode, TextPipelineConfig, TextStageConfig, TextStageKind,
+};
+use vhisper_core::llm::ModePrompts;
+use vhisper_core::{LlmError, LlmService, TextPipeline};
+
+/// 返回固定的译文
+struct TranslateLlm;
+
+#[async_trait]
+impl LlmService for TranslateLlm {
+    async fn process(&self, text: &str, mode: PostProcessMode) -> Result<String, LlmError> {
+        match mode {
+            PostProcessMode::Translate => {
+                Ok("We will ship the new settings page next week.".to_string())
+            }
+            _ => Ok(text.to_string()),
+        }
+    }
+}
+
+fn llm_then_guardrails() -> TextPipelineConfig {
+    TextPipelineConfig {
+        stages: vec![
+            TextStageConfig::new(TextStageKind::Llm, true),
+            TextStageConfig::new(TextStageKind::Guardrails, true),
+        ],
+        ..TextPipelineConfig::default()
+    }
+}
+
+#[test]
+fn builtin_prompts_differ_per_mode() {
+    let prompts = ModePrompts::new(None);
+    let refine = prompts.system_prompt(PostProcessMode::Refine);
+    assert!(refine.contains("校对"));
+    assert!(prompts
+        .system_prompt(PostProcessMode::Translate)
+        .contains("翻译为 English"));
+    assert!(prompts
+        .system_prompt(PostProcessMode::Summarize)
+        .contains("总结"));
+    assert!(prompts
+        .system_prompt(PostProcessMode::Format)
+        .contains("列表"));
+}
+
+#[test]
+fn custom_prompts_and_translate_target_come_from_config() {
+    let mut config = LlmConfig {
+        translate_target: "日语".to_string(),
+        ..LlmConfig::default()
+    };
+    config.prompts.insert(
+        PostProcessMode::Summarize,
+        "用一句话总结，语言：{language}".to_string(),
+    );
+    // 空白的自定义提示词视为未设置
+    config
+        .prompts
+        .insert(PostProcessMode::Refine, "  ".to_string());
+
+    let prompts = ModePrompts::from_config(&config);
+    assert_eq!(
+        prompts.system_prompt(PostProcessMode::Summarize),
+        "用一句话总结，语言：日语"
+    );
+    assert!(prompts
+        .system_prompt(PostProcessMode::Translate)
+        .contains("翻译为 日语"));
+    assert_eq!(
+        prompts.system_prompt(PostProcessMode::Refine),
+        ModePrompts::new(None).system_prompt(PostProcessMode::Refine)
+    );
+}
+
+#[tokio::test]
+async fn translation_is_not_rejected_by_length_guardrails() {
+    let text = "我们下周上线新的设置页";
+
+    let translated = TextPipeline::new(llm_then_guardrails())
+        .with_llm(Box::new(TranslateLlm))
+        .with_mode(PostProcessMode::Translate)
+        .process(text)
+        .await;
+    assert_eq!(translated, "We will ship the new settings page next week.");
+}
+
+#[test]
+fn mode_config_round_trips_through_json() {
+    let json = r#"{"mode":"Translate","prompts":{"Format":"整理"},"translate_target":"French"}"#;
+    let config: LlmConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.mode, PostProcessMode::Translate);
+    assert_eq!(
+        config.prompts.get(&PostProcessMode::Format).unwrap(),
+        "整理"
+    );
+    assert_eq!(config.translate_target, "French");
+
+    let defaults: LlmConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(defaults.mode, PostProcessMode::Refine);
+    assert_eq!(defaults.translate_target, "English");
+    assert!(!PostProcessMode::Summarize.works_per_sentence());
+}
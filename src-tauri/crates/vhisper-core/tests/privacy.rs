@@ -0,0 +1,28 @@
+//! 隐私模式
+
+use vhisper_core::pipeline::TranscriptContext;
+use vhisper_core::{is_incognito, set_incognito, EventPayload, VhisperEvent};
+
+// 隐私模式是全局状态，放在同一个测试中避免并发的测试互相影响
+#[test]
+fn incognito_marks_events_and_skips_context() {
+    let context = TranscriptContext::new();
+    context.push("公开的内容");
+
+    assert!(set_incognito(true));
+    assert!(!set_incognito(true));
+    assert!(is_incognito());
+
+    let event = VhisperEvent::new(Some("s1".to_string()), EventPayload::RecordingStarted);
+    assert!(event.incognito);
+    assert!(event.to_json().contains("\"incognito\":true"));
+    context.push("敏感的内容");
+
+    assert!(set_incognito(false));
+    let event = VhisperEvent::new(None, EventPayload::RecordingStarted);
+    assert!(!event.to_json().contains("incognito"));
+
+    let prompt = context.prompt(100).unwrap();
+    assert!(prompt.contains("公开的内容"));
+    assert!(!prompt.contains("敏感的内容"));
+}
@@ -0,0 +1,76 @@
+//! sherpa-onnx 非流式识别：整段音频中的每一句都要返回
+#![cfg(feature = "sherpa-onnx")]
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+use vhisper_core::asr::SherpaOnnxAsr;
+use vhisper_core::config::settings::SherpaOnnxConfig;
+use vhisper_core::AsrService;
+
+const SENTENCES: [&str; 2] = ["第一句。", "第二句。"];
+
+/// 模拟 sherpa-onnx 服务端：有声音频之后遇到静音即返回一个端点，
+/// 收到 "Done" 后返回当前句子并关闭连接
+async fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            // 启动前的端口探测不会完成握手
+            let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                continue;
+            };
+            let mut sentences = SENTENCES.iter();
+            let mut speaking = false;
+            while let Some(Ok(msg)) = ws.next().await {
+                match msg {
+                    Message::Binary(data) => {
+                        let voiced = data
+                            .chunks_exact(4)
+                            .any(|s| f32::from_le_bytes([s[0], s[1], s[2], s[3]]) != 0.0);
+                        if speaking && !voiced {
+                            let text = sentences.next().unwrap();
+                            let result = format!(r#"{{"text":"{}","is_final":true}}"#, text);
+                            ws.send(Message::Text(result.into())).await.unwrap();
+                        }
+                        speaking = voiced;
+                    }
+                    Message::Text(text) if text.as_str() == "Done" => {
+                        if speaking {
+                            let text = sentences.next().unwrap();
+                            let result = format!(r#"{{"text":"{}","is_final":true}}"#, text);
+                            ws.send(Message::Text(result.into())).await.unwrap();
+                        }
+                        ws.close(None).await.unwrap();
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    });
+    port
+}
+
+/// 16kHz 16-bit PCM
+fn pcm(amplitude: i16, millis: usize) -> Vec<u8> {
+    let samples = 16 * millis;
+    (0..samples).flat_map(|_| amplitude.to_le_bytes()).collect()
+}
+
+#[tokio::test]
+async fn returns_every_sentence_of_a_recording() {
+    let port = start_server().await;
+    let asr = SherpaOnnxAsr::new(SherpaOnnxConfig {
+        port,
+        ..SherpaOnnxConfig::default()
+    });
+
+    let mut audio = pcm(3000, 500);
+    audio.extend(pcm(0, 500));
+    audio.extend(pcm(3000, 500));
+
+    let result = asr.recognize(&audio, 16000).await.unwrap();
+    assert_eq!(result.text, "第一句。第二句。");
+}
@@ -0,0 +1,34 @@
+use vhisper_core::asr::AsrSegment;
+use vhisper_core::label_speakers;
+
+fn segment(text: &str, speaker: Option<u32>) -> AsrSegment {
+    AsrSegment {
+        text: text.to_string(),
+        start_ms: 0,
+        end_ms: 0,
+        speaker,
+    }
+}
+
+#[test]
+fn merges_consecutive_segments_per_speaker() {
+    let segments = [
+        segment("Hello everyone.", Some(1)),
+        segment("Let's start.", Some(1)),
+        segment("好的，", Some(2)),
+        segment("我先说。", Some(2)),
+        segment("Thanks.", Some(1)),
+    ];
+
+    assert_eq!(
+        label_speakers(&segments).as_deref(),
+        Some("Speaker 1: Hello everyone. Let's start.\nSpeaker 2: 好的，我先说。\nSpeaker 1: Thanks.")
+    );
+}
+
+#[test]
+fn leaves_text_unchanged_without_speaker_labels() {
+    let segments = [segment("Hello", None), segment("world", None)];
+
+    assert_eq!(label_speakers(&segments), None);
+}
@@ -0,0 +1,102 @@
+//! 拼写检查：只修正有把握的拼错，中文、缩写和用户词典中的词保持原样
+
+use async_trait::async_trait;
+use vhisper_core::config::settings::{
+    PostProcessMode, TextPipelineConfig, TextStageConfig, TextStageKind,
+};
+use vhisper_core::text::spellcheck::resolve_dictionary;
+use vhisper_core::{LlmError, LlmService, Spellchecker, TextPipeline};
+
+const AFF: &str = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'\n";
+const DIC: &str = "9\nhello\nworld\nreceive\nthe\nmeeting\ntomorrow\nschedule\nplease\ndon't\n";
+
+fn checker() -> Spellchecker {
+    Spellchecker::new(AFF, DIC).unwrap()
+}
+
+/// 原样返回文本
+struct EchoLlm;
+
+#[async_trait]
+impl LlmService for EchoLlm {
+    async fn process(&self, text: &str, _mode: PostProcessMode) -> Result<String, LlmError> {
+        Ok(text.to_string())
+    }
+}
+
+fn spellcheck_only() -> TextPipelineConfig {
+    TextPipelineConfig {
+        stages: vec![TextStageConfig::new(TextStageKind::Spellcheck, true)],
+        ..TextPipelineConfig::default()
+    }
+}
+
+#[test]
+fn corrects_obvious_misspellings() {
+    assert_eq!(
+        checker().correct("please recieve the schedul for tomorow"),
+        "please receive the schedule for tomorrow"
+    );
+}
+
+#[test]
+fn keeps_case_punctuation_and_chinese_text() {
+    assert_eq!(
+        checker().correct("Helo, 明天的 meetting 别忘了！"),
+        "Hello, 明天的 meeting 别忘了！"
+    );
+    assert_eq!(checker().correct("don't, 'hello'"), "don't, 'hello'");
+}
+
+#[test]
+fn skips_acronyms_camel_case_short_and_distant_words() {
+    let text = "API iPhone xz qwertyuiop";
+    assert_eq!(checker().correct(text), text);
+}
+
+#[test]
+fn user_words_are_never_corrected() {
+    let checker = checker().with_user_words(&["Helo".to_string(), " ".to_string()]);
+    assert_eq!(checker.correct("helo world"), "helo world");
+}
+
+#[test]
+fn resolves_dictionary_by_language() {
+    let available = vec![
+        "de_DE".to_string(),
+        "en_GB".to_string(),
+        "en_US".to_string(),
+    ];
+    assert_eq!(resolve_dictionary("en-US", &available), Some("en_US"));
+    assert_eq!(resolve_dictionary("en", &available), Some("en_GB"));
+    assert_eq!(resolve_dictionary("DE", &available), Some("de_DE"));
+    assert_eq!(resolve_dictionary("zh", &available), None);
+}
+
+#[tokio::test]
+async fn spellcheck_stage_runs_only_without_llm() {
+    let text = "recieve the meetting";
+
+    let corrected = TextPipeline::new(spellcheck_only())
+        .with_spellchecker(checker())
+        .process(text)
+        .await;
+    assert_eq!(corrected, "receive the meeting");
+
+    let with_llm = TextPipeline::new(spellcheck_only())
+        .with_spellchecker(checker())
+        .with_llm(Box::new(EchoLlm))
+        .process(text)
+        .await;
+    assert_eq!(with_llm, text);
+}
+
+#[test]
+fn spellcheck_config_defaults_to_disabled() {
+    let config: TextPipelineConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.spellcheck.enabled);
+    assert!(config
+        .stages
+        .iter()
+        .any(|stage| stage.kind == TextStageKind::Spellcheck && stage.enabled));
+}
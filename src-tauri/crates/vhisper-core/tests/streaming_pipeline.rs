@@ -0,0 +1,472 @@
+//! 流式管道集成测试：连接模拟的通义千问实时服务，覆盖提交、重连、错误和取消
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::mock_qwen::{MockQwen, Received, Turn};
+use common::{
+    chunk, mock_config, mock_pipeline, record_events, session_events, wait_until, CHUNK_SAMPLES,
+};
+use futures_util::StreamExt;
+use std::sync::Arc;
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::RwLock as TokioRwLock;
+use vhisper_core::asr::StreamingAsrEvent;
+use vhisper_core::events::{ErrorCode, EventPayload, SessionLimit, VhisperEvent};
+use vhisper_core::{AudioRecorder, DiffSpan, PipelineEvent, PipelineState, VoicePipeline};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 收集 Final 和 Error 事件，直到通道关闭或超时
+async fn collect_results(rx: &mut Receiver<StreamingAsrEvent>) -> Vec<StreamingAsrEvent> {
+    let mut results = Vec::new();
+    while let Ok(Some(event)) = tokio::time::timeout(TIMEOUT, rx.recv()).await {
+        if !matches!(event, StreamingAsrEvent::Partial { .. }) {
+            results.push(event);
+        }
+    }
+    results
+}
+
+fn finals(events: &[StreamingAsrEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            StreamingAsrEvent::Final { text } => Some(text.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn commit_returns_final_after_all_audio() {
+    record_events();
+    let server = MockQwen::start(vec![Turn::OnCommit("你好世界")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+    for _ in 0..10 {
+        pipeline.feed_audio(&chunk());
+        tokio::time::sleep(Duration::from_millis(7)).await;
+    }
+    pipeline.stop_streaming().await.unwrap();
+
+    assert_eq!(finals(&collect_results(&mut rx).await), vec!["你好世界"]);
+
+    // 停止前写入的音频全部在 commit 之前送达
+    let received = server.received(0);
+    assert_eq!(received.first(), Some(&Received::SessionUpdate));
+    assert_eq!(received.last(), Some(&Received::Commit));
+    assert_eq!(received.iter().filter(|r| **r == Received::Commit).count(), 1);
+    assert_eq!(server.audio_bytes(), 10 * CHUNK_SAMPLES * 2);
+
+    assert!(wait_until(TIMEOUT, || pipeline.get_state() == PipelineState::Idle).await);
+    let events = session_events(&session_id);
+    assert!(matches!(events.first(), Some(EventPayload::RecordingStarted)));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, EventPayload::SessionComplete { text } if text == "你好世界")));
+}
+
+#[tokio::test]
+async fn reconnects_after_server_final_and_keeps_order() {
+    record_events();
+    let server = MockQwen::start(vec![
+        Turn::AfterAudio("第一句。"),
+        Turn::AfterAudio("第二句。"),
+        Turn::OnCommit("第三句。"),
+    ])
+    .await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+
+    // 持续送入音频，直到第三个连接建立
+    let feeding = {
+        let pipeline = pipeline.clone();
+        tokio::spawn(async move {
+            loop {
+                pipeline.feed_audio(&chunk());
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+    };
+    assert!(wait_until(TIMEOUT, || server.connection_count() == 3).await);
+    feeding.abort();
+    pipeline.stop_streaming().await.unwrap();
+
+    assert_eq!(
+        finals(&collect_results(&mut rx).await),
+        vec!["第一句。", "第二句。", "第三句。"]
+    );
+    assert!(wait_until(TIMEOUT, || {
+        session_events(&session_id).iter().any(|e| {
+            matches!(e, EventPayload::SessionComplete { text } if text == "第一句。第二句。第三句。")
+        })
+    })
+    .await);
+    assert_eq!(pipeline.get_state(), PipelineState::Idle);
+    assert!(!pipeline.is_streaming());
+}
+
+#[tokio::test]
+async fn replays_recent_audio_after_reconnect() {
+    let server = MockQwen::start(vec![Turn::AfterAudio("第一句。"), Turn::OnCommit("第二句。")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let feeding = {
+        let pipeline = pipeline.clone();
+        tokio::spawn(async move {
+            loop {
+                pipeline.feed_audio(&chunk());
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+    };
+    assert!(wait_until(TIMEOUT, || server.connection_count() == 2).await);
+    feeding.abort();
+    pipeline.stop_streaming().await.unwrap();
+    assert_eq!(finals(&collect_results(&mut rx).await), vec!["第一句。", "第二句。"]);
+
+    // 新连接先收到上一连接最后 300ms 的音频（不足 300ms 时为全部）
+    let sent_before: usize = server
+        .received(0)
+        .iter()
+        .map(|r| match r {
+            Received::Audio(len) => *len,
+            _ => 0,
+        })
+        .sum();
+    let overlap_bytes = sent_before.min(16000 * 300 / 1000 * 2);
+    assert!(overlap_bytes > 0);
+    let received = server.received(1);
+    assert_eq!(received.first(), Some(&Received::SessionUpdate));
+    assert_eq!(received.get(1), Some(&Received::Audio(overlap_bytes)));
+}
+
+#[tokio::test]
+async fn vocabulary_is_sent_as_recognition_context() {
+    let server = MockQwen::start(vec![Turn::OnCommit("Vhisper 用 Tauri 写的")]).await;
+    let mut config = mock_config(&server.url);
+    config.asr.vocabulary = vec![
+        "Vhisper".into(),
+        " Tauri ".into(),
+        "".into(),
+        "Vhisper".into(),
+    ];
+    let pipeline = Arc::new(VoicePipeline::with_recorder(
+        Arc::new(TokioRwLock::new(config)),
+        AudioRecorder::external(),
+    ));
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    pipeline.feed_audio(&chunk());
+    pipeline.stop_streaming().await.unwrap();
+
+    assert_eq!(
+        finals(&collect_results(&mut rx).await),
+        vec!["Vhisper 用 Tauri 写的"]
+    );
+    assert_eq!(server.corpus(0).as_deref(), Some("Vhisper, Tauri"));
+}
+
+#[tokio::test]
+async fn manual_turn_disables_server_vad() {
+    let server = MockQwen::start(vec![Turn::OnCommit("整段话只有一个结果")]).await;
+    let mut config = mock_config(&server.url);
+    config.asr.qwen.as_mut().unwrap().manual_turn = true;
+    let pipeline = Arc::new(VoicePipeline::with_recorder(
+        Arc::new(TokioRwLock::new(config)),
+        AudioRecorder::external(),
+    ));
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    for _ in 0..5 {
+        pipeline.feed_audio(&chunk());
+    }
+    pipeline.stop_streaming().await.unwrap();
+
+    assert_eq!(
+        finals(&collect_results(&mut rx).await),
+        vec!["整段话只有一个结果"]
+    );
+    assert!(server.turn_detection(0).is_null());
+    assert_eq!(server.received(0).last(), Some(&Received::Commit));
+
+    // 默认使用服务端 VAD
+    let server = MockQwen::start(vec![Turn::OnCommit("")]).await;
+    let pipeline = mock_pipeline(&server.url);
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    pipeline.feed_audio(&chunk());
+    pipeline.stop_streaming().await.unwrap();
+    collect_results(&mut rx).await;
+    assert_eq!(server.turn_detection(0)["type"], "server_vad");
+}
+
+#[tokio::test]
+async fn confirmed_session_config_is_reused() {
+    let server = MockQwen::start(vec![Turn::OnCommit("第一次"), Turn::OnCommit("第二次")]).await;
+    server.set_confirm_delay(Duration::from_millis(800));
+    let pipeline = mock_pipeline(&server.url);
+
+    // 第一次连接等待服务端确认
+    let started = Instant::now();
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(800));
+    pipeline.feed_audio(&chunk());
+    pipeline.stop_streaming().await.unwrap();
+    assert_eq!(finals(&collect_results(&mut rx).await), vec!["第一次"]);
+    assert!(wait_until(TIMEOUT, || pipeline.get_state() == PipelineState::Idle).await);
+
+    // 相同配置不再等待确认
+    let started = Instant::now();
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    assert!(started.elapsed() < Duration::from_millis(800));
+    pipeline.feed_audio(&chunk());
+    pipeline.stop_streaming().await.unwrap();
+    assert_eq!(finals(&collect_results(&mut rx).await), vec!["第二次"]);
+    assert_eq!(server.received(1).first(), Some(&Received::SessionUpdate));
+}
+
+#[tokio::test]
+async fn events_stream_covers_whole_session() {
+    let server = MockQwen::start(vec![Turn::OnCommit("你好世界")]).await;
+    let pipeline = mock_pipeline(&server.url);
+    let mut events = Box::pin(pipeline.events());
+
+    // 只通过事件流接收，丢弃会话的接收器
+    drop(pipeline.start_streaming().await.unwrap());
+    pipeline.feed_audio(&chunk());
+    pipeline.stop_streaming().await.unwrap();
+
+    let mut received = Vec::new();
+    while let Ok(Some(event)) = tokio::time::timeout(TIMEOUT, events.next()).await {
+        let idle = matches!(event, PipelineEvent::StateChanged(PipelineState::Idle));
+        received.push(event);
+        if idle {
+            break;
+        }
+    }
+
+    assert!(matches!(
+        received.first(),
+        Some(PipelineEvent::StateChanged(PipelineState::Recording))
+    ));
+    assert!(received
+        .iter()
+        .any(|e| matches!(e, PipelineEvent::StateChanged(PipelineState::Processing))));
+    assert!(received
+        .iter()
+        .any(|e| matches!(e, PipelineEvent::Final { text } if text == "你好世界")));
+    assert!(received.iter().any(|e| matches!(
+        e,
+        PipelineEvent::Event(VhisperEvent { payload: EventPayload::RecordingStarted, .. })
+    )));
+    assert!(matches!(
+        received.last(),
+        Some(PipelineEvent::StateChanged(PipelineState::Idle))
+    ));
+}
+
+#[tokio::test]
+async fn server_error_ends_session() {
+    record_events();
+    let server = MockQwen::start(vec![Turn::Error("quota exceeded")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+    pipeline.feed_audio(&chunk());
+
+    let results = collect_results(&mut rx).await;
+    assert!(matches!(
+        results.as_slice(),
+        [StreamingAsrEvent::Error(message)] if message.contains("quota exceeded")
+    ));
+
+    assert!(wait_until(TIMEOUT, || pipeline.get_state() == PipelineState::Idle).await);
+    assert!(!pipeline.is_streaming());
+    assert!(session_events(&session_id).iter().any(|e| matches!(
+        e,
+        EventPayload::Error { code: ErrorCode::Asr, recoverable: true, .. }
+    )));
+    assert_eq!(server.connection_count(), 1);
+}
+
+#[tokio::test]
+async fn cancel_discards_result() {
+    record_events();
+    let server = MockQwen::start(vec![Turn::OnCommit("不应该出现")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+    pipeline.feed_audio(&chunk());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    pipeline.cancel_streaming().await.unwrap();
+
+    assert!(finals(&collect_results(&mut rx).await).is_empty());
+    assert_eq!(pipeline.get_state(), PipelineState::Idle);
+    assert!(!server.received(0).contains(&Received::Commit));
+
+    let events = session_events(&session_id);
+    assert!(matches!(events.last(), Some(EventPayload::RecordingCancelled)));
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, EventPayload::SessionComplete { .. })));
+
+    // 取消后可以立即开始新会话
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    pipeline.stop_streaming().await.unwrap();
+    assert_eq!(finals(&collect_results(&mut rx).await), vec![""]);
+}
+
+#[tokio::test]
+async fn max_duration_warns_and_stops() {
+    record_events();
+    let server = MockQwen::start(vec![Turn::OnCommit("到点了")]).await;
+    let mut config = mock_config(&server.url);
+    config.asr.max_streaming_secs = 1;
+    let pipeline = Arc::new(VoicePipeline::with_recorder(
+        Arc::new(TokioRwLock::new(config)),
+        AudioRecorder::external(),
+    ));
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+    pipeline.feed_audio(&chunk());
+
+    // 不调用 stop_streaming，到达时长后自动提交
+    assert_eq!(finals(&collect_results(&mut rx).await), vec!["到点了"]);
+    assert!(wait_until(TIMEOUT, || pipeline.get_state() == PipelineState::Idle).await);
+    assert_eq!(server.received(0).last(), Some(&Received::Commit));
+
+    // 宿主随后调用停止不会重复提交
+    pipeline.stop_streaming().await.unwrap();
+    assert_eq!(
+        server.received(0).iter().filter(|r| **r == Received::Commit).count(),
+        1
+    );
+
+    let events = session_events(&session_id);
+    assert!(events.iter().any(|e| matches!(
+        e,
+        EventPayload::SessionLimitWarning { limit: SessionLimit::MaxDuration, .. }
+    )));
+    assert!(events.iter().any(|e| matches!(e, EventPayload::RecordingStopped)));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, EventPayload::SessionComplete { text } if text == "到点了")));
+}
+
+#[tokio::test]
+async fn reports_speech_activity_while_recording() {
+    record_events();
+    let server = MockQwen::start(vec![Turn::OnCommit("你好")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+
+    for _ in 0..4 {
+        pipeline.feed_audio(&chunk());
+    }
+    assert!(pipeline.speech_detected());
+    assert!(
+        wait_until(TIMEOUT, || session_events(&session_id)
+            .iter()
+            .any(|e| matches!(e, EventPayload::InputActivity { speech: true, .. })))
+        .await
+    );
+
+    // 静音超过保持时长后不再判定为说话
+    for _ in 0..8 {
+        pipeline.feed_audio(&vec![0.0; CHUNK_SAMPLES]);
+    }
+    assert!(!pipeline.speech_detected());
+
+    pipeline.stop_streaming().await.unwrap();
+    assert_eq!(finals(&collect_results(&mut rx).await), vec!["你好"]);
+    assert!(!pipeline.speech_detected());
+}
+
+#[tokio::test]
+async fn reports_word_count_and_session_stats() {
+    record_events();
+    let server = MockQwen::start(vec![Turn::OnCommit("one two three four five six")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+    // 3 秒音频
+    for _ in 0..60 {
+        pipeline.feed_audio(&chunk());
+    }
+    pipeline.stop_streaming().await.unwrap();
+    assert_eq!(
+        finals(&collect_results(&mut rx).await),
+        vec!["one two three four five six"]
+    );
+
+    assert!(
+        wait_until(TIMEOUT, || session_events(&session_id)
+            .iter()
+            .any(|e| matches!(e, EventPayload::SessionStats { .. })))
+        .await
+    );
+    let events = session_events(&session_id);
+    assert!(events.iter().any(|e| matches!(
+        e,
+        EventPayload::DictationProgress { words: 6, wpm } if (*wpm - 120.0).abs() < 0.01
+    )));
+    let stats = events.iter().find_map(|e| match e {
+        EventPayload::SessionStats {
+            words,
+            duration_ms,
+            wpm,
+        } => Some((*words, *duration_ms, *wpm)),
+        _ => None,
+    });
+    assert_eq!(stats, Some((6, 3000, 120.0)));
+}
+
+#[tokio::test]
+async fn publishes_text_diff_after_session_complete() {
+    record_events();
+    let server = MockQwen::start(vec![Turn::OnCommit("你好世界")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    let session_id = pipeline.session_id().unwrap();
+    for _ in 0..10 {
+        pipeline.feed_audio(&chunk());
+    }
+    pipeline.stop_streaming().await.unwrap();
+    collect_results(&mut rx).await;
+
+    assert!(
+        wait_until(TIMEOUT, || session_events(&session_id)
+            .iter()
+            .any(|e| matches!(e, EventPayload::TextDiff { .. })))
+        .await
+    );
+    let events = session_events(&session_id);
+    let complete = events
+        .iter()
+        .position(|e| matches!(e, EventPayload::SessionComplete { .. }))
+        .unwrap();
+    let diff = events
+        .iter()
+        .position(|e| matches!(e, EventPayload::TextDiff { .. }))
+        .unwrap();
+    assert!(complete < diff);
+    assert!(matches!(
+        &events[diff],
+        EventPayload::TextDiff { spans, changed: false }
+            if spans == &vec![DiffSpan::Equal("你好世界".to_string())]
+    ));
+}
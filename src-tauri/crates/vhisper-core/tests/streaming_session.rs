@@ -0,0 +1,113 @@
+//! 流式会话：暂停期间发送静音，提交和取消，管道的按住静音
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_qwen::{MockQwen, Turn};
+use common::{chunk, mock_pipeline};
+use tokio::sync::mpsc;
+use vhisper_core::asr::{
+    StreamingAsrEvent, StreamingControl, StreamingSession, StreamingSessionState,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn session() -> (
+    StreamingSession,
+    mpsc::Receiver<StreamingControl>,
+    mpsc::Sender<StreamingAsrEvent>,
+) {
+    let (control_tx, control_rx) = mpsc::channel(8);
+    let (event_tx, event_rx) = mpsc::channel(8);
+    (StreamingSession::new(control_tx, event_rx), control_rx, event_tx)
+}
+
+async fn next_audio(control_rx: &mut mpsc::Receiver<StreamingControl>) -> Vec<u8> {
+    match control_rx.recv().await {
+        Some(StreamingControl::Audio(data)) => data,
+        other => panic!("expected audio, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn paused_session_sends_silence() {
+    let (session, mut control_rx, _event_tx) = session();
+
+    session.send_audio(vec![1, 2, 3, 4]).await.unwrap();
+    assert_eq!(next_audio(&mut control_rx).await, vec![1, 2, 3, 4]);
+
+    assert!(session.pause());
+    assert!(!session.pause());
+    assert_eq!(session.state(), StreamingSessionState::Paused);
+    session.send_audio(vec![1, 2, 3, 4]).await.unwrap();
+    assert_eq!(next_audio(&mut control_rx).await, vec![0, 0, 0, 0]);
+
+    assert!(session.resume());
+    assert_eq!(session.state(), StreamingSessionState::Active);
+    session.send_audio(vec![5, 6]).await.unwrap();
+    assert_eq!(next_audio(&mut control_rx).await, vec![5, 6]);
+}
+
+#[tokio::test]
+async fn commit_and_cancel_update_state() {
+    let (mut session, mut control_rx, event_tx) = session();
+
+    session.commit().await.unwrap();
+    assert_eq!(session.state(), StreamingSessionState::Committed);
+    assert!(matches!(control_rx.recv().await, Some(StreamingControl::Commit)));
+
+    event_tx
+        .send(StreamingAsrEvent::Final { text: "你好".to_string() })
+        .await
+        .unwrap();
+    assert!(matches!(
+        session.next_event().await,
+        Some(StreamingAsrEvent::Final { text }) if text == "你好"
+    ));
+
+    session.cancel().await;
+    session.cancel().await;
+    assert_eq!(session.state(), StreamingSessionState::Cancelled);
+    assert!(matches!(control_rx.recv().await, Some(StreamingControl::Cancel)));
+    assert!(session.send_audio(vec![1, 2]).await.is_err());
+
+    // 服务商结束连接后为 Closed
+    drop(control_rx);
+    assert_eq!(session.state(), StreamingSessionState::Closed);
+}
+
+#[tokio::test]
+async fn pipeline_pause_keeps_session_running() {
+    let server = MockQwen::start(vec![Turn::OnCommit("你好世界")]).await;
+    let pipeline = mock_pipeline(&server.url);
+
+    // 不在流式会话中时不能暂停
+    assert!(!pipeline.pause_streaming().await);
+
+    let mut rx = pipeline.start_streaming().await.unwrap();
+    pipeline.feed_audio(&chunk());
+    assert!(pipeline.pause_streaming().await);
+    assert!(!pipeline.pause_streaming().await);
+    assert!(pipeline.is_streaming_paused());
+    assert_eq!(
+        pipeline.streaming_session_state().await,
+        Some(StreamingSessionState::Paused)
+    );
+
+    pipeline.feed_audio(&chunk());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(pipeline.resume_streaming().await);
+    assert!(!pipeline.is_streaming_paused());
+    pipeline.feed_audio(&chunk());
+    pipeline.stop_streaming().await.unwrap();
+
+    let mut finals = Vec::new();
+    while let Ok(Some(event)) = tokio::time::timeout(TIMEOUT, rx.recv()).await {
+        if let StreamingAsrEvent::Final { text } = event {
+            finals.push(text);
+        }
+    }
+    assert_eq!(finals, vec!["你好世界"]);
+    assert_eq!(server.connection_count(), 1);
+}
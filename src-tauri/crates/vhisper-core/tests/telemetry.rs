@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use vhisper_core::telemetry::{latency_bucket, TelemetryCounters, TELEMETRY_SCHEMA_VERSION};
+
+#[test]
+fn latency_buckets_cover_all_durations() {
+    assert_eq!(latency_bucket(Duration::from_millis(0)), "<0.5s");
+    assert_eq!(latency_bucket(Duration::from_millis(500)), "0.5-1s");
+    assert_eq!(latency_bucket(Duration::from_millis(1999)), "1-2s");
+    assert_eq!(latency_bucket(Duration::from_millis(4000)), "2-5s");
+    assert_eq!(latency_bucket(Duration::from_secs(30)), ">5s");
+}
+
+#[test]
+fn counters_aggregate_per_provider() {
+    let mut counters = TelemetryCounters::default();
+    assert!(counters.is_empty());
+
+    counters.record("Qwen", true, Duration::from_millis(300));
+    counters.record("Qwen", true, Duration::from_millis(400));
+    counters.record("Qwen", false, Duration::from_secs(20));
+    counters.record("Deepgram", true, Duration::from_millis(1500));
+
+    let qwen = &counters.providers["Qwen"];
+    assert_eq!(qwen.requests, 3);
+    assert_eq!(qwen.failures, 1);
+    // 失败的请求不计入延迟分段
+    assert_eq!(qwen.latency_buckets.len(), 1);
+    assert_eq!(qwen.latency_buckets["<0.5s"], 2);
+    assert_eq!(counters.providers["Deepgram"].latency_buckets["1-2s"], 1);
+}
+
+#[test]
+fn report_contains_only_aggregate_fields() {
+    let mut counters = TelemetryCounters::default();
+    counters.record("Qwen", true, Duration::from_millis(300));
+
+    let report = counters.report("1.2.3");
+    assert_eq!(report.schema_version, TELEMETRY_SCHEMA_VERSION);
+    assert_eq!(report.app_version, "1.2.3");
+    assert_eq!(report.os, std::env::consts::OS);
+
+    let value = serde_json::to_value(&report).unwrap();
+    let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+    keys.sort();
+    assert_eq!(
+        keys,
+        ["app_version", "arch", "os", "providers", "schema_version"]
+    );
+}
+
+#[test]
+fn next_report_waits_for_the_rest_of_the_interval() {
+    let interval = Duration::from_secs(24 * 60 * 60);
+    let mut counters = TelemetryCounters::default();
+    // 从未上报过时立即上报
+    assert_eq!(counters.next_report_in(interval, 1_000_000), Duration::ZERO);
+
+    counters.last_report_ms = Some(1_000_000);
+    assert_eq!(
+        counters.next_report_in(interval, 1_000_000 + 60 * 60 * 1000),
+        Duration::from_secs(23 * 60 * 60)
+    );
+    assert_eq!(
+        counters.next_report_in(interval, 1_000_000 + 2 * 24 * 60 * 60 * 1000),
+        Duration::ZERO
+    );
+
+    // 上报时间不出现在上报内容中
+    let value = serde_json::to_value(counters.report("1.2.3")).unwrap();
+    assert!(value.get("last_report_ms").is_none());
+}
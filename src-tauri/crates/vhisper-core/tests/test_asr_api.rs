@@ -0,0 +1,28 @@
+//! 测试服务商：按实际识别的方式创建当前选中的服务商
+
+use vhisper_core::config::settings::DeepgramAsrConfig;
+use vhisper_core::{test_asr_api, AppConfig, AsrError};
+
+#[tokio::test]
+async fn reports_missing_provider_config() {
+    let mut config = AppConfig::default();
+    config.asr.provider = "Deepgram".to_string();
+
+    let error = test_asr_api(&config.asr).await.unwrap_err();
+    assert!(matches!(error, AsrError::Config(_)), "{error}");
+}
+
+#[tokio::test]
+async fn respects_local_only() {
+    let mut config = AppConfig::default();
+    config.asr.local_only = true;
+    config.asr.provider = "Deepgram".to_string();
+    config.asr.deepgram = Some(DeepgramAsrConfig {
+        api_key: "key".to_string(),
+        model: "nova-2".to_string(),
+    });
+
+    // 不发出请求，直接拒绝
+    let error = test_asr_api(&config.asr).await.unwrap_err();
+    assert!(error.to_string().contains("仅本机识别"));
+}
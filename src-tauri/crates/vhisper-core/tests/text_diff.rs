@@ -0,0 +1,93 @@
+//! 原始识别文本与输出文本的差异：中文逐字、英文按词比较，拼接后能还原两边的文本
+
+use vhisper_core::{diff_text, DiffSpan};
+
+fn equal(text: &str) -> DiffSpan {
+    DiffSpan::Equal(text.to_string())
+}
+
+fn insert(text: &str) -> DiffSpan {
+    DiffSpan::Insert(text.to_string())
+}
+
+fn delete(text: &str) -> DiffSpan {
+    DiffSpan::Delete(text.to_string())
+}
+
+/// 按差异片段还原（原始文本，修改后的文本）
+fn rebuild(spans: &[DiffSpan]) -> (String, String) {
+    let mut original = String::new();
+    let mut revised = String::new();
+    for span in spans {
+        match span {
+            DiffSpan::Equal(text) => {
+                original.push_str(text);
+                revised.push_str(text);
+            }
+            DiffSpan::Delete(text) => original.push_str(text),
+            DiffSpan::Insert(text) => revised.push_str(text),
+        }
+    }
+    (original, revised)
+}
+
+#[test]
+fn compares_chinese_text_per_character() {
+    assert_eq!(
+        diff_text("嗯 我们明天开会吧", "我们明天开会。"),
+        vec![
+            delete("嗯 "),
+            equal("我们明天开会"),
+            delete("吧"),
+            insert("。"),
+        ]
+    );
+}
+
+#[test]
+fn compares_english_text_per_word() {
+    assert_eq!(
+        diff_text("hello wrld, how are you", "Hello world, how are you?"),
+        vec![
+            delete("hello"),
+            insert("Hello"),
+            equal(" "),
+            delete("wrld"),
+            insert("world"),
+            equal(", how are you"),
+            insert("?"),
+        ]
+    );
+}
+
+#[test]
+fn unchanged_and_empty_texts() {
+    assert_eq!(diff_text("一样", "一样"), vec![equal("一样")]);
+    assert_eq!(diff_text("", "新增"), vec![insert("新增")]);
+    assert_eq!(diff_text("删除", ""), vec![delete("删除")]);
+    assert!(diff_text("", "").is_empty());
+}
+
+#[test]
+fn spans_rebuild_both_texts() {
+    let raw = "那个 我觉得 this feature 应该 um 下周上线 然后 再看看 反馈";
+    let refined = "我觉得这个 feature 应该下周上线，然后再看看反馈。";
+    let spans = diff_text(raw, refined);
+    assert_eq!(rebuild(&spans), (raw.to_string(), refined.to_string()));
+    // 相邻的同类片段已合并
+    assert!(spans
+        .windows(2)
+        .all(|pair| std::mem::discriminant(&pair[0]) != std::mem::discriminant(&pair[1])));
+}
+
+#[test]
+fn serializes_spans_with_op_tag() {
+    let json = serde_json::to_value(diff_text("好的", "好的。")).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            { "op": "equal", "text": "好的" },
+            { "op": "insert", "text": "。" },
+        ])
+    );
+}
@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use vhisper_core::config::settings::{
+    PostProcessMode, TextPipelineConfig, TextReplacement, TextStageConfig, TextStageKind,
+};
+use vhisper_core::text::stages::{
+    apply_replacements, format_plain, normalize, punctuate, within_guardrails,
+};
+use vhisper_core::{LlmError, LlmService, TextPipeline};
+
+struct FixedLlm(Result<&'static str, &'static str>);
+
+#[async_trait]
+impl LlmService for FixedLlm {
+    async fn process(&self, _text: &str, _mode: PostProcessMode) -> Result<String, LlmError> {
+        self.0
+            .map(str::to_string)
+            .map_err(|e| LlmError::Api(e.to_string()))
+    }
+}
+
+fn rule(from: &str, to: &str, case_sensitive: bool) -> TextReplacement {
+    TextReplacement {
+        from: from.to_string(),
+        to: to.to_string(),
+        case_sensitive,
+    }
+}
+
+fn only(stages: &[TextStageKind]) -> TextPipelineConfig {
+    TextPipelineConfig {
+        stages: stages
+            .iter()
+            .map(|&kind| TextStageConfig::new(kind, true))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn replacements_ignore_ascii_case_by_default() {
+    let rules = [rule("json", "JSON", false), rule("杰森", "JSON", false)];
+    assert_eq!(
+        apply_replacements("返回 Json 和杰森", &rules),
+        "返回 JSON 和JSON"
+    );
+}
+
+#[test]
+fn replacements_respect_case_sensitive_rules() {
+    let rules = [rule("Go", "Golang", true), rule("", "x", false)];
+    assert_eq!(apply_replacements("Go go", &rules), "Golang go");
+}
+
+#[test]
+fn normalize_collapses_whitespace_and_full_width() {
+    assert_eq!(normalize("  ＡＰＩ　版本   ３  "), "API 版本 3");
+}
+
+#[test]
+fn normalize_removes_spaces_between_cjk() {
+    assert_eq!(normalize("今天 天气 很好 ， ok 吗"), "今天天气很好， ok 吗");
+}
+
+#[test]
+fn normalize_keeps_line_breaks() {
+    assert_eq!(
+        normalize("说话人 1： 你好 \n 说话人 2：好的\r\n\n- 第一项  \n"),
+        "说话人 1：你好\n说话人 2：好的\n\n- 第一项"
+    );
+}
+
+#[test]
+fn punctuate_converts_after_cjk() {
+    assert_eq!(punctuate("好的,明天见."), "好的，明天见。");
+    assert_eq!(punctuate("Hello, world."), "Hello, world.");
+}
+
+#[test]
+fn punctuate_keeps_file_names_and_dedupes() {
+    assert_eq!(punctuate("打开文件.txt"), "打开文件.txt");
+    assert_eq!(punctuate("真的吗？？ 好。。"), "真的吗？好。");
+    assert_eq!(
+        punctuate("说话人 1：好的。\n说话人 2：嗯"),
+        "说话人 1：好的。\n说话人 2：嗯"
+    );
+}
+
+#[test]
+fn normalize_and_punctuation_are_off_by_default() {
+    let config = TextPipelineConfig::default();
+    for kind in [TextStageKind::Normalize, TextStageKind::Punctuation] {
+        assert!(config
+            .stages
+            .iter()
+            .any(|stage| stage.kind == kind && !stage.enabled));
+    }
+}
+
+#[test]
+fn format_plain_strips_markdown() {
+    assert_eq!(format_plain("**重点**：`code`"), "重点：code");
+}
+
+#[test]
+fn guardrails_reject_empty_and_runaway_output() {
+    assert!(within_guardrails("今天天气很好", "今天天气很好。", 2.0));
+    assert!(!within_guardrails("今天天气很好", "", 2.0));
+    assert!(!within_guardrails(
+        "天气",
+        "今天的天气晴朗，气温二十五度，适合出门散步。",
+        2.0
+    ));
+}
+
+#[tokio::test]
+async fn pipeline_runs_enabled_stages_in_order() {
+    let mut config = only(&[TextStageKind::Replacements, TextStageKind::Normalize]);
+    config.replacements = vec![rule("艾皮艾", "API", false)];
+    config
+        .stages
+        .push(TextStageConfig::new(TextStageKind::Punctuation, false));
+
+    let pipeline = TextPipeline::new(config);
+    assert_eq!(pipeline.process(" 调用 艾皮艾 ,好 ").await, "调用 API ,好");
+}
+
+#[tokio::test]
+async fn pipeline_uses_llm_output() {
+    let pipeline = TextPipeline::new(only(&[TextStageKind::Llm, TextStageKind::Guardrails]))
+        .with_llm(Box::new(FixedLlm(Ok("在吗？"))));
+    assert_eq!(pipeline.process("再吗").await, "在吗？");
+}
+
+#[tokio::test]
+async fn pipeline_keeps_text_when_llm_fails() {
+    let pipeline =
+        TextPipeline::new(only(&[TextStageKind::Llm])).with_llm(Box::new(FixedLlm(Err("boom"))));
+    assert_eq!(pipeline.process("原文").await, "原文");
+}
+
+#[tokio::test]
+async fn guardrails_revert_to_llm_input() {
+    let pipeline = TextPipeline::new(only(&[
+        TextStageKind::Normalize,
+        TextStageKind::Llm,
+        TextStageKind::Guardrails,
+    ]))
+    .with_llm(Box::new(FixedLlm(Ok(
+        "好的，以下是修正后的文本，并附上详细的解释说明：天气不错。",
+    ))));
+    assert_eq!(pipeline.process(" 天气 不错 ").await, "天气不错");
+}
+
+#[tokio::test]
+async fn formatter_is_skipped_for_rich_text() {
+    let config = only(&[TextStageKind::Formatter]);
+    assert_eq!(
+        TextPipeline::new(config.clone()).process("**粗体**").await,
+        "粗体"
+    );
+    assert_eq!(
+        TextPipeline::new(config)
+            .with_rich_text(true)
+            .process("**粗体**")
+            .await,
+        "**粗体**"
+    );
+}
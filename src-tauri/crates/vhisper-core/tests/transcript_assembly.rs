@@ -0,0 +1,101 @@
+//! 流式会话文本拼接：分段之间的空格、重连补发音频造成的重复和分段时间
+
+use std::time::Duration;
+
+use vhisper_core::history::{
+    read_session_transcript, write_session_transcript, TimedTranscript, TranscriptSegment,
+};
+use vhisper_core::pipeline::SessionTranscript;
+
+fn assemble(segments: &[&str], dedup: bool) -> String {
+    let mut transcript = SessionTranscript::new().with_dedup(dedup);
+    for segment in segments {
+        transcript.push(segment);
+    }
+    transcript.text().to_string()
+}
+
+#[test]
+fn joins_segments_with_spacing_rules() {
+    assert_eq!(assemble(&["你好。", "世界"], false), "你好。世界");
+    assert_eq!(assemble(&["hello", "world"], false), "hello world");
+    assert_eq!(assemble(&["hello", ", world"], false), "hello, world");
+}
+
+#[test]
+fn removes_repeated_cjk_at_join() {
+    assert_eq!(
+        assemble(&["今天天气不错。", "不错我们出去走走"], true),
+        "今天天气不错。我们出去走走"
+    );
+}
+
+#[test]
+fn removes_repeated_words_ignoring_case_and_punctuation() {
+    assert_eq!(
+        assemble(&["Let's meet at the office.", "The office, around noon"], true),
+        "Let's meet at the office. around noon"
+    );
+}
+
+#[test]
+fn keeps_text_without_overlap() {
+    assert_eq!(assemble(&["第一句。", "第二句。"], true), "第一句。第二句。");
+    // 单个汉字重复不视为补发造成的重复
+    assert_eq!(assemble(&["今天", "天气很好"], true), "今天天气很好");
+    // 只比较完整单词，不截断单词的一部分
+    assert_eq!(assemble(&["I said no", "nothing else"], true), "I said no nothing else");
+}
+
+#[test]
+fn push_returns_deduplicated_segment() {
+    let mut transcript = SessionTranscript::new().with_dedup(true);
+    assert_eq!(transcript.push("打开设置"), "打开设置");
+    assert_eq!(transcript.push("设置页面"), "页面");
+    assert_eq!(transcript.push("页面"), "");
+    assert_eq!(transcript.text(), "打开设置页面");
+}
+
+fn segment(start_ms: u64, end_ms: u64, text: &str) -> TranscriptSegment {
+    TranscriptSegment {
+        start_ms,
+        end_ms,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn push_at_records_consecutive_segment_times() {
+    let mut transcript = SessionTranscript::new().with_dedup(true);
+    transcript.push_at("打开设置", Duration::from_millis(2_500));
+    transcript.push_at("设置", Duration::from_millis(3_000));
+    transcript.push_at("设置页面", Duration::from_millis(6_000));
+    assert_eq!(
+        transcript.segments(),
+        &[segment(0, 2_500, "打开设置"), segment(2_500, 6_000, "页面")]
+    );
+    // push 追加的段不记录时间
+    transcript.push("完成");
+    assert_eq!(transcript.segments().len(), 2);
+}
+
+#[test]
+fn stores_session_transcript_by_id() {
+    let dir = std::env::temp_dir().join(format!("vhisper-sessions-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(read_session_transcript(&dir, "abc-123").unwrap(), None);
+
+    let record = TimedTranscript {
+        session_id: "abc-123".to_string(),
+        finished_at: 1_700_000_000,
+        segments: vec![segment(0, 1_000, "第一段"), segment(1_000, 2_000, "第二段")],
+    };
+    write_session_transcript(&dir, &record).unwrap();
+    assert_eq!(
+        read_session_transcript(&dir, "abc-123").unwrap(),
+        Some(record)
+    );
+    // 拒绝可能拼出目录外路径的 ID
+    assert!(read_session_transcript(&dir, "../abc").is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,74 @@
+//! 检查更新：版本比较和按通道选择新版本
+
+use std::cmp::Ordering;
+
+use vhisper_core::config::settings::{UpdateChannel, UpdateConfig};
+use vhisper_core::update::{compare_versions, select_update, GithubRelease};
+
+fn release(tag: &str, prerelease: bool) -> GithubRelease {
+    serde_json::from_value(serde_json::json!({
+        "tag_name": tag,
+        "name": format!("Vhisper {}", tag),
+        "html_url": format!("https://github.com/JobinJia/Vhisper/releases/tag/{}", tag),
+        "prerelease": prerelease,
+        "assets": [],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn compares_semver_with_prerelease() {
+    assert_eq!(compare_versions("v1.0.7", "1.0.6"), Some(Ordering::Greater));
+    assert_eq!(
+        compare_versions("1.0.7", "1.0.7-beta.1"),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        compare_versions("1.0.7-beta.10", "1.0.7-beta.2"),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        compare_versions("1.0.7-alpha", "1.0.7-beta"),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        compare_versions("1.0.7+build.5", "1.0.7"),
+        Some(Ordering::Equal)
+    );
+    assert_eq!(compare_versions("latest", "1.0.7"), None);
+}
+
+#[test]
+fn stable_channel_ignores_prereleases() {
+    let releases = vec![
+        release("v1.1.0-beta.1", true),
+        release("v1.0.8", false),
+        release("v1.0.7", false),
+    ];
+
+    let update = select_update(&releases, UpdateChannel::Stable, "1.0.7").unwrap();
+    assert_eq!(update.version, "1.0.8");
+    assert_eq!(update.current_version, "1.0.7");
+    assert!(!update.prerelease);
+
+    let update = select_update(&releases, UpdateChannel::Beta, "1.0.7").unwrap();
+    assert_eq!(update.version, "1.1.0-beta.1");
+    assert!(update.prerelease);
+
+    // 已是最新
+    assert!(select_update(&releases, UpdateChannel::Stable, "1.0.8").is_none());
+}
+
+#[test]
+fn beta_build_is_offered_the_final_release() {
+    let releases = vec![release("v1.0.7", false), release("v1.0.7-beta.1", true)];
+    let update = select_update(&releases, UpdateChannel::Stable, "1.0.7-beta.1").unwrap();
+    assert_eq!(update.version, "1.0.7");
+}
+
+#[test]
+fn startup_check_is_off_by_default() {
+    let config: UpdateConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.check_on_startup);
+    assert!(!UpdateConfig::default().check_on_startup);
+}
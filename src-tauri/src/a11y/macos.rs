@@ -0,0 +1,50 @@
+use core_foundation::base::{CFTypeRef, TCFType};
+use core_foundation::dictionary::CFDictionary;
+use core_foundation::number::CFNumber;
+use core_foundation::string::{CFString, CFStringRef};
+use objc2::runtime::AnyObject;
+use objc2::{class, msg_send};
+
+/// NSAccessibilityPriorityHigh
+const PRIORITY_HIGH: i64 = 90;
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSAccessibilityPostNotificationWithUserInfo(
+        element: *mut AnyObject,
+        notification: CFStringRef,
+        user_info: CFTypeRef,
+    );
+    static NSAccessibilityAnnouncementRequestedNotification: CFStringRef;
+    static NSAccessibilityAnnouncementKey: CFStringRef;
+    static NSAccessibilityPriorityKey: CFStringRef;
+}
+
+/// 通过 NSAccessibilityAnnouncementRequestedNotification 让 VoiceOver 播报
+pub fn announce(message: &str) {
+    unsafe {
+        let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+        if app.is_null() {
+            return;
+        }
+
+        let announcement_key = CFString::wrap_under_get_rule(NSAccessibilityAnnouncementKey);
+        let priority_key = CFString::wrap_under_get_rule(NSAccessibilityPriorityKey);
+        let user_info = CFDictionary::from_CFType_pairs(&[
+            (
+                announcement_key.as_CFType(),
+                CFString::new(message).as_CFType(),
+            ),
+            (
+                priority_key.as_CFType(),
+                CFNumber::from(PRIORITY_HIGH).as_CFType(),
+            ),
+        ]);
+
+        NSAccessibilityPostNotificationWithUserInfo(
+            app,
+            NSAccessibilityAnnouncementRequestedNotification,
+            user_info.as_CFTypeRef(),
+        );
+    }
+}
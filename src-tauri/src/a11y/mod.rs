@@ -0,0 +1,68 @@
+//! 屏幕阅读器播报
+//!
+//! 在录音、识别、输出等状态变化时通过 VoiceOver / NVDA 播报，
+//! 让不看悬浮窗的用户也能知道当前状态
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+/// 预计播报时长：屏幕阅读器不通知播报结束，按文字长度估算（中文约每字 200 毫秒）
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn estimated_duration(message: &str) -> std::time::Duration {
+    std::time::Duration::from_millis(300 + 200 * message.chars().count() as u64)
+}
+
+/// 播报消息（不检查配置）
+pub fn announce(app_handle: &AppHandle, message: &str) {
+    tracing::debug!("Accessibility announcement: {}", message);
+    // 播报声音会被麦克风录进去，期间录音静音
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    vhisper_core::audio::suppress_for(estimated_duration(message));
+
+    #[cfg(target_os = "macos")]
+    {
+        // AppKit 调用需要在主线程
+        let message = message.to_string();
+        let _ = app_handle.run_on_main_thread(move || macos::announce(&message));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = app_handle;
+        windows::announce(message);
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app_handle;
+    }
+}
+
+/// 在配置启用时播报
+pub fn announce_if_enabled(app_handle: &AppHandle, message: &str) {
+    let state = app_handle.state::<AppState>();
+    // 可能在异步上下文中调用，拿不到锁时跳过本次播报
+    let enabled = state
+        .config
+        .try_read()
+        .map(|config| config.accessibility.announcements)
+        .unwrap_or(false);
+
+    if enabled {
+        announce(app_handle, message);
+    }
+}
+
+/// 输出完成后的播报文案
+pub fn completion_message(text: &str) -> String {
+    match vhisper_core::count_words(text) {
+        0 => "没有识别到内容".to_string(),
+        n => format!("已输出 {} 个词", n),
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::OnceLock;
+
+use windows::core::{s, w};
+use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+/// nvdaController_speakText(const wchar_t *text)
+type SpeakTextFn = unsafe extern "system" fn(text: *const u16) -> u32;
+
+/// 加载 NVDA Controller Client 并查找播报函数，只在首次播报时加载一次
+///
+/// 库在进程生命周期内保持加载，函数指针一直有效
+fn speak_text_fn() -> Option<SpeakTextFn> {
+    static SPEAK_TEXT: OnceLock<Option<SpeakTextFn>> = OnceLock::new();
+    *SPEAK_TEXT.get_or_init(|| unsafe {
+        let library = match LoadLibraryW(w!("nvdaControllerClient.dll")) {
+            Ok(library) => library,
+            Err(e) => {
+                tracing::debug!("NVDA controller client not available: {}", e);
+                return None;
+            }
+        };
+
+        let proc = GetProcAddress(library, s!("nvdaController_speakText"))?;
+        Some(std::mem::transmute::<_, SpeakTextFn>(proc))
+    })
+}
+
+/// 通过 NVDA Controller Client 播报
+///
+/// 需要 nvdaControllerClient.dll 与程序放在一起；未安装或 NVDA 未运行时静默跳过
+pub fn announce(message: &str) {
+    let Some(speak_text) = speak_text_fn() else {
+        return;
+    };
+
+    let wide: Vec<u16> = message.encode_utf16().chain(std::iter::once(0)).collect();
+    // SAFETY: wide 以 0 结尾，调用期间有效
+    let result = unsafe { speak_text(wide.as_ptr()) };
+    if result != 0 {
+        tracing::debug!("nvdaController_speakText failed: {}", result);
+    }
+}
@@ -0,0 +1,121 @@
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use block2::RcBlock;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, Bool};
+use objc2::{class, msg_send, msg_send_id, sel};
+use vhisper_core::calendar::CalendarEvent;
+
+use crate::permissions::PermissionState;
+
+#[link(name = "EventKit", kind = "framework")]
+extern "C" {}
+
+/// EKEntityTypeEvent
+const ENTITY_TYPE_EVENT: usize = 0;
+/// 等待用户在授权对话框中选择的最长时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 日历访问权限（EKAuthorizationStatus）
+pub fn authorization() -> PermissionState {
+    let status: isize = unsafe {
+        msg_send![
+            class!(EKEventStore),
+            authorizationStatusForEntityType: ENTITY_TYPE_EVENT
+        ]
+    };
+    match status {
+        0 => PermissionState::NotDetermined,
+        1 => PermissionState::Restricted,
+        3 => PermissionState::Granted,
+        // 2 为拒绝，4 为只能写入（无法读取事件）
+        _ => PermissionState::Denied,
+    }
+}
+
+/// 弹出日历授权对话框，等待用户选择
+pub fn request_access() -> bool {
+    let (tx, rx) = mpsc::channel();
+    let completion = RcBlock::new(move |granted: Bool, _error: *mut AnyObject| {
+        let _ = tx.send(granted.as_bool());
+    });
+
+    unsafe {
+        let store: Retained<AnyObject> = msg_send_id![class!(EKEventStore), new];
+        // macOS 14 起需要请求完整访问权限
+        let full_access: bool = msg_send![
+            &store,
+            respondsToSelector: sel!(requestFullAccessToEventsWithCompletion:)
+        ];
+        if full_access {
+            let _: () = msg_send![&store, requestFullAccessToEventsWithCompletion: &*completion];
+        } else {
+            let _: () = msg_send![
+                &store,
+                requestAccessToEntityType: ENTITY_TYPE_EVENT,
+                completion: &*completion
+            ];
+        }
+    }
+    rx.recv_timeout(REQUEST_TIMEOUT).unwrap_or(false)
+}
+
+/// NSString 转为 String
+unsafe fn to_string(string: &AnyObject) -> String {
+    let utf8: *const c_char = msg_send![string, UTF8String];
+    if utf8.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(utf8).to_string_lossy().into_owned()
+}
+
+/// NSDate 转为 Unix 时间戳
+unsafe fn to_timestamp(date: &AnyObject) -> u64 {
+    let secs: f64 = msg_send![date, timeIntervalSince1970];
+    secs.max(0.0) as u64
+}
+
+/// 读取所有日历中与 [from, to) 有重叠的事件
+pub fn events_between(from: u64, to: u64) -> Result<Vec<CalendarEvent>, String> {
+    unsafe {
+        let store: Retained<AnyObject> = msg_send_id![class!(EKEventStore), new];
+        let start: Retained<AnyObject> =
+            msg_send_id![class!(NSDate), dateWithTimeIntervalSince1970: from as f64];
+        let end: Retained<AnyObject> =
+            msg_send_id![class!(NSDate), dateWithTimeIntervalSince1970: to as f64];
+        let all_calendars: *mut AnyObject = std::ptr::null_mut();
+        let predicate: Retained<AnyObject> = msg_send_id![
+            &store,
+            predicateForEventsWithStartDate: &*start,
+            endDate: &*end,
+            calendars: all_calendars
+        ];
+        let events: Option<Retained<AnyObject>> =
+            msg_send_id![&store, eventsMatchingPredicate: &*predicate];
+        let Some(events) = events else {
+            return Ok(Vec::new());
+        };
+
+        let count: usize = msg_send![&events, count];
+        let mut result = Vec::with_capacity(count);
+        for index in 0..count {
+            let event: Retained<AnyObject> = msg_send_id![&events, objectAtIndex: index];
+            let id: Option<Retained<AnyObject>> = msg_send_id![&event, eventIdentifier];
+            let title: Option<Retained<AnyObject>> = msg_send_id![&event, title];
+            let start: Retained<AnyObject> = msg_send_id![&event, startDate];
+            let end: Retained<AnyObject> = msg_send_id![&event, endDate];
+            let all_day: bool = msg_send![&event, isAllDay];
+            result.push(CalendarEvent {
+                id: id.map(|id| to_string(&id)).unwrap_or_default(),
+                title: title.map(|title| to_string(&title)).unwrap_or_default(),
+                start: to_timestamp(&start),
+                end: to_timestamp(&end),
+                all_day,
+            });
+        }
+        Ok(result)
+    }
+}
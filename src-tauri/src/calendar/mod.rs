@@ -0,0 +1,127 @@
+//! 日历会议提醒
+//!
+//! 用户开启（`calendar.enabled`）并授予日历权限后，后台定期读取本机日历
+//! （macOS 日历、Windows 上正在运行的 Outlook）。会议即将开始时显示主窗口并发送
+//! `meeting_starting` 事件，由界面询问是否开始会议记录（访谈录音）
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use vhisper_core::calendar::{CalendarEvent, MeetingReminder};
+use vhisper_core::EventPayload;
+
+use crate::permissions::PermissionState;
+use crate::{events, interview, AppState};
+
+/// 读取日历的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// 读取范围向前包含的时长，刚开始不久的会议也会提醒
+const LOOKBACK_SECS: u64 = 5 * 60;
+
+/// 日历访问权限，不支持的平台返回 NotApplicable
+pub fn permission() -> PermissionState {
+    #[cfg(target_os = "macos")]
+    {
+        macos::authorization()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        // Outlook 的访问由 Outlook 自身的安全设置控制
+        PermissionState::Granted
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        PermissionState::NotApplicable
+    }
+}
+
+/// 请求日历访问权限（macOS 弹出系统对话框，阻塞直到用户选择）
+pub fn request_permission() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        if macos::authorization() == PermissionState::Granted {
+            return true;
+        }
+        macos::request_access()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        permission() == PermissionState::Granted
+    }
+}
+
+/// 读取与 [from, to) 有重叠的日历事件
+fn events_between(from: u64, to: u64) -> Result<Vec<CalendarEvent>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::events_between(from, to)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::events_between(from, to)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (from, to);
+        Ok(Vec::new())
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// 在后台线程中定期检查即将开始的会议
+pub fn spawn_monitor(app_handle: AppHandle) {
+    thread::spawn(move || {
+        let mut reminder = MeetingReminder::new();
+        loop {
+            let config = app_handle
+                .state::<AppState>()
+                .config
+                .blocking_read()
+                .calendar
+                .clone();
+            // 未开启或未授权时不读取日历
+            if config.enabled && permission() == PermissionState::Granted {
+                let now = now_secs();
+                let lead_secs = config.lead_minutes as u64 * 60;
+                let window_end = now + lead_secs + POLL_INTERVAL.as_secs();
+                match events_between(now.saturating_sub(LOOKBACK_SECS), window_end) {
+                    Ok(events) => {
+                        for meeting in reminder.due(&events, now, lead_secs) {
+                            prompt(&app_handle, &meeting);
+                        }
+                    }
+                    Err(e) => tracing::debug!("Failed to read calendar: {}", e),
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// 显示主窗口，询问是否开始会议记录
+fn prompt(app_handle: &AppHandle, meeting: &CalendarEvent) {
+    if interview::is_running() {
+        return;
+    }
+    tracing::info!("Meeting starting: {}", meeting.title);
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    events::publish(EventPayload::Custom {
+        name: "meeting_starting".to_string(),
+        data: serde_json::to_value(meeting).unwrap_or_default(),
+    });
+}
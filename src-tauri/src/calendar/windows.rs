@@ -0,0 +1,72 @@
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+
+use serde::Deserialize;
+use vhisper_core::calendar::CalendarEvent;
+
+/// 不显示控制台窗口
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// 通过 Outlook COM 读取默认日历中与 [from, to) 重叠的事件，输出 JSON 数组
+///
+/// 只连接正在运行的 Outlook，不会自动启动它；重复会议按每次发生展开
+const SCRIPT: &str = r#"
+$ErrorActionPreference = 'Stop'
+[Console]::OutputEncoding = [Text.Encoding]::UTF8
+$outlook = [Runtime.InteropServices.Marshal]::GetActiveObject('Outlook.Application')
+$items = $outlook.GetNamespace('MAPI').GetDefaultFolder(9).Items
+$items.IncludeRecurrences = $true
+$items.Sort('[Start]')
+$from = [DateTimeOffset]::FromUnixTimeSeconds({FROM}).LocalDateTime.ToString('g')
+$to = [DateTimeOffset]::FromUnixTimeSeconds({TO}).LocalDateTime.ToString('g')
+$events = @($items.Restrict("[Start] < '$to' AND [End] > '$from'") | ForEach-Object {
+    [pscustomobject]@{
+        id = $_.GlobalAppointmentID
+        title = $_.Subject
+        start = ([DateTimeOffset]$_.Start).ToUnixTimeSeconds()
+        end = ([DateTimeOffset]$_.End).ToUnixTimeSeconds()
+        all_day = $_.AllDayEvent
+    }
+})
+ConvertTo-Json -InputObject $events -Compress
+"#;
+
+#[derive(Deserialize)]
+struct OutlookEvent {
+    id: Option<String>,
+    title: Option<String>,
+    start: i64,
+    end: i64,
+    all_day: bool,
+}
+
+/// 读取 Outlook 日历中与 [from, to) 有重叠的事件
+pub fn events_between(from: u64, to: u64) -> Result<Vec<CalendarEvent>, String> {
+    let script = SCRIPT
+        .replace("{FROM}", &from.to_string())
+        .replace("{TO}", &to.to_string());
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "无法读取 Outlook 日历（Outlook 需要正在运行）: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let events: Vec<OutlookEvent> =
+        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    Ok(events
+        .into_iter()
+        .map(|event| CalendarEvent {
+            id: event.id.unwrap_or_default(),
+            title: event.title.unwrap_or_default(),
+            start: event.start.max(0) as u64,
+            end: event.end.max(0) as u64,
+            all_day: event.all_day,
+        })
+        .collect())
+}
@@ -1,7 +1,12 @@
-use tauri::{AppHandle, Emitter, State};
+use std::time::Duration;
 
+use tauri::{AppHandle, State};
+
+use crate::a11y;
+use crate::events;
 use crate::output;
-use crate::{get_pipeline, AppState};
+use crate::{get_pipeline, pipelines, AppState};
+use vhisper_core::{EventPayload, PipelineError, PipelineSource, PipelineState};
 
 /// 开始录音
 #[tauri::command]
@@ -17,7 +22,7 @@ pub async fn start_recording(
     if let Some(pipeline) = get_pipeline() {
         pipeline.start_recording().map_err(|e| e.to_string())?;
         *is_recording = true;
-        let _ = app.emit("recording-started", ());
+        a11y::announce_if_enabled(&app, "开始录音");
         tracing::info!("Recording started via command");
     }
 
@@ -36,29 +41,36 @@ pub async fn stop_recording(
     }
 
     *is_recording = false;
-    let _ = app.emit("recording-stopped", ());
+    a11y::announce_if_enabled(&app, "正在识别");
 
     if let Some(pipeline) = get_pipeline() {
         let config = state.config.read().await;
         match pipeline.stop_and_process().await {
             Ok(text) => {
                 // 输出文本到当前应用
+                let mut announcement = a11y::completion_message(&text);
                 if !text.is_empty() {
-                    if let Err(e) = output::output_text(
-                        &text,
-                        config.output.restore_clipboard,
-                        config.output.paste_delay_ms,
-                        None,
-                    ) {
+                    if output::is_self_frontmost() {
+                        // 设置窗口可以把结果显示在测试输入框中
+                        events::publish(EventPayload::SelfDictation { text: text.clone() });
+                    }
+                    if let Err(e) = output::output_text(&text, &config.output, None) {
                         tracing::error!("Text output failed: {}", e);
+                        events::publish_output_error(&e);
+                        if e.copied_to_clipboard() {
+                            announcement = "无法粘贴，文本已复制到剪贴板".to_string();
+                        }
                     }
                 }
-                let _ = app.emit("processing-complete", ());
+                a11y::announce_if_enabled(&app, &announcement);
                 tracing::info!("Recording processed via command");
             }
+            Err(PipelineError::Cancelled) => {
+                tracing::info!("Processing cancelled via command");
+            }
             Err(e) => {
                 let error_msg = e.to_string();
-                let _ = app.emit("processing-error", &error_msg);
+                a11y::announce_if_enabled(&app, "识别失败");
                 return Err(error_msg);
             }
         }
@@ -66,3 +78,61 @@ pub async fn stop_recording(
 
     Ok(())
 }
+
+/// 取消录音，丢弃已录制的音频
+#[tauri::command]
+pub async fn cancel_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if let Some(pipeline) = get_pipeline() {
+        if pipeline.is_streaming() {
+            pipeline.cancel_streaming().await.map_err(|e| e.to_string())?;
+        } else {
+            pipeline.cancel().map_err(|e| e.to_string())?;
+        }
+    }
+
+    // 录音中才持有此锁；处理中 stop_recording 会一直持有，这里不等待
+    if let Ok(mut is_recording) = state.is_recording.try_write() {
+        *is_recording = false;
+    }
+
+    a11y::announce_if_enabled(&app, "已取消");
+    tracing::info!("Recording cancelled via command");
+    Ok(())
+}
+
+/// 取消正在进行的识别处理，结果不会输出
+#[tauri::command]
+pub async fn cancel_processing(app: AppHandle) -> Result<(), String> {
+    if let Some(pipeline) = get_pipeline() {
+        pipeline.cancel().map_err(|e| e.to_string())?;
+    }
+
+    a11y::announce_if_enabled(&app, "已取消");
+    tracing::info!("Processing cancelled via command");
+    Ok(())
+}
+
+/// 转写音频文件（WAV/MP3/M4A），返回经过后处理的文本
+#[tauri::command]
+pub async fn transcribe_file(path: String) -> Result<String, String> {
+    let pipeline = get_pipeline().ok_or("Pipeline 未初始化")?;
+    pipeline
+        .transcribe_file(&path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 前端播放提示音前调用，播放期间录音静音（回声抑制）
+#[tauri::command]
+pub fn suppress_capture(duration_ms: u64) {
+    vhisper_core::audio::suppress_for(Duration::from_millis(duration_ms));
+}
+
+/// 获取各音频来源 Pipeline 的状态
+#[tauri::command]
+pub fn get_pipeline_states() -> Vec<(PipelineSource, PipelineState)> {
+    pipelines().map(|registry| registry.states()).unwrap_or_default()
+}
@@ -0,0 +1,17 @@
+use crate::calendar;
+use crate::permissions::PermissionState;
+
+/// 日历访问权限
+#[tauri::command]
+pub fn get_calendar_permission() -> PermissionState {
+    calendar::permission()
+}
+
+/// 请求日历访问权限，返回是否已授权
+#[tauri::command]
+pub async fn request_calendar_permission() -> Result<bool, String> {
+    // macOS 上会等待用户在系统对话框中选择
+    tauri::async_runtime::spawn_blocking(calendar::request_permission)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,11 @@
-use tauri::State;
+use tauri::{AppHandle, State};
 
+use crate::crash;
+use crate::goals;
 use crate::hotkey;
+use crate::midi;
+use crate::remote;
+use crate::telemetry;
 use crate::{AppConfig, AppState};
 
 /// 获取当前配置
@@ -10,18 +15,50 @@ pub async fn get_config(state: State<'_, AppState>) -> Result<AppConfig, String>
     Ok(config.clone())
 }
 
+/// 被管理员配置锁定的配置项（点分隔的路径），设置界面据此提示
+#[tauri::command]
+pub fn get_managed_config_paths() -> Vec<String> {
+    vhisper_core::config::current_managed_paths()
+}
+
 /// 保存配置
 #[tauri::command]
-pub async fn save_config(state: State<'_, AppState>, config: AppConfig) -> Result<(), String> {
+pub async fn save_config(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    config: AppConfig,
+) -> Result<(), String> {
+    let mut user_config = config;
+    user_config.remote_trigger.ensure_token();
+    // 管理员锁定的配置项不能修改，只应用到内存中的配置，配置文件只保存用户的设置
+    let config = vhisper_core::config::apply_managed_config(user_config.clone())
+        .map_err(|e| e.to_string())?;
+    vhisper_core::net::set_proxy(config.network.proxy.as_deref()).map_err(|e| e.to_string())?;
+    vhisper_core::debug::set_payload_log(config.debug.payload_log);
+    crash::apply(config.debug.crash_reports, false);
+    telemetry::apply(config.telemetry.enabled);
+
     // 保存到文件
-    vhisper_core::save_config(&config).map_err(|e| e.to_string())?;
+    vhisper_core::save_config(&user_config).map_err(|e| e.to_string())?;
 
     // 获取新的快捷键绑定
     let new_binding = config.hotkey.binding.clone();
+    remote::apply(&app, &config.remote_trigger);
+    midi::apply(&app, &config.midi);
+    hotkey::set_privacy_hotkey(config.privacy.toggle_hotkey.clone());
+    hotkey::set_raw_text_hotkey(config.output.raw_text_hotkey.clone());
+    hotkey::set_mode_hotkeys(config.llm.mode_hotkeys.clone());
 
     // 更新内存中的配置
-    let mut current_config = state.config.write().await;
-    *current_config = config;
+    *state.config.write().await = config;
+    goals::refresh_tray(&app);
+
+    // 正在进行的流式会话在当前句结束后切换到新的 ASR 配置
+    if let Some(registry) = crate::pipelines() {
+        for pipeline in registry.all() {
+            pipeline.reload_streaming_asr().await;
+        }
+    }
 
     // 触发快捷键热重载
     hotkey::reload_hotkey(new_binding);
@@ -29,3 +66,49 @@ pub async fn save_config(state: State<'_, AppState>, config: AppConfig) -> Resul
     tracing::info!("Config saved and hotkey reloaded");
     Ok(())
 }
+
+/// 获取各 ASR 服务商最近的成功率和延迟统计
+#[tauri::command]
+pub fn get_asr_provider_stats() -> Vec<vhisper_core::ProviderStats> {
+    vhisper_core::provider_stats()
+}
+
+/// 查询 ASR 服务商可用的模型
+#[tauri::command]
+pub async fn list_asr_models(provider: String, api_key: String) -> Result<Vec<String>, String> {
+    vhisper_core::list_asr_models(&provider, &api_key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 查询 LLM 服务商可用的模型（Ollama 使用 endpoint，其他服务商使用 api_key）
+#[tauri::command]
+pub async fn list_llm_models(
+    provider: String,
+    api_key: String,
+    endpoint: String,
+) -> Result<Vec<String>, String> {
+    vhisper_core::list_llm_models(&provider, &api_key, &endpoint)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 将客户端证书密码保存到系统钥匙串
+#[tauri::command]
+pub fn set_certificate_passphrase(account: String, passphrase: String) -> Result<(), String> {
+    vhisper_core::net::store_certificate_passphrase(&account, &passphrase).map_err(|e| e.to_string())
+}
+
+/// 下载 sherpa-onnx 本地识别模型，返回模型目录
+#[tauri::command]
+pub async fn download_asr_model(model: String) -> Result<String, String> {
+    vhisper_core::download_sherpa_onnx_model(&model)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 检查快捷键是否和当前键盘布局或系统按键功能冲突
+#[tauri::command]
+pub fn check_hotkey_conflict(binding: vhisper_core::HotkeyBinding) -> Option<String> {
+    hotkey::hotkey_conflict(&binding)
+}
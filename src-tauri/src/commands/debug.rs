@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use vhisper_core::debug::{self, CaptureStatus};
+
+/// 默认抓取时长（分钟）
+const DEFAULT_CAPTURE_MINUTES: u64 = 10;
+
+/// 开启服务商请求/响应抓取，返回抓取目录
+///
+/// 抓取内容已脱敏（凭据替换、音频只保留哈希和时长），到时自动停止，最长 30 分钟
+#[tauri::command]
+pub fn start_debug_capture(minutes: Option<u64>) -> Result<String, String> {
+    let minutes = minutes.unwrap_or(DEFAULT_CAPTURE_MINUTES);
+    debug::start_capture(Duration::from_secs(minutes * 60))
+        .map(|dir| dir.display().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// 停止抓取，返回抓取目录
+#[tauri::command]
+pub fn stop_debug_capture() -> Option<String> {
+    debug::stop_capture().map(|dir| dir.display().to_string())
+}
+
+/// 获取抓取状态
+#[tauri::command]
+pub fn get_debug_capture_status() -> Option<CaptureStatus> {
+    debug::capture_status()
+}
+
+/// 服务商请求/响应日志的路径（配置 debug.payload_log 开启后写入）
+#[tauri::command]
+pub fn get_payload_log_path() -> Option<String> {
+    debug::payload_log_path().map(|path| path.display().to_string())
+}
+
+/// 本地保存的崩溃报告，最新的在前
+#[tauri::command]
+pub fn list_crash_reports() -> Vec<debug::CrashReport> {
+    debug::crash_dir()
+        .map(|dir| debug::list_reports(&dir))
+        .unwrap_or_default()
+}
+
+/// 把崩溃报告导出为 issue 正文（Markdown），由用户检查后提交
+#[tauri::command]
+pub fn export_crash_report(id: String) -> Result<String, String> {
+    list_crash_reports()
+        .into_iter()
+        .find(|report| report.id == id)
+        .map(|report| report.issue_text())
+        .ok_or_else(|| format!("崩溃报告不存在: {}", id))
+}
+
+/// 删除所有崩溃报告
+#[tauri::command]
+pub fn clear_crash_reports() -> Result<(), String> {
+    let Some(dir) = debug::crash_dir() else {
+        return Ok(());
+    };
+    debug::clear_reports(&dir).map_err(|e| e.to_string())
+}
+
+/// 导出最近一次流式识别会话的 WebSocket 消息追踪
+///
+/// 需要在配置中开启 debug.streaming_trace，否则返回 None
+#[tauri::command]
+pub fn dump_last_streaming_session() -> Option<debug::StreamingTrace> {
+    debug::last_streaming_trace()
+}
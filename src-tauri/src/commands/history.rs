@@ -0,0 +1,39 @@
+use tauri::{AppHandle, State};
+use vhisper_core::history::{self, GoalProgress, SessionRecord, TimedTranscript};
+
+use crate::{goals, tray, AppState};
+
+/// 获取听写统计历史（每个会话的词数、时长和语速）
+#[tauri::command]
+pub fn get_dictation_history() -> Result<Vec<SessionRecord>, String> {
+    history::load_history().map_err(|e| e.to_string())
+}
+
+/// 获取流式会话按时间排列的分段文本，没有记录时返回 None
+#[tauri::command]
+pub fn get_session_transcript(session_id: String) -> Result<Option<TimedTranscript>, String> {
+    history::load_session_transcript(&session_id).map_err(|e| e.to_string())
+}
+
+/// 获取每日目标进度和连续天数
+#[tauri::command]
+pub async fn get_goals(app: AppHandle) -> Result<GoalProgress, String> {
+    goals::progress(&app).await
+}
+
+/// 设置每日目标词数（0 表示不设目标），返回新的进度
+#[tauri::command]
+pub async fn set_goal(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    daily_words: usize,
+) -> Result<GoalProgress, String> {
+    {
+        let mut config = state.config.write().await;
+        config.history.daily_word_goal = daily_words;
+        vhisper_core::save_config(&config).map_err(|e| e.to_string())?;
+    }
+    let progress = goals::progress(&app).await?;
+    tray::set_goal_progress(&app, &progress);
+    Ok(progress)
+}
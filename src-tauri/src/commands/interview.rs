@@ -0,0 +1,34 @@
+use tauri::AppHandle;
+use vhisper_core::history::{self, DialogueTranscript};
+
+use crate::interview;
+
+/// 开始访谈录音（麦克风和系统声音分轨录制），返回访谈 ID
+#[tauri::command]
+pub async fn start_interview(app: AppHandle) -> Result<String, String> {
+    interview::start(&app).await
+}
+
+/// 停止访谈录音，返回按时间交错的对话记录
+#[tauri::command]
+pub async fn stop_interview() -> Result<DialogueTranscript, String> {
+    interview::stop().await
+}
+
+/// 是否正在访谈录音
+#[tauri::command]
+pub fn get_interview_status() -> bool {
+    interview::is_running()
+}
+
+/// 列出音频输入设备名称，用于选择系统声音回环设备
+#[tauri::command]
+pub fn list_input_devices() -> Vec<String> {
+    vhisper_core::audio::input_device_names()
+}
+
+/// 获取已保存的访谈对话记录，最新的在前
+#[tauri::command]
+pub fn list_interview_transcripts() -> Result<Vec<DialogueTranscript>, String> {
+    history::load_transcripts().map_err(|e| e.to_string())
+}
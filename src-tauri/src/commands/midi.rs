@@ -0,0 +1,7 @@
+use crate::midi::{self, MidiDevices};
+
+/// 列出可用的 MIDI 输入/输出设备
+#[tauri::command]
+pub fn list_midi_devices() -> Result<MidiDevices, String> {
+    midi::list_devices()
+}
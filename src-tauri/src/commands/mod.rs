@@ -1,4 +1,17 @@
 pub mod audio;
+pub mod calendar;
 pub mod config;
+pub mod debug;
+pub mod history;
+pub mod interview;
+pub mod midi;
+pub mod notifications;
+pub mod output;
+pub mod overlay;
 pub mod permissions;
+pub mod privacy;
+pub mod remote;
+pub mod telemetry;
 pub mod test;
+pub mod text;
+pub mod update;
@@ -0,0 +1,9 @@
+use tauri::AppHandle;
+
+use crate::dnd::{self, FocusModeState};
+
+/// 勿扰模式状态，前端播放提示音前检查
+#[tauri::command]
+pub fn get_focus_mode(app_handle: AppHandle) -> FocusModeState {
+    dnd::state(&app_handle)
+}
@@ -0,0 +1,23 @@
+use tauri::State;
+
+use crate::{output, AppState};
+
+/// 把最近一次只复制到剪贴板的结果粘贴到当前前台窗口
+#[tauri::command]
+pub async fn paste_anyway(state: State<'_, AppState>) -> Result<(), String> {
+    let output_config = state.config.read().await.output.clone();
+    tauri::async_runtime::spawn_blocking(move || output::paste_anyway(&output_config))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 把刚输出的文本替换为原始识别文本，返回是否执行了替换
+#[tauri::command]
+pub async fn insert_raw_text(state: State<'_, AppState>) -> Result<bool, String> {
+    let output_config = state.config.read().await.output.clone();
+    tauri::async_runtime::spawn_blocking(move || output::insert_raw_text(&output_config))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
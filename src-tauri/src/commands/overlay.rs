@@ -0,0 +1,24 @@
+use tauri::AppHandle;
+
+use crate::overlay::{self, OverlayAnchor};
+
+/// 悬浮窗默认尺寸（逻辑像素）
+const DEFAULT_OVERLAY_WIDTH: f64 = 220.0;
+const DEFAULT_OVERLAY_HEIGHT: f64 = 140.0;
+
+/// 录音开始时计算悬浮窗位置（插入点附近），取不到输入位置和鼠标位置时返回 None
+#[tauri::command]
+pub async fn get_overlay_anchor(
+    app_handle: AppHandle,
+    width: Option<f64>,
+    height: Option<f64>,
+) -> Result<Option<OverlayAnchor>, String> {
+    let width = width.unwrap_or(DEFAULT_OVERLAY_WIDTH);
+    let height = height.unwrap_or(DEFAULT_OVERLAY_HEIGHT);
+    // 辅助功能查询可能被无响应的前台应用阻塞，不占用主线程
+    tauri::async_runtime::spawn_blocking(move || {
+        overlay::overlay_anchor(&app_handle, width, height)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
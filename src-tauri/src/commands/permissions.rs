@@ -1,3 +1,5 @@
+use crate::health::{self, StartupHealth};
+use crate::output::{self, SecureInputStatus};
 use crate::permissions::{self, PermissionStatus};
 
 /// Check all system permissions
@@ -29,3 +31,15 @@ pub fn request_accessibility_permission() {
 pub fn open_microphone_settings() {
     permissions::open_microphone_settings();
 }
+
+/// Check whether Secure Keyboard Entry is active and which process holds it
+#[tauri::command]
+pub fn check_secure_input() -> SecureInputStatus {
+    output::secure_input_status()
+}
+
+/// Get the result of the most recent startup health check
+#[tauri::command]
+pub fn get_startup_health() -> Option<StartupHealth> {
+    health::last_health()
+}
@@ -0,0 +1,15 @@
+use tauri::AppHandle;
+
+use crate::privacy;
+
+/// 隐私模式是否开启
+#[tauri::command]
+pub fn get_incognito() -> bool {
+    vhisper_core::is_incognito()
+}
+
+/// 开启或关闭隐私模式
+#[tauri::command]
+pub fn set_incognito(app_handle: AppHandle, enabled: bool) {
+    privacy::set_enabled(&app_handle, enabled);
+}
@@ -0,0 +1,24 @@
+use tauri::{AppHandle, State};
+
+use crate::remote::{self, RemotePairing};
+use crate::AppState;
+
+/// 获取远程触发的配对信息（地址和二维码）
+#[tauri::command]
+pub async fn get_remote_pairing(state: State<'_, AppState>) -> Result<RemotePairing, String> {
+    let config = state.config.read().await;
+    remote::pairing(&config.remote_trigger)
+}
+
+/// 重新生成配对令牌，已配对的设备需要重新扫码
+#[tauri::command]
+pub async fn reset_remote_token(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RemotePairing, String> {
+    let mut config = state.config.write().await;
+    config.remote_trigger.regenerate_token();
+    vhisper_core::save_config(&config).map_err(|e| e.to_string())?;
+    remote::apply(&app, &config.remote_trigger);
+    remote::pairing(&config.remote_trigger)
+}
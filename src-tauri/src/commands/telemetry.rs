@@ -0,0 +1,15 @@
+use vhisper_core::telemetry::TelemetryReport;
+
+use crate::telemetry;
+
+/// 预览下次上报的统计内容
+#[tauri::command]
+pub fn get_telemetry_preview() -> TelemetryReport {
+    telemetry::preview()
+}
+
+/// 删除已累计的统计数据
+#[tauri::command]
+pub fn clear_telemetry() {
+    vhisper_core::telemetry::clear_counters();
+}
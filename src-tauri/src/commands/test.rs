@@ -1,31 +1,9 @@
-/// 测试通义千问 ASR API
-#[tauri::command]
-pub async fn test_qwen_api(api_key: String) -> Result<String, String> {
-    vhisper_core::test_qwen_api(&api_key)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// 测试 DashScope API
-#[tauri::command]
-pub async fn test_dashscope_api(api_key: String) -> Result<String, String> {
-    vhisper_core::test_dashscope_api(&api_key)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// 测试 OpenAI API
-#[tauri::command]
-pub async fn test_openai_api(api_key: String) -> Result<String, String> {
-    vhisper_core::test_openai_api(&api_key)
-        .await
-        .map_err(|e| e.to_string())
-}
+use vhisper_core::config::settings::AsrConfig;
 
-/// 测试 FunASR API
+/// 测试当前选中的 ASR 服务商（使用配置的模型识别一段静音）
 #[tauri::command]
-pub async fn test_funasr_api(endpoint: String) -> Result<String, String> {
-    vhisper_core::test_funasr_api(&endpoint)
+pub async fn test_asr_api(config: AsrConfig) -> Result<String, String> {
+    vhisper_core::test_asr_api(&config)
         .await
         .map_err(|e| e.to_string())
 }
@@ -0,0 +1,22 @@
+use tauri::State;
+
+use crate::AppState;
+
+/// 对任意文本执行后处理流程（替换、规范化、LLM 校对等）
+///
+/// - profile: 应用标识，匹配应用配置时使用其语言设置
+#[tauri::command]
+pub async fn process_text(
+    state: State<'_, AppState>,
+    text: String,
+    profile: Option<String>,
+) -> Result<String, String> {
+    let config = state.config.read().await.clone();
+    Ok(vhisper_core::process_text(&config, &text, profile.as_deref()).await)
+}
+
+/// 可用的拼写检查词典
+#[tauri::command]
+pub fn list_spell_dictionaries() -> Vec<String> {
+    vhisper_core::available_dictionaries()
+}
@@ -0,0 +1,28 @@
+use tauri::AppHandle;
+use vhisper_core::config::settings::UpdateChannel;
+use vhisper_core::update::UpdateInfo;
+
+use crate::update;
+
+/// 检查更新，没有新版本时返回 None；未指定通道时使用配置的通道
+#[tauri::command]
+pub async fn check_for_update(
+    app: AppHandle,
+    channel: Option<UpdateChannel>,
+) -> Result<Option<UpdateInfo>, String> {
+    update::check(&app, channel).await
+}
+
+/// 最近一次检查到的新版本
+#[tauri::command]
+pub fn get_available_update() -> Option<UpdateInfo> {
+    update::available()
+}
+
+/// 下载新版本的安装包，返回文件路径
+#[tauri::command]
+pub async fn download_update(app: AppHandle) -> Result<String, String> {
+    update::download(&app)
+        .await
+        .map(|path| path.display().to_string())
+}
@@ -0,0 +1,91 @@
+//! 崩溃报告
+//!
+//! panic hook 始终把 panic 打印到标准错误；用户在设置中同意后
+//! （`debug.crash_reports`），同时在本地保存报告并检测上次是否异常退出。
+//!
+//! 不捕获原生崩溃的 minidump（需要进程外的崩溃处理程序），段错误等原生崩溃
+//! 只在下次启动时记录为异常退出
+
+use vhisper_core::debug::{self, CrashReport};
+
+/// 应用版本
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 安装 panic hook
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|panic_info| {
+        eprintln!("!!! PANIC DETECTED !!!");
+        eprintln!("{}", panic_info);
+        if let Some(location) = panic_info.location() {
+            eprintln!(
+                "Location: {}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            );
+        }
+
+        if !debug::is_crash_reports_enabled() {
+            return;
+        }
+        let Some(dir) = debug::crash_dir() else {
+            return;
+        };
+        let payload = panic_info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let location = panic_info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+
+        let report = CrashReport::panic(APP_VERSION, message, location);
+        // 不删除运行标记：后台线程 panic 后进程仍在运行，之后的原生崩溃仍需检测
+        match debug::save_report(&dir, &report) {
+            Ok(path) => eprintln!("Crash report saved to {:?}", path),
+            Err(e) => eprintln!("Failed to save crash report: {}", e),
+        }
+    }));
+}
+
+/// 应用配置：开启时写入运行标记，关闭时删除
+///
+/// `startup` 为 true 时检查上次运行是否异常退出并记录报告
+pub fn apply(enabled: bool, startup: bool) {
+    let was_enabled = debug::is_crash_reports_enabled();
+    debug::set_crash_reports(enabled);
+    let Some(dir) = debug::crash_dir() else {
+        return;
+    };
+
+    if !enabled {
+        debug::clear_running(&dir);
+        return;
+    }
+    if was_enabled && !startup {
+        return;
+    }
+    let panicked = startup && debug::panic_recorded_since_start(&dir);
+    match debug::mark_running(&dir) {
+        Ok(true) if panicked => {
+            tracing::warn!("Previous run exited after a panic, crash report already saved");
+        }
+        Ok(true) if startup => {
+            tracing::warn!("Previous run did not exit cleanly, saving crash report");
+            if let Err(e) = debug::save_report(&dir, &CrashReport::unclean_shutdown(APP_VERSION)) {
+                tracing::warn!("Failed to save crash report: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to write crash marker: {}", e),
+    }
+}
+
+/// 正常退出时删除运行标记
+pub fn on_exit() {
+    if let Some(dir) = debug::crash_dir() {
+        debug::clear_running(&dir);
+    }
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+/// 专注模式开启时系统写入的断言记录
+const ASSERTIONS_PATH: &str = "Library/DoNotDisturb/DB/Assertions.json";
+
+/// 专注模式是否开启
+///
+/// 手动开启的专注模式在断言记录中有条目；按日程自动开启的不会写入，检测不到。
+/// 文件在沙盒和没有完全磁盘访问权限时可能不可读，此时返回 None
+pub fn is_focus_active() -> Option<bool> {
+    let path = PathBuf::from(std::env::var_os("HOME")?).join(ASSERTIONS_PATH);
+    let content = std::fs::read_to_string(path).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let active = json["data"].as_array()?.iter().any(|entry| {
+        entry["storeAssertionRecords"]
+            .as_array()
+            .is_some_and(|records| !records.is_empty())
+    });
+    Some(active)
+}
@@ -0,0 +1,88 @@
+//! 勿扰模式
+//!
+//! 检测 macOS 专注模式和 Windows 专注助手，开启时按配置静音提示音、
+//! 不显示系统通知。状态变化时发送 `focus_mode` 事件
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use vhisper_core::EventPayload;
+
+use crate::{events, AppState};
+
+/// 检查勿扰模式的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 最近一次检查的结果
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// 勿扰模式状态和按配置生效的静音设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FocusModeState {
+    pub active: bool,
+    pub mute_sounds: bool,
+    pub hide_notifications: bool,
+}
+
+/// 在后台线程中检查勿扰模式
+pub fn spawn_monitor() {
+    thread::spawn(|| loop {
+        let active = query().unwrap_or(false);
+        if ACTIVE.swap(active, Ordering::Relaxed) != active {
+            tracing::info!("Focus mode {}", if active { "on" } else { "off" });
+            events::publish(EventPayload::Custom {
+                name: "focus_mode".to_string(),
+                data: serde_json::json!({ "active": active }),
+            });
+        }
+        thread::sleep(POLL_INTERVAL);
+    });
+}
+
+/// 勿扰模式是否开启（最近一次检查的结果）
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// 当前状态
+pub fn state(app_handle: &AppHandle) -> FocusModeState {
+    let active = is_active();
+    // 可能在异步上下文中调用，拿不到锁时按默认配置
+    let config = app_handle
+        .state::<AppState>()
+        .config
+        .try_read()
+        .map(|config| config.notifications.clone())
+        .unwrap_or_default();
+
+    FocusModeState {
+        active,
+        mute_sounds: active && config.mute_sounds_in_focus,
+        hide_notifications: active && config.hide_notifications_in_focus,
+    }
+}
+
+/// 查询系统的勿扰模式，无法检测时返回 None
+fn query() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::is_focus_active()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::is_focus_active()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
@@ -0,0 +1,13 @@
+use windows::Win32::UI::Shell::{
+    SHQueryUserNotificationState, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME,
+    QUNS_RUNNING_D3D_FULL_SCREEN,
+};
+
+/// 专注助手、演示模式或全屏游戏是否开启（系统此时也会暂停通知）
+pub fn is_focus_active() -> Option<bool> {
+    let state = unsafe { SHQueryUserNotificationState() }.ok()?;
+    Some(matches!(
+        state,
+        QUNS_QUIET_TIME | QUNS_PRESENTATION_MODE | QUNS_RUNNING_D3D_FULL_SCREEN
+    ))
+}
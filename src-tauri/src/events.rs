@@ -0,0 +1,44 @@
+//! 事件通道
+//!
+//! 核心和应用层的事件都通过 `vhisper://event` 发送给前端
+
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+use vhisper_core::events::{self, ErrorCode, EventPayload, EventStage, EVENT_CHANNEL};
+
+use crate::get_pipeline;
+use crate::goals;
+use crate::output::OutputError;
+
+/// 把核心事件转发到前端
+pub fn init(app_handle: AppHandle) {
+    events::set_event_listener(Some(Arc::new(move |event| {
+        let _ = app_handle.emit(EVENT_CHANNEL, event);
+        // 会话统计发布前已写入历史，刷新每日目标进度
+        if matches!(event.payload, EventPayload::SessionStats { .. }) {
+            goals::refresh_tray(&app_handle);
+        }
+    })));
+}
+
+/// 发布应用层事件，归属到当前麦克风会话
+pub fn publish(payload: EventPayload) {
+    let session_id = get_pipeline().and_then(|p| p.session_id());
+    events::publish(session_id, payload);
+}
+
+/// 发布输出阶段的错误
+pub fn publish_output_error(error: &OutputError) {
+    let code = match error {
+        OutputError::PasteBlocked(_) => ErrorCode::PasteBlocked,
+        OutputError::ElevatedTarget => ErrorCode::ElevatedTarget,
+        _ => ErrorCode::Output,
+    };
+    publish(EventPayload::Error {
+        stage: EventStage::Output,
+        code,
+        message: error.to_string(),
+        recoverable: true,
+    });
+}
@@ -0,0 +1,32 @@
+//! 每日听写目标
+//!
+//! 托盘菜单显示今日词数和连续天数，每次会话统计（`session_stats` 事件）后刷新
+
+use tauri::{AppHandle, Manager};
+use vhisper_core::history::{self, GoalProgress};
+
+use crate::{tray, AppState};
+
+/// 当前目标进度
+pub async fn progress(app_handle: &AppHandle) -> Result<GoalProgress, String> {
+    let daily_goal = app_handle
+        .state::<AppState>()
+        .config
+        .read()
+        .await
+        .history
+        .daily_word_goal;
+    let records = history::load_history().map_err(|e| e.to_string())?;
+    Ok(history::current_goal_progress(&records, daily_goal))
+}
+
+/// 在后台重新计算进度并更新托盘
+pub fn refresh_tray(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        match progress(&app_handle).await {
+            Ok(progress) => tray::set_goal_progress(&app_handle, &progress),
+            Err(e) => tracing::warn!("Failed to compute goal progress: {}", e),
+        }
+    });
+}
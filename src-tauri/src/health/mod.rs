@@ -0,0 +1,146 @@
+//! 启动健康检查
+//!
+//! 启动时在后台检查权限、麦克风、ASR 服务商和快捷键，汇总后发送 `startup-health` 事件，
+//! 有问题时在托盘图标上显示提示，避免用户在使用时才发现无法听写
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use vhisper_core::EventPayload;
+
+use crate::events;
+use crate::hotkey;
+use crate::permissions::{self, PermissionState};
+use crate::tray;
+use crate::AppState;
+
+/// 等待快捷键监听启动的时间
+const HOTKEY_STARTUP_GRACE: Duration = Duration::from_secs(2);
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+impl HealthCheck {
+    fn new(name: &str, ok: bool, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok,
+            message: message.into(),
+        }
+    }
+}
+
+/// 汇总的启动健康状态
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupHealth {
+    pub checks: Vec<HealthCheck>,
+    /// 是否有检查未通过（降级模式）
+    pub degraded: bool,
+}
+
+/// 最近一次检查结果，供前端晚于事件加载时查询
+static LAST_HEALTH: Mutex<Option<StartupHealth>> = Mutex::new(None);
+
+/// 获取最近一次启动检查结果
+pub fn last_health() -> Option<StartupHealth> {
+    LAST_HEALTH.lock().ok().and_then(|h| h.clone())
+}
+
+/// 在后台运行启动检查，不阻塞启动
+pub fn spawn_startup_checks(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let health = run_checks(&app_handle).await;
+
+        for check in health.checks.iter().filter(|c| !c.ok) {
+            tracing::warn!("Startup check failed: {} - {}", check.name, check.message);
+        }
+
+        let problems: Vec<&str> = health
+            .checks
+            .iter()
+            .filter(|c| !c.ok)
+            .map(|c| c.message.as_str())
+            .collect();
+        tray::set_health_badge(&app_handle, &problems);
+
+        if let Ok(mut last) = LAST_HEALTH.lock() {
+            *last = Some(health.clone());
+        }
+        events::publish(EventPayload::Custom {
+            name: "startup_health".to_string(),
+            data: serde_json::to_value(&health).unwrap_or_default(),
+        });
+    });
+}
+
+async fn run_checks(app_handle: &AppHandle) -> StartupHealth {
+    let mut checks = Vec::new();
+
+    let permission_status = permissions::check_permissions();
+    checks.push(HealthCheck::new(
+        "accessibility",
+        permission_status.accessibility,
+        if permission_status.accessibility {
+            "辅助功能权限已授予"
+        } else {
+            "未授予辅助功能权限，无法监听快捷键和粘贴文本"
+        },
+    ));
+
+    let microphone_ok = matches!(
+        permission_status.microphone,
+        PermissionState::Granted | PermissionState::NotApplicable
+    );
+    checks.push(HealthCheck::new(
+        "microphone_permission",
+        microphone_ok,
+        if microphone_ok {
+            "麦克风权限已授予"
+        } else {
+            "未授予麦克风权限"
+        },
+    ));
+
+    let has_device = vhisper_core::has_input_device();
+    checks.push(HealthCheck::new(
+        "microphone_device",
+        has_device,
+        if has_device {
+            "已找到麦克风"
+        } else {
+            "未找到可用的麦克风"
+        },
+    ));
+
+    let asr_config = {
+        let state = app_handle.state::<AppState>();
+        let config = state.config.read().await;
+        config.asr.clone()
+    };
+    checks.push(match vhisper_core::check_provider_reachable(&asr_config).await {
+        Ok(provider) => HealthCheck::new("provider", true, format!("{} 可以连接", provider)),
+        Err(e) => HealthCheck::new("provider", false, format!("ASR 服务不可用: {}", e)),
+    });
+
+    // 快捷键监听在单独线程中启动，给它一点时间
+    tokio::time::sleep(HOTKEY_STARTUP_GRACE).await;
+    let hotkey_ok = hotkey::is_listener_active();
+    checks.push(HealthCheck::new(
+        "hotkey",
+        hotkey_ok,
+        if hotkey_ok {
+            "快捷键已注册"
+        } else {
+            "快捷键注册失败"
+        },
+    ));
+
+    let degraded = checks.iter().any(|c| !c.ok);
+    StartupHealth { checks, degraded }
+}
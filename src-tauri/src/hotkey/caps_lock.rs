@@ -0,0 +1,125 @@
+//! CapsLock 作为快捷键时的大写锁定状态修正
+//!
+//! 系统在按下 CapsLock 时就切换了大写锁定，监听无法阻止；
+//! 松开后把锁定状态恢复为按下前的值，用户感知不到切换。
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::{c_char, c_int};
+
+    /// CapsLock 的 CGKeyCode
+    pub const CAPS_LOCK_KEYCODE: u16 = 0x39;
+
+    /// kIOHIDParamConnectType
+    const HID_PARAM_CONNECT_TYPE: u32 = 1;
+    /// kIOHIDCapsLockState
+    const HID_CAPS_LOCK_STATE: c_int = 1;
+    /// kCGEventSourceStateHIDSystemState
+    const HID_SYSTEM_STATE: i32 = 1;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        static mach_task_self_: u32;
+        fn IOServiceMatching(name: *const c_char) -> *mut std::ffi::c_void;
+        fn IOServiceGetMatchingService(main_port: u32, matching: *mut std::ffi::c_void) -> u32;
+        fn IOServiceOpen(service: u32, owning_task: u32, connect_type: u32, connect: *mut u32) -> i32;
+        fn IOServiceClose(connect: u32) -> i32;
+        fn IOObjectRelease(object: u32) -> i32;
+        fn IOHIDGetModifierLockState(handle: u32, selector: c_int, state: *mut bool) -> i32;
+        fn IOHIDSetModifierLockState(handle: u32, selector: c_int, state: bool) -> i32;
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceKeyState(state_id: i32, key: u16) -> bool;
+    }
+
+    /// 打开 IOHIDSystem 连接执行操作，结束后关闭
+    fn with_hid_system<T>(f: impl FnOnce(u32) -> Option<T>) -> Option<T> {
+        // SAFETY: 服务和连接在本函数内打开和释放；matching 字典由 IOServiceGetMatchingService 接管
+        unsafe {
+            let matching = IOServiceMatching(c"IOHIDSystem".as_ptr());
+            let service = IOServiceGetMatchingService(0, matching);
+            if service == 0 {
+                return None;
+            }
+            let mut connect = 0u32;
+            let result = IOServiceOpen(service, mach_task_self_, HID_PARAM_CONNECT_TYPE, &mut connect);
+            IOObjectRelease(service);
+            if result != 0 {
+                return None;
+            }
+            let value = f(connect);
+            IOServiceClose(connect);
+            value
+        }
+    }
+
+    /// CapsLock 键当前是否被按住（物理按键状态，不是大写锁定状态）
+    pub fn is_key_down() -> bool {
+        // SAFETY: 纯查询
+        unsafe { CGEventSourceKeyState(HID_SYSTEM_STATE, CAPS_LOCK_KEYCODE) }
+    }
+
+    /// 当前大写锁定是否开启
+    pub fn is_locked() -> Option<bool> {
+        with_hid_system(|connect| {
+            let mut state = false;
+            // SAFETY: connect 为有效的 IOHIDSystem 连接
+            let result = unsafe { IOHIDGetModifierLockState(connect, HID_CAPS_LOCK_STATE, &mut state) };
+            (result == 0).then_some(state)
+        })
+    }
+
+    /// 设置大写锁定状态
+    pub fn set_locked(locked: bool) -> bool {
+        with_hid_system(|connect| {
+            // SAFETY: connect 为有效的 IOHIDSystem 连接
+            let result = unsafe { IOHIDSetModifierLockState(connect, HID_CAPS_LOCK_STATE, locked) };
+            (result == 0).then_some(())
+        })
+        .is_some()
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, GetKeyState, VK_CAPITAL};
+
+    use crate::output::send_combo;
+
+    /// CapsLock 键当前是否被按住（物理按键状态，不是大写锁定状态）
+    pub fn is_key_down() -> bool {
+        // SAFETY: 纯查询
+        let state = unsafe { GetAsyncKeyState(VK_CAPITAL.0 as i32) };
+        (state as u16 & 0x8000) != 0
+    }
+
+    /// 当前大写锁定是否开启
+    pub fn is_locked() -> Option<bool> {
+        // SAFETY: 纯查询，最低位为切换状态
+        let state = unsafe { GetKeyState(VK_CAPITAL.0 as i32) };
+        Some(state & 1 != 0)
+    }
+
+    /// 设置大写锁定状态
+    ///
+    /// Windows 没有直接设置的接口，状态不同时模拟按一次 CapsLock
+    pub fn set_locked(locked: bool) -> bool {
+        if is_locked() == Some(locked) {
+            return true;
+        }
+        send_combo(&[], VK_CAPITAL).is_ok()
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub use imp::*;
+
+/// 恢复按下 CapsLock 前的大写锁定状态
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn restore(before: bool) {
+    if is_locked() != Some(before) && !set_locked(before) {
+        tracing::warn!("Failed to restore Caps Lock state");
+    }
+}
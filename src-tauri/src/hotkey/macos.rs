@@ -6,11 +6,20 @@ use core_foundation::runloop::{kCFRunLoopCommonModes, kCFRunLoopDefaultMode, CFR
 use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 use std::sync::mpsc::{Receiver, TryRecvError};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 
-use vhisper_core::{HotkeyBinding, KeyCode};
+use vhisper_core::events::{ErrorCode, EventPayload, EventStage};
+use vhisper_core::{
+    DualKeyChord, HotkeyBinding, KeyCode, KeySide, PipelineError, PipelineState, VoicePipeline,
+};
+use super::caps_lock;
+use crate::a11y;
+use crate::events;
 use crate::get_pipeline;
-use crate::output::{self, get_frontmost_app_pid};
+use crate::output::{
+    self, get_frontmost_app_id, get_frontmost_app_pid, is_secure_input_enabled,
+    secure_input_holder,
+};
 use crate::AppState;
 
 #[derive(Debug, thiserror::Error)]
@@ -86,10 +95,22 @@ fn keycode_to_cg_keycode(key: &KeyCode) -> Option<u16> {
         KeyCode::Backquote => Some(0x32),
 
         // 修饰键不需要 CGKeyCode (通过 flags 检测)
-        KeyCode::Alt | KeyCode::Control | KeyCode::Shift | KeyCode::Meta => None,
+        KeyCode::Alt | KeyCode::Control | KeyCode::Shift | KeyCode::Meta | KeyCode::Fn => None,
     }
 }
 
+/// Esc 的 CGKeyCode
+const ESCAPE_KEYCODE: u16 = 0x35;
+
+/// kCGEventSourceStateHIDSystemState
+const HID_SYSTEM_STATE: i32 = 1;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn CGEventSourceKeyState(state_id: i32, key: u16) -> bool;
+    fn CGEventSourceFlagsState(state_id: i32) -> u64;
+}
+
 /// 将 KeyCode 转换为 CGEventFlags
 fn keycode_to_cg_flag(key: &KeyCode) -> Option<CGEventFlags> {
     match key {
@@ -97,10 +118,27 @@ fn keycode_to_cg_flag(key: &KeyCode) -> Option<CGEventFlags> {
         KeyCode::Control => Some(CGEventFlags::CGEventFlagControl),
         KeyCode::Shift => Some(CGEventFlags::CGEventFlagShift),
         KeyCode::Meta => Some(CGEventFlags::CGEventFlagCommand),
+        // NX_SECONDARYFNMASK
+        KeyCode::Fn => Some(CGEventFlags::CGEventFlagSecondaryFn),
         _ => None,
     }
 }
 
+/// 系统设置中“按下 🌐 键时”的动作（com.apple.HIToolbox AppleFnUsageType）
+///
+/// 0 不执行任何操作，1 更改输入法，2 显示表情与符号，3 开始听写；读取失败时返回 None
+pub fn globe_key_action() -> Option<u32> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "com.apple.HIToolbox", "AppleFnUsageType"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        // 从未修改过时没有该键，系统默认为更改输入法
+        return Some(1);
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
 /// 检查修饰键是否匹配
 fn check_modifiers(flags: CGEventFlags, required: &[KeyCode]) -> bool {
     for modifier in required {
@@ -113,6 +151,54 @@ fn check_modifiers(flags: CGEventFlags, required: &[KeyCode]) -> bool {
     true
 }
 
+/// 快捷键当前是否按下（查询物理按键状态），主键为修饰键时返回 false
+pub fn is_binding_down(binding: &HotkeyBinding) -> bool {
+    let Some(key) = keycode_to_cg_keycode(&binding.key) else {
+        return false;
+    };
+    // SAFETY: 纯查询
+    let (down, flags) = unsafe {
+        (
+            CGEventSourceKeyState(HID_SYSTEM_STATE, key),
+            CGEventSourceFlagsState(HID_SYSTEM_STATE),
+        )
+    };
+    down && check_modifiers(CGEventFlags::from_bits_truncate(flags), &binding.modifiers)
+}
+
+/// 左右两侧修饰键的设备相关标志位 (左, 右)，对应 IOKit 的 NX_DEVICE*KEYMASK
+fn device_side_masks(key: &KeyCode) -> Option<(u64, u64)> {
+    match key {
+        KeyCode::Control => Some((0x0000_0001, 0x0000_2000)),
+        KeyCode::Shift => Some((0x0000_0002, 0x0000_0004)),
+        KeyCode::Meta => Some((0x0000_0008, 0x0000_0010)),
+        KeyCode::Alt => Some((0x0000_0020, 0x0000_0040)),
+        _ => None,
+    }
+}
+
+/// 检查双键组合要求
+fn check_chord(flags: CGEventFlags, key: &KeyCode, chord: DualKeyChord) -> bool {
+    match chord {
+        DualKeyChord::Single => true,
+        DualKeyChord::BothSides => device_side_masks(key)
+            .is_some_and(|(left, right)| flags.bits() & left != 0 && flags.bits() & right != 0),
+        DualKeyChord::WithFn => flags.contains(CGEventFlags::CGEventFlagSecondaryFn),
+    }
+}
+
+/// 检查主键的左右侧要求
+fn check_side(flags: CGEventFlags, key: &KeyCode, side: KeySide) -> bool {
+    let Some((left, right)) = device_side_masks(key) else {
+        return true;
+    };
+    match side {
+        KeySide::Any => true,
+        KeySide::Left => flags.bits() & left != 0,
+        KeySide::Right => flags.bits() & right != 0,
+    }
+}
+
 /// 启动 macOS 快捷键监听
 pub fn start_listener(
     app_handle: AppHandle,
@@ -123,8 +209,11 @@ pub fn start_listener(
     let is_recording = Arc::new(AtomicBool::new(false));
     let original_app_pid = Arc::new(AtomicI32::new(-1));
 
-    // 判断主键类型
-    let is_modifier_key = binding.key.is_modifier();
+    // 判断主键类型（Fn 和修饰键一样通过 flags 检测）
+    let is_fn_key = binding.key == KeyCode::Fn;
+    let is_modifier_key = binding.key.is_modifier() || is_fn_key;
+    let chord = binding.chord_for_key();
+    let side = binding.side_for_key();
     let binding_clone = binding.clone();
 
     let is_key_pressed_clone = is_key_pressed.clone();
@@ -153,14 +242,40 @@ pub fn start_listener(
                 let key_pressed = if is_modifier_key {
                     // 修饰键作为主键
                     if let Some(flag) = main_key_flag {
-                        flags.contains(flag) && check_modifiers(flags, &binding_clone.modifiers)
+                        flags.contains(flag)
+                            && check_modifiers(flags, &binding_clone.modifiers)
+                            && check_chord(flags, &binding_clone.key, chord)
+                            && check_side(flags, &binding_clone.key, side)
                     } else {
                         false
                     }
                 } else if matches!(binding_clone.key, KeyCode::CapsLock) {
-                    // CapsLock 特殊处理
-                    flags.contains(CGEventFlags::CGEventFlagAlphaShift)
+                    // CapsLock 特殊处理：AlphaShift 反映的是大写锁定状态而不是按键状态，
+                    // 只在按下时收到事件，松开由轮询检测
+                    // CGEventField 9 = kCGKeyboardEventKeycode
+                    if event.get_integer_value_field(9) as u16 == caps_lock::CAPS_LOCK_KEYCODE
+                        && caps_lock::is_key_down()
+                        && !is_key_pressed_clone.load(Ordering::SeqCst)
                         && check_modifiers(flags, &binding_clone.modifiers)
+                    {
+                        // 事件中的锁定状态已经切换，按下前为相反值
+                        let locked_before = !flags.contains(CGEventFlags::CGEventFlagAlphaShift);
+                        handle_key_state_change(
+                            true,
+                            &is_key_pressed_clone,
+                            &is_recording_clone,
+                            &original_app_pid_clone,
+                            &app_handle,
+                        );
+                        watch_caps_lock_release(
+                            locked_before,
+                            is_key_pressed_clone.clone(),
+                            is_recording_clone.clone(),
+                            original_app_pid_clone.clone(),
+                            app_handle.clone(),
+                        );
+                    }
+                    return None;
                 } else {
                     // 其他键不通过 FlagsChanged 处理
                     return None;
@@ -176,6 +291,21 @@ pub fn start_listener(
             }
 
             CGEventType::KeyDown => {
+                // CGEventField 9 = kCGKeyboardEventKeycode
+                if event.get_integer_value_field(9) as u16 == ESCAPE_KEYCODE
+                    && !matches!(binding_clone.key, KeyCode::Escape)
+                {
+                    handle_escape(&is_recording_clone, &app_handle);
+                    return None;
+                }
+
+                if is_fn_key {
+                    // Fn 和其他键一起按下是 Fn 组合键（方向键翻页、F1~F12 等），不是听写
+                    if is_key_pressed_clone.load(Ordering::SeqCst) {
+                        handle_fn_combo(&is_recording_clone, &app_handle);
+                    }
+                    return None;
+                }
                 if is_modifier_key {
                     return None;
                 }
@@ -226,12 +356,12 @@ pub fn start_listener(
         None
     };
 
-    // 订阅的事件类型取决于主键类型
+    // 订阅的事件类型取决于主键类型（KeyDown 始终订阅，用于 Esc 取消）
     let event_types = if is_modifier_key {
-        vec![CGEventType::FlagsChanged]
+        vec![CGEventType::FlagsChanged, CGEventType::KeyDown]
     } else if matches!(binding.key, KeyCode::CapsLock) {
         // CapsLock 通过 FlagsChanged 检测
-        vec![CGEventType::FlagsChanged]
+        vec![CGEventType::FlagsChanged, CGEventType::KeyDown]
     } else {
         vec![
             CGEventType::FlagsChanged,
@@ -268,6 +398,7 @@ pub fn start_listener(
     }
 
     tracing::info!("macOS hotkey listener started");
+    super::set_listener_active(true);
 
     // 使用带超时的运行循环，定期检查停止信号
     loop {
@@ -290,6 +421,7 @@ pub fn start_listener(
         }
     }
 
+    super::set_listener_active(false);
     tracing::info!("macOS hotkey listener stopped");
 
     Ok(())
@@ -309,6 +441,22 @@ fn handle_key_state_change(
         is_key_pressed.store(true, Ordering::SeqCst);
 
         if !is_recording.load(Ordering::SeqCst) {
+            // 安全输入模式下粘贴必然失败，直接跳过本次录音并提示
+            if is_secure_input_enabled() {
+                let holder = secure_input_holder();
+                tracing::warn!("Secure input is enabled by {:?}, skipping recording", holder);
+                let name = holder
+                    .and_then(|h| h.name)
+                    .unwrap_or_else(|| "未知程序".to_string());
+                events::publish(EventPayload::Error {
+                    stage: EventStage::Recording,
+                    code: ErrorCode::SecureInput,
+                    message: format!("{} 开启了安全输入模式，无法粘贴", name),
+                    recoverable: true,
+                });
+                return;
+            }
+
             is_recording.store(true, Ordering::SeqCst);
 
             // 记录当前活跃应用的 PID
@@ -316,9 +464,10 @@ fn handle_key_state_change(
             original_app_pid.store(pid, Ordering::SeqCst);
             tracing::info!("Hotkey pressed - starting recording (app pid: {})", pid);
 
+            let app_id = get_frontmost_app_id();
             let app_handle = app_handle.clone();
             std::thread::spawn(move || {
-                start_recording(&app_handle);
+                start_recording(&app_handle, app_id.as_deref(), if pid >= 0 { Some(pid) } else { None });
             });
         }
     } else if !key_pressed && was_pressed {
@@ -338,24 +487,129 @@ fn handle_key_state_change(
     }
 }
 
-fn start_recording(app_handle: &AppHandle) {
-    // 发送事件到前端
-    let _ = app_handle.emit("recording-started", ());
+/// 轮询等待 CapsLock 松开，结束录音并恢复按下前的大写锁定状态
+fn watch_caps_lock_release(
+    locked_before: bool,
+    is_key_pressed: Arc<AtomicBool>,
+    is_recording: Arc<AtomicBool>,
+    original_app_pid: Arc<AtomicI32>,
+    app_handle: AppHandle,
+) {
+    std::thread::spawn(move || {
+        while caps_lock::is_key_down() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        handle_key_state_change(
+            false,
+            &is_key_pressed,
+            &is_recording,
+            &original_app_pid,
+            &app_handle,
+        );
+        caps_lock::restore(locked_before);
+    });
+}
+
+/// Fn 组合键：取消按下 Fn 时开始的录音，松开 Fn 时不再处理
+fn handle_fn_combo(is_recording: &AtomicBool, app_handle: &AppHandle) {
+    if !is_recording.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    tracing::info!("Fn used as a combo key - cancelling recording");
+
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        cancel_recording(&app_handle);
+    });
+}
+
+/// Esc：取消正在进行的录音或处理
+fn handle_escape(is_recording: &AtomicBool, app_handle: &AppHandle) {
+    let active = get_pipeline().is_some_and(|p| p.get_state() != PipelineState::Idle);
+    if !active {
+        return;
+    }
+
+    // 之后松开快捷键时不再触发处理
+    is_recording.store(false, Ordering::SeqCst);
+    tracing::info!("Escape pressed - cancelling");
+
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        cancel_recording(&app_handle);
+    });
+}
+
+fn cancel_recording(app_handle: &AppHandle) {
+    if let Some(pipeline) = get_pipeline() {
+        if let Err(e) = pipeline.cancel() {
+            tracing::error!("Failed to cancel: {}", e);
+            return;
+        }
+    }
+
+    a11y::announce_if_enabled(app_handle, "已取消");
+}
+
+fn start_recording(app_handle: &AppHandle, app_id: Option<&str>, original_app_pid: Option<i32>) {
+    a11y::announce_if_enabled(app_handle, "开始录音");
 
     // 获取 pipeline 并开始录音
     if let Some(pipeline) = get_pipeline() {
+        // 按前台应用选择识别语言
+        let language = app_id.and_then(|id| {
+            let state = app_handle.state::<AppState>();
+            let config = state.config.blocking_read();
+            config.language_for_app(id)
+        });
+        tracing::info!("Frontmost app: {:?}, language override: {:?}", app_id, language);
+        pipeline.set_language_override(language);
+
         if let Err(e) = pipeline.start_recording() {
             tracing::error!("Failed to start recording: {}", e);
-            let _ = app_handle.emit("processing-error", e.to_string());
+            a11y::announce_if_enabled(app_handle, "录音失败");
+            return;
         }
+
+        start_auto_submit(app_handle, pipeline, original_app_pid);
+    }
+}
+
+/// 按住快捷键时检测到停顿，自动输出已说完的句子
+fn start_auto_submit(
+    app_handle: &AppHandle,
+    pipeline: Arc<VoicePipeline>,
+    original_app_pid: Option<i32>,
+) {
+    let (auto_submit, output_config) = {
+        let state = app_handle.state::<AppState>();
+        let config = state.config.blocking_read();
+        (config.hotkey.auto_submit.clone(), config.output.clone())
+    };
+    if !auto_submit.enabled {
+        return;
     }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        pipeline.start_auto_submit(auto_submit, move |result| match result {
+            Ok(text) if !text.is_empty() => {
+                tracing::info!("Auto submitted segment: {}", text);
+                if let Err(e) = output::output_text(&text, &output_config, original_app_pid) {
+                    tracing::error!("Text output failed: {}", e);
+                    events::publish_output_error(&e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Auto submit segment failed: {}", e),
+        });
+    });
 }
 
 fn stop_recording(app_handle: &AppHandle, original_app_pid: Option<i32>) {
     tracing::info!("stop_recording called");
 
-    // 发送事件到前端
-    let _ = app_handle.emit("recording-stopped", ());
+    a11y::announce_if_enabled(app_handle, "正在识别");
 
     // 获取 pipeline 并停止录音、处理
     if let Some(pipeline) = get_pipeline() {
@@ -375,23 +629,30 @@ fn stop_recording(app_handle: &AppHandle, original_app_pid: Option<i32>) {
                     tracing::info!("Processing completed successfully, text: {}", text);
 
                     // 输出文本到当前应用
+                    let mut announcement = a11y::completion_message(&text);
                     if !text.is_empty() {
+                        if output::is_self_frontmost() {
+                            // 设置窗口可以把结果显示在测试输入框中
+                            events::publish(EventPayload::SelfDictation { text: text.clone() });
+                        }
                         let cfg = config.read().await;
-                        if let Err(e) = output::output_text(
-                            &text,
-                            cfg.output.restore_clipboard,
-                            cfg.output.paste_delay_ms,
-                            original_app_pid,
-                        ) {
+                        if let Err(e) = output::output_text(&text, &cfg.output, original_app_pid) {
                             tracing::error!("Text output failed: {}", e);
+                            events::publish_output_error(&e);
+                            if e.copied_to_clipboard() {
+                                announcement = "无法粘贴，文本已复制到剪贴板".to_string();
+                            }
                         }
                     }
 
-                    let _ = app_handle_clone.emit("processing-complete", ());
+                    a11y::announce_if_enabled(&app_handle_clone, &announcement);
+                }
+                Err(PipelineError::Cancelled) => {
+                    tracing::info!("Processing cancelled");
                 }
                 Err(e) => {
                     tracing::error!("Processing error: {}", e);
-                    let _ = app_handle_clone.emit("processing-error", e.to_string());
+                    a11y::announce_if_enabled(&app_handle_clone, "识别失败");
                 }
             }
             tracing::info!("Async task finished");
@@ -1,14 +1,22 @@
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+mod caps_lock;
 #[cfg(target_os = "macos")]
 mod macos;
 
 #[cfg(target_os = "windows")]
 mod windows;
 
-use std::sync::OnceLock;
-use tauri::AppHandle;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
 use tokio::sync::mpsc;
 
-use vhisper_core::HotkeyBinding;
+use vhisper_core::config::settings::ModeHotkey;
+use vhisper_core::{DualKeyChord, HotkeyBinding, KeyCode, KeySide};
+
+use crate::output::{current_keyboard_layout, KeyboardLayout};
+use crate::AppState;
 
 #[derive(Debug, thiserror::Error)]
 pub enum HotkeyError {
@@ -19,6 +27,110 @@ pub enum HotkeyError {
 /// 全局的配置更新发送器
 static CONFIG_SENDER: OnceLock<mpsc::UnboundedSender<HotkeyBinding>> = OnceLock::new();
 
+/// 快捷键监听是否已成功注册
+static LISTENER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// 隐私模式切换快捷键
+static PRIVACY_HOTKEY: RwLock<Option<HotkeyBinding>> = RwLock::new(None);
+
+/// 把刚输出的文本替换为原始识别文本的快捷键
+static RAW_TEXT_HOTKEY: RwLock<Option<HotkeyBinding>> = RwLock::new(None);
+
+/// 按指定后处理模式录音的快捷键
+static MODE_HOTKEYS: RwLock<Vec<ModeHotkey>> = RwLock::new(Vec::new());
+
+/// 轮询隐私模式切换、原始文本快捷键的间隔
+const TOGGLE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 快捷键监听是否正在运行
+pub fn is_listener_active() -> bool {
+    LISTENER_ACTIVE.load(Ordering::SeqCst)
+}
+
+fn set_listener_active(active: bool) {
+    LISTENER_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+/// 当前键盘布局是否用 Alt/Option 输入字符
+#[cfg(target_os = "macos")]
+fn layout_uses_alt_for_text(id: &str) -> bool {
+    // US / ABC 布局很少用到 Option 字符，其余布局的重音字母、货币符号都依赖 Option
+    !matches!(id, "com.apple.keylayout.US" | "com.apple.keylayout.ABC")
+}
+
+/// 当前键盘布局是否用 Alt/Option 输入字符
+#[cfg(target_os = "windows")]
+fn layout_uses_alt_for_text(id: &str) -> bool {
+    // 用 AltGr（右 Alt）输入 €、@、重音字母等的主语言
+    const ALTGR_LANGUAGES: &[u16] = &[
+        0x05, 0x06, 0x07, 0x0A, 0x0B, 0x0C, 0x0E, 0x10, 0x13, 0x14, 0x15, 0x16, 0x18, 0x1A,
+        0x1B, 0x1D, 0x1F, 0x24, 0x25, 0x26, 0x27,
+    ];
+    u16::from_str_radix(id, 16).is_ok_and(|lang_id| ALTGR_LANGUAGES.contains(&(lang_id & 0x3FF)))
+}
+
+/// 当前键盘布局是否用 Alt/Option 输入字符
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn layout_uses_alt_for_text(_id: &str) -> bool {
+    false
+}
+
+/// 检查快捷键是否和当前键盘布局的文本输入冲突，冲突时返回提示
+///
+/// 单独使用 Alt/Option 触发时，输入 ü、ñ、€ 等字符也会开始录音
+fn layout_conflict(binding: &HotkeyBinding) -> Option<String> {
+    // macOS 左右 Option 都能输入字符；Windows 只有右 Alt 是 AltGr，绑定左 Alt 不冲突
+    let free_side = !cfg!(target_os = "macos") && binding.side_for_key() == KeySide::Left;
+    let alone = binding.key == KeyCode::Alt
+        && binding.modifiers.is_empty()
+        && binding.chord_for_key() == DualKeyChord::Single
+        && !free_side;
+    if !alone {
+        return None;
+    }
+
+    match current_keyboard_layout() {
+        KeyboardLayout::Layout(id) if layout_uses_alt_for_text(&id) => Some(format!(
+            "当前键盘布局（{}）使用 {} 输入特殊字符，单独用它触发录音会干扰打字，建议启用双键组合",
+            id,
+            if cfg!(target_os = "macos") { "Option" } else { "AltGr" }
+        )),
+        _ => None,
+    }
+}
+
+/// 检查快捷键是否和系统的 🌐 键动作冲突，冲突时返回提示
+///
+/// 系统在单独按下并松开 🌐 键时执行设置中的动作（切换输入法、表情、听写），
+/// 监听是只读的，无法拦截，只能提示用户在系统设置中关闭
+#[cfg(target_os = "macos")]
+fn globe_key_conflict(binding: &HotkeyBinding) -> Option<String> {
+    if binding.key != KeyCode::Fn {
+        return None;
+    }
+    let action = match macos::globe_key_action()? {
+        1 => "更改输入法",
+        2 => "显示表情与符号",
+        3 => "开始听写",
+        _ => return None,
+    };
+    Some(format!(
+        "按下 🌐 键时系统会{}，请在“系统设置 > 键盘”中将“按下 🌐 键时”设为“不执行任何操作”",
+        action
+    ))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn globe_key_conflict(binding: &HotkeyBinding) -> Option<String> {
+    (binding.key == KeyCode::Fn || binding.modifiers.contains(&KeyCode::Fn))
+        .then(|| "Fn 键由键盘固件处理，当前系统无法检测，请选择其他按键".to_string())
+}
+
+/// 检查快捷键是否和键盘布局或系统按键功能冲突，冲突时返回提示
+pub fn hotkey_conflict(binding: &HotkeyBinding) -> Option<String> {
+    layout_conflict(binding).or_else(|| globe_key_conflict(binding))
+}
+
 /// 请求重新加载快捷键配置
 pub fn reload_hotkey(binding: HotkeyBinding) {
     if let Some(sender) = CONFIG_SENDER.get() {
@@ -27,6 +139,149 @@ pub fn reload_hotkey(binding: HotkeyBinding) {
     }
 }
 
+/// 设置隐私模式切换快捷键，主键为修饰键的绑定会被忽略（单独按修饰键太容易误触）
+pub fn set_privacy_hotkey(binding: Option<HotkeyBinding>) {
+    let binding = binding.filter(|b| !b.key.is_modifier() && b.key != KeyCode::Fn);
+    tracing::info!("Privacy hotkey: {:?}", binding);
+    if let Ok(mut current) = PRIVACY_HOTKEY.write() {
+        *current = binding;
+    }
+}
+
+/// 在后台线程中轮询隐私模式切换快捷键，按下时切换
+///
+/// 录音快捷键的监听只处理一个绑定，切换快捷键单独轮询按键状态
+pub fn spawn_privacy_hotkey_watcher(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let mut was_down = false;
+        loop {
+            std::thread::sleep(TOGGLE_POLL_INTERVAL);
+            let binding = PRIVACY_HOTKEY.read().ok().and_then(|b| b.clone());
+            let down = binding.as_ref().is_some_and(is_binding_down);
+            if down && !was_down {
+                crate::privacy::toggle(&app_handle);
+            }
+            was_down = down;
+        }
+    });
+}
+
+/// 设置替换为原始文本的快捷键，主键为修饰键的绑定会被忽略
+pub fn set_raw_text_hotkey(binding: Option<HotkeyBinding>) {
+    let binding = binding.filter(|b| !b.key.is_modifier() && b.key != KeyCode::Fn);
+    tracing::info!("Raw text hotkey: {:?}", binding);
+    if let Ok(mut current) = RAW_TEXT_HOTKEY.write() {
+        *current = binding;
+    }
+}
+
+/// 在后台线程中轮询替换为原始文本的快捷键
+///
+/// 松开时才执行：按住的修饰键会和模拟的退格键组合成删除整词等操作
+pub fn spawn_raw_text_hotkey_watcher(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let mut was_down = false;
+        loop {
+            std::thread::sleep(TOGGLE_POLL_INTERVAL);
+            let binding = RAW_TEXT_HOTKEY.read().ok().and_then(|b| b.clone());
+            let down = binding.as_ref().is_some_and(is_binding_down);
+            if was_down && !down {
+                let output_config = app_handle
+                    .state::<AppState>()
+                    .config
+                    .blocking_read()
+                    .output
+                    .clone();
+                if let Err(e) = crate::output::insert_raw_text(&output_config) {
+                    tracing::error!("Failed to insert raw text: {}", e);
+                    crate::events::publish_output_error(&e);
+                }
+            }
+            was_down = down;
+        }
+    });
+}
+
+/// 设置按指定后处理模式录音的快捷键，主键为修饰键的绑定会被忽略
+pub fn set_mode_hotkeys(hotkeys: Vec<ModeHotkey>) {
+    let hotkeys: Vec<ModeHotkey> = hotkeys
+        .into_iter()
+        .filter(|h| !h.binding.key.is_modifier() && h.binding.key != KeyCode::Fn)
+        .collect();
+    tracing::info!("Mode hotkeys: {:?}", hotkeys);
+    if let Ok(mut current) = MODE_HOTKEYS.write() {
+        *current = hotkeys;
+    }
+}
+
+/// 在后台线程中轮询后处理模式快捷键
+///
+/// 按下时开始录音，本次会话按该模式处理；录音中再按任一模式快捷键结束录音
+pub fn spawn_mode_hotkey_watcher(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let mut was_down: Vec<bool> = Vec::new();
+        loop {
+            std::thread::sleep(TOGGLE_POLL_INTERVAL);
+            let hotkeys = MODE_HOTKEYS.read().map(|h| h.clone()).unwrap_or_default();
+            was_down.resize(hotkeys.len(), false);
+            for (hotkey, was_down) in hotkeys.iter().zip(was_down.iter_mut()) {
+                let down = is_binding_down(&hotkey.binding);
+                if down && !*was_down {
+                    toggle_mode_recording(&app_handle, hotkey);
+                }
+                *was_down = down;
+            }
+        }
+    });
+}
+
+/// 开始按指定模式录音，或结束正在进行的录音
+fn toggle_mode_recording(app_handle: &AppHandle, hotkey: &ModeHotkey) {
+    let recording = *app_handle.state::<AppState>().is_recording.blocking_read();
+    let app = app_handle.clone();
+    if recording {
+        tracing::info!("Mode hotkey: stop dictation");
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = crate::commands::audio::stop_recording(app.clone(), app.state()).await {
+                tracing::error!("Failed to stop recording: {}", e);
+            }
+        });
+        return;
+    }
+
+    tracing::info!("Mode hotkey: start dictation ({:?})", hotkey.mode);
+    if let Some(pipeline) = crate::get_pipeline() {
+        pipeline.set_mode_override(Some(hotkey.mode));
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::commands::audio::start_recording(app.clone(), app.state()).await {
+            tracing::error!("Failed to start recording: {}", e);
+            if let Some(pipeline) = crate::get_pipeline() {
+                pipeline.set_mode_override(None);
+            }
+        }
+    });
+}
+
+/// 快捷键当前是否按下
+fn is_binding_down(binding: &HotkeyBinding) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::is_binding_down(binding)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::is_binding_down(binding)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = binding;
+        false
+    }
+}
+
 /// 启动快捷键监听（带热重载支持）
 pub fn start_listener(app_handle: AppHandle, initial_binding: HotkeyBinding) -> Result<(), HotkeyError> {
     // 创建配置更新 channel
@@ -37,6 +292,9 @@ pub fn start_listener(app_handle: AppHandle, initial_binding: HotkeyBinding) ->
 
     loop {
         tracing::info!("Starting hotkey listener with binding: {:?}", current_binding);
+        if let Some(conflict) = hotkey_conflict(&current_binding) {
+            tracing::warn!("{}", conflict);
+        }
 
         #[cfg(target_os = "macos")]
         {
@@ -1,19 +1,21 @@
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::Input::KeyboardAndMouse::{
     GetAsyncKeyState, VK_CAPITAL, VK_CONTROL, VK_ESCAPE, VK_F1, VK_F10, VK_F11, VK_F12, VK_F2,
-    VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8, VK_F9, VK_LWIN, VK_MENU, VK_OEM_3, VK_SHIFT,
-    VK_SPACE, VK_TAB, VIRTUAL_KEY,
+    VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8, VK_F9, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN,
+    VK_MENU, VK_OEM_3, VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT, VK_SPACE, VK_TAB,
+    VIRTUAL_KEY,
 };
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, TryRecvError};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
 
-use crate::config::settings::{HotkeyBinding, KeyCode};
-use crate::get_pipeline;
+use super::caps_lock;
+use crate::{events, get_pipeline, output, AppState};
+use vhisper_core::config::settings::{DualKeyChord, HotkeyBinding, KeyCode, KeySide};
 
 #[derive(Debug, thiserror::Error)]
 pub enum HotkeyError {
@@ -30,6 +32,8 @@ fn keycode_to_vk(key: &KeyCode) -> VIRTUAL_KEY {
         KeyCode::Control => VK_CONTROL,
         KeyCode::Shift => VK_SHIFT,
         KeyCode::Meta => VK_LWIN,
+        // Fn 由键盘固件处理，不会产生虚拟键码
+        KeyCode::Fn => VIRTUAL_KEY(0),
 
         // 功能键
         KeyCode::F1 => VK_F1,
@@ -107,6 +111,37 @@ fn check_modifiers(modifiers: &[KeyCode]) -> bool {
     modifiers.iter().all(|m| is_key_down(keycode_to_vk(m)))
 }
 
+/// 快捷键当前是否按下，主键为修饰键时返回 false
+#[cfg(target_os = "windows")]
+pub fn is_binding_down(binding: &HotkeyBinding) -> bool {
+    !binding.key.is_modifier()
+        && is_key_down(keycode_to_vk(&binding.key))
+        && check_modifiers(&binding.modifiers)
+}
+
+/// 修饰键左右两侧的虚拟键码 (左, 右)
+#[cfg(target_os = "windows")]
+fn side_vks(key: &KeyCode) -> Option<(VIRTUAL_KEY, VIRTUAL_KEY)> {
+    match key {
+        KeyCode::Alt => Some((VK_LMENU, VK_RMENU)),
+        KeyCode::Control => Some((VK_LCONTROL, VK_RCONTROL)),
+        KeyCode::Shift => Some((VK_LSHIFT, VK_RSHIFT)),
+        KeyCode::Meta => Some((VK_LWIN, VK_RWIN)),
+        _ => None,
+    }
+}
+
+/// 检查主键状态（含双键组合和左右侧要求）
+#[cfg(target_os = "windows")]
+fn is_main_key_down(key: &KeyCode, chord: DualKeyChord, side: KeySide) -> bool {
+    match (chord, side, side_vks(key)) {
+        (DualKeyChord::BothSides, _, Some((left, right))) => is_key_down(left) && is_key_down(right),
+        (_, KeySide::Left, Some((left, _))) => is_key_down(left),
+        (_, KeySide::Right, Some((_, right))) => is_key_down(right),
+        _ => is_key_down(keycode_to_vk(key)),
+    }
+}
+
 /// 启动 Windows 快捷键监听
 #[cfg(target_os = "windows")]
 pub fn start_listener(
@@ -118,25 +153,49 @@ pub fn start_listener(
     let is_recording = Arc::new(AtomicBool::new(false));
 
     let main_vk = keycode_to_vk(&binding.key);
+    let chord = binding.chord_for_key();
+    let side = binding.side_for_key();
+    if binding.key == KeyCode::Fn || binding.modifiers.contains(&KeyCode::Fn) {
+        tracing::warn!("Fn key is not visible to Windows, hotkey will never trigger");
+    }
+    if chord == DualKeyChord::WithFn {
+        // Fn 由键盘固件处理，不会产生虚拟键码
+        tracing::warn!("Fn chord is not supported on Windows, using single key");
+    }
+
+    // CapsLock 作为主键时，松开后恢复按下前的大写锁定状态
+    let is_caps_lock = binding.key == KeyCode::CapsLock;
+    let mut locked_before = caps_lock::is_locked().unwrap_or(false);
+    // 恢复状态时模拟的 CapsLock 按键不能再次触发录音
+    let mut ignore_caps_until: Option<Instant> = None;
 
     tracing::info!(
         "Starting Windows hotkey listener for: {:?} (vk: {:?})",
         binding,
         main_vk
     );
+    super::set_listener_active(true);
 
     loop {
         // 检查是否收到停止信号
         match stop_rx.try_recv() {
             Ok(_) | Err(TryRecvError::Disconnected) => {
                 tracing::info!("Windows hotkey listener stopped");
+                super::set_listener_active(false);
                 break;
             }
             Err(TryRecvError::Empty) => {}
         }
 
         // 检查主键状态
-        let main_key_down = is_key_down(main_vk);
+        let ignoring_caps = ignore_caps_until.is_some_and(|until| Instant::now() < until);
+        let main_key_down = !ignoring_caps && is_main_key_down(&binding.key, chord, side);
+
+        let was_pressed = is_key_pressed.load(Ordering::SeqCst);
+        if is_caps_lock && !was_pressed && !main_key_down && !ignoring_caps {
+            // 空闲时记录锁定状态；检测到按下时系统已经切换过了
+            locked_before = caps_lock::is_locked().unwrap_or(locked_before);
+        }
 
         // 检查修饰键状态
         let modifiers_down = check_modifiers(&binding.modifiers);
@@ -144,8 +203,6 @@ pub fn start_listener(
         // 组合判断：主键按下 + 所有修饰键按下
         let hotkey_active = main_key_down && modifiers_down;
 
-        let was_pressed = is_key_pressed.load(Ordering::SeqCst);
-
         if hotkey_active && !was_pressed {
             // 快捷键激活
             is_key_pressed.store(true, Ordering::SeqCst);
@@ -159,6 +216,11 @@ pub fn start_listener(
             // 快捷键释放 (主键释放或任一修饰键释放)
             is_key_pressed.store(false, Ordering::SeqCst);
 
+            if is_caps_lock {
+                caps_lock::restore(locked_before);
+                ignore_caps_until = Some(Instant::now() + Duration::from_millis(100));
+            }
+
             if is_recording.load(Ordering::SeqCst) {
                 is_recording.store(false, Ordering::SeqCst);
                 tracing::info!("Hotkey released - stopping recording");
@@ -189,34 +251,45 @@ pub fn start_listener(
 }
 
 fn start_recording(app_handle: &AppHandle) {
-    let _ = app_handle.emit("recording-started", ());
-
     if let Some(pipeline) = get_pipeline() {
+        // 按前台应用选择识别语言
+        let app_id = output::get_frontmost_app_id();
+        let language = app_id.as_deref().and_then(|id| {
+            let state = app_handle.state::<AppState>();
+            let config = state.config.blocking_read();
+            config.language_for_app(id)
+        });
+        tracing::info!("Frontmost app: {:?}, language override: {:?}", app_id, language);
+        pipeline.set_language_override(language);
+
         if let Err(e) = pipeline.start_recording() {
             tracing::error!("Failed to start recording: {}", e);
-            let _ = app_handle.emit("processing-error", e.to_string());
         }
     }
 }
 
 fn stop_recording(app_handle: &AppHandle) {
-    let _ = app_handle.emit("recording-stopped", ());
 
     if let Some(pipeline) = get_pipeline() {
-        let app_handle_clone = app_handle.clone();
+        let config = app_handle.state::<AppState>().config.clone();
 
         tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap()
             .block_on(async {
-                match pipeline.stop_and_process(None).await {
-                    Ok(_) => {
-                        let _ = app_handle_clone.emit("processing-complete", ());
+                match pipeline.stop_and_process().await {
+                    Ok(text) if !text.is_empty() => {
+                        // 目标窗口权限更高时降级为仅复制到剪贴板，并通过事件提示用户
+                        let cfg = config.read().await;
+                        if let Err(e) = output::output_text(&text, &cfg.output, None) {
+                            tracing::error!("Text output failed: {}", e);
+                            events::publish_output_error(&e);
+                        }
                     }
+                    Ok(_) => {}
                     Err(e) => {
                         tracing::error!("Processing error: {}", e);
-                        let _ = app_handle_clone.emit("processing-error", e.to_string());
                     }
                 }
             });
@@ -0,0 +1,133 @@
+//! 访谈录音
+//!
+//! 麦克风和系统声音同时录成两条音轨，停止后各自识别并按时间交错成对话，
+//! 保存到对话记录（隐私模式下不保存）。开始时发送 `interview_started` 事件，
+//! 识别完成后发送 `interview_transcript` 事件
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use vhisper_core::audio::{AudioRecorder, CaptureDevice};
+use vhisper_core::history::{self, DialogueTranscript};
+use vhisper_core::EventPayload;
+
+use crate::{events, pipelines, AppState, PipelineSource};
+
+/// 正在进行的访谈录音
+struct RunningInterview {
+    id: String,
+    started_at: u64,
+    mic: AudioRecorder,
+    system: AudioRecorder,
+}
+
+static INTERVIEW: Mutex<Option<RunningInterview>> = Mutex::new(None);
+
+/// 系统声音设备：未指定名称时使用默认输出设备的回环
+fn loopback_device(name: &str) -> CaptureDevice {
+    if name.is_empty() {
+        CaptureDevice::OutputLoopback
+    } else {
+        CaptureDevice::Named(name.to_string())
+    }
+}
+
+/// 是否正在访谈录音
+pub fn is_running() -> bool {
+    INTERVIEW.lock().is_ok_and(|interview| interview.is_some())
+}
+
+/// 开始访谈录音，返回访谈 ID
+pub async fn start(app_handle: &AppHandle) -> Result<String, String> {
+    let audio = app_handle
+        .state::<AppState>()
+        .config
+        .read()
+        .await
+        .audio
+        .clone();
+
+    let mut interview = INTERVIEW.lock().map_err(|e| e.to_string())?;
+    if interview.is_some() {
+        return Err("访谈录音已在进行中".to_string());
+    }
+
+    let mut mic = AudioRecorder::new().map_err(|e| e.to_string())?;
+    let mut system = AudioRecorder::with_device(loopback_device(&audio.loopback_device))
+        .map_err(|e| e.to_string())?;
+    mic.set_realtime_priority(audio.realtime_priority);
+    system.set_realtime_priority(audio.realtime_priority);
+
+    // 两条音轨尽量同时开始，时间戳才能对齐
+    mic.start().map_err(|e| e.to_string())?;
+    if let Err(e) = system.start() {
+        let _ = mic.stop();
+        return Err(e.to_string());
+    }
+
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    // 以开始时间命名，对话记录文件按时间排列
+    let id = format!("interview-{}", started_at);
+    tracing::info!("Interview recording started: {}", id);
+    *interview = Some(RunningInterview {
+        id: id.clone(),
+        started_at,
+        mic,
+        system,
+    });
+    drop(interview);
+
+    events::publish(EventPayload::Custom {
+        name: "interview_started".to_string(),
+        data: serde_json::json!({ "id": id }),
+    });
+    Ok(id)
+}
+
+/// 停止访谈录音，识别两条音轨并返回对话记录
+pub async fn stop() -> Result<DialogueTranscript, String> {
+    let RunningInterview {
+        id,
+        started_at,
+        mut mic,
+        mut system,
+    } = INTERVIEW
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or("没有正在进行的访谈录音")?;
+
+    let mic_samples = mic.stop().map_err(|e| e.to_string())?;
+    let system_samples = system.stop().map_err(|e| e.to_string())?;
+    tracing::info!(
+        "Interview recording stopped: {} ({} mic samples, {} system samples)",
+        id,
+        mic_samples.len(),
+        system_samples.len()
+    );
+
+    // 使用回环来源的管道识别，不影响快捷键听写
+    let pipeline = pipelines()
+        .ok_or("Pipeline not initialized")?
+        .get_or_create(PipelineSource::Loopback)
+        .map_err(|e| e.to_string())?;
+    let transcript = pipeline
+        .transcribe_dialogue(id, started_at, mic_samples, system_samples)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !vhisper_core::is_incognito() {
+        if let Err(e) = history::save_transcript(&transcript) {
+            tracing::warn!("Failed to save interview transcript: {}", e);
+        }
+    }
+    events::publish(EventPayload::Custom {
+        name: "interview_transcript".to_string(),
+        data: serde_json::to_value(&transcript).unwrap_or_default(),
+    });
+    Ok(transcript)
+}
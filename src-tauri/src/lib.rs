@@ -1,22 +1,48 @@
+pub mod a11y;
+pub mod calendar;
 pub mod commands;
+pub mod crash;
+pub mod dnd;
+pub mod events;
+pub mod goals;
+pub mod health;
 pub mod hotkey;
+pub mod interview;
+pub mod midi;
 pub mod output;
+pub mod overlay;
 pub mod permissions;
+pub mod power;
+pub mod privacy;
+pub mod remote;
+pub mod telemetry;
+pub mod toast;
 pub mod tray;
+pub mod update;
 
 use std::sync::{Arc, OnceLock};
 use tauri::{Manager, RunEvent, WindowEvent};
 use tokio::sync::RwLock;
 
 // 从 vhisper-core 导入
-pub use vhisper_core::{AppConfig, VoicePipeline};
+pub use vhisper_core::{AppConfig, PipelineRegistry, PipelineSource, VoicePipeline};
 
-/// 全局 Pipeline 实例
-static VOICE_PIPELINE: OnceLock<Arc<VoicePipeline>> = OnceLock::new();
+/// 全局 Pipeline 注册表，按音频来源区分
+static PIPELINES: OnceLock<PipelineRegistry> = OnceLock::new();
 
-/// 获取全局 Pipeline
+/// 获取 Pipeline 注册表
+pub fn pipelines() -> Option<&'static PipelineRegistry> {
+    PIPELINES.get()
+}
+
+/// 获取麦克风（快捷键听写）使用的 Pipeline
 pub fn get_pipeline() -> Option<Arc<VoicePipeline>> {
-    VOICE_PIPELINE.get().cloned()
+    get_pipeline_for(PipelineSource::Microphone)
+}
+
+/// 获取指定来源的 Pipeline
+pub fn get_pipeline_for(source: PipelineSource) -> Option<Arc<VoicePipeline>> {
+    PIPELINES.get()?.get(source)
 }
 
 /// 应用全局状态
@@ -37,13 +63,7 @@ impl AppState {
 /// 初始化应用
 pub fn run() {
     // 设置 panic hook 捕获所有 panic
-    std::panic::set_hook(Box::new(|panic_info| {
-        eprintln!("!!! PANIC DETECTED !!!");
-        eprintln!("{}", panic_info);
-        if let Some(location) = panic_info.location() {
-            eprintln!("Location: {}:{}:{}", location.file(), location.line(), location.column());
-        }
-    }));
+    crash::install_panic_hook();
 
     // 初始化日志
     tracing_subscriber::fmt()
@@ -64,15 +84,24 @@ pub fn run() {
 
             let config_arc = Arc::new(RwLock::new(config.clone()));
 
-            // 初始化 VoicePipeline
-            match VoicePipeline::new(config_arc.clone()) {
-                Ok(pipeline) => {
-                    let _ = VOICE_PIPELINE.set(Arc::new(pipeline));
-                    tracing::info!("VoicePipeline initialized");
-                }
-                Err(e) => {
-                    tracing::error!("Failed to initialize VoicePipeline: {}", e);
-                }
+            if let Err(e) = vhisper_core::net::set_proxy(config.network.proxy.as_deref()) {
+                tracing::warn!("Ignoring invalid proxy setting: {}", e);
+            }
+            vhisper_core::debug::set_payload_log(config.debug.payload_log);
+
+            // 用户同意后记录崩溃报告
+            crash::apply(config.debug.crash_reports, true);
+            telemetry::apply(config.telemetry.enabled);
+
+            // 核心事件转发到前端
+            events::init(app.handle().clone());
+            toast::init(app.handle().clone());
+
+            // 初始化 Pipeline 注册表，麦克风 Pipeline 随启动创建
+            let registry = PIPELINES.get_or_init(|| PipelineRegistry::new(config_arc.clone()));
+            match registry.get_or_create(PipelineSource::Microphone) {
+                Ok(_) => tracing::info!("VoicePipeline initialized"),
+                Err(e) => tracing::error!("Failed to initialize VoicePipeline: {}", e),
             }
 
             // 初始化应用状态
@@ -84,6 +113,7 @@ pub fn run() {
 
             // 设置系统托盘（必须保持 TrayIcon 存活，否则点击无效）
             let tray_icon = tray::setup_tray(app.handle())?;
+            goals::refresh_tray(app.handle());
             app.manage(tray_icon);
 
             // 启动全局快捷键监听
@@ -95,6 +125,43 @@ pub fn run() {
                 }
             });
 
+            // 隐私模式切换快捷键
+            hotkey::set_privacy_hotkey(config.privacy.toggle_hotkey.clone());
+            hotkey::spawn_privacy_hotkey_watcher(app.handle().clone());
+
+            // 替换为原始识别文本的快捷键
+            hotkey::set_raw_text_hotkey(config.output.raw_text_hotkey.clone());
+            hotkey::spawn_raw_text_hotkey_watcher(app.handle().clone());
+
+            // 按指定后处理模式录音的快捷键
+            hotkey::set_mode_hotkeys(config.llm.mode_hotkeys.clone());
+            hotkey::spawn_mode_hotkey_watcher(app.handle().clone());
+
+            // 局域网远程触发
+            remote::apply(app.handle(), &config.remote_trigger);
+
+            // MIDI 控制器触发
+            midi::apply(app.handle(), &config.midi);
+
+            // 后台运行启动健康检查
+            health::spawn_startup_checks(app.handle().clone());
+
+            // 后台检查更新
+            update::spawn_startup_check(app.handle().clone());
+
+            // 用户开启后定期上报匿名统计
+            telemetry::spawn_reporter(app.handle().clone());
+
+            // 空闲时释放资源，锁屏和休眠唤醒时取消会话
+            power::spawn_idle_release();
+            power::spawn_monitor(app.handle().clone());
+
+            // 勿扰模式
+            dnd::spawn_monitor();
+
+            // 日历会议提醒（用户开启并授权后才读取日历）
+            calendar::spawn_monitor(app.handle().clone());
+
             // macOS: 设置为 Accessory 应用 (只显示托盘图标)
             #[cfg(target_os = "macos")]
             {
@@ -107,18 +174,64 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::config::get_config,
             commands::config::save_config,
+            commands::config::get_managed_config_paths,
+            commands::config::get_asr_provider_stats,
+            commands::config::list_asr_models,
+            commands::config::list_llm_models,
+            commands::config::set_certificate_passphrase,
+            commands::config::download_asr_model,
+            commands::config::check_hotkey_conflict,
             commands::audio::start_recording,
             commands::audio::stop_recording,
-            commands::test::test_qwen_api,
-            commands::test::test_dashscope_api,
-            commands::test::test_openai_api,
-            commands::test::test_funasr_api,
+            commands::audio::cancel_recording,
+            commands::audio::cancel_processing,
+            commands::audio::get_pipeline_states,
+            commands::audio::transcribe_file,
+            commands::audio::suppress_capture,
+            commands::calendar::get_calendar_permission,
+            commands::calendar::request_calendar_permission,
+            commands::history::get_dictation_history,
+            commands::history::get_session_transcript,
+            commands::history::get_goals,
+            commands::history::set_goal,
+            commands::interview::start_interview,
+            commands::interview::stop_interview,
+            commands::interview::get_interview_status,
+            commands::interview::list_interview_transcripts,
+            commands::interview::list_input_devices,
+            commands::output::paste_anyway,
+            commands::output::insert_raw_text,
+            commands::overlay::get_overlay_anchor,
+            commands::midi::list_midi_devices,
+            commands::notifications::get_focus_mode,
+            commands::privacy::get_incognito,
+            commands::privacy::set_incognito,
+            commands::remote::get_remote_pairing,
+            commands::remote::reset_remote_token,
+            commands::test::test_asr_api,
             commands::test::test_ollama_api,
             commands::permissions::check_permissions,
             commands::permissions::request_microphone_permission,
             commands::permissions::request_accessibility_permission,
             commands::permissions::open_accessibility_settings,
             commands::permissions::open_microphone_settings,
+            commands::permissions::check_secure_input,
+            commands::permissions::get_startup_health,
+            commands::text::process_text,
+            commands::text::list_spell_dictionaries,
+            commands::debug::start_debug_capture,
+            commands::debug::stop_debug_capture,
+            commands::debug::get_debug_capture_status,
+            commands::debug::dump_last_streaming_session,
+            commands::debug::get_payload_log_path,
+            commands::debug::list_crash_reports,
+            commands::debug::export_crash_report,
+            commands::debug::clear_crash_reports,
+            commands::update::check_for_update,
+            commands::update::get_available_update,
+            commands::update::download_update,
+            commands::telemetry::get_telemetry_preview,
+            commands::telemetry::clear_telemetry,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -160,6 +273,9 @@ pub fn run() {
                 }
                 RunEvent::Exit => {
                     tracing::warn!("!!! RunEvent::Exit - Application is exiting !!!");
+                    vhisper_core::stop_sherpa_onnx_server();
+                    crash::on_exit();
+                    telemetry::on_exit();
                 }
                 _ => {}
             }
@@ -0,0 +1,257 @@
+//! MIDI 控制器触发
+//!
+//! 把控制器上的打击垫、按钮或踏板作为听写快捷键（Note 或 CC 消息），
+//! 可选在录音时把输入电平以 CC 消息回传给带 LED 的控制器。
+
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use midir::{Ignore, MidiInput, MidiInputConnection, MidiOutput, MidiOutputConnection};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::audio;
+use crate::{get_pipeline, AppState};
+use vhisper_core::config::settings::{MidiMessageKind, MidiTriggerConfig};
+
+/// MIDI 客户端名称
+const CLIENT_NAME: &str = "Vhisper";
+/// 电平回传间隔
+const FEEDBACK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 正在运行的监听
+struct RunningMidi {
+    config: MidiTriggerConfig,
+    stop_tx: mpsc::Sender<()>,
+    worker: JoinHandle<()>,
+}
+
+static MIDI: Mutex<Option<RunningMidi>> = Mutex::new(None);
+
+/// 可用的 MIDI 设备
+#[derive(Debug, Clone, Serialize)]
+pub struct MidiDevices {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+/// 列出可用的 MIDI 设备
+pub fn list_devices() -> Result<MidiDevices, String> {
+    let input = MidiInput::new(CLIENT_NAME).map_err(|e| e.to_string())?;
+    let output = MidiOutput::new(CLIENT_NAME).map_err(|e| e.to_string())?;
+    Ok(MidiDevices {
+        inputs: input
+            .ports()
+            .iter()
+            .filter_map(|port| input.port_name(port).ok())
+            .collect(),
+        outputs: output
+            .ports()
+            .iter()
+            .filter_map(|port| output.port_name(port).ok())
+            .collect(),
+    })
+}
+
+/// 按配置启动、重启或停止监听
+pub fn apply(app: &AppHandle, config: &MidiTriggerConfig) {
+    let Ok(mut midi) = MIDI.lock() else {
+        return;
+    };
+
+    if midi.as_ref().is_some_and(|m| &m.config == config) {
+        return;
+    }
+    if let Some(running) = midi.take() {
+        let _ = running.stop_tx.send(());
+        let _ = running.worker.join();
+        tracing::info!("MIDI trigger stopped");
+    }
+    if !config.enabled {
+        return;
+    }
+
+    // MIDI 连接不一定能跨线程移动，在工作线程中创建并持有
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let app = app.clone();
+    let worker_config = config.clone();
+    let worker = std::thread::spawn(move || run(app, worker_config, stop_rx));
+
+    *midi = Some(RunningMidi {
+        config: config.clone(),
+        stop_tx,
+        worker,
+    });
+}
+
+/// 工作线程：连接设备，直到收到停止信号
+fn run(app: AppHandle, config: MidiTriggerConfig, stop_rx: mpsc::Receiver<()>) {
+    let inputs = connect_inputs(&app, &config);
+    if inputs.is_empty() {
+        tracing::warn!("No MIDI input matches {:?}", config.device);
+        return;
+    }
+
+    let mut feedback = if config.level_feedback {
+        let device = if config.feedback_device.is_empty() {
+            &config.device
+        } else {
+            &config.feedback_device
+        };
+        connect_output(device)
+    } else {
+        None
+    };
+    let channel = config.channel.unwrap_or(1).clamp(1, 16) - 1;
+    let mut last_value = 0u8;
+
+    loop {
+        match stop_rx.recv_timeout(FEEDBACK_INTERVAL) {
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            _ => break,
+        }
+        let Some(output) = feedback.as_mut() else {
+            continue;
+        };
+        let level = get_pipeline()
+            .filter(|pipeline| pipeline.is_recording())
+            .map(|pipeline| pipeline.input_level())
+            .unwrap_or(0.0);
+        let value = level_to_value(level);
+        if value != last_value {
+            let _ = output.send(&[0xB0 | channel, config.feedback_cc & 0x7F, value]);
+            last_value = value;
+        }
+    }
+
+    if let Some(output) = feedback.as_mut() {
+        let _ = output.send(&[0xB0 | channel, config.feedback_cc & 0x7F, 0]);
+    }
+    drop(inputs);
+}
+
+/// 电平转换为 0-127 的 CC 值
+///
+/// 按分贝映射（-60dB 到 0dB），说话时 LED 变化更明显
+fn level_to_value(level: f32) -> u8 {
+    if level <= 0.001 {
+        return 0;
+    }
+    let db = 20.0 * level.log10();
+    (((db + 60.0) / 60.0).clamp(0.0, 1.0) * 127.0) as u8
+}
+
+/// 连接名称匹配的所有输入设备
+fn connect_inputs(app: &AppHandle, config: &MidiTriggerConfig) -> Vec<MidiInputConnection<bool>> {
+    let ports = match MidiInput::new(CLIENT_NAME) {
+        Ok(input) => input
+            .ports()
+            .into_iter()
+            .filter_map(|port| Some((input.port_name(&port).ok()?, port)))
+            .filter(|(name, _)| name.contains(config.device.as_str()))
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            tracing::error!("Failed to open MIDI input: {}", e);
+            return Vec::new();
+        }
+    };
+
+    // connect 会消耗 MidiInput，每个端口单独创建
+    let mut connections = Vec::new();
+    for (name, port) in ports {
+        let Ok(mut input) = MidiInput::new(CLIENT_NAME) else {
+            continue;
+        };
+        input.ignore(Ignore::All);
+        let app = app.clone();
+        let config = config.clone();
+        match input.connect(
+            &port,
+            "vhisper-trigger",
+            move |_, message, pressed| handle_message(&app, &config, message, pressed),
+            false,
+        ) {
+            Ok(connection) => {
+                tracing::info!("MIDI trigger listening on {}", name);
+                connections.push(connection);
+            }
+            Err(e) => tracing::warn!("Failed to connect MIDI input {}: {}", name, e),
+        }
+    }
+    connections
+}
+
+/// 连接名称匹配的第一个输出设备
+fn connect_output(device: &str) -> Option<MidiOutputConnection> {
+    let output = MidiOutput::new(CLIENT_NAME).ok()?;
+    let port = output
+        .ports()
+        .into_iter()
+        .find(|port| output.port_name(port).is_ok_and(|name| name.contains(device)))?;
+    match output.connect(&port, "vhisper-level") {
+        Ok(connection) => Some(connection),
+        Err(e) => {
+            tracing::warn!("Failed to connect MIDI output for level feedback: {}", e);
+            None
+        }
+    }
+}
+
+/// 解析为按下（true）/ 松开（false），与触发配置不匹配时返回 None
+fn parse_trigger(config: &MidiTriggerConfig, message: &[u8]) -> Option<bool> {
+    let &[status, number, value] = message else {
+        return None;
+    };
+    if config
+        .channel
+        .is_some_and(|channel| channel.wrapping_sub(1) != status & 0x0F)
+    {
+        return None;
+    }
+    if number != config.number {
+        return None;
+    }
+    match (config.message, status & 0xF0) {
+        (MidiMessageKind::Note, 0x90) => Some(value > 0),
+        (MidiMessageKind::Note, 0x80) => Some(false),
+        (MidiMessageKind::ControlChange, 0xB0) => Some(value >= 64),
+        _ => None,
+    }
+}
+
+fn handle_message(app: &AppHandle, config: &MidiTriggerConfig, message: &[u8], pressed: &mut bool) {
+    let Some(down) = parse_trigger(config, message) else {
+        return;
+    };
+    // 踏板等 CC 控制器会连续发送相同状态，只处理变化
+    if down == *pressed {
+        return;
+    }
+    *pressed = down;
+
+    let start = match (config.toggle, down) {
+        (false, down) => Some(down),
+        (true, true) => None,
+        (true, false) => return,
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let start = match start {
+            Some(start) => start,
+            None => !*app.state::<AppState>().is_recording.read().await,
+        };
+        let result = if start {
+            tracing::info!("MIDI trigger: start dictation");
+            audio::start_recording(app.clone(), app.state()).await
+        } else {
+            tracing::info!("MIDI trigger: stop dictation");
+            audio::stop_recording(app.clone(), app.state()).await
+        };
+        if let Err(e) = result {
+            tracing::error!("MIDI trigger failed: {}", e);
+        }
+    });
+}
@@ -1,4 +1,4 @@
-use arboard::Clipboard;
+use arboard::{Clipboard, ImageData};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ClipboardError {
@@ -27,3 +27,77 @@ pub fn set_clipboard_text(text: &str) -> Result<(), ClipboardError> {
 
     Ok(())
 }
+
+/// 设置剪贴板内容，并标记为不进入剪贴板历史
+///
+/// - macOS: 附加 org.nspasteboard.ConcealedType
+/// - Windows: 设置 ExcludeClipboardContentFromMonitorProcessing 等排除格式
+pub fn set_clipboard_text_concealed(text: &str) -> Result<(), ClipboardError> {
+    let mut clipboard = Clipboard::new().map_err(|e| ClipboardError::Clipboard(e.to_string()))?;
+
+    conceal(clipboard.set())
+        .text(text)
+        .map_err(|e| ClipboardError::Clipboard(e.to_string()))?;
+
+    Ok(())
+}
+
+/// 同时设置 HTML 和纯文本两种格式
+///
+/// 富文本编辑器会使用 HTML，其他应用回退到纯文本
+pub fn set_clipboard_html(html: &str, plain: &str, concealed: bool) -> Result<(), ClipboardError> {
+    let mut clipboard = Clipboard::new().map_err(|e| ClipboardError::Clipboard(e.to_string()))?;
+
+    let set = if concealed {
+        conceal(clipboard.set())
+    } else {
+        clipboard.set()
+    };
+
+    set.html(html, Some(plain))
+        .map_err(|e| ClipboardError::Clipboard(e.to_string()))?;
+
+    Ok(())
+}
+
+/// 为写入操作附加平台相关的“不记录历史”标记
+#[allow(unused_mut)]
+fn conceal(mut set: arboard::Set<'_>) -> arboard::Set<'_> {
+    #[cfg(target_os = "macos")]
+    {
+        use arboard::SetExtApple;
+        set = set.exclude_from_history();
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use arboard::SetExtWindows;
+        set = set.exclude_from_monitoring();
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    ))]
+    {
+        use arboard::SetExtLinux;
+        set = set.exclude_from_history();
+    }
+
+    set
+}
+
+/// 设置剪贴板图片（RGBA 像素）
+pub fn set_clipboard_image(width: usize, height: usize, rgba: Vec<u8>) -> Result<(), ClipboardError> {
+    let mut clipboard = Clipboard::new().map_err(|e| ClipboardError::Clipboard(e.to_string()))?;
+
+    clipboard
+        .set_image(ImageData {
+            width,
+            height,
+            bytes: rgba.into(),
+        })
+        .map_err(|e| ClipboardError::Clipboard(e.to_string()))?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-//! 应用焦点管理模块 (macOS)
+//! 应用焦点管理模块（macOS、Windows）
 
 #[cfg(target_os = "macos")]
 use objc2_app_kit::NSWorkspace;
@@ -18,11 +18,66 @@ pub fn get_frontmost_app_pid() -> Option<i32> {
     .flatten()
 }
 
-/// Windows 占位实现
+/// 获取当前活跃应用的标识（bundle id），用于匹配按应用的配置
+#[cfg(target_os = "macos")]
+pub fn get_frontmost_app_id() -> Option<String> {
+    std::panic::catch_unwind(|| {
+        unsafe {
+            let workspace = NSWorkspace::sharedWorkspace();
+            let app = workspace.frontmostApplication()?;
+            app.bundleIdentifier().map(|id| id.to_string())
+        }
+    })
+    .ok()
+    .flatten()
+}
+
+/// 当前活跃应用是否是 Vhisper 自己（例如设置窗口在前台）
+pub fn is_self_frontmost() -> bool {
+    get_frontmost_app_pid() == Some(std::process::id() as i32)
+}
+
+/// 获取前台窗口所属进程的 ID
 #[cfg(target_os = "windows")]
 pub fn get_frontmost_app_pid() -> Option<i32> {
-    // TODO: Windows 实现
-    None
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    let mut pid = 0u32;
+    // SAFETY: pid 为有效的输出参数
+    unsafe { GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut pid)) };
+    (pid != 0).then_some(pid as i32)
+}
+
+/// 获取前台窗口所属进程的可执行文件名（如 WeChat.exe），用于匹配按应用的配置
+#[cfg(target_os = "windows")]
+pub fn get_frontmost_app_id() -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let pid = get_frontmost_app_pid()? as u32;
+    let mut buffer = [0u16; 1024];
+    let mut len = buffer.len() as u32;
+    // SAFETY: 进程句柄在本函数内打开和关闭，len 为缓冲区长度
+    let path = unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+        String::from_utf16_lossy(&buffer[..len as usize])
+    };
+
+    std::path::Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
 }
 
 /// 其他平台占位实现
@@ -30,3 +85,9 @@ pub fn get_frontmost_app_pid() -> Option<i32> {
 pub fn get_frontmost_app_pid() -> Option<i32> {
     None
 }
+
+/// 其他平台占位实现
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn get_frontmost_app_id() -> Option<String> {
+    None
+}
@@ -1,10 +1,55 @@
 mod clipboard;
 mod focus;
 mod paste;
+mod qr;
+mod secure_input;
+#[cfg(target_os = "windows")]
+mod send_input;
+mod sink;
+mod typing;
 
-pub use clipboard::{get_clipboard_text, set_clipboard_text, ClipboardError};
-pub use focus::get_frontmost_app_pid;
+pub use clipboard::{
+    get_clipboard_text, set_clipboard_html, set_clipboard_image, set_clipboard_text,
+    set_clipboard_text_concealed, ClipboardError,
+};
+pub use focus::{get_frontmost_app_id, get_frontmost_app_pid, is_self_frontmost};
 pub use paste::{simulate_paste, PasteError};
+pub use qr::{render_qr_svg, QrCodeSink};
+#[cfg(target_os = "windows")]
+pub use send_input::send_combo;
+pub use secure_input::{
+    is_secure_input_enabled, secure_input_holder, secure_input_status, SecureInputHolder,
+    SecureInputStatus,
+};
+pub use sink::{create_output_sink, ClipboardSink, OutputSink, PasteSink, TypingSink};
+pub use typing::{
+    current_keyboard_layout, delete_backward, type_text, type_text_direct, KeyboardLayout,
+    TypingError,
+};
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use unicode_segmentation::UnicodeSegmentation;
+use vhisper_core::config::settings::{OutputConfig, PasteFallback};
+
+/// 最近一次只复制到剪贴板的文本，供"仍然粘贴"使用
+static COPY_ONLY_TEXT: Mutex<Option<String>> = Mutex::new(None);
+/// 最近一次送达目标应用、可以替换为原始文本的听写
+static REPLACEABLE_OUTPUT: Mutex<Option<ReplaceableOutput>> = Mutex::new(None);
+/// 输出后多久内可以替换为原始文本，之后用户很可能已经继续编辑
+const REPLACE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 已送达目标应用的听写输出
+struct ReplaceableOutput {
+    /// 原始识别文本
+    raw: String,
+    /// 实际输入的字形簇数（用户看到的字符数）
+    graphemes: usize,
+    /// 输出时的前台应用
+    app_pid: Option<i32>,
+    inserted_at: Instant,
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum OutputError {
@@ -12,25 +57,264 @@ pub enum OutputError {
     Clipboard(#[from] ClipboardError),
     #[error("Paste error: {0}")]
     Paste(#[from] PasteError),
+    #[error("Typing error: {0}")]
+    Typing(#[from] TypingError),
+    #[error("无法粘贴（{0}），文本已复制到剪贴板")]
+    PasteBlocked(String),
+    #[error("目标窗口以管理员身份运行，文本已复制到剪贴板。请以管理员身份运行 Vhisper 或手动粘贴")]
+    ElevatedTarget,
+    #[error("Render error: {0}")]
+    Render(String),
+}
+
+impl OutputError {
+    /// 输出降级为仅复制到剪贴板
+    pub fn copied_to_clipboard(&self) -> bool {
+        matches!(self, OutputError::PasteBlocked(_) | OutputError::ElevatedTarget)
+    }
 }
 
-/// 输出文本到当前应用
+/// 前台窗口是否会丢弃本进程的模拟输入（Windows 下权限更高的窗口）
+pub(crate) fn target_is_elevated() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        send_input::foreground_integrity_higher()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+/// 记录只复制到剪贴板的文本并通知用户
+pub(crate) fn notify_copy_only(text: &str, message: &str) {
+    if let Ok(mut last) = COPY_ONLY_TEXT.lock() {
+        *last = Some(text.to_string());
+    }
+    crate::toast::show_copy_only(message);
+}
+
+/// 把最近一次只复制到剪贴板的文本粘贴到当前前台窗口
+///
+/// 通知上的"仍然粘贴"按钮和前端都通过这里重新粘贴
+pub fn paste_anyway(config: &OutputConfig) -> Result<(), OutputError> {
+    let Some(text) = COPY_ONLY_TEXT.lock().ok().and_then(|last| last.clone()) else {
+        tracing::info!("paste_anyway: nothing to paste");
+        return Ok(());
+    };
+
+    forget_output();
+    // 用户可能已经复制了别的内容，重新写入
+    set_clipboard_text(&text)?;
+    if target_is_elevated() {
+        return Err(OutputError::ElevatedTarget);
+    }
+    simulate_paste(config.paste_delay_ms, config.paste_combo)?;
+
+    if let Ok(mut last) = COPY_ONLY_TEXT.lock() {
+        *last = None;
+    }
+    Ok(())
+}
+
+/// 把刚输出的文本替换为原始识别文本（未经 LLM 校对）
+///
+/// 只替换确实粘贴或输入到目标应用的听写：按输出文本的字形簇数退格删除后粘贴原始文本。
+/// 前台应用已切换或输出后超过一段时间时不删除，只把原始文本复制到剪贴板；
+/// 无法检测用户在同一应用中继续输入或移动光标，这种情况下仍会误删。
+/// 每次听写只能替换一次，返回是否执行了替换
+pub fn insert_raw_text(config: &OutputConfig) -> Result<bool, OutputError> {
+    let replaceable = REPLACEABLE_OUTPUT
+        .lock()
+        .ok()
+        .and_then(|mut last| last.take());
+    // 与核心库的记录保持一致，替换后不能再次替换
+    vhisper_core::take_last_dictation();
+    let Some(output) = replaceable else {
+        tracing::info!("insert_raw_text: no dictation to replace");
+        return Ok(false);
+    };
+
+    if output.inserted_at.elapsed() > REPLACE_WINDOW {
+        tracing::info!("insert_raw_text: output is too old, copying raw text only");
+        set_clipboard_text(&output.raw)?;
+        return Ok(false);
+    }
+    let current_pid = get_frontmost_app_pid();
+    if current_pid != output.app_pid {
+        tracing::info!(
+            "insert_raw_text: focus changed ({:?} -> {:?}), copying raw text only",
+            output.app_pid,
+            current_pid
+        );
+        set_clipboard_text(&output.raw)?;
+        return Ok(false);
+    }
+    if target_is_elevated() {
+        set_clipboard_text(&output.raw)?;
+        return Err(OutputError::ElevatedTarget);
+    }
+
+    delete_backward(output.graphemes)?;
+
+    let original_clipboard = if config.restore_clipboard {
+        get_clipboard_text()?
+    } else {
+        None
+    };
+    if config.conceal_clipboard || vhisper_core::is_incognito() {
+        set_clipboard_text_concealed(&output.raw)?;
+    } else {
+        set_clipboard_text(&output.raw)?;
+    }
+    simulate_paste(config.paste_delay_ms, config.paste_combo)?;
+
+    if let Some(original) = original_clipboard {
+        // 延迟一下再恢复，确保粘贴完成
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        set_clipboard_text(&original)?;
+    }
+    tracing::info!(
+        "insert_raw_text: replaced {} characters with raw text",
+        output.graphemes
+    );
+    Ok(true)
+}
+
+/// 新的输出之后光标位置已变化，之前的听写不能再替换
+fn forget_output() {
+    if let Ok(mut last) = REPLACEABLE_OUTPUT.lock() {
+        *last = None;
+    }
+}
+
+/// 记录已送达目标应用的输出，供替换为原始文本使用
+///
+/// `refined` 是听写输出的文本，`inserted` 是实际粘贴或输入的文本（富文本时去掉了 Markdown 标记）。
+/// 不是最近一次听写的输出（如“仍然粘贴”）时不记录
+pub(crate) fn remember_output(refined: &str, inserted: &str) {
+    let Some(dictation) = vhisper_core::last_dictation()
+        .filter(|d| d.refined == refined && !d.raw.is_empty() && d.raw != d.refined)
+    else {
+        return;
+    };
+    let output = ReplaceableOutput {
+        raw: dictation.raw,
+        // 退格按用户看到的字符删除：emoji、组合字符和 \r\n 都算一个
+        graphemes: inserted.graphemes(true).count(),
+        app_pid: get_frontmost_app_pid(),
+        inserted_at: Instant::now(),
+    };
+    if let Ok(mut last) = REPLACEABLE_OUTPUT.lock() {
+        *last = Some(output);
+    }
+}
+
+/// 按输出配置把文本写入剪贴板，返回写入的纯文本
+///
+/// 富文本模式下同时写入 HTML，纯文本格式去掉 Markdown 标记；
+/// 开启隐藏或隐私模式时不进入剪贴板历史
+pub(crate) fn write_output_clipboard(
+    text: &str,
+    config: &OutputConfig,
+) -> Result<String, OutputError> {
+    let concealed = config.conceal_clipboard || vhisper_core::is_incognito();
+    tracing::info!(
+        "output_text: setting clipboard text (concealed={}, rich_text={})",
+        concealed,
+        config.rich_text
+    );
+
+    if config.rich_text {
+        let plain = vhisper_core::strip_markdown(text);
+        let html = vhisper_core::markdown_to_html(text);
+        set_clipboard_html(&html, &plain, concealed)?;
+        Ok(plain)
+    } else if concealed {
+        set_clipboard_text_concealed(text)?;
+        Ok(text.to_string())
+    } else {
+        set_clipboard_text(text)?;
+        Ok(text.to_string())
+    }
+}
+
+/// 粘贴会被吞掉的原因
+enum PasteBlocker {
+    /// 安全输入模式：模拟按键同样会被系统丢弃，无法改用键盘输入
+    SecureInput(String),
+    /// 剪贴板被其他程序改写或无法读取
+    Clipboard(String),
+}
+
+impl PasteBlocker {
+    fn reason(self) -> String {
+        match self {
+            PasteBlocker::SecureInput(reason) | PasteBlocker::Clipboard(reason) => reason,
+        }
+    }
+}
+
+/// 检查当前环境下 Cmd/Ctrl+V 是否会生效
+///
+/// 返回 Some 表示粘贴会被吞掉
+fn detect_paste_blocker(text: &str) -> Option<PasteBlocker> {
+    if is_secure_input_enabled() {
+        let holder = secure_input_holder()
+            .and_then(|h| h.name)
+            .unwrap_or_else(|| "未知程序".to_string());
+        return Some(PasteBlocker::SecureInput(format!(
+            "{} 开启了安全输入模式",
+            holder
+        )));
+    }
+
+    // 剪贴板管理器等程序可能在写入后立即改写剪贴板
+    match get_clipboard_text() {
+        Ok(Some(current)) if current == text => None,
+        Ok(_) => Some(PasteBlocker::Clipboard("剪贴板被其他程序占用".to_string())),
+        Err(e) => Some(PasteBlocker::Clipboard(format!("无法读取剪贴板: {}", e))),
+    }
+}
+
+/// 按配置的输出目标输出文本
+pub fn output_text(
+    text: &str,
+    config: &OutputConfig,
+    original_app_pid: Option<i32>,
+) -> Result<(), OutputError> {
+    forget_output();
+
+    // Vhisper 自己的窗口在前台时粘贴会改写设置项，只复制到剪贴板
+    let sink: Box<dyn OutputSink> = if is_self_frontmost() {
+        tracing::info!("output_text: Vhisper is frontmost, copying to clipboard only");
+        Box::new(ClipboardSink)
+    } else {
+        create_output_sink(config.sink)
+    };
+    tracing::info!("output_text: using sink {}", sink.name());
+    sink.output(text, config, original_app_pid)
+}
+
+/// 粘贴文本到当前应用
 ///
 /// - 如果 `original_app_pid` 与当前活跃应用相同，则执行粘贴
 /// - 如果不同（用户切换了应用），则只复制到剪贴板
+/// - 如果粘贴会被阻止，按 `config.paste_fallback` 改为键盘输入或返回 `PasteBlocked`；
+///   安全输入模式下键盘输入同样会被丢弃，总是返回 `PasteBlocked`
 ///
 /// 参数:
 /// - `text`: 要输出的文本
-/// - `restore_clipboard`: 是否恢复原剪贴板内容
-/// - `paste_delay_ms`: 粘贴前的延迟（毫秒）
+/// - `config`: 输出配置
 /// - `original_app_pid`: 开始录音时的应用 PID，None 表示总是粘贴
-pub fn output_text(
+fn paste_text(
     text: &str,
-    restore_clipboard: bool,
-    paste_delay_ms: u64,
+    config: &OutputConfig,
     original_app_pid: Option<i32>,
 ) -> Result<(), OutputError> {
     tracing::info!("output_text: starting, original_app_pid={:?}", original_app_pid);
+    let refined = text;
 
     // 检查是否需要粘贴（用户是否还在原应用）
     let should_paste = match original_app_pid {
@@ -54,33 +338,67 @@ pub fn output_text(
     tracing::info!("output_text: should_paste={}", should_paste);
 
     // 保存当前剪贴板内容
-    let original_clipboard = if restore_clipboard && should_paste {
+    let original_clipboard = if config.restore_clipboard && should_paste {
         tracing::info!("output_text: getting original clipboard");
         get_clipboard_text()?
     } else {
         None
     };
 
-    tracing::info!("output_text: setting clipboard text");
-    // 设置新的剪贴板内容
-    set_clipboard_text(text)?;
+    let plain = write_output_clipboard(text, config)?;
+    let text = plain.as_str();
     tracing::info!("output_text: clipboard text set successfully");
 
-    // 只有在同一应用时才模拟粘贴
-    if should_paste {
-        tracing::info!("output_text: simulating paste with delay {}ms", paste_delay_ms);
-        simulate_paste(paste_delay_ms)?;
-        tracing::info!("output_text: paste simulated successfully");
-
-        // 恢复原剪贴板内容
-        if restore_clipboard {
-            if let Some(original) = original_clipboard {
-                tracing::info!("output_text: restoring original clipboard");
-                // 延迟一下再恢复，确保粘贴完成
-                std::thread::sleep(std::time::Duration::from_millis(100));
-                set_clipboard_text(&original)?;
-                tracing::info!("output_text: original clipboard restored");
+    if !should_paste {
+        notify_copy_only(text, "已切换到其他应用，文本已复制到剪贴板");
+        tracing::info!("output_text: completed successfully");
+        return Ok(());
+    }
+
+    // 模拟按键和键盘输入都到不了管理员窗口，只保留在剪贴板
+    if target_is_elevated() {
+        tracing::warn!("output_text: target window is elevated, leaving text in clipboard");
+        let error = OutputError::ElevatedTarget;
+        notify_copy_only(text, &error.to_string());
+        return Err(error);
+    }
+
+    // 粘贴会被吞掉时走回退策略
+    if let Some(blocker) = detect_paste_blocker(text) {
+        return match (blocker, config.paste_fallback) {
+            // 剪贴板被占用时模拟按键仍然有效，只用 Unicode 注入，不再经过剪贴板
+            (PasteBlocker::Clipboard(reason), PasteFallback::Typing) => {
+                tracing::warn!("output_text: paste blocked ({}), typing instead", reason);
+                type_text_direct(text)?;
+                remember_output(refined, text);
+                if let Some(original) = original_clipboard {
+                    set_clipboard_text(&original)?;
+                }
+                tracing::info!("output_text: typed via fallback");
+                Ok(())
+            }
+            // 安全输入模式下键盘输入同样无效，文本留在剪贴板
+            (blocker, _) => {
+                let reason = blocker.reason();
+                tracing::warn!("output_text: paste blocked ({}), leaving text in clipboard", reason);
+                Err(OutputError::PasteBlocked(reason))
             }
+        };
+    }
+
+    tracing::info!("output_text: simulating paste with delay {}ms", config.paste_delay_ms);
+    simulate_paste(config.paste_delay_ms, config.paste_combo)?;
+    tracing::info!("output_text: paste simulated successfully");
+    remember_output(refined, text);
+
+    // 恢复原剪贴板内容
+    if config.restore_clipboard {
+        if let Some(original) = original_clipboard {
+            tracing::info!("output_text: restoring original clipboard");
+            // 延迟一下再恢复，确保粘贴完成
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            set_clipboard_text(&original)?;
+            tracing::info!("output_text: original clipboard restored");
         }
     }
 
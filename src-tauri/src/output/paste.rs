@@ -1,6 +1,8 @@
 use std::thread;
 use std::time::Duration;
 
+use vhisper_core::config::settings::PasteCombo;
+
 #[derive(Debug, thiserror::Error)]
 pub enum PasteError {
     #[error("Paste error: {0}")]
@@ -8,7 +10,9 @@ pub enum PasteError {
 }
 
 /// 模拟粘贴操作
-pub fn simulate_paste(delay_ms: u64) -> Result<(), PasteError> {
+///
+/// `combo` 只在 Windows / Linux 生效，macOS 始终使用 Cmd+V
+pub fn simulate_paste(delay_ms: u64, combo: PasteCombo) -> Result<(), PasteError> {
     tracing::info!("simulate_paste: sleeping for {}ms", delay_ms);
     // 等待一小段时间，确保剪贴板内容已就绪
     thread::sleep(Duration::from_millis(delay_ms));
@@ -16,6 +20,8 @@ pub fn simulate_paste(delay_ms: u64) -> Result<(), PasteError> {
 
     #[cfg(target_os = "macos")]
     {
+        let _ = combo;
+
         use core_graphics::event::{CGEvent, CGEventFlags, CGKeyCode};
         use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
@@ -48,20 +54,10 @@ pub fn simulate_paste(delay_ms: u64) -> Result<(), PasteError> {
 
     #[cfg(target_os = "windows")]
     {
-        use enigo::{Enigo, Key, Keyboard, Settings};
-
-        let mut enigo = Enigo::new(&Settings::default())
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
+        use super::send_input::send_paste;
 
-        enigo
-            .key(Key::Control, enigo::Direction::Press)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Unicode('v'), enigo::Direction::Click)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Control, enigo::Direction::Release)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
+        tracing::info!("simulate_paste: using SendInput for {:?}", combo);
+        send_paste(combo)?;
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
@@ -71,15 +67,25 @@ pub fn simulate_paste(delay_ms: u64) -> Result<(), PasteError> {
         let mut enigo = Enigo::new(&Settings::default())
             .map_err(|e| PasteError::Paste(e.to_string()))?;
 
+        let (modifiers, key): (&[Key], Key) = match combo {
+            PasteCombo::CtrlV => (&[Key::Control], Key::Unicode('v')),
+            PasteCombo::ShiftInsert => (&[Key::Shift], Key::Insert),
+            PasteCombo::CtrlShiftV => (&[Key::Control, Key::Shift], Key::Unicode('v')),
+        };
+
+        for modifier in modifiers {
+            enigo
+                .key(*modifier, enigo::Direction::Press)
+                .map_err(|e| PasteError::Paste(e.to_string()))?;
+        }
         enigo
-            .key(Key::Control, enigo::Direction::Press)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Unicode('v'), enigo::Direction::Click)
-            .map_err(|e| PasteError::Paste(e.to_string()))?;
-        enigo
-            .key(Key::Control, enigo::Direction::Release)
+            .key(key, enigo::Direction::Click)
             .map_err(|e| PasteError::Paste(e.to_string()))?;
+        for modifier in modifiers.iter().rev() {
+            enigo
+                .key(*modifier, enigo::Direction::Release)
+                .map_err(|e| PasteError::Paste(e.to_string()))?;
+        }
     }
 
     tracing::info!("simulate_paste: completed successfully");
@@ -0,0 +1,82 @@
+//! 二维码输出：把文本渲染为二维码图片放到剪贴板
+
+use qrcode::{Color, QrCode};
+
+use super::clipboard::set_clipboard_image;
+use super::sink::OutputSink;
+use super::OutputError;
+use vhisper_core::config::settings::OutputConfig;
+
+/// 每个模块的像素边长
+const MODULE_PIXELS: usize = 8;
+/// 四周留白（模块数），扫码器需要
+const QUIET_ZONE: usize = 4;
+
+/// 二维码输出
+pub struct QrCodeSink;
+
+impl OutputSink for QrCodeSink {
+    fn name(&self) -> &'static str {
+        "QrCode"
+    }
+
+    fn output(
+        &self,
+        text: &str,
+        _config: &OutputConfig,
+        _original_app_pid: Option<i32>,
+    ) -> Result<(), OutputError> {
+        let code = QrCode::new(text.as_bytes())
+            .map_err(|e| OutputError::Render(format!("文本过长，无法生成二维码: {}", e)))?;
+        let (size, rgba) = render_rgba(&code);
+        set_clipboard_image(size, size, rgba)?;
+        tracing::info!("QR code ({}x{}) copied to clipboard", size, size);
+        Ok(())
+    }
+}
+
+/// 渲染为 SVG，供设置页显示配对二维码等
+pub fn render_qr_svg(text: &str) -> Result<String, OutputError> {
+    let code = QrCode::new(text.as_bytes())
+        .map_err(|e| OutputError::Render(format!("文本过长，无法生成二维码: {}", e)))?;
+    let modules = code.width();
+    let size = modules + QUIET_ZONE * 2;
+
+    let mut path = String::new();
+    for (index, color) in code.to_colors().iter().enumerate() {
+        if *color == Color::Dark {
+            let x = index % modules + QUIET_ZONE;
+            let y = index / modules + QUIET_ZONE;
+            path.push_str(&format!("M{} {}h1v1h-1z", x, y));
+        }
+    }
+
+    Ok(format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" shape-rendering="crispEdges"><rect width="{size}" height="{size}" fill="#fff"/><path d="{path}" fill="#000"/></svg>"##
+    ))
+}
+
+/// 渲染为 RGBA 像素，返回 (边长, 像素数据)
+fn render_rgba(code: &QrCode) -> (usize, Vec<u8>) {
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + QUIET_ZONE * 2) * MODULE_PIXELS;
+
+    let mut rgba = vec![255u8; size * size * 4];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x0 = (index % modules + QUIET_ZONE) * MODULE_PIXELS;
+        let y0 = (index / modules + QUIET_ZONE) * MODULE_PIXELS;
+        for y in y0..y0 + MODULE_PIXELS {
+            let row = y * size * 4;
+            for x in x0..x0 + MODULE_PIXELS {
+                let offset = row + x * 4;
+                rgba[offset..offset + 3].fill(0);
+            }
+        }
+    }
+
+    (size, rgba)
+}
@@ -0,0 +1,80 @@
+//! 安全输入检测
+//!
+//! macOS 上密码框等会开启 Secure Keyboard Entry，此时事件监听收不到按键，
+//! 模拟的按键事件也会被系统丢弃
+
+use serde::Serialize;
+
+/// 持有安全输入的进程
+#[derive(Debug, Clone, Serialize)]
+pub struct SecureInputHolder {
+    pub pid: i32,
+    /// 进程名，获取失败时为 None
+    pub name: Option<String>,
+}
+
+/// 安全输入状态
+#[derive(Debug, Clone, Serialize)]
+pub struct SecureInputStatus {
+    pub enabled: bool,
+    pub holder: Option<SecureInputHolder>,
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn IsSecureEventInputEnabled() -> u8;
+}
+
+/// 当前是否处于安全输入模式
+#[cfg(target_os = "macos")]
+pub fn is_secure_input_enabled() -> bool {
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
+
+/// 其他平台没有等价机制
+#[cfg(not(target_os = "macos"))]
+pub fn is_secure_input_enabled() -> bool {
+    false
+}
+
+/// 查找开启安全输入的进程
+///
+/// 系统没有公开 API，这里读取 IORegistry 中的 kCGSSessionSecureInputPID
+#[cfg(target_os = "macos")]
+pub fn secure_input_holder() -> Option<SecureInputHolder> {
+    use std::process::Command;
+
+    let output = Command::new("ioreg").args(["-l", "-w", "0"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let pid = stdout.lines().find_map(|line| {
+        let (_, rest) = line.split_once("\"kCGSSessionSecureInputPID\"=")?;
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse::<i32>().ok()
+    })?;
+
+    let name = Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|path| path.rsplit('/').next().unwrap_or(&path).to_string());
+
+    Some(SecureInputHolder { pid, name })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn secure_input_holder() -> Option<SecureInputHolder> {
+    None
+}
+
+/// 获取安全输入状态（仅在开启时查找持有进程）
+pub fn secure_input_status() -> SecureInputStatus {
+    let enabled = is_secure_input_enabled();
+    SecureInputStatus {
+        enabled,
+        holder: if enabled { secure_input_holder() } else { None },
+    }
+}
@@ -0,0 +1,168 @@
+//! Windows SendInput 按键模拟
+//!
+//! 以扫描码发送按键，只读取扫描码的程序（游戏、远程桌面等）也能收到；
+//! Insert、方向键、右侧修饰键等扩展键带 KEYEVENTF_EXTENDEDKEY 标志。
+
+use std::ffi::c_void;
+use std::mem::size_of;
+
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Security::{
+    GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation, TokenIntegrityLevel,
+    TOKEN_MANDATORY_LABEL, TOKEN_QUERY,
+};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    MapVirtualKeyW, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_SCANCODE, MAPVK_VK_TO_VSC, VIRTUAL_KEY,
+    VK_APPS, VK_CONTROL, VK_DELETE, VK_DIVIDE, VK_DOWN, VK_END, VK_HOME, VK_INSERT, VK_LEFT,
+    VK_LWIN, VK_NEXT, VK_NUMLOCK, VK_PRIOR, VK_RCONTROL, VK_RIGHT, VK_RMENU, VK_RWIN, VK_SHIFT,
+    VK_SNAPSHOT, VK_UP,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+use super::paste::PasteError;
+use vhisper_core::config::settings::PasteCombo;
+
+/// 扩展键：扫描码带 E0 前缀，需要 KEYEVENTF_EXTENDEDKEY
+fn is_extended(vk: VIRTUAL_KEY) -> bool {
+    matches!(
+        vk,
+        VK_INSERT
+            | VK_DELETE
+            | VK_HOME
+            | VK_END
+            | VK_PRIOR
+            | VK_NEXT
+            | VK_LEFT
+            | VK_RIGHT
+            | VK_UP
+            | VK_DOWN
+            | VK_RCONTROL
+            | VK_RMENU
+            | VK_LWIN
+            | VK_RWIN
+            | VK_APPS
+            | VK_DIVIDE
+            | VK_NUMLOCK
+            | VK_SNAPSHOT
+    )
+}
+
+fn key_input(vk: VIRTUAL_KEY, key_up: bool) -> INPUT {
+    // SAFETY: 纯查询，无副作用
+    let scan = unsafe { MapVirtualKeyW(vk.0 as u32, MAPVK_VK_TO_VSC) } as u16;
+
+    let mut flags: KEYBD_EVENT_FLAGS = KEYEVENTF_SCANCODE;
+    if is_extended(vk) {
+        flags |= KEYEVENTF_EXTENDEDKEY;
+    }
+    if key_up {
+        flags |= KEYEVENTF_KEYUP;
+    }
+
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: scan,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// 一次性发送组合键：依次按下修饰键和主键，再逆序释放
+pub fn send_combo(modifiers: &[VIRTUAL_KEY], key: VIRTUAL_KEY) -> Result<(), PasteError> {
+    let mut inputs = Vec::with_capacity(modifiers.len() * 2 + 2);
+    inputs.extend(modifiers.iter().map(|&vk| key_input(vk, false)));
+    inputs.push(key_input(key, false));
+    inputs.push(key_input(key, true));
+    inputs.extend(modifiers.iter().rev().map(|&vk| key_input(vk, true)));
+
+    // SAFETY: inputs 均为合法的键盘输入
+    let sent = unsafe { SendInput(&inputs, size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        return Err(PasteError::Paste(format!(
+            "SendInput 只发送了 {}/{} 个按键事件: {}",
+            sent,
+            inputs.len(),
+            windows::core::Error::from_win32()
+        )));
+    }
+    Ok(())
+}
+
+/// 发送配置的粘贴组合键
+pub fn send_paste(combo: PasteCombo) -> Result<(), PasteError> {
+    const VK_V: VIRTUAL_KEY = VIRTUAL_KEY(0x56);
+    match combo {
+        PasteCombo::CtrlV => send_combo(&[VK_CONTROL], VK_V),
+        PasteCombo::ShiftInsert => send_combo(&[VK_SHIFT], VK_INSERT),
+        PasteCombo::CtrlShiftV => send_combo(&[VK_CONTROL, VK_SHIFT], VK_V),
+    }
+}
+
+/// 读取进程的完整性级别（SECURITY_MANDATORY_*_RID），无权读取时返回 None
+fn process_integrity_level(process: HANDLE) -> Option<u32> {
+    // SAFETY: 令牌句柄在本函数内打开和关闭，缓冲区按 GetTokenInformation 返回的长度分配并按 8 字节对齐
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(process, TOKEN_QUERY, &mut token).ok()?;
+
+        let mut len = 0u32;
+        let _ = GetTokenInformation(token, TokenIntegrityLevel, None, 0, &mut len);
+        let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+        let result = GetTokenInformation(
+            token,
+            TokenIntegrityLevel,
+            Some(buffer.as_mut_ptr() as *mut c_void),
+            len,
+            &mut len,
+        );
+        let _ = CloseHandle(token);
+        result.ok()?;
+
+        let label = &*(buffer.as_ptr() as *const TOKEN_MANDATORY_LABEL);
+        let count = *GetSidSubAuthorityCount(label.Label.Sid);
+        if count == 0 {
+            return None;
+        }
+        Some(*GetSidSubAuthority(label.Label.Sid, count as u32 - 1))
+    }
+}
+
+/// 前台窗口的完整性级别是否高于本进程（如以管理员身份运行的程序）
+///
+/// UIPI 会静默丢弃发往更高完整性级别窗口的模拟输入，SendInput 的返回值无法反映
+pub fn foreground_integrity_higher() -> bool {
+    // SAFETY: 伪句柄无需关闭
+    let Some(own) = process_integrity_level(unsafe { GetCurrentProcess() }) else {
+        return false;
+    };
+
+    let mut pid = 0u32;
+    // SAFETY: pid 为有效的输出参数
+    unsafe { GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut pid)) };
+    if pid == 0 {
+        return false;
+    }
+
+    // SAFETY: 进程句柄在本函数内打开和关闭
+    let target = unsafe {
+        let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            return false;
+        };
+        let level = process_integrity_level(process);
+        let _ = CloseHandle(process);
+        level
+    };
+
+    // 低完整性级别的进程通常无权读取更高级别进程的令牌，读不到时按更高处理
+    target.is_none_or(|level| level > own)
+}
@@ -0,0 +1,102 @@
+//! 输出目标抽象
+//!
+//! 识别结果最终交给一个 OutputSink，由配置决定粘贴、仅复制或其他形式
+
+use super::clipboard::set_clipboard_text;
+use super::qr::QrCodeSink;
+use super::typing::type_text;
+use super::{
+    notify_copy_only, paste_text, remember_output, target_is_elevated, write_output_clipboard,
+    OutputError,
+};
+use vhisper_core::config::settings::{OutputConfig, OutputSinkKind};
+
+/// 输出目标 trait
+pub trait OutputSink: Send + Sync {
+    /// 名称（用于日志）
+    fn name(&self) -> &'static str;
+
+    /// 输出文本
+    fn output(
+        &self,
+        text: &str,
+        config: &OutputConfig,
+        original_app_pid: Option<i32>,
+    ) -> Result<(), OutputError>;
+}
+
+/// 粘贴到当前应用
+pub struct PasteSink;
+
+impl OutputSink for PasteSink {
+    fn name(&self) -> &'static str {
+        "Paste"
+    }
+
+    fn output(
+        &self,
+        text: &str,
+        config: &OutputConfig,
+        original_app_pid: Option<i32>,
+    ) -> Result<(), OutputError> {
+        paste_text(text, config, original_app_pid)
+    }
+}
+
+/// 只复制到剪贴板
+pub struct ClipboardSink;
+
+impl OutputSink for ClipboardSink {
+    fn name(&self) -> &'static str {
+        "Clipboard"
+    }
+
+    fn output(
+        &self,
+        text: &str,
+        config: &OutputConfig,
+        _original_app_pid: Option<i32>,
+    ) -> Result<(), OutputError> {
+        write_output_clipboard(text, config)?;
+        Ok(())
+    }
+}
+
+/// 模拟键盘逐字输入
+pub struct TypingSink;
+
+impl OutputSink for TypingSink {
+    fn name(&self) -> &'static str {
+        "Typing"
+    }
+
+    fn output(
+        &self,
+        text: &str,
+        config: &OutputConfig,
+        _original_app_pid: Option<i32>,
+    ) -> Result<(), OutputError> {
+        // 模拟输入到不了管理员窗口，改为只复制到剪贴板
+        if target_is_elevated() {
+            set_clipboard_text(text)?;
+            let error = OutputError::ElevatedTarget;
+            notify_copy_only(text, &error.to_string());
+            return Err(error);
+        }
+
+        // 剪贴板辅助片段由 type_text 按配置隐藏和恢复剪贴板
+        type_text(text, config)?;
+        remember_output(text, text);
+        Ok(())
+    }
+}
+
+/// 根据配置创建输出目标
+pub fn create_output_sink(kind: OutputSinkKind) -> Box<dyn OutputSink> {
+    match kind {
+        OutputSinkKind::Paste => Box::new(PasteSink),
+        OutputSinkKind::Clipboard => Box::new(ClipboardSink),
+        OutputSinkKind::Typing => Box::new(TypingSink),
+        OutputSinkKind::QrCode => Box::new(QrCodeSink),
+    }
+}
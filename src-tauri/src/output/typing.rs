@@ -0,0 +1,221 @@
+//! 键盘输入输出
+//!
+//! 逐字模拟键盘输入，按当前键盘布局/输入法为每段字符选择策略：
+//! - Unicode 注入：直接发送字符，不依赖物理键位，非 US 布局也能得到正确字符
+//! - 剪贴板辅助：输入法激活时按键会被输入法截获，CJK 等字符也常被应用错误处理，
+//!   这些字符改为写入剪贴板后粘贴；与粘贴输出一样按配置隐藏和恢复剪贴板
+
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+use super::clipboard::{get_clipboard_text, set_clipboard_text, set_clipboard_text_concealed};
+use super::paste::simulate_paste;
+use vhisper_core::config::settings::OutputConfig;
+
+/// 剪贴板辅助输入时，写入剪贴板到粘贴之间的延迟
+const CLIPBOARD_ASSIST_DELAY_MS: u64 = 30;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TypingError {
+    #[error("Typing error: {0}")]
+    Typing(String),
+}
+
+/// 当前键盘布局
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    /// 普通键盘布局（US、German、French 等），值为系统中的布局标识
+    Layout(String),
+    /// 输入法（拼音、日文、韩文等），按键会先进入输入法
+    InputMethod(String),
+    /// 无法检测
+    Unknown,
+}
+
+/// 单个字符的输入策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharStrategy {
+    Unicode,
+    Clipboard,
+}
+
+/// 是否是需要剪贴板辅助的字符（CJK、表情等）
+fn needs_clipboard(c: char) -> bool {
+    let code = c as u32;
+    // CJK 部首及之后的区段、补充平面字符（表情等）
+    (0x2E80..=0xD7FF).contains(&code) || (0xF900..=0xFAFF).contains(&code) || code > 0xFFFF
+}
+
+fn strategy_for(c: char, layout: &KeyboardLayout) -> CharStrategy {
+    match layout {
+        // 输入法会截获模拟按键，全部走剪贴板
+        KeyboardLayout::InputMethod(_) => CharStrategy::Clipboard,
+        _ if needs_clipboard(c) => CharStrategy::Clipboard,
+        _ => CharStrategy::Unicode,
+    }
+}
+
+/// 按策略将文本切分为连续片段
+fn split_runs(text: &str, layout: &KeyboardLayout) -> Vec<(CharStrategy, String)> {
+    let mut runs: Vec<(CharStrategy, String)> = Vec::new();
+    for c in text.chars() {
+        let strategy = strategy_for(c, layout);
+        match runs.last_mut() {
+            Some((last, run)) if *last == strategy => run.push(c),
+            _ => runs.push((strategy, c.to_string())),
+        }
+    }
+    runs
+}
+
+/// 检测当前键盘布局 (macOS: TIS 输入源)
+#[cfg(target_os = "macos")]
+pub fn current_keyboard_layout() -> KeyboardLayout {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+    use std::ffi::c_void;
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn TISCopyCurrentKeyboardInputSource() -> *mut c_void;
+        fn TISGetInputSourceProperty(source: *mut c_void, key: CFStringRef) -> *const c_void;
+        static kTISPropertyInputSourceID: CFStringRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+
+    unsafe {
+        let source = TISCopyCurrentKeyboardInputSource();
+        if source.is_null() {
+            return KeyboardLayout::Unknown;
+        }
+        let id_ref = TISGetInputSourceProperty(source, kTISPropertyInputSourceID);
+        let id = if id_ref.is_null() {
+            None
+        } else {
+            Some(CFString::wrap_under_get_rule(id_ref as CFStringRef).to_string())
+        };
+        CFRelease(source);
+
+        match id {
+            // 例如 com.apple.inputmethod.SCIM.ITABC、com.apple.inputmethod.Kotoeri.*
+            Some(id) if id.contains(".inputmethod.") => KeyboardLayout::InputMethod(id),
+            Some(id) => KeyboardLayout::Layout(id),
+            None => KeyboardLayout::Unknown,
+        }
+    }
+}
+
+/// 检测当前键盘布局 (Windows: 前台窗口线程的 HKL)
+#[cfg(target_os = "windows")]
+pub fn current_keyboard_layout() -> KeyboardLayout {
+    use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyboardLayout;
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    let hkl = unsafe {
+        let hwnd = GetForegroundWindow();
+        let thread_id = GetWindowThreadProcessId(hwnd, None);
+        GetKeyboardLayout(thread_id)
+    };
+
+    // 低 16 位为 LANGID，低 10 位为主语言
+    let lang_id = (hkl.0 as usize & 0xFFFF) as u16;
+    let primary = lang_id & 0x3FF;
+    let id = format!("{:04X}", lang_id);
+
+    match primary {
+        // LANG_CHINESE / LANG_JAPANESE / LANG_KOREAN 通常伴随输入法
+        0x04 | 0x11 | 0x12 => KeyboardLayout::InputMethod(id),
+        _ => KeyboardLayout::Layout(id),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn current_keyboard_layout() -> KeyboardLayout {
+    KeyboardLayout::Unknown
+}
+
+/// 通过模拟键盘输入逐字输入文本
+///
+/// 不依赖 Cmd/Ctrl+V 直接粘贴整段文本，用于打字输出模式。
+/// 剪贴板辅助片段以 `paste_combo` 粘贴，写入时按 `conceal_clipboard` 隐藏，
+/// 结束后按 `restore_clipboard` 恢复原剪贴板内容
+pub fn type_text(text: &str, config: &OutputConfig) -> Result<(), TypingError> {
+    let layout = current_keyboard_layout();
+    let runs = split_runs(text, &layout);
+    let uses_clipboard = runs
+        .iter()
+        .any(|(strategy, _)| *strategy == CharStrategy::Clipboard);
+    tracing::info!(
+        "type_text: typing {} chars in {} runs, layout={:?}",
+        text.chars().count(),
+        runs.len(),
+        layout
+    );
+
+    let original_clipboard = if uses_clipboard && config.restore_clipboard {
+        get_clipboard_text().map_err(|e| TypingError::Typing(e.to_string()))?
+    } else {
+        None
+    };
+    let concealed = config.conceal_clipboard || vhisper_core::is_incognito();
+
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| TypingError::Typing(e.to_string()))?;
+
+    for (strategy, run) in runs {
+        match strategy {
+            CharStrategy::Unicode => {
+                enigo
+                    .text(&run)
+                    .map_err(|e| TypingError::Typing(e.to_string()))?;
+            }
+            CharStrategy::Clipboard => {
+                let written = if concealed {
+                    set_clipboard_text_concealed(&run)
+                } else {
+                    set_clipboard_text(&run)
+                };
+                written.map_err(|e| TypingError::Typing(e.to_string()))?;
+                simulate_paste(CLIPBOARD_ASSIST_DELAY_MS, config.paste_combo)
+                    .map_err(|e| TypingError::Typing(e.to_string()))?;
+            }
+        }
+    }
+
+    if let Some(original) = original_clipboard {
+        // 延迟一下再恢复，确保最后一段粘贴完成
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        set_clipboard_text(&original).map_err(|e| TypingError::Typing(e.to_string()))?;
+    }
+
+    tracing::info!("type_text: completed successfully");
+    Ok(())
+}
+
+/// 只通过 Unicode 注入输入文本，不借助剪贴板
+///
+/// 粘贴被阻止时的回退使用：剪贴板辅助片段走的正是刚刚失败的粘贴路径
+pub fn type_text_direct(text: &str) -> Result<(), TypingError> {
+    tracing::info!("type_text_direct: typing {} chars", text.chars().count());
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| TypingError::Typing(e.to_string()))?;
+    enigo
+        .text(text)
+        .map_err(|e| TypingError::Typing(e.to_string()))
+}
+
+/// 模拟按 `count` 次退格键，删除光标前的字符
+pub fn delete_backward(count: usize) -> Result<(), TypingError> {
+    tracing::info!("delete_backward: {} chars", count);
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| TypingError::Typing(e.to_string()))?;
+    for _ in 0..count {
+        enigo
+            .key(Key::Backspace, Direction::Click)
+            .map_err(|e| TypingError::Typing(e.to_string()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use std::ffi::c_void;
+use std::ptr;
+
+use core_foundation::base::{CFType, CFTypeRef, TCFType};
+use core_foundation::string::{CFString, CFStringRef};
+use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+
+use super::{AnchorSource, ScreenRect};
+
+type AXUIElementRef = CFTypeRef;
+type AXValueRef = CFTypeRef;
+
+const AX_ERROR_SUCCESS: i32 = 0;
+const AX_VALUE_CG_POINT: u32 = 1;
+const AX_VALUE_CG_SIZE: u32 = 2;
+const AX_VALUE_CG_RECT: u32 = 3;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+    fn AXUIElementCopyAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXUIElementCopyParameterizedAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        parameter: CFTypeRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXValueGetValue(value: AXValueRef, value_type: u32, out: *mut c_void) -> bool;
+}
+
+/// 插入点或前台窗口的位置（点，左上角为原点），需要辅助功能权限
+pub fn focus_bounds() -> Option<(ScreenRect, AnchorSource)> {
+    unsafe {
+        let system = AXUIElementCreateSystemWide();
+        if system.is_null() {
+            return None;
+        }
+        let system = CFType::wrap_under_create_rule(system);
+
+        let caret = copy_attribute(&system, "AXFocusedUIElement")
+            .and_then(|focused| caret_bounds(&focused));
+        if let Some(rect) = caret {
+            return Some((rect, AnchorSource::Caret));
+        }
+
+        let app = copy_attribute(&system, "AXFocusedApplication")?;
+        let window = copy_attribute(&app, "AXFocusedWindow")?;
+        let position: CGPoint = copy_value(&window, "AXPosition", AX_VALUE_CG_POINT)?;
+        let size: CGSize = copy_value(&window, "AXSize", AX_VALUE_CG_SIZE)?;
+        Some((
+            ScreenRect {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            },
+            AnchorSource::Window,
+        ))
+    }
+}
+
+/// 选中范围（没有选中时为插入点）的屏幕位置
+unsafe fn caret_bounds(element: &CFType) -> Option<ScreenRect> {
+    let range = copy_attribute(element, "AXSelectedTextRange")?;
+    let attribute = CFString::from_static_string("AXBoundsForRange");
+    let mut value: CFTypeRef = ptr::null();
+    let err = AXUIElementCopyParameterizedAttributeValue(
+        element.as_CFTypeRef(),
+        attribute.as_concrete_TypeRef(),
+        range.as_CFTypeRef(),
+        &mut value,
+    );
+    if err != AX_ERROR_SUCCESS || value.is_null() {
+        return None;
+    }
+    let value = CFType::wrap_under_create_rule(value);
+    let rect: CGRect = value_of(&value, AX_VALUE_CG_RECT)?;
+    // 部分应用（如 Electron）返回全零的矩形
+    if rect.origin.x == 0.0 && rect.origin.y == 0.0 && rect.size.height == 0.0 {
+        return None;
+    }
+    Some(ScreenRect {
+        x: rect.origin.x,
+        y: rect.origin.y,
+        width: rect.size.width,
+        height: rect.size.height,
+    })
+}
+
+unsafe fn copy_attribute(element: &CFType, name: &'static str) -> Option<CFType> {
+    let attribute = CFString::from_static_string(name);
+    let mut value: CFTypeRef = ptr::null();
+    let err = AXUIElementCopyAttributeValue(
+        element.as_CFTypeRef(),
+        attribute.as_concrete_TypeRef(),
+        &mut value,
+    );
+    if err != AX_ERROR_SUCCESS || value.is_null() {
+        return None;
+    }
+    Some(CFType::wrap_under_create_rule(value))
+}
+
+unsafe fn copy_value<T>(element: &CFType, name: &'static str, value_type: u32) -> Option<T> {
+    let value = copy_attribute(element, name)?;
+    value_of(&value, value_type)
+}
+
+unsafe fn value_of<T>(value: &CFType, value_type: u32) -> Option<T> {
+    let mut out = std::mem::MaybeUninit::<T>::uninit();
+    if AXValueGetValue(
+        value.as_CFTypeRef(),
+        value_type,
+        out.as_mut_ptr() as *mut c_void,
+    ) {
+        Some(out.assume_init())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,202 @@
+//! 悬浮窗定位
+//!
+//! 录音开始时找到输入位置：插入点（macOS 辅助功能、Windows GUITHREADINFO），
+//! 取不到时用前台窗口，再退回鼠标位置。按所在显示器的缩放比例换算后，
+//! 算出悬浮窗左上角的物理坐标，前端据此把悬浮窗放在插入点附近
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use serde::Serialize;
+use tauri::{AppHandle, Monitor};
+
+/// 悬浮窗与插入点的间距（逻辑像素）
+const OVERLAY_GAP: f64 = 12.0;
+
+/// 屏幕上的矩形
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ScreenRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ScreenRect {
+    fn scaled(self, factor: f64) -> Self {
+        Self {
+            x: self.x * factor,
+            y: self.y * factor,
+            width: self.width * factor,
+            height: self.height * factor,
+        }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// 定位依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorSource {
+    /// 文本插入点
+    Caret,
+    /// 前台窗口
+    Window,
+    /// 鼠标位置
+    Cursor,
+}
+
+/// 平台返回的坐标单位
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Units {
+    /// macOS 的点，乘以所在显示器的缩放比例得到物理像素
+    Logical,
+    Physical,
+}
+
+/// 悬浮窗位置
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayAnchor {
+    /// 悬浮窗左上角（物理像素）
+    pub x: i32,
+    pub y: i32,
+    /// 定位依据的矩形（物理像素）
+    pub target: ScreenRect,
+    pub source: AnchorSource,
+    /// 所在显示器的缩放比例
+    pub scale_factor: f64,
+    pub monitor: Option<String>,
+}
+
+/// 计算悬浮窗位置，width / height 为悬浮窗的逻辑尺寸
+pub fn overlay_anchor(app_handle: &AppHandle, width: f64, height: f64) -> Option<OverlayAnchor> {
+    let monitors = app_handle.available_monitors().ok()?;
+    if monitors.is_empty() {
+        return None;
+    }
+
+    let (target, source, monitor) = match focus_bounds() {
+        Some((rect, source, units)) => {
+            let (rect, monitor) = to_physical(&monitors, rect, units);
+            (rect, source, monitor)
+        }
+        None => {
+            let cursor = app_handle.cursor_position().ok()?;
+            let rect = ScreenRect {
+                x: cursor.x,
+                y: cursor.y,
+                width: 0.0,
+                height: 0.0,
+            };
+            let (rect, monitor) = to_physical(&monitors, rect, Units::Physical);
+            (rect, AnchorSource::Cursor, monitor)
+        }
+    };
+
+    let scale = monitor.scale_factor();
+    let (x, y) = place(
+        target,
+        source,
+        monitor_bounds(monitor),
+        width * scale,
+        height * scale,
+        OVERLAY_GAP * scale,
+    );
+
+    Some(OverlayAnchor {
+        x: x.round() as i32,
+        y: y.round() as i32,
+        target,
+        source,
+        scale_factor: scale,
+        monitor: monitor.name().cloned(),
+    })
+}
+
+/// 当前输入位置，不支持的平台返回 None
+fn focus_bounds() -> Option<(ScreenRect, AnchorSource, Units)> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::focus_bounds().map(|(rect, source)| (rect, source, Units::Logical))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::focus_bounds().map(|(rect, source)| (rect, source, Units::Physical))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// 显示器的物理像素范围
+fn monitor_bounds(monitor: &Monitor) -> ScreenRect {
+    ScreenRect {
+        x: monitor.position().x as f64,
+        y: monitor.position().y as f64,
+        width: monitor.size().width as f64,
+        height: monitor.size().height as f64,
+    }
+}
+
+/// 找到矩形中心所在的显示器并换算成物理像素，不在任何显示器上时用第一个显示器
+///
+/// 各显示器的缩放比例可能不同：逻辑坐标要先按各显示器自己的比例比较范围
+fn to_physical(monitors: &[Monitor], rect: ScreenRect, units: Units) -> (ScreenRect, &Monitor) {
+    let center_x = rect.x + rect.width / 2.0;
+    let center_y = rect.y + rect.height / 2.0;
+    let monitor = monitors
+        .iter()
+        .find(|monitor| {
+            let bounds = monitor_bounds(monitor);
+            match units {
+                Units::Physical => bounds.contains(center_x, center_y),
+                Units::Logical => bounds
+                    .scaled(1.0 / monitor.scale_factor())
+                    .contains(center_x, center_y),
+            }
+        })
+        .unwrap_or(&monitors[0]);
+
+    let rect = match units {
+        Units::Physical => rect,
+        Units::Logical => rect.scaled(monitor.scale_factor()),
+    };
+    (rect, monitor)
+}
+
+/// 悬浮窗左上角：插入点和鼠标下方，放不下时放在上方；窗口时放在窗口底部居中。
+/// 结果限制在显示器范围内
+fn place(
+    target: ScreenRect,
+    source: AnchorSource,
+    bounds: ScreenRect,
+    width: f64,
+    height: f64,
+    gap: f64,
+) -> (f64, f64) {
+    let x = target.x + target.width / 2.0 - width / 2.0;
+    let y = match source {
+        AnchorSource::Window => target.y + target.height - height - gap,
+        AnchorSource::Caret | AnchorSource::Cursor => {
+            let below = target.y + target.height + gap;
+            if below + height <= bounds.y + bounds.height {
+                below
+            } else {
+                target.y - gap - height
+            }
+        }
+    };
+
+    let max_x = (bounds.x + bounds.width - width).max(bounds.x);
+    let max_y = (bounds.y + bounds.height - height).max(bounds.y);
+    (x.clamp(bounds.x, max_x), y.clamp(bounds.y, max_y))
+}
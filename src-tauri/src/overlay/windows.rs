@@ -0,0 +1,57 @@
+use windows::Win32::Foundation::{POINT, RECT};
+use windows::Win32::Graphics::Gdi::ClientToScreen;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, GetGUIThreadInfo, GetWindowRect, GetWindowThreadProcessId, GUITHREADINFO,
+};
+
+use super::{AnchorSource, ScreenRect};
+
+/// 插入点或前台窗口的位置（物理像素，应用按显示器感知 DPI）
+///
+/// 只有使用系统插入点的应用（Win32 编辑框、记事本等）能取到插入点，
+/// 其余应用（Chromium、WPF 等）退回前台窗口
+pub fn focus_bounds() -> Option<(ScreenRect, AnchorSource)> {
+    unsafe {
+        let foreground = GetForegroundWindow();
+        if foreground.0.is_null() {
+            return None;
+        }
+
+        let thread = GetWindowThreadProcessId(foreground, None);
+        let mut info = GUITHREADINFO {
+            cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+            ..Default::default()
+        };
+        if GetGUIThreadInfo(thread, &mut info).is_ok() && !info.hwndCaret.0.is_null() {
+            // rcCaret 是相对 hwndCaret 客户区的坐标
+            let caret = info.rcCaret;
+            let mut origin = POINT {
+                x: caret.left,
+                y: caret.top,
+            };
+            if ClientToScreen(info.hwndCaret, &mut origin).as_bool() {
+                return Some((
+                    ScreenRect {
+                        x: origin.x as f64,
+                        y: origin.y as f64,
+                        width: (caret.right - caret.left) as f64,
+                        height: (caret.bottom - caret.top) as f64,
+                    },
+                    AnchorSource::Caret,
+                ));
+            }
+        }
+
+        let mut rect = RECT::default();
+        GetWindowRect(foreground, &mut rect).ok()?;
+        Some((
+            ScreenRect {
+                x: rect.left as f64,
+                y: rect.top as f64,
+                width: (rect.right - rect.left) as f64,
+                height: (rect.bottom - rect.top) as f64,
+            },
+            AnchorSource::Window,
+        ))
+    }
+}
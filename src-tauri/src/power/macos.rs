@@ -0,0 +1,28 @@
+use core_foundation::base::{CFType, TCFType};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+use core_foundation::string::CFString;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGSessionCopyCurrentDictionary() -> CFDictionaryRef;
+}
+
+/// 当前登录会话是否已锁屏，没有图形会话时返回 None
+pub fn is_session_locked() -> Option<bool> {
+    unsafe {
+        let dict = CGSessionCopyCurrentDictionary();
+        if dict.is_null() {
+            return None;
+        }
+        let dict: CFDictionary<CFString, CFType> = CFDictionary::wrap_under_create_rule(dict);
+        // 未锁屏时该键不存在
+        let key = CFString::from_static_string("CGSSessionScreenIsLocked");
+        let locked = dict
+            .find(&key)
+            .and_then(|value| value.downcast::<CFBoolean>())
+            .map(bool::from)
+            .unwrap_or(false);
+        Some(locked)
+    }
+}
@@ -0,0 +1,154 @@
+//! 电源和会话状态
+//!
+//! - 节能：空闲时释放本地模型、sherpa-onnx 服务端进程和 LLM 保活连接。
+//!   录音结束后麦克风已关闭，这里处理其余在空闲时仍被占用的资源，
+//!   释放后下次按下快捷键时按需重新建立
+//! - 休眠和锁屏：锁屏时取消进行中的会话（避免把文本输出到锁屏界面）并释放资源；
+//!   休眠期间进程不运行，唤醒后根据墙上时钟的跳变发现休眠，取消休眠前的会话
+//!   （音频设备和连接已失效），重新检查权限和麦克风，并发送 `power` 事件
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use vhisper_core::EventPayload;
+
+use crate::{events, health, pipelines, AppState};
+
+/// 检查空闲时长的间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// 检查锁屏和休眠的间隔
+const POWER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// 两次检查之间的墙上时钟间隔超出轮询间隔多少时认为系统休眠过
+const SLEEP_GAP: Duration = Duration::from_secs(10);
+
+/// 电源和会话状态变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum PowerEvent {
+    /// 从休眠中恢复
+    Wake { slept_secs: u64 },
+    Locked,
+    Unlocked,
+}
+
+/// 在后台定时检查，所有管道空闲超过配置的时长时释放资源
+pub fn spawn_idle_release() {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Some(registry) = pipelines() {
+                registry.release_if_idle();
+            }
+        }
+    });
+}
+
+/// 在后台线程中监测休眠唤醒和锁屏
+pub fn spawn_monitor(app_handle: AppHandle) {
+    thread::spawn(move || {
+        let mut last_tick = SystemTime::now();
+        let mut locked = is_session_locked().unwrap_or(false);
+
+        loop {
+            thread::sleep(POWER_POLL_INTERVAL);
+
+            let now = SystemTime::now();
+            let elapsed = now.duration_since(last_tick).unwrap_or_default();
+            last_tick = now;
+            if elapsed > POWER_POLL_INTERVAL + SLEEP_GAP {
+                handle(
+                    &app_handle,
+                    PowerEvent::Wake {
+                        slept_secs: elapsed.as_secs(),
+                    },
+                );
+            }
+
+            if let Some(now_locked) = is_session_locked() {
+                if now_locked != locked {
+                    locked = now_locked;
+                    let event = if locked {
+                        PowerEvent::Locked
+                    } else {
+                        PowerEvent::Unlocked
+                    };
+                    handle(&app_handle, event);
+                }
+            }
+        }
+    });
+}
+
+/// 当前会话是否已锁屏，不支持检测的平台返回 None
+fn is_session_locked() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::is_session_locked()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::is_session_locked()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+fn handle(app_handle: &AppHandle, event: PowerEvent) {
+    tracing::info!("Power event: {:?}", event);
+
+    match event {
+        PowerEvent::Wake { .. } => {
+            cancel_sessions(app_handle);
+            // 休眠期间可能拔掉了麦克风或撤销了权限，重新检查并更新托盘提示
+            health::spawn_startup_checks(app_handle.clone());
+        }
+        PowerEvent::Locked => {
+            cancel_sessions(app_handle);
+            if let Some(registry) = pipelines() {
+                registry.release_resources();
+            }
+        }
+        PowerEvent::Unlocked => {}
+    }
+
+    events::publish(EventPayload::Custom {
+        name: "power".to_string(),
+        data: serde_json::to_value(event).unwrap_or_default(),
+    });
+}
+
+/// 取消所有进行中的会话，悬浮窗和托盘随 RecordingCancelled 事件恢复
+fn cancel_sessions(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(registry) = pipelines() else {
+            return;
+        };
+        for pipeline in registry.all() {
+            let result = if pipeline.is_streaming() {
+                pipeline.cancel_streaming().await
+            } else {
+                pipeline.cancel()
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to cancel session: {}", e);
+            }
+        }
+
+        let state = app_handle.state::<AppState>();
+        if let Ok(mut is_recording) = state.is_recording.try_write() {
+            *is_recording = false;
+        }
+    });
+}
@@ -0,0 +1,20 @@
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::System::StationsAndDesktops::{
+    CloseDesktop, OpenInputDesktop, SwitchDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_SWITCHDESKTOP,
+};
+
+/// 当前会话是否已锁屏
+///
+/// 锁屏时输入桌面切换到 Winlogon，普通进程无法打开或切换到它
+pub fn is_session_locked() -> Option<bool> {
+    unsafe {
+        match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), BOOL::from(false), DESKTOP_SWITCHDESKTOP) {
+            Ok(desktop) => {
+                let switchable = SwitchDesktop(desktop).is_ok();
+                let _ = CloseDesktop(desktop);
+                Some(!switchable)
+            }
+            Err(_) => Some(true),
+        }
+    }
+}
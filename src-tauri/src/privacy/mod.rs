@@ -0,0 +1,33 @@
+//! 隐私模式（无痕听写）
+//!
+//! 托盘菜单、命令和快捷键共用同一个开关（见 `vhisper_core::privacy`），
+//! 切换时同步托盘勾选状态并发送 `incognito` 事件
+
+use tauri::AppHandle;
+use vhisper_core::EventPayload;
+
+use crate::{a11y, events, tray};
+
+/// 开启或关闭隐私模式
+pub fn set_enabled(app_handle: &AppHandle, enabled: bool) {
+    tray::set_privacy_checked(app_handle, enabled);
+    if !vhisper_core::set_incognito(enabled) {
+        return;
+    }
+
+    events::publish(EventPayload::Custom {
+        name: "incognito".to_string(),
+        data: serde_json::json!({ "enabled": enabled }),
+    });
+    let message = if enabled {
+        "隐私模式已开启"
+    } else {
+        "隐私模式已关闭"
+    };
+    a11y::announce_if_enabled(app_handle, message);
+}
+
+/// 切换隐私模式（快捷键）
+pub fn toggle(app_handle: &AppHandle) {
+    set_enabled(app_handle, !vhisper_core::is_incognito());
+}
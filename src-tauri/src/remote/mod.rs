@@ -0,0 +1,271 @@
+//! 局域网远程触发
+//!
+//! 在本机开放一个简单的 HTTP 接口，配对的手机快捷指令或手表应用可以远程开始/停止听写。
+//! 每个请求都必须携带配对令牌，推荐放在 `Authorization: Bearer <token>` 请求头中，
+//! 兼容旧设备的 `?token=<token>` 查询参数；令牌通过设置页的二维码分享给设备。
+//!
+//! 接口是明文 HTTP，同一局域网内能抓包的人可以看到令牌（无论放在请求头还是地址中），
+//! 只应在可信网络中启用，怀疑泄露时在设置页重新生成令牌。
+//!
+//! 接口：
+//! - `GET  /status`：当前是否在录音
+//! - `POST /dictation/start`、`/dictation/stop`、`/dictation/toggle`
+
+use std::net::{IpAddr, UdpSocket};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::commands::audio;
+use crate::AppState;
+use vhisper_core::config::settings::RemoteTriggerConfig;
+
+/// 请求头的最大长度
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// 读取请求的超时时间
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// 接受连接出错（如文件描述符耗尽）后的等待时间，避免空转占满 CPU
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(500);
+
+/// 正在运行的服务
+struct RunningServer {
+    config: RemoteTriggerConfig,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+/// 配对信息
+#[derive(Debug, Clone, Serialize)]
+pub struct RemotePairing {
+    /// 切换听写的地址（不含令牌）
+    pub url: String,
+    pub token: String,
+    /// 请求需要携带的 Authorization 请求头的值
+    pub authorization: String,
+    /// 配对数据（地址和请求头，JSON）的二维码（SVG）
+    pub qr_svg: String,
+}
+
+/// 二维码中的配对数据
+#[derive(Serialize)]
+struct PairingPayload<'a> {
+    url: &'a str,
+    authorization: &'a str,
+}
+
+/// 按配置启动、重启或停止服务
+pub fn apply(app: &AppHandle, config: &RemoteTriggerConfig) {
+    let Ok(mut server) = SERVER.lock() else {
+        return;
+    };
+
+    if server.as_ref().is_some_and(|s| &s.config == config) {
+        return;
+    }
+    if let Some(running) = server.take() {
+        running.task.abort();
+        tracing::info!("Remote trigger server stopped");
+    }
+    if !config.enabled || config.token.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    let port = config.port;
+    let token = config.token.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(("0.0.0.0", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!("Failed to start remote trigger server on port {}: {}", port, e);
+                return;
+            }
+        };
+        tracing::info!("Remote trigger server listening on port {}", port);
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!("Remote trigger server failed to accept connection: {}", e);
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            };
+            let app = app.clone();
+            let token = token.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = handle_connection(&app, stream, &token).await {
+                    tracing::debug!("Remote trigger request from {} failed: {}", peer, e);
+                }
+            });
+        }
+    });
+
+    *server = Some(RunningServer {
+        config: config.clone(),
+        task,
+    });
+}
+
+/// 生成配对信息
+pub fn pairing(config: &RemoteTriggerConfig) -> Result<RemotePairing, String> {
+    if config.token.is_empty() {
+        return Err("远程触发未启用".to_string());
+    }
+    let host = local_ip().ok_or_else(|| "无法获取本机局域网地址".to_string())?;
+    let url = format!("http://{}:{}/dictation/toggle", host, config.port);
+    let authorization = format!("Bearer {}", config.token);
+    let payload = serde_json::to_string(&PairingPayload {
+        url: &url,
+        authorization: &authorization,
+    })
+    .map_err(|e| e.to_string())?;
+    let qr_svg = crate::output::render_qr_svg(&payload).map_err(|e| e.to_string())?;
+    Ok(RemotePairing {
+        url,
+        token: config.token.clone(),
+        authorization,
+        qr_svg,
+    })
+}
+
+/// 本机的局域网地址
+///
+/// UDP connect 只选择出口网卡，不会发送数据
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// 解析后的请求
+struct Request {
+    method: String,
+    path: String,
+    query_token: Option<String>,
+    bearer_token: Option<String>,
+}
+
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query_token = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .map(str::to_string);
+
+    let bearer_token = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query_token,
+        bearer_token,
+    })
+}
+
+/// 比较令牌，耗时与不匹配的位置无关
+fn token_matches(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn handle_connection(app: &AppHandle, mut stream: TcpStream, token: &str) -> std::io::Result<()> {
+    // 读到请求头结束，请求体不需要
+    let mut buffer = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    let head = tokio::time::timeout(READ_TIMEOUT, async {
+        loop {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+            if buffer.windows(4).any(|w| w == b"\r\n\r\n") || buffer.len() >= MAX_REQUEST_BYTES {
+                break;
+            }
+        }
+        Ok::<_, std::io::Error>(String::from_utf8_lossy(&buffer).into_owned())
+    })
+    .await
+    .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "read timeout"))??;
+
+    let (status, body) = match parse_request(&head) {
+        None => (400, r#"{"error":"bad request"}"#.to_string()),
+        Some(request) => {
+            let provided = request.bearer_token.or(request.query_token).unwrap_or_default();
+            if token_matches(&provided, token) {
+                route(app, &request.method, &request.path).await
+            } else {
+                (401, r#"{"error":"unauthorized"}"#.to_string())
+            }
+        }
+    };
+
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// 处理已认证的请求，返回 (状态码, JSON)
+async fn route(app: &AppHandle, method: &str, path: &str) -> (u16, String) {
+    let recording = *app.state::<AppState>().is_recording.read().await;
+
+    let start = match (method, path) {
+        ("GET", "/status") => return (200, format!(r#"{{"recording":{}}}"#, recording)),
+        ("POST", "/dictation/start") => true,
+        ("POST", "/dictation/stop") => false,
+        ("POST", "/dictation/toggle") => !recording,
+        _ => return (404, r#"{"error":"not found"}"#.to_string()),
+    };
+
+    if start {
+        tracing::info!("Remote trigger: start dictation");
+        match audio::start_recording(app.clone(), app.state()).await {
+            Ok(()) => (200, r#"{"recording":true}"#.to_string()),
+            Err(e) => (500, serde_json::json!({ "error": e }).to_string()),
+        }
+    } else {
+        // 识别和输出可能需要几秒，先返回，避免手表端请求超时
+        tracing::info!("Remote trigger: stop dictation");
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = audio::stop_recording(app.clone(), app.state()).await {
+                tracing::error!("Remote stop failed: {}", e);
+            }
+        });
+        (202, r#"{"recording":false}"#.to_string())
+    }
+}
@@ -0,0 +1,70 @@
+//! 匿名使用统计
+//!
+//! 用户开启（`telemetry.enabled`）并配置了上报地址时，后台每天上报一次累计数据。
+//! 上次上报时间保存在累计数据中，重启后按剩余时间继续等待
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use vhisper_core::telemetry::{self, TelemetryReport};
+
+use crate::AppState;
+
+/// 上报间隔
+const REPORT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// 未开启或上报失败后再次检查的间隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// 启动后至少等待的时间，避免和启动时的其他网络请求挤在一起
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+/// 累计数据写入文件的间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+/// 应用版本
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 应用开关，关闭时删除已累计的数据
+pub fn apply(enabled: bool) {
+    telemetry::set_telemetry(enabled);
+}
+
+/// 下次上报的内容
+pub fn preview() -> TelemetryReport {
+    telemetry::preview_report(APP_VERSION)
+}
+
+/// 退出时保存尚未写入的累计数据
+pub fn on_exit() {
+    telemetry::flush_counters();
+}
+
+/// 后台定期上报，并定期保存累计数据
+pub fn spawn_reporter(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            let _ = tokio::task::spawn_blocking(telemetry::flush_counters).await;
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            tokio::time::sleep(telemetry::next_report_in(REPORT_INTERVAL)).await;
+
+            let config = app_handle
+                .state::<AppState>()
+                .config
+                .read()
+                .await
+                .telemetry
+                .clone();
+            if !config.enabled || config.endpoint.trim().is_empty() {
+                tokio::time::sleep(RETRY_INTERVAL).await;
+                continue;
+            }
+            if let Err(e) = telemetry::send_report(config.endpoint.trim(), APP_VERSION).await {
+                tracing::warn!("Telemetry report failed: {}", e);
+                tokio::time::sleep(RETRY_INTERVAL).await;
+            }
+        }
+    });
+}
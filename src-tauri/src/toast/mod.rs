@@ -0,0 +1,63 @@
+//! 系统通知
+//!
+//! 输出降级为仅复制到剪贴板（应用已切换、目标窗口权限更高）时提示用户，
+//! Windows 上的通知带"仍然粘贴"按钮，点击后向当前前台窗口重新粘贴。
+//! 勿扰模式开启时按配置不显示
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::{output, AppState};
+
+/// 通知回调需要读取配置
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// 初始化通知（应用启动时调用）
+pub fn init(app_handle: AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+/// 提示文本只复制到了剪贴板
+pub fn show_copy_only(message: &str) {
+    tracing::info!("Copy-only notification: {}", message);
+
+    if let Some(app_handle) = APP_HANDLE.get() {
+        if crate::dnd::state(app_handle).hide_notifications {
+            tracing::debug!("Notification suppressed in focus mode");
+            return;
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Err(e) = windows::show_copy_only(message) {
+            tracing::warn!("Failed to show toast: {}", e);
+        }
+    }
+}
+
+/// 通知上的"仍然粘贴"按钮被点击
+fn on_paste_anyway() {
+    let Some(app_handle) = APP_HANDLE.get() else {
+        return;
+    };
+    let config = app_handle.state::<AppState>().config.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let output_config = config.read().await.output.clone();
+        // 模拟按键会阻塞等待粘贴延迟，放到阻塞线程池
+        let result =
+            tauri::async_runtime::spawn_blocking(move || output::paste_anyway(&output_config)).await;
+        match result {
+            Ok(Err(e)) => {
+                tracing::error!("Paste anyway failed: {}", e);
+                crate::events::publish_output_error(&e);
+            }
+            Err(e) => tracing::error!("Paste anyway task failed: {}", e),
+            Ok(Ok(())) => {}
+        }
+    });
+}
@@ -0,0 +1,57 @@
+use std::sync::Mutex;
+
+use windows::core::{IInspectable, Interface, HSTRING};
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Foundation::TypedEventHandler;
+use windows::UI::Notifications::{
+    ToastActivatedEventArgs, ToastNotification, ToastNotificationManager,
+};
+
+/// 安装包注册的 AppUserModelID（与 tauri.conf.json 的 identifier 一致）
+const APP_ID: &str = "com.vhisper.vhisper";
+
+/// "仍然粘贴"按钮的参数
+const PASTE_ANYWAY_ARG: &str = "paste-anyway";
+
+/// 保留最近一条通知，确保激活回调在通知存在期间有效
+static LAST_TOAST: Mutex<Option<ToastNotification>> = Mutex::new(None);
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 显示带"仍然粘贴"按钮的通知
+pub fn show_copy_only(message: &str) -> windows::core::Result<()> {
+    let payload = format!(
+        r#"<toast><visual><binding template="ToastGeneric"><text>Vhisper</text><text>{}</text></binding></visual><actions><action content="仍然粘贴" arguments="{}" activationType="foreground"/></actions></toast>"#,
+        escape_xml(message),
+        PASTE_ANYWAY_ARG
+    );
+
+    let xml = XmlDocument::new()?;
+    xml.LoadXml(&HSTRING::from(payload))?;
+
+    let toast = ToastNotification::CreateToastNotification(&xml)?;
+    toast.Activated(&TypedEventHandler::new(
+        |_sender: &Option<ToastNotification>, args: &Option<IInspectable>| {
+            let arguments = args
+                .as_ref()
+                .and_then(|args| args.cast::<ToastActivatedEventArgs>().ok())
+                .and_then(|args| args.Arguments().ok());
+            if arguments.is_some_and(|arguments| arguments == PASTE_ANYWAY_ARG) {
+                super::on_paste_anyway();
+            }
+            Ok(())
+        },
+    ))?;
+
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?.Show(&toast)?;
+
+    if let Ok(mut last) = LAST_TOAST.lock() {
+        *last = Some(toast);
+    }
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager,
 };
+use vhisper_core::history::GoalProgress;
 
 #[derive(Debug, thiserror::Error)]
 pub enum TrayError {
@@ -11,6 +12,12 @@ pub enum TrayError {
     Setup(String),
 }
 
+/// 隐私模式菜单项，切换后同步勾选状态
+struct PrivacyMenuItem(CheckMenuItem<tauri::Wry>);
+
+/// 每日目标进度菜单项（不可点击，只显示进度）
+struct GoalMenuItem(MenuItem<tauri::Wry>);
+
 // 嵌入图标
 const ICON_BYTES: &[u8] = include_bytes!("../../icons/icon.png");
 
@@ -20,6 +27,21 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
     let settings_item = MenuItem::with_id(app, "settings", "设置...", true, None::<&str>)
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
+    let privacy_item = CheckMenuItem::with_id(
+        app,
+        "privacy",
+        "隐私模式",
+        true,
+        vhisper_core::is_incognito(),
+        None::<&str>,
+    )
+    .map_err(|e| TrayError::Setup(e.to_string()))?;
+    app.manage(PrivacyMenuItem(privacy_item.clone()));
+
+    let goal_item = MenuItem::with_id(app, "goal", "今日 0 词", false, None::<&str>)
+        .map_err(|e| TrayError::Setup(e.to_string()))?;
+    app.manage(GoalMenuItem(goal_item.clone()));
+
     let separator = PredefinedMenuItem::separator(app)
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
@@ -27,7 +49,10 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
     // 创建菜单
-    let menu = Menu::with_items(app, &[&settings_item, &separator, &quit_item])
+    let menu = Menu::with_items(
+        app,
+        &[&goal_item, &settings_item, &privacy_item, &separator, &quit_item],
+    )
         .map_err(|e| TrayError::Setup(e.to_string()))?;
 
     // 从 PNG 解码图标
@@ -66,6 +91,9 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
                         let _ = window.set_focus();
                     }
                 }
+                "privacy" => {
+                    crate::privacy::toggle(app);
+                }
                 "quit" => {
                     tracing::info!("Quit menu clicked");
                     app.exit(0);
@@ -80,6 +108,48 @@ pub fn setup_tray(app: &AppHandle) -> Result<TrayIcon, TrayError> {
     Ok(tray)
 }
 
+/// 根据启动检查结果更新托盘提示，有问题时在图标旁显示警告标记
+pub fn set_health_badge(app: &AppHandle, problems: &[&str]) {
+    let Some(tray) = app.try_state::<TrayIcon>() else {
+        return;
+    };
+
+    if problems.is_empty() {
+        let _ = tray.set_title(None::<&str>);
+        let _ = tray.set_tooltip(Some("vhisper - 语音输入"));
+    } else {
+        let _ = tray.set_title(Some("⚠"));
+        let _ = tray.set_tooltip(Some(format!("vhisper - {}", problems.join("；"))));
+    }
+}
+
+/// 更新隐私模式菜单项的勾选状态
+pub fn set_privacy_checked(app: &AppHandle, checked: bool) {
+    if let Some(item) = app.try_state::<PrivacyMenuItem>() {
+        let _ = item.0.set_checked(checked);
+    }
+}
+
+/// 更新每日目标进度菜单项
+pub fn set_goal_progress(app: &AppHandle, progress: &GoalProgress) {
+    let Some(item) = app.try_state::<GoalMenuItem>() else {
+        return;
+    };
+
+    let mut text = if progress.daily_goal > 0 {
+        format!("今日 {}/{} 词", progress.today_words, progress.daily_goal)
+    } else {
+        format!("今日 {} 词", progress.today_words)
+    };
+    if progress.goal_met_today && progress.daily_goal > 0 {
+        text.push_str(" ✓");
+    }
+    if progress.current_streak > 0 {
+        text.push_str(&format!(" · 连续 {} 天", progress.current_streak));
+    }
+    let _ = item.0.set_text(text);
+}
+
 /// 从 PNG 数据加载图标
 fn load_icon_from_png(png_data: &[u8]) -> Result<Image<'static>, String> {
     let decoder = png::Decoder::new(png_data);
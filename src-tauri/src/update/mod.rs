@@ -0,0 +1,110 @@
+//! 检查更新
+//!
+//! 启动后在后台按配置的通道检查一次，发现新版本时发送 `update_available` 事件；
+//! 开启自动下载时接着下载安装包，完成后发送 `update_downloaded` 事件
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use vhisper_core::config::settings::UpdateChannel;
+use vhisper_core::update::{self, UpdateInfo};
+use vhisper_core::EventPayload;
+
+use crate::events;
+use crate::AppState;
+
+/// 启动后等待多久再检查，避免和启动检查抢网络
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+
+/// 最近一次检查到的新版本，供前端晚于事件加载时查询
+static AVAILABLE: Mutex<Option<UpdateInfo>> = Mutex::new(None);
+
+/// 最近一次检查到的新版本
+pub fn available() -> Option<UpdateInfo> {
+    AVAILABLE.lock().ok().and_then(|u| u.clone())
+}
+
+/// 检查更新，未指定通道时使用配置的通道
+pub async fn check(
+    app_handle: &AppHandle,
+    channel: Option<UpdateChannel>,
+) -> Result<Option<UpdateInfo>, String> {
+    let config = app_handle
+        .state::<AppState>()
+        .config
+        .read()
+        .await
+        .update
+        .clone();
+    let channel = channel.unwrap_or(config.channel);
+    let current_version = app_handle.package_info().version.to_string();
+
+    let update = update::check_for_update(channel, &current_version)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Ok(mut available) = AVAILABLE.lock() {
+        *available = update.clone();
+    }
+
+    if let Some(update) = &update {
+        events::publish(EventPayload::Custom {
+            name: "update_available".to_string(),
+            data: serde_json::to_value(update).unwrap_or_default(),
+        });
+        if config.auto_download && update.asset.is_some() {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = download(&app_handle).await {
+                    tracing::warn!("Failed to download update: {}", e);
+                }
+            });
+        }
+    }
+    Ok(update)
+}
+
+/// 下载最近一次检查到的新版本，返回安装包路径
+pub async fn download(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let update = match available() {
+        Some(update) => update,
+        None => check(app_handle, None).await?.ok_or("当前已是最新版本")?,
+    };
+    let dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("updates");
+
+    let path = update::download_update(&update, &dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    events::publish(EventPayload::Custom {
+        name: "update_downloaded".to_string(),
+        data: serde_json::json!({
+            "version": update.version,
+            "path": path.display().to_string(),
+        }),
+    });
+    Ok(path)
+}
+
+/// 启动后在后台检查一次更新（配置关闭或仅本机识别时跳过）
+pub fn spawn_startup_check(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let enabled = {
+            let state = app_handle.state::<AppState>();
+            let config = state.config.read().await;
+            config.update.check_on_startup && !config.asr.local_only
+        };
+        if !enabled {
+            return;
+        }
+
+        tokio::time::sleep(STARTUP_DELAY).await;
+        if let Err(e) = check(&app_handle, None).await {
+            tracing::warn!("Update check failed: {}", e);
+        }
+    });
+}
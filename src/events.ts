@@ -0,0 +1,84 @@
+// 与 vhisper-core events 模块对应的事件协议（version 1）
+
+export const EVENT_CHANNEL = 'vhisper://event';
+export const EVENT_SCHEMA_VERSION = 1;
+
+export type EventStage = 'startup' | 'recording' | 'asr' | 'post_process' | 'output';
+
+export type ErrorCode =
+  | 'audio'
+  | 'asr'
+  | 'llm'
+  | 'timeout'
+  | 'paste_blocked'
+  | 'elevated_target'
+  | 'output'
+  | 'secure_input'
+  | 'other';
+
+export type SessionLimit = 'max_duration' | 'provider_session';
+
+// 原始识别文本到输出文本的差异片段，同一处替换先 delete 后 insert
+export type DiffSpan = { op: 'equal' | 'insert' | 'delete'; text: string };
+
+export type EventPayload =
+  | { type: 'recording_started' }
+  | { type: 'recording_stopped' }
+  | { type: 'recording_cancelled' }
+  | { type: 'processing_complete'; data: { text: string } }
+  | { type: 'session_complete'; data: { text: string } }
+  | { type: 'self_dictation'; data: { text: string } }
+  | { type: 'input_activity'; data: { level: number; speech: boolean } }
+  | { type: 'dictation_progress'; data: { words: number; wpm: number } }
+  | { type: 'text_diff'; data: { spans: DiffSpan[]; changed: boolean } }
+  | { type: 'session_stats'; data: { words: number; duration_ms: number; wpm: number } }
+  | { type: 'session_limit_warning'; data: { limit: SessionLimit; remaining_secs: number } }
+  | {
+      type: 'error';
+      data: { stage: EventStage; code: ErrorCode; message: string; recoverable: boolean };
+    }
+  | { type: 'custom'; data: { name: string; data: unknown } };
+
+export type VhisperEvent = EventPayload & {
+  version: number;
+  session_id: string | null;
+  // 隐私模式下发布的事件，不要保存其中的文本
+  incognito?: boolean;
+};
+
+// custom 事件 'power' 的数据：休眠唤醒和锁屏状态变化
+export type PowerEvent =
+  | { state: 'wake'; slept_secs: number }
+  | { state: 'locked' }
+  | { state: 'unlocked' };
+
+// custom 事件 'focus_mode' 的数据：勿扰模式开启或关闭
+export type FocusModeEvent = { active: boolean };
+
+// custom 事件 'incognito' 的数据：隐私模式开启或关闭
+export type IncognitoEvent = { enabled: boolean };
+
+// custom 事件 'update_available' 的数据：发现新版本
+export type UpdateAvailableEvent = {
+  version: string;
+  current_version: string;
+  name: string;
+  notes: string;
+  url: string;
+  prerelease: boolean;
+  published_at: string | null;
+  // 当前平台的安装包，没有时只能打开发布页下载
+  asset: { name: string; browser_download_url: string; size: number } | null;
+};
+
+// custom 事件 'update_downloaded' 的数据：安装包已下载
+export type UpdateDownloadedEvent = { version: string; path: string };
+
+// custom 事件 'meeting_starting' 的数据：日历中的会议即将开始
+export type MeetingStartingEvent = {
+  id: string;
+  title: string;
+  start: number;
+  end: number;
+  all_day: boolean;
+};